@@ -246,7 +246,10 @@ impl FromStr for PropertyStorageOpt {
         match opt.as_str() {
             "SIMPLELDBC" => Ok(PropertyStorageOpt::SimpleLDBC),
             "ROCKSDB" => Ok(PropertyStorageOpt::RocksDB),
-            _ => GDBResult::Err(GDBError::ParseError),
+            _ => GDBResult::Err(GDBError::ParseError(format!(
+                "unknown property storage option {:?}",
+                _opt
+            ))),
         }
     }
 }
@@ -15,7 +15,7 @@
 
 use crate::common::{Label, LabelId};
 use crate::error::{GDBError, GDBResult};
-use crate::graph_db_impl::{IndexData, LargeGraphDB, MutableGraphDB};
+use crate::graph_db_impl::{IndexData, LargeGraphDB, MutableGraphDB, PropertyIndices};
 use crate::io::import;
 use crate::schema::LDBCGraphSchema;
 use crate::table::PropertyTableTrait;
@@ -190,9 +190,8 @@ impl GraphDBConfig {
             let curr_path = entry?.path();
             let path_str = curr_path
                 .file_name()
-                .ok_or(GDBError::UnknownError)?
-                .to_str()
-                .ok_or(GDBError::UnknownError)?
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| GDBError::InvalidPathError(curr_path.display().to_string()))?
                 .to_string();
 
             if !path_str.starts_with(PARTITION_PREFIX) {
@@ -219,7 +218,8 @@ impl GraphDBConfig {
         let graph =
             import::<DiGraph<Label, LabelId, I>, _>(&partition_dir.join(FILE_GRAPH_STRUCT))?;
         let vertex_prop_table = N::import(&partition_dir, FILE_NODE_PPT_DATA)?;
-        let edge_prop_table = E::import(&partition_dir, FILE_EDGE_PPT_DATA)?;
+        let edge_prop_table =
+            E::import_shared(&partition_dir, FILE_EDGE_PPT_DATA, vertex_prop_table.rocks_db())?;
         let index_data = import::<IndexData<G, I>, _>(&partition_dir.join(FILE_INDEX_DATA))?;
 
         let graph_db = LargeGraphDB {
@@ -229,6 +229,7 @@ impl GraphDBConfig {
             vertex_prop_table,
             edge_prop_table,
             index_data,
+            property_indices: PropertyIndices::default(),
         };
 
         info!("Time elapsed: {:?}", timer.elapsed().as_secs_f64());
@@ -254,7 +255,9 @@ impl GraphDBConfig {
         let edge_ppt_dir = partition_dir.join(FILE_EDGE_PPT_DATA);
 
         let vertex_prop_table = N::new(&vertex_ppt_dir);
-        let edge_prop_table = E::new(&edge_ppt_dir);
+        // the edges are stored in the RocksDB of the vertices if both are RocksDB-based, so that
+        // the properties of both are committed at once by `WriteBatch::commit()`
+        let edge_prop_table = E::new_shared(&edge_ppt_dir, vertex_prop_table.rocks_db());
 
         MutableGraphDB {
             root_dir: self.root_dir.clone(),
@@ -263,6 +266,9 @@ impl GraphDBConfig {
             vertex_prop_table,
             edge_prop_table,
             index_data: IndexData::new(self.number_vertex_labels),
+            schema: None,
+            property_indices: PropertyIndices::default(),
+            read_only: false,
         }
     }
 
@@ -0,0 +1,358 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Load a graph from csv files, where the header of a file annotates the type of each column,
+//! e.g. `id:id,name:string,age:int`. Unlike the LDBC files, no schema is required.
+
+use crate::common::{LabelId, INVALID_LABEL_ID};
+use crate::error::{GDBError, GDBResult};
+use crate::graph_db::GlobalStoreUpdate;
+use crate::graph_db_impl::MutableGraphDB;
+use crate::ldbc::LDBCVertexParser;
+use crate::parser::{parse_properties, DataType, EdgeMeta, VertexMeta};
+use crate::schema::{END_ID_FIELD, ID_FIELD, START_ID_FIELD};
+use crate::table::{PropertyTableTrait, Row};
+use csv::{ReaderBuilder, StringRecord};
+use petgraph::graph::IndexType;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Parse the header of a csv file, where each column is annotated with its type like `age:int`,
+/// and a column without annotation is of `DataType::String`.
+fn parse_header(record: &StringRecord) -> GDBResult<Vec<(String, DataType)>> {
+    record
+        .iter()
+        .map(|column| {
+            let (name, ty) = if let Some(pos) = column.rfind(':') {
+                (&column[..pos], DataType::from(&column[pos + 1..]))
+            } else {
+                (column, DataType::String)
+            };
+            if ty == DataType::NULL {
+                Err(GDBError::ParseError(format!("unsupported type of column {:?}", column)))
+            } else {
+                Ok((name.trim().to_string(), ty))
+            }
+        })
+        .collect()
+}
+
+/// Get the position of the column `field` of `DataType::ID` in the header.
+fn get_id_column(header: &[(String, DataType)], field: &str) -> GDBResult<usize> {
+    header.iter().position(|(name, ty)| name == field && ty == &DataType::ID).ok_or_else(|| {
+        GDBError::ParseError(format!("column \"{}:id\" is not found in the header", field))
+    })
+}
+
+/// A loader of the vertices and the edges of a certain label from csv files, each of which has
+/// a header of typed columns. A vertex file must have an `id:id` column, and an edge file must
+/// have both `start_id:id` and `end_id:id` columns, which are the ids of the vertices it links.
+/// The properties are the other columns in order, with values coerced into the column types,
+/// while the id of a vertex is kept as its first property, as is in the LDBC data.
+///
+/// Each row must have exactly the columns of the header, where a row of missing or extra
+/// columns, as well as a value that can not be coerced into the type of the column, is
+/// reported as `GDBError::ParseError` with the file and the line of the row.
+pub struct CsvLoader {
+    delim: u8,
+}
+
+impl Default for CsvLoader {
+    fn default() -> Self {
+        CsvLoader { delim: b',' }
+    }
+}
+
+impl CsvLoader {
+    pub fn with_delimiter(mut self, delim: u8) -> Self {
+        self.delim = delim;
+        self
+    }
+
+    /// Read the records of a csv file, with the header parsed, and each record paired with
+    /// its line in the file for reporting errors
+    fn read_records<P: AsRef<Path>>(
+        &self, path: P,
+    ) -> GDBResult<(Vec<(String, DataType)>, Vec<(u64, StringRecord)>)> {
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(self.delim)
+            .buffer_capacity(4096)
+            .comment(Some(b'#'))
+            // the number of columns is validated, to report it as a parse error
+            .flexible(true)
+            .has_headers(false)
+            .from_reader(BufReader::new(File::open(path.as_ref())?));
+
+        let row_error = |line: u64, msg: String| {
+            GDBError::ParseError(format!("{:?} line {}: {}", path.as_ref(), line, msg))
+        };
+        let mut records = rdr.records();
+        let header = match records.next() {
+            Some(record) => {
+                let record = record.map_err(|e| row_error(1, e.to_string()))?;
+                parse_header(&record).map_err(|e| row_error(1, e.to_string()))?
+            }
+            None => return Err(row_error(1, "the header is not found".to_string())),
+        };
+
+        let mut result = vec![];
+        for record in records {
+            let record = record.map_err(|e| {
+                let line = e.position().map(|pos| pos.line()).unwrap_or_default();
+                row_error(line, e.to_string())
+            })?;
+            let line = record.position().map(|pos| pos.line()).unwrap_or_default();
+            if record.len() != header.len() {
+                return Err(row_error(
+                    line,
+                    format!("expect {} columns, while got {}", header.len(), record.len()),
+                ));
+            }
+            result.push((line, record));
+        }
+
+        Ok((header, result))
+    }
+
+    /// Parse the vertices of `label_id` from a csv file, whose global ids are encoded from the
+    /// ids and the label as in `LDBCVertexParser::to_global_id()`. The file is parsed as a
+    /// whole, and any malformed row fails the parsing.
+    pub fn read_vertices<G: IndexType, P: AsRef<Path>>(
+        &self, path: P, label_id: LabelId,
+    ) -> GDBResult<Vec<(VertexMeta<G>, Row)>> {
+        let (header, records) = self.read_records(path.as_ref())?;
+        let id_column = get_id_column(&header, ID_FIELD)?;
+        let mut vertices = Vec::with_capacity(records.len());
+        for (line, record) in records {
+            let parse = || -> GDBResult<(VertexMeta<G>, Row)> {
+                let id = parse_id(&record[id_column], ID_FIELD)?;
+                let global_id = LDBCVertexParser::to_global_id(id, label_id);
+                let properties = parse_properties(record.iter(), Some(&header))?;
+                Ok((VertexMeta { global_id, label: [label_id, INVALID_LABEL_ID] }, properties))
+            };
+            vertices.push(parse().map_err(|e| {
+                GDBError::ParseError(format!("{:?} line {}: {}", path.as_ref(), line, e))
+            })?);
+        }
+
+        Ok(vertices)
+    }
+
+    /// Parse the edges of `label_id` from a csv file, which link the vertices of `src_label_id`
+    /// to those of `dst_label_id`. The file is parsed as a whole, and any malformed row fails
+    /// the parsing.
+    pub fn read_edges<G: IndexType, P: AsRef<Path>>(
+        &self, path: P, label_id: LabelId, src_label_id: LabelId, dst_label_id: LabelId,
+    ) -> GDBResult<Vec<(EdgeMeta<G>, Row)>> {
+        let (header, records) = self.read_records(path.as_ref())?;
+        let src_column = get_id_column(&header, START_ID_FIELD)?;
+        let dst_column = get_id_column(&header, END_ID_FIELD)?;
+        let mut edges = Vec::with_capacity(records.len());
+        for (line, record) in records {
+            let parse = || -> GDBResult<(EdgeMeta<G>, Row)> {
+                let src_id = parse_id(&record[src_column], START_ID_FIELD)?;
+                let dst_id = parse_id(&record[dst_column], END_ID_FIELD)?;
+                let meta = EdgeMeta {
+                    src_global_id: LDBCVertexParser::to_global_id(src_id, src_label_id),
+                    src_label_id,
+                    dst_global_id: LDBCVertexParser::to_global_id(dst_id, dst_label_id),
+                    dst_label_id,
+                    label_id,
+                };
+                // the ids of both ends are not recorded as properties
+                let properties = parse_properties(record.iter(), Some(&header))?;
+                Ok((meta, properties))
+            };
+            edges.push(parse().map_err(|e| {
+                GDBError::ParseError(format!("{:?} line {}: {}", path.as_ref(), line, e))
+            })?);
+        }
+
+        Ok(edges)
+    }
+
+    /// Load the vertices of `label_id` from a csv file into the graph, return the number of
+    /// the vertices newly added. Nothing is added if the file is malformed.
+    pub fn load_vertices<G, I, N, E, P>(
+        &self, graph: &mut MutableGraphDB<G, I, N, E>, path: P, label_id: LabelId,
+    ) -> GDBResult<usize>
+    where
+        G: Eq + IndexType + Send + Sync,
+        I: IndexType + Send + Sync,
+        N: PropertyTableTrait + Sync,
+        E: PropertyTableTrait + Sync,
+        P: AsRef<Path>,
+    {
+        let vertices = self.read_vertices(path, label_id)?;
+        graph.add_vertex_batches(
+            vertices.into_iter().map(|(meta, properties)| (meta.global_id, meta.label, properties)),
+        )
+    }
+
+    /// Load the edges of `label_id` from a csv file into the graph, return the number of the
+    /// edges added, where an edge is not added if any of its ends does not present. Nothing is
+    /// added if the file is malformed.
+    pub fn load_edges<G, I, N, E, P>(
+        &self, graph: &mut MutableGraphDB<G, I, N, E>, path: P, label_id: LabelId,
+        src_label_id: LabelId, dst_label_id: LabelId,
+    ) -> GDBResult<usize>
+    where
+        G: Eq + IndexType + Send + Sync,
+        I: IndexType + Send + Sync,
+        N: PropertyTableTrait + Sync,
+        E: PropertyTableTrait + Sync,
+        P: AsRef<Path>,
+    {
+        let edges = self.read_edges(path, label_id, src_label_id, dst_label_id)?;
+        graph.add_edge_batches(edges.into_iter().map(|(meta, properties)| {
+            (meta.src_global_id, meta.dst_global_id, meta.label_id, properties)
+        }))
+    }
+}
+
+fn parse_id(val: &str, field: &str) -> GDBResult<usize> {
+    val.parse::<usize>()
+        .map_err(|_| GDBError::ParseError(format!("can't parse {:?} of {:?} as an id", val, field)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{DefaultId, InternalId};
+    use crate::config::GraphDBConfig;
+    use crate::table::PropertyTable;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    fn write_file(dir: &TempDir, name: &str, content: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        File::create(&path).unwrap().write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_csv() {
+        let dir = TempDir::new("test_load_csv").unwrap();
+        let persons = write_file(
+            &dir,
+            "person.csv",
+            "id:id,name,age:int,weight:double\n1,marko,29,60.5\n2,vadas,27,55\n",
+        );
+        let knows = write_file(
+            &dir,
+            "knows.csv",
+            "start_id:id,end_id:id,weight:float\n1,2,0.5\n# a comment\n2,3,1.0\n",
+        );
+
+        let loader = CsvLoader::default();
+        let vertices = loader.read_vertices::<usize, _>(&persons, 1).unwrap();
+        assert_eq!(vertices.len(), 2);
+        assert_eq!(vertices[0].0.global_id, LDBCVertexParser::<usize>::to_global_id(1, 1));
+        assert_eq!(vertices[0].0.label, [1, INVALID_LABEL_ID]);
+        assert_eq!(
+            vertices[0].1,
+            Row::from(vec![json!(1_usize), json!("marko"), json!(29), json!(60.5_f64)])
+        );
+        assert_eq!(vertices[1].1.get(3), Some(&json!(55_f64)));
+
+        let edges = loader.read_edges::<usize, _>(&knows, 12, 1, 1).unwrap();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[1].0.src_global_id, LDBCVertexParser::<usize>::to_global_id(2, 1));
+        assert_eq!(edges[1].0.dst_global_id, LDBCVertexParser::<usize>::to_global_id(3, 1));
+        assert_eq!(edges[1].0.label_id, 12);
+        assert_eq!(edges[0].1, Row::from(vec![json!(0.5_f32)]));
+
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, PropertyTable, PropertyTable> =
+            GraphDBConfig::default().number_vertex_labels(20).new();
+        assert_eq!(loader.load_vertices(&mut graphdb, &persons, 1).unwrap(), 2);
+        // the end vertex 3 does not present
+        assert_eq!(loader.load_edges(&mut graphdb, &knows, 12, 1, 1).unwrap(), 1);
+        assert_eq!(graphdb.edge_count(), 1);
+
+        let persons = write_file(&dir, "person.tsv", "id:id|name\n3|josh\n");
+        let vertices = CsvLoader::default()
+            .with_delimiter(b'|')
+            .read_vertices::<usize, _>(&persons, 1)
+            .unwrap();
+        assert_eq!(vertices[0].1, Row::from(vec![json!(3_usize), json!("josh")]));
+
+        // a double is parsed without losing precision or range
+        let persons = write_file(
+            &dir,
+            "person_double.csv",
+            "id:id,weight:double
+1,0.1
+2,1e300
+",
+        );
+        let vertices = loader.read_vertices::<usize, _>(&persons, 1).unwrap();
+        assert_eq!(vertices[0].1.get(1).and_then(|v| v.as_f64()), Some(0.1_f64));
+        assert_eq!(vertices[1].1.get(1).and_then(|v| v.as_f64()), Some(1e300_f64));
+    }
+
+    #[test]
+    fn test_load_malformed_csv() {
+        let dir = TempDir::new("test_load_malformed_csv").unwrap();
+        let loader = CsvLoader::default();
+        let expect_error = |content: &str, expected: &str| {
+            let path = write_file(&dir, "malformed.csv", content);
+            match loader.read_vertices::<usize, _>(&path, 1) {
+                Err(GDBError::ParseError(msg)) => {
+                    assert!(msg.contains(expected), "{:?} doesn't contain {:?}", msg, expected)
+                }
+                other => panic!("expect a parse error, got {:?}", other.map(|v| v.len())),
+            }
+        };
+
+        // a cell of wrong type
+        expect_error("id:id,name,age:int\n1,marko,29\n2,vadas,old\n", "line 3");
+        expect_error("id:id,name,age:int\n1,marko,29\n2,vadas,old\n", "old");
+        // an id that is not a number
+        expect_error("id:id,name\nx1,marko\n", "line 2");
+        // a missing column
+        expect_error("id:id,name,age:int\n1,marko\n", "expect 3 columns, while got 2");
+        // an extra column
+        expect_error("id:id,name\n1,marko,29\n", "expect 2 columns, while got 3");
+        // no id column
+        expect_error("name,age:int\nmarko,29\n", "\"id:id\" is not found");
+        // unsupported type
+        expect_error("id:id,name:text\n1,marko\n", "unsupported type of column \"name:text\"");
+        // an empty file
+        expect_error("", "the header is not found");
+
+        // a row that the csv reader fails to read, e.g., of invalid utf-8
+        let path = dir.path().join("invalid_utf8.csv");
+        File::create(&path).unwrap().write_all(b"id:id,name\n1,marko\n2,\xff\n").unwrap();
+        match loader.read_vertices::<usize, _>(&path, 1) {
+            Err(GDBError::ParseError(msg)) => {
+                assert!(msg.contains("invalid_utf8.csv"), "{:?} doesn't contain the file", msg);
+                assert!(msg.contains("line 3"), "{:?} doesn't contain the line", msg);
+            }
+            other => panic!("expect a parse error, got {:?}", other.map(|v| v.len())),
+        }
+
+        // edges without the id of the end vertex
+        let path = write_file(&dir, "knows.csv", "start_id:id,weight:float\n1,0.5\n");
+        assert!(loader.read_edges::<usize, _>(&path, 12, 1, 1).is_err());
+
+        // nothing is loaded from a malformed file
+        let path = write_file(&dir, "person.csv", "id:id,age:int\n1,29\n2,old\n");
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, PropertyTable, PropertyTable> =
+            GraphDBConfig::default().number_vertex_labels(20).new();
+        assert!(loader.load_vertices(&mut graphdb, &path, 1).is_err());
+        assert_eq!(graphdb.node_count(), 0);
+    }
+}
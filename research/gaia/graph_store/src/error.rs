@@ -13,11 +13,20 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use std::fmt::Display;
 use std::io::Error;
 use std::num::{ParseFloatError, ParseIntError};
 
 pub type GDBResult<T> = Result<T, GDBError>;
 
+/// The errors of the graph database. Note that a unit error can't be converted into `GDBError`,
+/// which must be mapped into a specific variant explicitly:
+///
+/// ```compile_fail
+/// use graph_store::prelude::GDBError;
+///
+/// let _err: GDBError = ().into();
+/// ```
 #[derive(Debug)]
 pub enum GDBError {
     ModifyReadOnlyError,
@@ -37,10 +46,94 @@ pub enum GDBError {
     UnknownError,
     CrossComparisonError,
     OutOfBoundError,
-    ParseError,
+    /// Failed to parse a value, which carries the message of what failed to parse;
+    ParseError(String),
     InvalidFunctionCallError,
     InvalidTypeError,
     FieldNotExistError,
+    /// A required field is not set, e.g., of a builder, which carries the name of the field;
+    MissingFieldError(String),
+    /// The field exists in the schema, but the value written to it is not of the expected type;
+    SchemaTypeMismatch {
+        field: String,
+        expected: String,
+        got: String,
+    },
+    /// The path is invalid, e.g., it has no file name or is not valid unicode;
+    InvalidPathError(String),
+    /// No index is built for the lookup, e.g. by the value of a property;
+    IndexNotFoundError,
+}
+
+impl Display for GDBError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GDBError::ModifyReadOnlyError => write!(f, "try to modify a read-only graph"),
+            GDBError::RocksError(e) => write!(f, "rocksdb error: {}", e),
+            GDBError::BincodeError(e) => write!(f, "bincode error: {}", e),
+            GDBError::JsonError(e) => write!(f, "json error: {}", e),
+            GDBError::CborError(e) => write!(f, "cbor error: {}", e),
+            GDBError::IOError(e) => write!(f, "io error: {}", e),
+            GDBError::DBNotFoundError => write!(f, "database not found"),
+            GDBError::LruZeroCapacity => write!(f, "the capacity of lru cache is zero"),
+            GDBError::JsonObjectFieldError => write!(f, "invalid field of json object"),
+            GDBError::BooleanExpressionError => write!(f, "invalid boolean expression"),
+            GDBError::StringExpressionError => write!(f, "invalid string expression"),
+            GDBError::NumberExpressionError => write!(f, "invalid number expression"),
+            GDBError::EdgeNotFoundError => write!(f, "edge not found"),
+            GDBError::VertexNotFoundError => write!(f, "vertex not found"),
+            GDBError::UnknownError => write!(f, "unknown error"),
+            GDBError::CrossComparisonError => write!(f, "compare values of different types"),
+            GDBError::OutOfBoundError => write!(f, "out of bound"),
+            GDBError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            GDBError::InvalidFunctionCallError => write!(f, "invalid function call"),
+            GDBError::InvalidTypeError => write!(f, "invalid type"),
+            GDBError::FieldNotExistError => write!(f, "field not exist"),
+            GDBError::MissingFieldError(field) => write!(f, "missing field {:?}", field),
+            GDBError::SchemaTypeMismatch { field, expected, got } => {
+                write!(f, "field {:?} expects a value of {}, but got {:?}", field, expected, got)
+            }
+            GDBError::InvalidPathError(path) => write!(f, "invalid path: {}", path),
+            GDBError::IndexNotFoundError => write!(f, "index not found"),
+        }
+    }
+}
+
+/// Errors are equal if they are of the same variant with equal payloads, where the wrapped
+/// external errors are compared by their messages;
+impl PartialEq for GDBError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GDBError::RocksError(a), GDBError::RocksError(b)) => a.to_string() == b.to_string(),
+            (GDBError::BincodeError(a), GDBError::BincodeError(b)) => {
+                a.to_string() == b.to_string()
+            }
+            (GDBError::JsonError(a), GDBError::JsonError(b)) => a.to_string() == b.to_string(),
+            (GDBError::CborError(a), GDBError::CborError(b)) => a.to_string() == b.to_string(),
+            (GDBError::IOError(a), GDBError::IOError(b)) => a.to_string() == b.to_string(),
+            (GDBError::ParseError(a), GDBError::ParseError(b)) => a == b,
+            (GDBError::InvalidPathError(a), GDBError::InvalidPathError(b)) => a == b,
+            (GDBError::MissingFieldError(a), GDBError::MissingFieldError(b)) => a == b,
+            (
+                GDBError::SchemaTypeMismatch { field: f1, expected: e1, got: g1 },
+                GDBError::SchemaTypeMismatch { field: f2, expected: e2, got: g2 },
+            ) => f1 == f2 && e1 == e2 && g1 == g2,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl std::error::Error for GDBError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GDBError::RocksError(e) => Some(e),
+            GDBError::BincodeError(e) => Some(e.as_ref()),
+            GDBError::JsonError(e) => Some(e),
+            GDBError::CborError(e) => Some(e),
+            GDBError::IOError(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::io::Error> for GDBError {
@@ -50,14 +143,14 @@ impl From<std::io::Error> for GDBError {
 }
 
 impl From<std::num::ParseIntError> for GDBError {
-    fn from(_error: ParseIntError) -> Self {
-        GDBError::ParseError
+    fn from(error: ParseIntError) -> Self {
+        GDBError::ParseError(format!("parse int error: {}", error))
     }
 }
 
 impl From<std::num::ParseFloatError> for GDBError {
-    fn from(_error: ParseFloatError) -> Self {
-        GDBError::ParseError
+    fn from(error: ParseFloatError) -> Self {
+        GDBError::ParseError(format!("parse float error: {}", error))
     }
 }
 
@@ -85,8 +178,83 @@ impl From<Box<bincode::ErrorKind>> for GDBError {
     }
 }
 
-impl From<()> for GDBError {
-    fn from(_error: ()) -> Self {
-        GDBError::UnknownError
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as StdError;
+
+    #[test]
+    fn test_error_source() {
+        let err: GDBError = Error::new(std::io::ErrorKind::NotFound, "no such file").into();
+        assert!(err.source().is_some());
+        assert!(err.to_string().contains("no such file"));
+
+        let err: GDBError = serde_json::from_str::<i32>("x").unwrap_err().into();
+        assert!(err.source().is_some());
+
+        let err: GDBError = bincode::deserialize::<u64>(&[0u8; 2]).unwrap_err().into();
+        assert!(err.source().is_some());
+
+        assert!(GDBError::DBNotFoundError.source().is_none());
+        assert!(GDBError::VertexNotFoundError.source().is_none());
+        assert_eq!(GDBError::DBNotFoundError.to_string(), "database not found");
+    }
+
+    #[test]
+    fn test_into_boxed_error() {
+        fn open() -> Result<(), Box<dyn StdError>> {
+            let res: GDBResult<()> = Err(GDBError::DBNotFoundError);
+            res?;
+            Ok(())
+        }
+        assert_eq!(open().unwrap_err().to_string(), "database not found");
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        let err: GDBError = "abc".parse::<i32>().unwrap_err().into();
+        match &err {
+            GDBError::ParseError(msg) => assert!(msg.contains("invalid digit")),
+            _ => panic!("expect parse error"),
+        }
+        assert!(err.to_string().contains("parse int error"));
+
+        let err: GDBError = "1.2.3".parse::<f64>().unwrap_err().into();
+        match &err {
+            GDBError::ParseError(msg) => assert!(msg.contains("invalid float literal")),
+            _ => panic!("expect parse error"),
+        }
+        assert!(err.to_string().contains("parse float error"));
+    }
+
+    #[test]
+    fn test_error_eq() {
+        assert_eq!(GDBError::DBNotFoundError, GDBError::DBNotFoundError);
+        assert_ne!(GDBError::DBNotFoundError, GDBError::VertexNotFoundError);
+
+        assert_eq!(GDBError::ParseError("a".to_string()), GDBError::ParseError("a".to_string()));
+        assert_ne!(GDBError::ParseError("a".to_string()), GDBError::ParseError("b".to_string()));
+        assert_ne!(
+            GDBError::ParseError("a".to_string()),
+            GDBError::InvalidPathError("a".to_string())
+        );
+        assert_ne!(
+            GDBError::MissingFieldError("id".to_string()),
+            GDBError::MissingFieldError("label".to_string())
+        );
+
+        let mismatch = |got: &str| GDBError::SchemaTypeMismatch {
+            field: "age".to_string(),
+            expected: "Integer".to_string(),
+            got: got.to_string(),
+        };
+        assert_eq!(mismatch("abc"), mismatch("abc"));
+        assert_ne!(mismatch("abc"), mismatch("def"));
+
+        let io_err = |msg: &str| -> GDBError { Error::new(std::io::ErrorKind::Other, msg).into() };
+        assert_eq!(io_err("broken"), io_err("broken"));
+        assert_ne!(io_err("broken"), io_err("lost"));
+        let json_err: GDBError = serde_json::from_str::<i32>("x").unwrap_err().into();
+        assert_ne!(io_err("broken"), json_err);
     }
 }
@@ -28,6 +28,24 @@ use std::sync::Arc;
 use itertools::Itertools;
 pub use petgraph::Direction;
 
+/// The direction to expand from a vertex, as that of the `out()`, `in()` and `both()` steps of
+/// the traversal layer, where `Both` follows both the outgoing and the incoming edges.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ExpandDirection {
+    Out,
+    In,
+    Both,
+}
+
+/// The identity of a vertex or an edge to write, where an edge is identified by the global ids of
+/// its source and destination vertices and its label, which covers all the parallel edges of the
+/// label, if any.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ElementId<G> {
+    Vertex(G),
+    Edge(G, G, LabelId),
+}
+
 /// Construct a row with its schema
 #[derive(Clone)]
 pub struct RowWithSchema<'a> {
@@ -58,10 +76,10 @@ impl<'a> RowWithSchema<'a> {
     ///
     /// Return a `JsonValue` indicating the data type and value, and `None` if:
     /// * `Self::header` is `None` and `key` is not a number value
-    /// * The property given by `key` does not exist
+    /// * The property given by `key` does not exist, or is null, e.g. in place of a deleted one
     ///
     pub fn get(&self, key: &str) -> Option<&JsonValue> {
-        if let Some(header) = &self.header {
+        let value = if let Some(header) = &self.header {
             if let Some((_, index)) = header.get(key) {
                 self.row.get(*index)
             } else {
@@ -73,15 +91,16 @@ impl<'a> RowWithSchema<'a> {
             } else {
                 None
             }
-        }
+        };
+        value.filter(|v| !v.is_null())
     }
 
-    /// Turn into a map of all properties
+    /// Turn into a map of all properties, where the null ones are absent
     pub fn into_properties(self) -> Option<JsonValue> {
         self.header.and_then(|header| {
             let mut map = HashMap::new();
             for (key, (_, index)) in header.iter().sorted_by(|x, y| x.1 .1.cmp(&y.1 .1)) {
-                if let Some(val) = self.row.get(*index) {
+                if let Some(val) = self.row.get(*index).filter(|v| !v.is_null()) {
                     map.insert(key.clone(), val.clone());
                 }
             }
@@ -227,13 +246,19 @@ pub trait GlobalStoreTrait<G: IndexType, I: IndexType> {
         self.get_adj_vertices(src_id, edge_labels, Direction::Incoming)
     }
 
-    /// Concatenate `get_out_vertices()` and `get_in_vertices()`
+    /// Concatenate `get_out_vertices()` and `get_in_vertices()`. A self-loop is both an outgoing
+    /// and an incoming edge of `src_id`, but `src_id` is only returned once for it, as the
+    /// incoming part skips the self-loops.
     fn get_both_vertices(
         &self, src_id: G, edge_labels: Option<&Vec<LabelId>>,
     ) -> Iter<LocalVertex<G>> {
+        // compare by the index, as the iterator is `Send` while `G` may not be
+        let src_index = src_id.index();
         Iter::from_iter(
-            self.get_out_vertices(src_id, edge_labels)
-                .chain(self.get_in_vertices(src_id, edge_labels)),
+            self.get_out_vertices(src_id, edge_labels).chain(
+                self.get_in_vertices(src_id, edge_labels)
+                    .filter(move |v| v.get_id().index() != src_index),
+            ),
         )
     }
 
@@ -249,21 +274,65 @@ pub trait GlobalStoreTrait<G: IndexType, I: IndexType> {
         self.get_adj_edges(src_id, edge_labels, Direction::Incoming)
     }
 
-    /// A wrapper of `Self::get_adj_edges()` for both directions.
+    /// A wrapper of `Self::get_adj_edges()` for both directions. Like `get_both_vertices()`, a
+    /// self-loop is only returned once, as an outgoing edge.
     fn get_both_edges(
         &self, src_id: G, edge_labels: Option<&Vec<LabelId>>,
     ) -> Iter<LocalEdge<G, I>> {
+        let src_index = src_id.index();
         Iter::from_iter(
-            self.get_out_edges(src_id, edge_labels).chain(self.get_in_edges(src_id, edge_labels)),
+            self.get_out_edges(src_id, edge_labels).chain(
+                self.get_in_edges(src_id, edge_labels)
+                    .filter(move |e| e.get_src_id().index() != src_index),
+            ),
         )
     }
 
+    /// Get all the edges from the vertex `src_id` to the vertex `dst_id`, including the parallel
+    /// ones, that satisfy the edge labels `edge_labels`. If `None` labels is given, the edges of
+    /// all labels are returned.
+    ///
+    /// # Return
+    /// * A vector of edges, if query successfully
+    /// * An empty vector, if either vertex does not present or there is no such edge.
+    fn get_edges_between(
+        &self, src_id: G, dst_id: G, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<Vec<LocalEdge<G, I>>> {
+        Ok(self.get_out_edges(src_id, edge_labels).filter(|e| e.get_dst_id() == dst_id).collect())
+    }
+
     /// Get the vertex of given global identity
     fn get_vertex(&self, id: G) -> Option<LocalVertex<G>>;
 
+    /// Get the vertices of given global identities in batch, in the order of the ids, where the
+    /// vertex of an absent id is `None`. The properties of the vertices are fetched at once,
+    /// which saves the round-trips to a disk-based storage comparing to `Self::get_vertex()`.
+    fn get_vertices(&self, ids: &[G]) -> GDBResult<Vec<Option<LocalVertex<G>>>> {
+        Ok(ids.iter().map(|id| self.get_vertex(*id)).collect())
+    }
+
     /// Get all vertices of a given labels. If `None` label is given, return all vertices.
     fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>>;
 
+    /// Scan the vertices of the label `label_id`, either primary or secondary, in ascending
+    /// order of their global ids. Only the vertices of the label are visited through the index of
+    /// labels, rather than scanning all the vertices. The properties are read while iterating, and
+    /// a vertex whose properties fail to be read is yielded as an `Err` in its place, after which
+    /// the scan continues, so the caller decides whether to stop or to skip it.
+    ///
+    /// # Return
+    /// * An iterator of vertices of the label, or errors of reading their properties
+    /// * An empty iterator, if the label does not present or it contains no vertex.
+    fn scan_vertices_by_label(&self, label_id: LabelId) -> Iter<GDBResult<LocalVertex<G>>>;
+
+    /// Scan all the edges whose start vertices are local to current partition, i.e., those counted
+    /// by `Self::count_all_edges()`, in ascending order of their internal ids. The edges are
+    /// yielded one by one, with their properties read while iterating, so that the memory is
+    /// bounded regardless of the number of edges, e.g. for algorithms over the full graph. An edge
+    /// whose properties fail to be read, e.g. a corrupt record, is yielded as an `Err` in its
+    /// place, after which the scan continues, like `Self::scan_vertices_by_label()`.
+    fn scan_all_edges(&self) -> Iter<GDBResult<LocalEdge<G, I>>>;
+
     /// Get all edges of given labels. If `None` label is given, return all vertices.
     fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>>;
 
@@ -294,6 +363,20 @@ pub trait GlobalStoreUpdate<G: Copy, I> {
     /// vertex type, therefore, we add here `label_id` instead of a full-version label.
     fn add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> bool;
 
+    /// Add a vertex as `Self::add_vertex()` does, but return the error if the write is rejected,
+    /// e.g. `ModifyReadOnlyError` if the storage is read-only, which `Self::add_vertex()` can't
+    /// tell apart from a vertex that already presents. Return `Ok(true)` if added, `Ok(false)`
+    /// if the vertex already presents.
+    fn try_add_vertex(&mut self, global_id: G, label: Label) -> GDBResult<bool> {
+        Ok(self.add_vertex(global_id, label))
+    }
+
+    /// Add a corner vertex as `Self::add_corner_vertex()` does, but return the error if the write
+    /// is rejected, see `Self::try_add_vertex()`.
+    fn try_add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> GDBResult<bool> {
+        Ok(self.add_corner_vertex(global_id, label_id))
+    }
+
     /// Add or update a vertex (cannot be corner vertex)'s properties. Return
     /// * `Err` if the vertex does not exist or unexpected errors occur.
     /// * `Ok(None)` if the vertex's properties do not present, and the data is inserted
@@ -309,7 +392,7 @@ pub trait GlobalStoreUpdate<G: Copy, I> {
     fn add_vertex_with_properties(
         &mut self, global_id: G, label: Label, properties: Row,
     ) -> GDBResult<Option<Row>> {
-        self.add_vertex(global_id, label);
+        self.try_add_vertex(global_id, label)?;
         self.add_or_update_vertex_properties(global_id, properties)
     }
 
@@ -318,6 +401,15 @@ pub trait GlobalStoreUpdate<G: Copy, I> {
     /// same pair of (src, dst).
     fn add_edge(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> bool;
 
+    /// Add an edge as `Self::add_edge()` does, but return the error if the write is rejected, see
+    /// `Self::try_add_vertex()`. Return `Ok(true)` if added, `Ok(false)` if either the src or the
+    /// dst vertex does not present.
+    fn try_add_edge(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId,
+    ) -> GDBResult<bool> {
+        Ok(self.add_edge(global_src_id, global_dst_id, label_id))
+    }
+
     /// And an edge with its properties, Returns
     /// * `Err` if either the src vertex or dst vertex do not present or unexpected errors occur.
     /// * `Ok(None)` if the edge's properties do not present, and the data is inserted
@@ -348,4 +440,349 @@ pub trait GlobalStoreUpdate<G: Copy, I> {
     fn add_edge_batches<Iter: Iterator<Item = (G, G, LabelId, Row)>>(
         &mut self, iter: Iter,
     ) -> GDBResult<usize>;
+
+    /// Reject all the writes to the storage from now on if `read_only` is `true`, e.g. for a
+    /// serving replica, or accept them again otherwise. In read-only mode, the writes fail with
+    /// `ModifyReadOnlyError` without changing anything. Note that `Self::add_vertex()`,
+    /// `Self::add_corner_vertex()` and `Self::add_edge()` can only return `false`, where their
+    /// `try_` versions, e.g. `Self::try_add_vertex()`, return the error.
+    fn set_read_only(&mut self, read_only: bool);
+
+    fn is_read_only(&self) -> bool;
+
+    /// Build a secondary index on the property `key` of the vertices of `label`, for looking up
+    /// the vertices by `Self::lookup_by_property()`. The existing vertices of the label are
+    /// indexed at once, and the index is kept consistent with the properties written or removed
+    /// afterwards. As the properties are stored in rows, the schema is required to locate the
+    /// property. Return
+    /// * `FieldNotExistError` if no schema is given, or the label has no such property
+    /// * `Ok(())` if the index is built, or it already presents
+    fn create_vertex_index(&mut self, label: LabelId, key: &str) -> GDBResult<()>;
+
+    /// Look up the global ids of the vertices of `label`, whose property `key` equals to `value`,
+    /// in ascending order. The index of the property must be built by
+    /// `Self::create_vertex_index()`, otherwise `IndexNotFoundError` is returned, rather than
+    /// falling back to scanning all the vertices of the label.
+    fn lookup_by_property(&self, label: LabelId, key: &str, value: &ItemType) -> GDBResult<Vec<G>>;
+
+    /// Remove all the properties of a vertex, and remove the vertex from the indices of its
+    /// properties. Return
+    /// * `Err` if the vertex does not exist or unexpected errors occur.
+    /// * `Ok(None)` if the vertex's properties do not present, e.g. of a corner vertex.
+    /// * `Ok(Some(old_data))` if the vertex's properties do present, and are removed.
+    fn remove_vertex_properties(&mut self, global_id: G) -> GDBResult<Option<Row>>;
+
+    /// Delete the property `key` of a vertex or an edge, see `ElementId`. As the properties are
+    /// stored in rows in the order of the header of the label, the schema is required to locate
+    /// the property. The property is removed from the row, which is shortened if no property
+    /// after it presents, or otherwise leaves a null in its place that is read as absent; A row
+    /// left without any property is removed. The vertex is also removed from the index of the
+    /// property, if any.
+    ///
+    /// An `ElementId::Edge` covers all the parallel edges of its label between the two vertices,
+    /// where the property is deleted from every one of them having it, and the others are left
+    /// unchanged; The deletion succeeds if any of them has the property. Return
+    /// * `VertexNotFoundError` or `EdgeNotFoundError` if the vertex or the edge does not exist
+    /// * `FieldNotExistError` if no schema is given, the label has no such property, or the
+    ///   property is absent, i.e. in none of the parallel edges
+    /// * `ModifyReadOnlyError` if the storage is read-only, where nothing is changed
+    /// * `Ok(())` if the property is deleted
+    fn delete_property(&mut self, element_id: ElementId<G>, key: &str) -> GDBResult<()>;
+}
+
+/// A graph store that is both readable and writable, for a caller to build and query a graph in
+/// place without knowing the storage, e.g. `MemGraphStore`. Note that the `RocksDB`-based storage
+/// separates the two, where `MutableGraphDB` writes the graph and `LargeGraphDB` reads it.
+pub trait GraphStore<G: IndexType, I: IndexType>:
+    GlobalStoreTrait<G, I> + GlobalStoreUpdate<G, I>
+{
+}
+
+impl<G: IndexType, I: IndexType, S> GraphStore<G, I> for S where
+    S: GlobalStoreTrait<G, I> + GlobalStoreUpdate<G, I>
+{
+}
+
+/// The behaviours shared by the stores, which are tested through `GlobalStoreUpdate` and
+/// `GlobalStoreTrait` against both `MemGraphStore` and the RocksDB-based `MutableGraphDB`
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{GraphDBConfig, JsonConf};
+    use crate::error::GDBError;
+    use crate::graph_db_impl::MutableGraphDB;
+    use crate::ldbc::LABEL_SHIFT_BITS;
+    use crate::mem_store::MemGraphStore;
+    use crate::schema::LDBCGraphSchema;
+    use tempdir::TempDir;
+
+    type RocksGraphDB = MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable>;
+
+    static PIDS: [DefaultId; 5] = [
+        1 << LABEL_SHIFT_BITS | 111,
+        1 << LABEL_SHIFT_BITS | 222,
+        1 << LABEL_SHIFT_BITS | 333,
+        1 << LABEL_SHIFT_BITS | 444,
+        1 << LABEL_SHIFT_BITS | 555,
+    ];
+
+    fn schema() -> LDBCGraphSchema {
+        LDBCGraphSchema::from_json_file("data/schema.json").expect("Get Schema error!")
+    }
+
+    fn mem_store() -> MemGraphStore {
+        MemGraphStore::new(0, schema())
+    }
+
+    fn rocks_store(dir: &TempDir) -> RocksGraphDB {
+        let mut graphdb: RocksGraphDB =
+            GraphDBConfig::default().root_dir(dir.path()).number_vertex_labels(20).new();
+        graphdb.set_schema(schema());
+        graphdb
+    }
+
+    fn add_persons<S: GlobalStoreUpdate<DefaultId, InternalId>>(store: &mut S) {
+        for (i, pid) in PIDS.iter().enumerate().take(3) {
+            let prop = Row::from(vec![json!(i), json!(format!("person_{}", i))]);
+            assert!(store
+                .add_vertex_with_properties(*pid, [1, INVALID_LABEL_ID], prop)
+                .unwrap()
+                .is_none());
+        }
+        // the corner vertex is on another partition, which has no properties here
+        assert!(store.add_corner_vertex(PIDS[3], 1));
+        assert!(!store.add_corner_vertex(PIDS[3], 1));
+        let edge_prop = Row::from(vec![json!(20200202_u64)]);
+        assert!(store.add_edge_with_properties(PIDS[0], PIDS[1], 12, edge_prop).unwrap().is_none());
+        assert!(store.add_edge(PIDS[0], PIDS[2], 12));
+        assert!(store.add_edge(PIDS[3], PIDS[0], 12));
+        assert!(!store.add_edge(PIDS[0], PIDS[4], 12));
+    }
+
+    fn check_persons<S: GlobalStoreTrait<DefaultId, InternalId>>(store: &S) {
+        assert_eq!(3, store.count_all_vertices(None));
+        assert_eq!(3, store.count_all_vertices(Some(&vec![1])));
+        // the edge from the corner vertex is not counted
+        assert_eq!(2, store.count_all_edges(None));
+        assert_eq!(0, store.count_all_edges(Some(&vec![13])));
+
+        let vertex = store.get_vertex(PIDS[1]).unwrap();
+        assert_eq!(Some(&json!("person_1")), vertex.get_property("firstName"));
+        let corner = store.get_vertex(PIDS[3]).unwrap();
+        assert_eq!([1, INVALID_LABEL_ID], corner.get_label());
+        assert!(corner.get_property("firstName").is_none());
+        assert!(store.get_vertex(PIDS[4]).is_none());
+
+        let mut out_vertices: Vec<DefaultId> =
+            store.get_out_vertices(PIDS[0], Some(&vec![12])).map(|v| v.get_id()).collect();
+        out_vertices.sort();
+        assert_eq!(vec![PIDS[1], PIDS[2]], out_vertices);
+        let in_vertices: Vec<DefaultId> =
+            store.get_in_vertices(PIDS[0], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[3]], in_vertices);
+        let edge_props: Vec<Option<JsonValue>> = store
+            .get_edges_between(PIDS[0], PIDS[1], None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.get_property("creationDate").cloned())
+            .collect();
+        assert_eq!(vec![Some(json!(20200202_u64))], edge_props);
+
+        let scanned: Vec<DefaultId> =
+            store.scan_vertices_by_label(1).map(|v| v.unwrap().get_id()).collect();
+        assert_eq!(PIDS[0..3].to_vec(), scanned);
+        assert_eq!(2, store.scan_all_edges().count());
+        assert_eq!(0, store.get_current_partition());
+    }
+
+    fn check_read_only<S: GlobalStoreUpdate<DefaultId, InternalId>>(store: &mut S) {
+        let row = Row::from(vec![json!(0), json!("John")]);
+        store.add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], row.clone()).unwrap();
+        store.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+        store.create_vertex_index(1, "firstName").unwrap();
+
+        store.set_read_only(true);
+        assert!(store.is_read_only());
+        assert!(!store.add_vertex(PIDS[2], [1, INVALID_LABEL_ID]));
+        assert!(!store.add_corner_vertex(PIDS[3], 1));
+        assert!(!store.add_edge(PIDS[0], PIDS[1], 12));
+        // the rejection is told apart from a present vertex or a missing end
+        assert_eq!(
+            store.try_add_vertex(PIDS[2], [1, INVALID_LABEL_ID]),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+        assert_eq!(store.try_add_corner_vertex(PIDS[3], 1), Err(GDBError::ModifyReadOnlyError));
+        assert_eq!(store.try_add_edge(PIDS[0], PIDS[1], 12), Err(GDBError::ModifyReadOnlyError));
+        let rejected = [
+            store.add_vertex_with_properties(PIDS[2], [1, INVALID_LABEL_ID], row.clone()),
+            store.add_or_update_vertex_properties(PIDS[1], row.clone()),
+            store.add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::default()),
+            store.remove_vertex_properties(PIDS[0]),
+        ];
+        for result in rejected {
+            assert_eq!(result, Err(GDBError::ModifyReadOnlyError));
+        }
+        assert_eq!(
+            store.add_vertex_batches(
+                vec![(PIDS[2], [1, INVALID_LABEL_ID], row.clone())].into_iter()
+            ),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+        assert_eq!(
+            store.add_edge_batches(vec![(PIDS[0], PIDS[1], 12, Row::default())].into_iter()),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+        assert_eq!(
+            store.delete_property(ElementId::Vertex(PIDS[0]), "firstName"),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+
+        // nothing is changed
+        store.set_read_only(false);
+        assert!(!store.is_read_only());
+        assert_eq!(
+            store.delete_property(ElementId::Edge(PIDS[0], PIDS[1], 12), "creationDate"),
+            Err(GDBError::EdgeNotFoundError)
+        );
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("John")), Ok(vec![PIDS[0]]));
+        assert_eq!(store.add_or_update_vertex_properties(PIDS[0], row.clone()), Ok(Some(row)));
+        assert_eq!(store.try_add_vertex(PIDS[0], [1, INVALID_LABEL_ID]), Ok(false));
+        assert_eq!(store.try_add_vertex(PIDS[2], [1, INVALID_LABEL_ID]), Ok(true));
+        assert_eq!(store.try_add_corner_vertex(PIDS[3], 1), Ok(true));
+        assert_eq!(store.try_add_edge(PIDS[0], PIDS[4], 12), Ok(false));
+        assert_eq!(store.try_add_edge(PIDS[0], PIDS[1], 12), Ok(true));
+        assert!(store.delete_property(ElementId::Vertex(PIDS[0]), "firstName").is_ok());
+        assert!(store.remove_vertex_properties(PIDS[0]).unwrap().is_some());
+    }
+
+    fn check_property_index<S: GlobalStoreUpdate<DefaultId, InternalId>>(store: &mut S) {
+        assert_eq!(store.create_vertex_index(1, "noSuchKey"), Err(GDBError::FieldNotExistError));
+        assert_eq!(
+            store.lookup_by_property(1, "firstName", &json!("John")),
+            Err(GDBError::IndexNotFoundError)
+        );
+
+        // the existing vertices are indexed on creation
+        let row = Row::from(vec![json!(0), json!("John")]);
+        store.add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], row).unwrap();
+        store.create_vertex_index(1, "firstName").unwrap();
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("John")), Ok(vec![PIDS[0]]));
+
+        let batches = vec![
+            (PIDS[2], [1, INVALID_LABEL_ID], Row::from(vec![json!(2), json!("John")])),
+            (PIDS[1], [1, INVALID_LABEL_ID], Row::from(vec![json!(1), json!("Tom")])),
+        ];
+        store.add_vertex_batches(batches.into_iter()).unwrap();
+        assert_eq!(
+            store.lookup_by_property(1, "firstName", &json!("John")),
+            Ok(vec![PIDS[0], PIDS[2]])
+        );
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("Tom")), Ok(vec![PIDS[1]]));
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("Jack")), Ok(vec![]));
+
+        // update moves the vertex to the new value
+        store
+            .add_or_update_vertex_properties(PIDS[0], Row::from(vec![json!(0), json!("Jack")]))
+            .unwrap();
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("John")), Ok(vec![PIDS[2]]));
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("Jack")), Ok(vec![PIDS[0]]));
+
+        // removal, or deletion of the property drops the vertex from the index
+        assert_eq!(
+            store.remove_vertex_properties(PIDS[2]),
+            Ok(Some(Row::from(vec![json!(2), json!("John")])))
+        );
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("John")), Ok(vec![]));
+        assert_eq!(store.remove_vertex_properties(PIDS[2]), Ok(None));
+        assert_eq!(store.remove_vertex_properties(PIDS[4]), Err(GDBError::VertexNotFoundError));
+        store.delete_property(ElementId::Vertex(PIDS[1]), "firstName").unwrap();
+        assert_eq!(store.lookup_by_property(1, "firstName", &json!("Tom")), Ok(vec![]));
+    }
+
+    fn check_delete_property<S: GlobalStoreUpdate<DefaultId, InternalId>>(store: &mut S) {
+        let row = Row::from(vec![json!(0), json!("John"), json!("Doe")]);
+        store.add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], row).unwrap();
+        store.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+        store.add_corner_vertex(PIDS[3], 1);
+        store.add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::from(20200202_u64)).unwrap();
+        // a parallel edge without the property
+        store.add_edge(PIDS[0], PIDS[1], 12);
+
+        // a property before a present one leaves a null in its place
+        store.delete_property(ElementId::Vertex(PIDS[0]), "firstName").unwrap();
+        let row = Row::from(vec![json!(0), JsonValue::Null, json!("Doe")]);
+        assert_eq!(store.add_or_update_vertex_properties(PIDS[0], row.clone()), Ok(Some(row)));
+        // the last property is removed with the nulls before it
+        store.delete_property(ElementId::Vertex(PIDS[0]), "lastName").unwrap();
+        let row = Row::from(vec![json!(0)]);
+        assert_eq!(store.add_or_update_vertex_properties(PIDS[0], row.clone()), Ok(Some(row)));
+        // deleted already, absent in the row, or not in the schema
+        for key in ["firstName", "lastName", "gender", "noSuchKey"] {
+            assert_eq!(
+                store.delete_property(ElementId::Vertex(PIDS[0]), key),
+                Err(GDBError::FieldNotExistError)
+            );
+        }
+        // the row is removed without any property
+        store.delete_property(ElementId::Vertex(PIDS[0]), "id").unwrap();
+        let old = store.remove_vertex_properties(PIDS[0]).unwrap();
+        assert!(old.map(|row| row.is_empty()).unwrap_or(true));
+        assert_eq!(
+            store.delete_property(ElementId::Vertex(PIDS[3]), "firstName"),
+            Err(GDBError::FieldNotExistError)
+        );
+        assert_eq!(
+            store.delete_property(ElementId::Vertex(PIDS[4]), "firstName"),
+            Err(GDBError::VertexNotFoundError)
+        );
+
+        // the property is deleted from the parallel edge having it
+        let edge = ElementId::Edge(PIDS[0], PIDS[1], 12);
+        store.delete_property(edge, "creationDate").unwrap();
+        assert_eq!(store.delete_property(edge, "creationDate"), Err(GDBError::FieldNotExistError));
+        assert_eq!(store.delete_property(edge, "firstName"), Err(GDBError::FieldNotExistError));
+        for edge in [
+            ElementId::Edge(PIDS[1], PIDS[0], 12),
+            ElementId::Edge(PIDS[0], PIDS[1], 13),
+            ElementId::Edge(PIDS[0], PIDS[4], 12),
+        ] {
+            assert_eq!(
+                store.delete_property(edge, "creationDate"),
+                Err(GDBError::EdgeNotFoundError)
+            );
+        }
+    }
+
+    #[test]
+    fn test_store_persons() {
+        let mut store = mem_store();
+        add_persons(&mut store);
+        check_persons(&store);
+
+        let dir = TempDir::new("test_store_persons").unwrap();
+        let mut graphdb = rocks_store(&dir);
+        add_persons(&mut graphdb);
+        check_persons(&graphdb.into_graph(schema()));
+    }
+
+    #[test]
+    fn test_store_read_only() {
+        check_read_only(&mut mem_store());
+        let dir = TempDir::new("test_store_read_only").unwrap();
+        check_read_only(&mut rocks_store(&dir));
+    }
+
+    #[test]
+    fn test_store_property_index() {
+        check_property_index(&mut mem_store());
+        let dir = TempDir::new("test_store_property_index").unwrap();
+        check_property_index(&mut rocks_store(&dir));
+    }
+
+    #[test]
+    fn test_store_delete_property() {
+        check_delete_property(&mut mem_store());
+        let dir = TempDir::new("test_store_delete_property").unwrap();
+        check_delete_property(&mut rocks_store(&dir));
+    }
 }
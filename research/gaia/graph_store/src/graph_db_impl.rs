@@ -27,7 +27,7 @@ use petgraph::graph::{EdgeReference, IndexType};
 use petgraph::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::create_dir_all;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -37,7 +37,7 @@ use std::sync::Arc;
 /// graph database. This structure maintains the mapping of:
 ///     global id <-> internal index
 ///     label id -> all vertices' global ids that have the given label
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct IndexData<G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
     /// A mapping from global vertex id to internal vertex index.
     global_id_to_index: HashMap<G, NodeIndex<I>>,
@@ -104,6 +104,43 @@ where
         !existed
     }
 
+    /// Remove the vertex of given internal_id, while the vertex of `last_id`, which is the last
+    /// one of the internal ids, takes the internal id of the removed vertex, as is in `petgraph`.
+    fn remove_vertex(
+        &mut self, internal_id: NodeIndex<I>, label: Label, last_id: NodeIndex<I>,
+        last_label: Label,
+    ) {
+        let global_id = self.index_to_global_id[internal_id.index()];
+        if self.global_id_to_index.get(&global_id) == Some(&internal_id) {
+            self.global_id_to_index.remove(&global_id);
+            for label_id in label.iter().filter(|l| **l != INVALID_LABEL_ID) {
+                if let Some(indices) = self.label_indices.get_mut(*label_id as usize) {
+                    indices.retain(|index| *index != internal_id);
+                }
+            }
+        } else {
+            self.corner_global_id_to_index.remove(&global_id);
+        }
+
+        if internal_id != last_id {
+            let moved_id = self.index_to_global_id[last_id.index()];
+            if let Some(index) = self.global_id_to_index.get_mut(&moved_id) {
+                *index = internal_id;
+                for label_id in last_label.iter().filter(|l| **l != INVALID_LABEL_ID) {
+                    if let Some(indices) = self.label_indices.get_mut(*label_id as usize) {
+                        for index in indices.iter_mut().filter(|index| **index == last_id) {
+                            *index = internal_id;
+                        }
+                    }
+                }
+            } else if let Some(index) = self.corner_global_id_to_index.get_mut(&moved_id) {
+                *index = internal_id;
+            }
+            self.index_to_global_id[internal_id.index()] = moved_id;
+        }
+        self.index_to_global_id.truncate(last_id.index());
+    }
+
     /// Get internal id from a given global id for both a local vertex and a corner vertex.
     /// Return `None` if the vertex does not present.
     fn get_internal_id(&self, global_id: G) -> Option<NodeIndex<I>> {
@@ -139,6 +176,101 @@ where
     }
 }
 
+/// The secondary indices on the vertex properties, each of which maps the values of a property
+/// of a label to the vertices of the values, for looking up vertices by property equality, e.g.
+/// `has("email", "x@y.com")`. The values are keyed by their json text, so a value is matched as
+/// json values are equal, e.g. `15` doesn't match `15.0`.
+pub(crate) struct PropertyIndices<G: Ord> {
+    indices: HashMap<(LabelId, String), PropertyIndex<G>>,
+}
+
+impl<G: Ord> Default for PropertyIndices<G> {
+    fn default() -> Self {
+        PropertyIndices { indices: HashMap::new() }
+    }
+}
+
+struct PropertyIndex<G: Ord> {
+    /// The position of the property in the rows of the label
+    field: usize,
+    entries: HashMap<String, BTreeSet<G>>,
+}
+
+/// Remove the property at `field` from a row of properties, where the nulls at the end of the row,
+/// including the removed one, are trimmed. Return `None` if the property is absent or null.
+pub(crate) fn remove_field(row: &Row, field: usize) -> Option<Row> {
+    let mut new = row.clone();
+    new.replace(field, ItemType::Null).filter(|value| !value.is_null())?;
+    let len = (0..new.len()).rev().find(|index| !new.get(*index).unwrap().is_null());
+    new.truncate(len.map(|index| index + 1).unwrap_or(0));
+    Some(new)
+}
+
+impl<G: IndexType> PropertyIndices<G> {
+    /// Whether the index on the property `key` of `label` presents
+    pub(crate) fn contains(&self, label: LabelId, key: &str) -> bool {
+        self.indices.contains_key(&(label, key.to_string()))
+    }
+
+    /// Add the index on the property `key` of `label`, which is at `field` of the rows of the
+    /// label, with the existing vertices of the label and their `values` of the property, where a
+    /// null value is not indexed.
+    pub(crate) fn insert<Iter: IntoIterator<Item = (G, ItemType)>>(
+        &mut self, label: LabelId, key: &str, field: usize, values: Iter,
+    ) {
+        let mut index = PropertyIndex { field, entries: HashMap::new() };
+        for (global_id, value) in values.into_iter().filter(|(_, value)| !value.is_null()) {
+            index.entries.entry(value.to_string()).or_insert_with(BTreeSet::new).insert(global_id);
+        }
+        self.indices.insert((label, key.to_string()), index);
+    }
+
+    /// Look up the vertices of `label` whose property `key` equals to `value`, in the order of
+    /// their global ids. Return `IndexNotFoundError` if the index of the property is not built.
+    pub(crate) fn lookup(&self, label: LabelId, key: &str, value: &ItemType) -> GDBResult<Vec<G>> {
+        let index =
+            self.indices.get(&(label, key.to_string())).ok_or(GDBError::IndexNotFoundError)?;
+        Ok(index
+            .entries
+            .get(&value.to_string())
+            .map(|ids| ids.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Update the indices of the labels of a vertex, whose properties change from `old` to `new`,
+    /// where `None` means the vertex has no property. A null value, e.g. of a deleted property,
+    /// is not indexed.
+    pub(crate) fn update(
+        &mut self, global_id: G, label: Label, old: Option<&Row>, new: Option<&Row>,
+    ) {
+        for ((label_id, _), index) in self.indices.iter_mut() {
+            if *label_id != label[0] && *label_id != label[1] {
+                continue;
+            }
+            if let Some(value) = old.and_then(|row| row.get(index.field)) {
+                let value = value.to_string();
+                if let Some(ids) = index.entries.get_mut(&value) {
+                    ids.remove(&global_id);
+                    if ids.is_empty() {
+                        index.entries.remove(&value);
+                    }
+                }
+            }
+            if let Some(value) = new.and_then(|row| row.get(index.field)).filter(|v| !v.is_null()) {
+                index
+                    .entries
+                    .entry(value.to_string())
+                    .or_insert_with(BTreeSet::new)
+                    .insert(global_id);
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
 /// This is a large-scale, distributed property graph storage.
 /// Each vertex will be assigned a global unique id as GID, and each edge, which is directed,
 /// will be identified as (startGID, endGID). In the distributed context, a vertex will be
@@ -179,28 +311,65 @@ pub struct LargeGraphDB<
     pub(crate) edge_prop_table: E,
     /// The index data that maintains the mapping between vertices' global ids and their internal ids
     pub(crate) index_data: IndexData<G, I>,
+    /// The secondary indices on vertex properties, which are not exported with the graph
+    pub(crate) property_indices: PropertyIndices<G>,
 }
 
-impl<G, I, N, E> LargeGraphDB<G, I, N, E>
+/// The reading of a `LargeGraphDB`, where the graph structure is read from `db`, and the
+/// properties are read from `props`, which is either the graph itself or a `GraphSnapshot` of it.
+struct GraphView<'a, G, I, N, E, P>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait,
+    E: PropertyTableTrait,
+{
+    db: &'a LargeGraphDB<G, I, N, E>,
+    props: &'a P,
+}
+
+impl<'a, G, I, N, E, P> Clone for GraphView<'a, G, I, N, E, P>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait,
+    E: PropertyTableTrait,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, G, I, N, E, P> Copy for GraphView<'a, G, I, N, E, P>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait,
+    E: PropertyTableTrait,
+{
+}
+
+impl<'a, G, I, N, E, P> GraphView<'a, G, I, N, E, P>
 where
     G: Eq + IndexType + Send + Sync,
     I: IndexType + Send + Sync,
     N: PropertyTableTrait + Sync,
     E: PropertyTableTrait + Sync,
+    P: PrivatePropertyTrait<I> + Sync,
 {
     // Below are some private helper functions
     fn index_to_local_vertex(
-        &self, index: NodeIndex<I>, with_property: bool,
-    ) -> Option<LocalVertex<G>> {
-        if let Some(global_id) = self.index_data.get_global_id(index) {
-            let label = self.graph.node_weight(index).cloned().unwrap();
+        self, index: NodeIndex<I>, with_property: bool,
+    ) -> Option<LocalVertex<'a, G>> {
+        if let Some(global_id) = self.db.index_data.get_global_id(index) {
+            let label = self.db.graph.node_weight(index).cloned().unwrap();
             if with_property {
                 Some(LocalVertex::with_property(
                     global_id,
                     label,
                     RowWithSchema::new(
-                        self.get_all_vertex_property(&index),
-                        self.graph_schema.get_vertex_schema(label[0]),
+                        self.props.get_all_vertex_property(&index),
+                        self.db.graph_schema.get_vertex_schema(label[0]),
                     ),
                 ))
             } else {
@@ -211,20 +380,22 @@ where
         }
     }
 
-    fn edge_ref_to_local_edge(&self, edge: EdgeReference<LabelId, I>) -> Option<LocalEdge<G, I>> {
-        let src_global_id = self.index_data.get_global_id(edge.source());
-        let dst_global_id = self.index_data.get_global_id(edge.target());
+    fn edge_ref_to_local_edge(
+        self, edge: EdgeReference<'a, LabelId, I>,
+    ) -> Option<LocalEdge<'a, G, I>> {
+        let src_global_id = self.db.index_data.get_global_id(edge.source());
+        let dst_global_id = self.db.index_data.get_global_id(edge.target());
 
         if src_global_id.is_some() && dst_global_id.is_some() {
             let edge_id = edge.id();
             let label = *edge.weight();
-            if let Some(property) = self.get_all_edge_property(&edge_id) {
+            if let Some(property) = self.props.get_all_edge_property(&edge_id) {
                 Some(LocalEdge::with_property(
                     src_global_id.unwrap(),
                     dst_global_id.unwrap(),
                     label,
                     edge_id,
-                    RowWithSchema::new(Some(property), self.graph_schema.get_edge_schema(label)),
+                    RowWithSchema::new(Some(property), self.db.graph_schema.get_edge_schema(label)),
                 ))
             } else {
                 Some(LocalEdge::new(
@@ -239,29 +410,21 @@ where
         }
     }
 
-    /// Verify if a vertex of given `index` is local to this partition
-    fn _is_vertex_local(&self, index: NodeIndex<I>) -> bool {
-        if let Some(gid) = self.index_data.get_global_id(index) {
-            self.index_data.global_id_to_index.contains_key(&gid)
-        } else {
-            false
-        }
-    }
-
     /// Get all the vertices regarding to the edges (with direction `dir`) of the given vertex `src_id`.
     /// Return an iterator. If the given vertex does not present or it contains no outgoing
     /// edges, an empty iterator is returned.
     fn _get_adj_vertices(
-        &self, src_id: G, edge_label: Option<LabelId>, dir: Direction,
-    ) -> Iter<LocalVertex<G>> {
-        let index = self.index_data.get_internal_id(src_id);
+        self, src_id: G, edge_label: Option<LabelId>, dir: Direction,
+    ) -> Iter<'a, LocalVertex<'a, G>> {
+        let index = self.db.index_data.get_internal_id(src_id);
         if index.is_some() {
             Iter::from_iter(
-                self.graph
+                self.db
+                    .graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
                         if edge_label.is_some() {
-                            self.graph.edge_weight(edge.id()) == edge_label.as_ref()
+                            self.db.graph.edge_weight(edge.id()) == edge_label.as_ref()
                         } else {
                             true
                         }
@@ -283,15 +446,16 @@ where
     /// Note that an empty `edge_labels` with return empty results. To obtain all adjacent vertices,
     /// call `Self::_get_adj_vertices()` with `None` label instead.
     fn _get_adj_vertices_of_labels(
-        &self, src_id: G, edge_labels: Vec<LabelId>, dir: Direction,
-    ) -> Iter<LocalVertex<G>> {
-        let index = self.index_data.get_internal_id(src_id);
+        self, src_id: G, edge_labels: Vec<LabelId>, dir: Direction,
+    ) -> Iter<'a, LocalVertex<'a, G>> {
+        let index = self.db.index_data.get_internal_id(src_id);
         if index.is_some() {
             Iter::from_iter(
-                self.graph
+                self.db
+                    .graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
+                        edge_labels.contains(self.db.graph.edge_weight(edge.id()).unwrap())
                     })
                     .map(move |edge| {
                         if dir == Direction::Outgoing {
@@ -307,16 +471,17 @@ where
     }
 
     fn _get_adj_edges(
-        &self, src_id: G, edge_label: Option<LabelId>, dir: Direction,
-    ) -> Iter<LocalEdge<G, I>> {
-        let index = self.index_data.get_internal_id(src_id);
+        self, src_id: G, edge_label: Option<LabelId>, dir: Direction,
+    ) -> Iter<'a, LocalEdge<'a, G, I>> {
+        let index = self.db.index_data.get_internal_id(src_id);
         if index.is_some() {
             Iter::from_iter(
-                self.graph
+                self.db
+                    .graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
                         if edge_label.is_some() {
-                            self.graph.edge_weight(edge.id()) == edge_label.as_ref()
+                            self.db.graph.edge_weight(edge.id()) == edge_label.as_ref()
                         } else {
                             true
                         }
@@ -329,15 +494,16 @@ where
     }
 
     fn _get_adj_edges_of_labels(
-        &self, src_id: G, edge_labels: Vec<LabelId>, dir: Direction,
-    ) -> Iter<LocalEdge<G, I>> {
-        let index = self.index_data.get_internal_id(src_id);
+        self, src_id: G, edge_labels: Vec<LabelId>, dir: Direction,
+    ) -> Iter<'a, LocalEdge<'a, G, I>> {
+        let index = self.db.index_data.get_internal_id(src_id);
         if index.is_some() {
             Iter::from_iter(
-                self.graph
+                self.db
+                    .graph
                     .edges_directed(index.unwrap(), dir)
                     .filter(move |edge| {
-                        edge_labels.contains(self.graph.edge_weight(edge.id()).unwrap())
+                        edge_labels.contains(self.db.graph.edge_weight(edge.id()).unwrap())
                     })
                     .map(move |edge| self.edge_ref_to_local_edge(edge).unwrap()),
             )
@@ -346,9 +512,10 @@ where
         }
     }
 
-    fn _get_all_vertices(&self, _label: Option<LabelId>) -> Iter<LocalVertex<G>> {
+    fn _get_all_vertices(self, _label: Option<LabelId>) -> Iter<'a, LocalVertex<'a, G>> {
         if let Some(label) = _label {
             let iter = self
+                .db
                 .index_data
                 .get_indices_of_label(label as LabelId)
                 .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap());
@@ -356,19 +523,21 @@ where
         } else {
             // return all vertices
             let iter = self
+                .db
                 .graph
                 .node_indices()
-                .filter(move |internal_id| self._is_vertex_local(*internal_id))
+                .filter(move |internal_id| self.db._is_vertex_local(*internal_id))
                 .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap());
 
             Iter::from_iter(iter)
         }
     }
 
-    fn _get_all_vertices_of_labels(&self, labels: Vec<LabelId>) -> Iter<LocalVertex<G>> {
+    fn _get_all_vertices_of_labels(self, labels: Vec<LabelId>) -> Iter<'a, LocalVertex<'a, G>> {
         let mut result_iter = vec![];
         for label in labels.into_iter() {
             let result_iter_of_label = self
+                .db
                 .index_data
                 .get_indices_of_label(label as LabelId)
                 .map(move |internal_id| self.index_to_local_vertex(internal_id, true).unwrap());
@@ -378,14 +547,15 @@ where
         Iter::from_iter(IterList::new(result_iter))
     }
 
-    fn _get_all_edges(&self, label_id: Option<LabelId>) -> Iter<LocalEdge<G, I>> {
+    fn _get_all_edges(self, label_id: Option<LabelId>) -> Iter<'a, LocalEdge<'a, G, I>> {
         let result_iter = self
+            .db
             .graph
             .edge_references()
             .filter(move |edge| {
-                if self._is_vertex_local(edge.source()) {
+                if self.db._is_vertex_local(edge.source()) {
                     if label_id.is_some() {
-                        self.graph.edge_weight(edge.id()) == label_id.as_ref()
+                        self.db.graph.edge_weight(edge.id()) == label_id.as_ref()
                     } else {
                         true
                     }
@@ -399,13 +569,14 @@ where
         Iter::from_iter(result_iter)
     }
 
-    fn _get_all_edges_of_labels(&self, labels: Vec<LabelId>) -> Iter<LocalEdge<G, I>> {
+    fn _get_all_edges_of_labels(self, labels: Vec<LabelId>) -> Iter<'a, LocalEdge<'a, G, I>> {
         let result_iter = self
+            .db
             .graph
             .edge_references()
             .filter(move |edge| {
-                if self._is_vertex_local(edge.source()) {
-                    labels.contains(self.graph.edge_weight(edge.id()).unwrap())
+                if self.db._is_vertex_local(edge.source()) {
+                    labels.contains(self.db.graph.edge_weight(edge.id()).unwrap())
                 } else {
                     false
                 }
@@ -414,32 +585,142 @@ where
 
         Iter::from_iter(result_iter)
     }
+}
+
+impl<G, I, N, E> LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    /// The view of the graph that reads the properties from the property tables of the graph
+    fn view(&self) -> GraphView<G, I, N, E, Self> {
+        GraphView { db: self, props: self }
+    }
+
+    /// Take a snapshot of the graph, through which the reads see the properties as of the moment
+    /// the snapshot is taken, see `GraphSnapshot`.
+    pub fn snapshot(&self) -> GraphSnapshot<G, I, N, E> {
+        let vertex_snapshot = self.vertex_prop_table.snapshot(None);
+        let edge_snapshot = self.edge_prop_table.snapshot(vertex_snapshot.as_ref());
+        GraphSnapshot { db: self, vertex_snapshot, edge_snapshot }
+    }
+
+    /// Verify if a vertex of given `index` is local to this partition
+    fn _is_vertex_local(&self, index: NodeIndex<I>) -> bool {
+        if let Some(gid) = self.index_data.get_global_id(index) {
+            self.index_data.global_id_to_index.contains_key(&gid)
+        } else {
+            false
+        }
+    }
+
+    /// Count the edges of the given vertex `index` with the labels `edge_labels` and direction
+    /// `dir`, by walking the adjacency list only, without constructing the edges or reading their
+    /// properties. A self-loop is skipped for `Direction::Incoming` if `skip_loops` is `true`.
+    fn _count_adj_edges(
+        &self, index: NodeIndex<I>, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+        skip_loops: bool,
+    ) -> usize {
+        self.graph
+            .edges_directed(index, dir)
+            .filter(|edge| !skip_loops || edge.source() != edge.target())
+            .filter(|edge| edge_labels.map(|labels| labels.contains(edge.weight())).unwrap_or(true))
+            .count()
+    }
 
-    /// Get incoming degree of a vertex
-    pub fn in_degree(&self, global_id: G) -> usize {
+    /// Get incoming degree of a vertex, counting only the edges of `edge_labels` if any.
+    /// Return `Ok(0)` if the vertex does not present.
+    pub fn in_degree(&self, global_id: G, edge_labels: Option<&Vec<LabelId>>) -> GDBResult<usize> {
         if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_directed(id, Direction::Incoming).count()
+            Ok(self._count_adj_edges(id, edge_labels, Direction::Incoming, false))
         } else {
-            0
+            Ok(0)
         }
     }
 
-    /// Get outgoing degree of a vertex
-    pub fn out_degree(&self, global_id: G) -> usize {
+    /// Get outgoing degree of a vertex, counting only the edges of `edge_labels` if any.
+    /// Return `Ok(0)` if the vertex does not present.
+    pub fn out_degree(&self, global_id: G, edge_labels: Option<&Vec<LabelId>>) -> GDBResult<usize> {
         if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_directed(id, Direction::Outgoing).count()
+            Ok(self._count_adj_edges(id, edge_labels, Direction::Outgoing, false))
         } else {
-            0
+            Ok(0)
         }
     }
 
-    /// Get both incoming and outgoing degree of a vertex
-    pub fn degree(&self, global_id: G) -> usize {
+    /// Get both incoming and outgoing degree of a vertex, counting only the edges of
+    /// `edge_labels` if any. Like `GlobalStoreTrait::get_both_edges()`, a self-loop is counted
+    /// once, so the degree is the number of the edges returned by it.
+    /// Return `Ok(0)` if the vertex does not present.
+    pub fn both_degree(
+        &self, global_id: G, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<usize> {
         if let Some(id) = self.index_data.get_internal_id(global_id) {
-            self.graph.neighbors_undirected(id).count()
+            Ok(self._count_adj_edges(id, edge_labels, Direction::Outgoing, false)
+                + self._count_adj_edges(id, edge_labels, Direction::Incoming, true))
         } else {
-            0
+            Ok(0)
+        }
+    }
+
+    /// Expand from the vertex `start` by up to `hops` hops in a breadth-first manner, following
+    /// the edges of `edge_labels` if any, in the direction `dir`. Each reachable vertex is
+    /// returned once, in the order of the hop it is first reached at, and the visited vertices are
+    /// not expanded again, so that a cycle terminates the expansion. The vertex `start` is never
+    /// returned, even if it is reached through a cycle. Return
+    /// * `Ok(vec![])` if the vertex does not present, or `hops` is 0
+    /// * `Ok(ids)` of the distinct vertices reached within `hops` hops otherwise.
+    pub fn neighborhood(
+        &self, start: G, hops: usize, dir: ExpandDirection, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<Vec<G>> {
+        let start_index = match self.index_data.get_internal_id(start) {
+            Some(index) => index,
+            None => return Ok(vec![]),
+        };
+
+        let dirs: &[Direction] = match dir {
+            ExpandDirection::Out => &[Direction::Outgoing],
+            ExpandDirection::In => &[Direction::Incoming],
+            ExpandDirection::Both => &[Direction::Outgoing, Direction::Incoming],
+        };
+        let mut visited = HashSet::new();
+        visited.insert(start_index);
+        let mut frontier = vec![start_index];
+        let mut reached = vec![];
+        for _ in 0..hops {
+            let mut next = vec![];
+            for index in frontier {
+                for dir in dirs {
+                    let edges = self.graph.edges_directed(index, *dir).filter(|edge| {
+                        edge_labels.map(|labels| labels.contains(edge.weight())).unwrap_or(true)
+                    });
+                    for edge in edges {
+                        let neighbor =
+                            if *dir == Direction::Outgoing { edge.target() } else { edge.source() };
+                        if visited.insert(neighbor) {
+                            next.push(neighbor);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            reached.extend(next.iter().filter_map(|index| self.index_data.get_global_id(*index)));
+            frontier = next;
         }
+        Ok(reached)
+    }
+
+    /// Look up the global ids of the vertices of `label`, whose property `key` equals to `value`,
+    /// in ascending order, by the indices built in `MutableGraphDB` before turning into this graph.
+    /// Return `IndexNotFoundError` if the index of the property is not built.
+    pub fn lookup_by_property(
+        &self, label: LabelId, key: &str, value: &ItemType,
+    ) -> GDBResult<Vec<G>> {
+        self.property_indices.lookup(label, key, value)
     }
 
     /// Verify if a vertex of given `global_id` is local to this partition
@@ -474,12 +755,25 @@ where
 }
 
 /// Do not expose these apis, only for internal use
-trait PrivatePropertyTrait<I> {
+trait PrivatePropertyTrait<I: IndexType> {
+    /// Read the properties of a given vertex specified by the index of its internal id
+    fn read_vertex_row(&self, index: usize) -> GDBResult<RowRef>;
+
+    /// Read the properties of the vertices specified by the indices of their internal ids in order
+    fn read_vertex_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>>;
+
+    /// Read the properties of a given edge specified by the index of its internal id
+    fn read_edge_row(&self, index: usize) -> GDBResult<RowRef>;
+
     /// Get all properties of a given vertex specified by an internal vertex id
-    fn get_all_vertex_property(&self, internal_id: &NodeIndex<I>) -> Option<RowRef>;
+    fn get_all_vertex_property(&self, internal_id: &NodeIndex<I>) -> Option<RowRef> {
+        self.read_vertex_row(internal_id.index()).ok()
+    }
 
     /// Get all properties of a given edge specified by an internal edge id
-    fn get_all_edge_property(&self, internal_id: &EdgeIndex<I>) -> Option<RowRef>;
+    fn get_all_edge_property(&self, internal_id: &EdgeIndex<I>) -> Option<RowRef> {
+        self.read_edge_row(internal_id.index()).ok()
+    }
 }
 
 impl<G, I, N, E> PrivatePropertyTrait<I> for LargeGraphDB<G, I, N, E>
@@ -489,24 +783,96 @@ where
     N: PropertyTableTrait,
     E: PropertyTableTrait,
 {
-    fn get_all_vertex_property(&self, internal_id: &NodeIndex<I>) -> Option<RowRef> {
-        self.vertex_prop_table.get_row(internal_id.index()).ok()
+    fn read_vertex_row(&self, index: usize) -> GDBResult<RowRef> {
+        self.vertex_prop_table.get_row(index)
     }
-    fn get_all_edge_property(&self, internal_id: &EdgeIndex<I>) -> Option<RowRef> {
-        self.edge_prop_table.get_row(internal_id.index()).ok()
+
+    fn read_vertex_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>> {
+        self.vertex_prop_table.get_rows(indices)
+    }
+
+    fn read_edge_row(&self, index: usize) -> GDBResult<RowRef> {
+        self.edge_prop_table.get_row(index)
     }
 }
 
-impl<G, I, N, E> GlobalStoreTrait<G, I> for LargeGraphDB<G, I, N, E>
+/// A snapshot of a `LargeGraphDB`, through which the reads see the properties of the vertices and
+/// the edges as of the moment the snapshot is taken, regardless of the writes to the property
+/// tables afterwards, e.g. through `RocksTable::write_rows()`. A long-running reader, e.g. a job
+/// of traversal, takes one snapshot by `LargeGraphDB::snapshot()` and reads the graph through it
+/// for its duration, so that it sees a stable graph. The snapshots of the property tables are
+/// released once it is dropped, see `RocksSnapshot`.
+///
+/// Only the property tables of RocksDB are read through their snapshots, where the snapshots of
+/// the vertex and the edge tables sharing one RocksDB are of the same moment. The other tables,
+/// e.g. `PropertyTable`, and the graph structure can not be modified while borrowed by the
+/// snapshot, and are read directly.
+pub struct GraphSnapshot<'a, G, I, N, E>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait,
+    E: PropertyTableTrait,
+{
+    db: &'a LargeGraphDB<G, I, N, E>,
+    vertex_snapshot: Option<RocksSnapshot<'a>>,
+    edge_snapshot: Option<RocksSnapshot<'a>>,
+}
+
+impl<'a, G, I, N, E> GraphSnapshot<'a, G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    /// The view of the graph that reads the properties through this snapshot
+    fn view(&self) -> GraphView<G, I, N, E, Self> {
+        GraphView { db: self.db, props: self }
+    }
+}
+
+impl<'a, G, I, N, E> PrivatePropertyTrait<I> for GraphSnapshot<'a, G, I, N, E>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait,
+    E: PropertyTableTrait,
+{
+    fn read_vertex_row(&self, index: usize) -> GDBResult<RowRef> {
+        match &self.vertex_snapshot {
+            Some(snapshot) => snapshot.get_row(index),
+            None => self.db.vertex_prop_table.get_row(index),
+        }
+    }
+
+    fn read_vertex_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>> {
+        match &self.vertex_snapshot {
+            Some(snapshot) => snapshot.get_rows(indices),
+            None => self.db.vertex_prop_table.get_rows(indices),
+        }
+    }
+
+    fn read_edge_row(&self, index: usize) -> GDBResult<RowRef> {
+        match &self.edge_snapshot {
+            Some(snapshot) => snapshot.get_row(index),
+            None => self.db.edge_prop_table.get_row(index),
+        }
+    }
+}
+
+impl<'a, G, I, N, E, P> GraphView<'a, G, I, N, E, P>
 where
     G: Eq + IndexType + Send + Sync,
     I: IndexType + Send + Sync,
     N: PropertyTableTrait + Sync,
     E: PropertyTableTrait + Sync,
+    P: PrivatePropertyTrait<I> + Sync,
 {
+    // Below are the reads of `GlobalStoreTrait` shared by `LargeGraphDB` and `GraphSnapshot`
     fn get_adj_vertices(
-        &self, src_id: G, _edge_labels: Option<&Vec<LabelId>>, dir: Direction,
-    ) -> Iter<LocalVertex<G>> {
+        self, src_id: G, _edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<'a, LocalVertex<'a, G>> {
         if let Some(edge_labels) = _edge_labels {
             if edge_labels.len() == 1 {
                 self._get_adj_vertices(src_id, Some(edge_labels[0]), dir)
@@ -519,8 +885,8 @@ where
     }
 
     fn get_adj_edges(
-        &self, src_id: G, _edge_labels: Option<&Vec<LabelId>>, dir: Direction,
-    ) -> Iter<LocalEdge<G, I>> {
+        self, src_id: G, _edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<'a, LocalEdge<'a, G, I>> {
         if let Some(edge_labels) = _edge_labels {
             if edge_labels.len() == 1 {
                 self._get_adj_edges(src_id, Some(edge_labels[0]), dir)
@@ -532,15 +898,69 @@ where
         }
     }
 
-    fn get_vertex(&self, id: G) -> Option<LocalVertex<G>> {
-        if let Some(index) = self.index_data.get_internal_id(id) {
+    fn get_edges_between(
+        self, src_id: G, dst_id: G, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<Vec<LocalEdge<'a, G, I>>> {
+        let (src_index, dst_index) = match (
+            self.db.index_data.get_internal_id(src_id),
+            self.db.index_data.get_internal_id(dst_id),
+        ) {
+            (Some(src_index), Some(dst_index)) => (src_index, dst_index),
+            _ => return Ok(vec![]),
+        };
+
+        // only the outgoing adjacency of `src_id` is visited, rather than all its edges
+        let mut edges = vec![];
+        for edge in self.db.graph.edges_connecting(src_index, dst_index) {
+            let label = *edge.weight();
+            if edge_labels.map(|labels| labels.contains(&label)).unwrap_or(true) {
+                let row = self.props.read_edge_row(edge.id().index())?;
+                edges.push(LocalEdge::with_property(
+                    src_id,
+                    dst_id,
+                    label,
+                    edge.id(),
+                    RowWithSchema::new(Some(row), self.db.graph_schema.get_edge_schema(label)),
+                ));
+            }
+        }
+        Ok(edges)
+    }
+
+    fn get_vertex(self, id: G) -> Option<LocalVertex<'a, G>> {
+        if let Some(index) = self.db.index_data.get_internal_id(id) {
             self.index_to_local_vertex(index, true)
         } else {
             None
         }
     }
 
-    fn get_all_vertices(&self, _labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>> {
+    fn get_vertices(self, ids: &[G]) -> GDBResult<Vec<Option<LocalVertex<'a, G>>>> {
+        let indices: Vec<Option<NodeIndex<I>>> =
+            ids.iter().map(|id| self.db.index_data.get_internal_id(*id)).collect();
+        let present: Vec<usize> = indices.iter().flatten().map(|index| index.index()).collect();
+        let mut rows = self.props.read_vertex_rows(&present)?.into_iter();
+
+        let mut vertices = Vec::with_capacity(ids.len());
+        for (id, index) in ids.iter().zip(indices) {
+            if let Some(index) = index {
+                // a stale index whose vertex is absent in the topology is regarded as absent
+                let row = rows.next();
+                vertices.push(self.db.graph.node_weight(index).map(|label| {
+                    LocalVertex::with_property(
+                        *id,
+                        *label,
+                        RowWithSchema::new(row, self.db.graph_schema.get_vertex_schema(label[0])),
+                    )
+                }));
+            } else {
+                vertices.push(None);
+            }
+        }
+        Ok(vertices)
+    }
+
+    fn get_all_vertices(self, _labels: Option<&Vec<LabelId>>) -> Iter<'a, LocalVertex<'a, G>> {
         if let Some(labels) = _labels {
             if labels.len() == 1 {
                 self._get_all_vertices(Some(labels[0]))
@@ -552,7 +972,54 @@ where
         }
     }
 
-    fn get_all_edges(&self, _labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
+    fn scan_vertices_by_label(self, label_id: LabelId) -> Iter<'a, GDBResult<LocalVertex<'a, G>>> {
+        // the index of a label is in the order of insertion, sort it by the global ids
+        let mut indices: Vec<(G, NodeIndex<I>)> = self
+            .db
+            .index_data
+            .get_indices_of_label(label_id)
+            .filter_map(|index| self.db.index_data.get_global_id(index).map(|id| (id, index)))
+            .collect();
+        indices.sort_by_key(|(global_id, _)| *global_id);
+
+        let iter = indices.into_iter().map(move |(global_id, index)| {
+            // the index may be stale, e.g., if the vertex was removed from the topology only
+            let label =
+                self.db.graph.node_weight(index).cloned().ok_or(GDBError::VertexNotFoundError)?;
+            let row = self.props.read_vertex_row(index.index())?;
+            Ok(LocalVertex::with_property(
+                global_id,
+                label,
+                RowWithSchema::new(Some(row), self.db.graph_schema.get_vertex_schema(label[0])),
+            ))
+        });
+        Iter::from_iter(iter)
+    }
+
+    fn scan_all_edges(self) -> Iter<'a, GDBResult<LocalEdge<'a, G, I>>> {
+        let iter = self
+            .db
+            .graph
+            .edge_references()
+            .filter(move |edge| self.db._is_vertex_local(edge.source()))
+            .filter_map(move |edge| {
+                let src_global_id = self.db.index_data.get_global_id(edge.source())?;
+                let dst_global_id = self.db.index_data.get_global_id(edge.target())?;
+                let label = *edge.weight();
+                Some(self.props.read_edge_row(edge.id().index()).map(|row| {
+                    LocalEdge::with_property(
+                        src_global_id,
+                        dst_global_id,
+                        label,
+                        edge.id(),
+                        RowWithSchema::new(Some(row), self.db.graph_schema.get_edge_schema(label)),
+                    )
+                }))
+            });
+        Iter::from_iter(iter)
+    }
+
+    fn get_all_edges(self, _labels: Option<&Vec<LabelId>>) -> Iter<'a, LocalEdge<'a, G, I>> {
         if let Some(labels) = _labels {
             if labels.len() == 1 {
                 self._get_all_edges(Some(labels[0]))
@@ -563,6 +1030,56 @@ where
             self._get_all_edges(None)
         }
     }
+}
+
+impl<G, I, N, E> GlobalStoreTrait<G, I> for LargeGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    fn get_adj_vertices(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalVertex<G>> {
+        self.view().get_adj_vertices(src_id, edge_labels, dir)
+    }
+
+    fn get_adj_edges(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalEdge<G, I>> {
+        self.view().get_adj_edges(src_id, edge_labels, dir)
+    }
+
+    fn get_edges_between(
+        &self, src_id: G, dst_id: G, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<Vec<LocalEdge<G, I>>> {
+        self.view().get_edges_between(src_id, dst_id, edge_labels)
+    }
+
+    fn get_vertex(&self, id: G) -> Option<LocalVertex<G>> {
+        self.view().get_vertex(id)
+    }
+
+    fn get_vertices(&self, ids: &[G]) -> GDBResult<Vec<Option<LocalVertex<G>>>> {
+        self.view().get_vertices(ids)
+    }
+
+    fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>> {
+        self.view().get_all_vertices(labels)
+    }
+
+    fn scan_vertices_by_label(&self, label_id: LabelId) -> Iter<GDBResult<LocalVertex<G>>> {
+        self.view().scan_vertices_by_label(label_id)
+    }
+
+    fn scan_all_edges(&self) -> Iter<GDBResult<LocalEdge<G, I>>> {
+        self.view().scan_all_edges()
+    }
+
+    fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
+        self.view().get_all_edges(labels)
+    }
 
     fn count_all_vertices(&self, _labels: Option<&Vec<LabelId>>) -> usize {
         let mut count = 0;
@@ -607,48 +1124,120 @@ where
     }
 }
 
-/// A mutable version of `LargeGraphDB`
-pub struct MutableGraphDB<
-    G: Send + Sync + IndexType = DefaultId,
-    I: Send + Sync + IndexType = InternalId,
-    N: PropertyTableTrait = PropertyTable,
-    E: PropertyTableTrait = SingleValueTable,
-> {
-    /// The root directory to maintain all the data
-    pub(crate) root_dir: PathBuf,
-    /// Which partition of this part of data
-    pub(crate) partition: usize,
-    /// The graph structure, the label will be encoded as `LabelId`
-    pub(crate) graph: DiGraph<Label, LabelId, I>,
-    /// Table from internal vertexs' indices to their properties
-    pub(crate) vertex_prop_table: N,
-    /// Table from internal edges' indices to their properties
-    pub(crate) edge_prop_table: E,
-    /// The index data that maintains the mapping between vertices' global ids and their internal ids
-    pub(crate) index_data: IndexData<G, I>,
-}
-
-/// for graph construction
-impl<G, I, N, E> MutableGraphDB<G, I, N, E>
+impl<'a, G, I, N, E> GlobalStoreTrait<G, I> for GraphSnapshot<'a, G, I, N, E>
 where
     G: Eq + IndexType + Send + Sync,
     I: IndexType + Send + Sync,
     N: PropertyTableTrait + Sync,
     E: PropertyTableTrait + Sync,
 {
-    /// A private function that adds a vertex into the graph database.
-    ///
-    /// If the vertex specified by given id already presents, update the vertex's label, and return
-    /// with a `false` indicator. Otherwise, return the newly assigned internal id of the vertex,
-    /// with a `true` indicator
-    fn add_vertex_internal(&mut self, global_id: G, label: Label) -> (bool, NodeIndex<I>) {
-        if let Some(existed_vertex) = self.index_data.global_id_to_index.get(&global_id) {
-            // update a more fine-grained label
-            if label[1] != INVALID_LABEL_ID {
-                if let Some(w) = self.graph.node_weight_mut(*existed_vertex) {
-                    *w = label;
-                }
-            }
+    fn get_adj_vertices(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalVertex<G>> {
+        self.view().get_adj_vertices(src_id, edge_labels, dir)
+    }
+
+    fn get_adj_edges(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalEdge<G, I>> {
+        self.view().get_adj_edges(src_id, edge_labels, dir)
+    }
+
+    fn get_edges_between(
+        &self, src_id: G, dst_id: G, edge_labels: Option<&Vec<LabelId>>,
+    ) -> GDBResult<Vec<LocalEdge<G, I>>> {
+        self.view().get_edges_between(src_id, dst_id, edge_labels)
+    }
+
+    fn get_vertex(&self, id: G) -> Option<LocalVertex<G>> {
+        self.view().get_vertex(id)
+    }
+
+    fn get_vertices(&self, ids: &[G]) -> GDBResult<Vec<Option<LocalVertex<G>>>> {
+        self.view().get_vertices(ids)
+    }
+
+    fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>> {
+        self.view().get_all_vertices(labels)
+    }
+
+    fn scan_vertices_by_label(&self, label_id: LabelId) -> Iter<GDBResult<LocalVertex<G>>> {
+        self.view().scan_vertices_by_label(label_id)
+    }
+
+    fn scan_all_edges(&self) -> Iter<GDBResult<LocalEdge<G, I>>> {
+        self.view().scan_all_edges()
+    }
+
+    fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
+        self.view().get_all_edges(labels)
+    }
+
+    fn count_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        self.db.count_all_vertices(labels)
+    }
+
+    fn count_all_edges(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        self.db.count_all_edges(labels)
+    }
+
+    fn get_schema(&self) -> Arc<dyn Schema> {
+        self.db.get_schema()
+    }
+
+    fn get_current_partition(&self) -> usize {
+        self.db.get_current_partition()
+    }
+}
+
+/// A mutable version of `LargeGraphDB`
+pub struct MutableGraphDB<
+    G: Send + Sync + IndexType = DefaultId,
+    I: Send + Sync + IndexType = InternalId,
+    N: PropertyTableTrait = PropertyTable,
+    E: PropertyTableTrait = SingleValueTable,
+> {
+    /// The root directory to maintain all the data
+    pub(crate) root_dir: PathBuf,
+    /// Which partition of this part of data
+    pub(crate) partition: usize,
+    /// The graph structure, the label will be encoded as `LabelId`
+    pub(crate) graph: DiGraph<Label, LabelId, I>,
+    /// Table from internal vertexs' indices to their properties
+    pub(crate) vertex_prop_table: N,
+    /// Table from internal edges' indices to their properties
+    pub(crate) edge_prop_table: E,
+    /// The index data that maintains the mapping between vertices' global ids and their internal ids
+    pub(crate) index_data: IndexData<G, I>,
+    /// The schema to validate the properties written, any property is accepted if `None`
+    pub(crate) schema: Option<LDBCGraphSchema>,
+    /// The secondary indices on vertex properties, which are kept consistent with the writes
+    pub(crate) property_indices: PropertyIndices<G>,
+    /// Whether the writes are rejected, see `Self::set_read_only()`
+    pub(crate) read_only: bool,
+}
+
+/// for graph construction
+impl<G, I, N, E> MutableGraphDB<G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    /// A private function that adds a vertex into the graph database.
+    ///
+    /// If the vertex specified by given id already presents, update the vertex's label, and return
+    /// with a `false` indicator. Otherwise, return the newly assigned internal id of the vertex,
+    /// with a `true` indicator
+    fn add_vertex_internal(&mut self, global_id: G, label: Label) -> (bool, NodeIndex<I>) {
+        if let Some(existed_vertex) = self.index_data.global_id_to_index.get(&global_id) {
+            // update a more fine-grained label
+            if label[1] != INVALID_LABEL_ID {
+                if let Some(w) = self.graph.node_weight_mut(*existed_vertex) {
+                    *w = label;
+                }
+            }
 
             (false, existed_vertex.clone())
         } else {
@@ -693,6 +1282,200 @@ where
         }
     }
 
+    /// A private function that removes a vertex, as well as its edges, from the graph structure.
+    /// As `petgraph` moves the last vertex (edge) to the internal id of a removed one, the edges
+    /// are removed in descending order of their internal ids, so that the edge moved is never one
+    /// to remove, which `BatchPlanner::remove_vertex()` relies on.
+    fn remove_vertex_internal(&mut self, internal_id: NodeIndex<I>) {
+        let edges: BTreeSet<EdgeIndex<I>> = self
+            .graph
+            .edges_directed(internal_id, Direction::Outgoing)
+            .chain(self.graph.edges_directed(internal_id, Direction::Incoming))
+            .map(|edge| edge.id())
+            .collect();
+        for edge_id in edges.into_iter().rev() {
+            self.graph.remove_edge(edge_id);
+        }
+
+        let label = self.graph[internal_id];
+        let last_id = NodeIndex::new(self.graph.node_count() - 1);
+        let last_label = self.graph[last_id];
+        self.graph.remove_node(internal_id);
+        self.index_data.remove_vertex(internal_id, label, last_id, last_label);
+    }
+
+    /// Plan the operations of a batch in order against the graph structure, without modifying
+    /// it, see `BatchPlanner`. Return the first failed operation's error, if any.
+    fn plan_batch(&self, ops: Vec<BatchOp<G>>) -> GDBResult<BatchPlan<G>> {
+        let mut planner = BatchPlanner::new(&self.graph, &self.index_data);
+        let mut plan = BatchPlan::default();
+        for op in ops {
+            match op {
+                BatchOp::AddVertex(global_id, label, properties) => {
+                    self.validate_vertex_row(label[0], &properties)?;
+                    let internal_id = planner.add_vertex(global_id, label);
+                    // only non-empty properties will be added
+                    if !properties.is_empty() {
+                        let old = plan.vertex_row(internal_id);
+                        plan.vertex_changes.push((
+                            global_id,
+                            planner.label_at(internal_id),
+                            old,
+                            Some(properties.clone()),
+                        ));
+                        plan.vertex_rows.insert(internal_id, RowSource::New(properties));
+                    }
+                    plan.ops.push(GraphOp::AddVertex(global_id, label));
+                }
+                BatchOp::AddEdge(src_id, dst_id, label_id, properties) => {
+                    self.validate_edge_row(label_id, &properties)?;
+                    let edge_id =
+                        planner.add_edge(src_id, dst_id).ok_or(GDBError::EdgeNotFoundError)?;
+                    if !properties.is_empty() {
+                        plan.edge_rows.insert(edge_id, RowSource::New(properties));
+                    }
+                    plan.ops.push(GraphOp::AddEdge(src_id, dst_id, label_id));
+                }
+                BatchOp::DeleteVertex(global_id) => {
+                    let key = planner.get_key(global_id).ok_or(GDBError::VertexNotFoundError)?;
+                    planner.remove_vertex(key, &mut plan);
+                    plan.ops.push(GraphOp::DeleteVertex(global_id));
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Apply an operation of a planned batch to the graph structure, which never fails, as the
+    /// batch is checked by `Self::plan_batch()`.
+    fn apply_graph_op(&mut self, op: GraphOp<G>) {
+        match op {
+            GraphOp::AddVertex(global_id, label) => {
+                self.add_vertex_internal(global_id, label);
+            }
+            GraphOp::AddEdge(src_id, dst_id, label_id) => {
+                self.add_edge_internal(src_id, dst_id, label_id);
+            }
+            GraphOp::DeleteVertex(global_id) => {
+                if let Some(internal_id) = self.index_data.get_internal_id(global_id) {
+                    self.remove_vertex_internal(internal_id);
+                }
+            }
+        }
+    }
+
+    fn check_writable(&self) -> GDBResult<()> {
+        if self.read_only {
+            Err(GDBError::ModifyReadOnlyError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Start a batch of writes, which are applied atomically on `WriteBatch::commit()`
+    pub fn write_batch(&mut self) -> WriteBatch<G, I, N, E> {
+        WriteBatch { db: self, ops: Vec::new() }
+    }
+
+    /// Validate the properties written against the `schema` from now on, where the properties
+    /// of a vertex or an edge must be in the order of the header of its label in the schema. The
+    /// properties of unknown fields are rejected with `FieldNotExistError`, and those of wrong
+    /// types are rejected with `InvalidTypeError`, see `Schema::validate_vertex_row()`.
+    pub fn set_schema(&mut self, mut schema: LDBCGraphSchema) {
+        // the labels and the ids of edge ends are not written as properties
+        schema.trim();
+        self.schema = Some(schema);
+    }
+
+    fn validate_vertex_row(&self, label_id: LabelId, properties: &Row) -> GDBResult<()> {
+        if let Some(schema) = self.schema.as_ref() {
+            schema.validate_vertex_row(label_id, properties)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn validate_edge_row(&self, label_id: LabelId, properties: &Row) -> GDBResult<()> {
+        if let Some(schema) = self.schema.as_ref() {
+            schema.validate_edge_row(label_id, properties)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn delete_vertex_property(&mut self, global_id: G, key: &str) -> GDBResult<()> {
+        let internal_id =
+            self.index_data.get_internal_id(global_id).ok_or(GDBError::VertexNotFoundError)?;
+        let label = self.graph[internal_id];
+        let field = self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.get_vertex_schema(label[0]))
+            .and_then(|header| header.get(key))
+            .map(|(_, field)| *field)
+            .ok_or(GDBError::FieldNotExistError)?;
+        let old = self
+            .vertex_prop_table
+            .get_row(internal_id.index())?
+            .into_row()
+            .ok_or(GDBError::FieldNotExistError)?;
+        let new = remove_field(&old, field).ok_or(GDBError::FieldNotExistError)?;
+        if new.is_empty() {
+            self.vertex_prop_table.remove(internal_id.index())?;
+            self.property_indices.update(global_id, label, Some(&old), None);
+        } else {
+            self.vertex_prop_table.insert(internal_id.index(), new.clone())?;
+            self.property_indices.update(global_id, label, Some(&old), Some(&new));
+        }
+        Ok(())
+    }
+
+    fn delete_edge_property(
+        &mut self, src_id: G, dst_id: G, label_id: LabelId, key: &str,
+    ) -> GDBResult<()> {
+        let edges: Vec<EdgeIndex<I>> = match (
+            self.index_data.get_internal_id(src_id),
+            self.index_data.get_internal_id(dst_id),
+        ) {
+            (Some(src_index), Some(dst_index)) => self
+                .graph
+                .edges_connecting(src_index, dst_index)
+                .filter(|edge| *edge.weight() == label_id)
+                .map(|edge| edge.id())
+                .collect(),
+            _ => vec![],
+        };
+        if edges.is_empty() {
+            return Err(GDBError::EdgeNotFoundError);
+        }
+        let field = self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.get_edge_schema(label_id))
+            .and_then(|header| header.get(key))
+            .map(|(_, field)| *field)
+            .ok_or(GDBError::FieldNotExistError)?;
+
+        let mut deleted = false;
+        for edge_id in edges {
+            let old = self.edge_prop_table.get_row(edge_id.index())?.into_row();
+            if let Some(new) = old.and_then(|old| remove_field(&old, field)) {
+                if new.is_empty() {
+                    self.edge_prop_table.remove(edge_id.index())?;
+                } else {
+                    self.edge_prop_table.insert(edge_id.index(), new)?;
+                }
+                deleted = true;
+            }
+        }
+        if deleted {
+            Ok(())
+        } else {
+            Err(GDBError::FieldNotExistError)
+        }
+    }
+
     /// Verify if a vertex of given `global_id` is local to this partition
     pub fn is_vertex_local(&self, global_id: G) -> bool {
         self.index_data.global_id_to_index.contains_key(&global_id)
@@ -720,6 +1503,7 @@ where
             edge_prop_table: self.edge_prop_table,
             index_data: self.index_data,
             graph_schema: Arc::new(schema),
+            property_indices: self.property_indices,
         }
     }
 }
@@ -755,30 +1539,70 @@ where
     E: PropertyTableTrait + Sync,
 {
     fn add_vertex(&mut self, global_id: G, label: Label) -> bool {
-        self.add_vertex_internal(global_id, label).0
+        self.try_add_vertex(global_id, label).unwrap_or(false)
     }
 
     fn add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> bool {
-        self.add_corner_vertex_internal(global_id, label_id).0
+        self.try_add_corner_vertex(global_id, label_id).unwrap_or(false)
+    }
+
+    fn try_add_vertex(&mut self, global_id: G, label: Label) -> GDBResult<bool> {
+        self.check_writable()?;
+        Ok(self.add_vertex_internal(global_id, label).0)
+    }
+
+    fn try_add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> GDBResult<bool> {
+        self.check_writable()?;
+        Ok(self.add_corner_vertex_internal(global_id, label_id).0)
     }
 
     fn add_or_update_vertex_properties(
         &mut self, global_id: G, properties: Row,
     ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
         if let Some(internal_id) = self.index_data.get_internal_id(global_id) {
-            self.vertex_prop_table.insert(internal_id.index(), properties)
+            let label = self.graph.node_weight(internal_id).cloned();
+            if let Some(label) = label {
+                self.validate_vertex_row(label[0], &properties)?;
+            }
+            if let (Some(label), false) = (label, self.property_indices.is_empty()) {
+                let old = self.vertex_prop_table.insert(internal_id.index(), properties.clone())?;
+                self.property_indices.update(global_id, label, old.as_ref(), Some(&properties));
+                Ok(old)
+            } else {
+                self.vertex_prop_table.insert(internal_id.index(), properties)
+            }
         } else {
             Err(GDBError::VertexNotFoundError)
         }
     }
 
+    fn add_vertex_with_properties(
+        &mut self, global_id: G, label: Label, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        // validate before adding, so that no vertex is added without its properties
+        self.validate_vertex_row(label[0], &properties)?;
+        self.try_add_vertex(global_id, label)?;
+        self.add_or_update_vertex_properties(global_id, properties)
+    }
+
     fn add_edge(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> bool {
-        self.add_edge_internal(global_src_id, global_dst_id, label_id).is_some()
+        self.try_add_edge(global_src_id, global_dst_id, label_id).unwrap_or(false)
+    }
+
+    fn try_add_edge(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId,
+    ) -> GDBResult<bool> {
+        self.check_writable()?;
+        Ok(self.add_edge_internal(global_src_id, global_dst_id, label_id).is_some())
     }
 
     fn add_edge_with_properties(
         &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId, properties: Row,
     ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        self.validate_edge_row(label_id, &properties)?;
         if let Some(edge_id) = self.add_edge_internal(global_src_id, global_dst_id, label_id) {
             self.edge_prop_table.insert(edge_id.index(), properties)
         } else {
@@ -789,30 +1613,54 @@ where
     fn add_vertex_batches<Iter: Iterator<Item = (G, Label, Row)>>(
         &mut self, iter: Iter,
     ) -> GDBResult<usize> {
+        self.check_writable()?;
         let mut properties: Vec<(usize, Row)> = Vec::new();
         let mut count = 0;
+        let mut invalid = None;
         for (nid, label, ppt) in iter {
+            // stop at an invalid vertex, while the vertices before it are added
+            if let Err(e) = self.validate_vertex_row(label[0], &ppt) {
+                invalid = Some(e);
+                break;
+            }
             let (is_new, inner_id) = self.add_vertex_internal(nid, label);
             if is_new {
                 count += 1;
             }
             // only non-empty properties will be added
             if !ppt.is_empty() {
-                properties.push((inner_id.index(), ppt));
+                if self.property_indices.is_empty() {
+                    properties.push((inner_id.index(), ppt));
+                } else {
+                    // insert one by one to index against the old properties
+                    let old = self.vertex_prop_table.insert(inner_id.index(), ppt.clone())?;
+                    self.property_indices.update(nid, label, old.as_ref(), Some(&ppt));
+                }
             }
         }
 
         self.vertex_prop_table.insert_batches(properties.into_iter())?;
 
-        Ok(count)
+        if let Some(e) = invalid {
+            Err(e)
+        } else {
+            Ok(count)
+        }
     }
 
     fn add_edge_batches<Iter: Iterator<Item = (G, G, LabelId, Row)>>(
         &mut self, iter: Iter,
     ) -> GDBResult<usize> {
+        self.check_writable()?;
         let mut properties: Vec<(usize, Row)> = Vec::new();
         let mut count = 0;
+        let mut invalid = None;
         for (src_id, dst_id, label_id, ppt) in iter {
+            // stop at an invalid edge, while the edges before it are added
+            if let Err(e) = self.validate_edge_row(label_id, &ppt) {
+                invalid = Some(e);
+                break;
+            }
             if let Some(inner_id) = self.add_edge_internal(src_id, dst_id, label_id) {
                 count += 1;
                 // only non-empty properties will be added
@@ -824,101 +1672,560 @@ where
 
         let _ = self.edge_prop_table.insert_batches(properties.into_iter())?;
 
-        Ok(count)
+        if let Some(e) = invalid {
+            Err(e)
+        } else {
+            Ok(count)
+        }
     }
-}
-
-#[cfg(test)]
-mod test {
 
-    use super::*;
-    use crate::config::{GraphDBConfig, JsonConf};
-    use crate::ldbc::*;
-    use crate::parser::DataType;
-    use crate::schema::ID_FIELD;
-    use crate::serde_json::Value as JsonValue;
+    fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
 
-    // person ids
-    static PIDS: [DefaultId; 9] = [
-        1 << LABEL_SHIFT_BITS | 111,
-        1 << LABEL_SHIFT_BITS | 222,
-        1 << LABEL_SHIFT_BITS | 333,
-        1 << LABEL_SHIFT_BITS | 444,
-        1 << LABEL_SHIFT_BITS | 555,
-        1 << LABEL_SHIFT_BITS | 666,
-        1 << LABEL_SHIFT_BITS | 777,
-        1 << LABEL_SHIFT_BITS | 888,
-        1 << LABEL_SHIFT_BITS | 999,
-    ];
-    // comment ids
-    static CIDS: [DefaultId; 9] = [
-        2 << LABEL_SHIFT_BITS | 111,
-        2 << LABEL_SHIFT_BITS | 222,
-        2 << LABEL_SHIFT_BITS | 333,
-        2 << LABEL_SHIFT_BITS | 444,
-        2 << LABEL_SHIFT_BITS | 555,
-        2 << LABEL_SHIFT_BITS | 666,
-        2 << LABEL_SHIFT_BITS | 777,
-        2 << LABEL_SHIFT_BITS | 888,
-        2 << LABEL_SHIFT_BITS | 999,
-    ];
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
 
-    #[test]
-    fn test_graph_store_update() {
-        let root_dir = "data/simple_data";
-        let mut graphdb: MutableGraphDB<DefaultId, InternalId> =
-            GraphDBConfig::default().root_dir(root_dir).number_vertex_labels(20).new();
-        assert!(graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
-        // Cannot re-add a vertex
-        assert!(!graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
-        assert!(graphdb.add_vertex(PIDS[2], [1, INVALID_LABEL_ID]));
-        assert!(graphdb.add_corner_vertex(PIDS[5], 1));
+    // The schema to locate the property is set by `Self::set_schema()`
+    fn create_vertex_index(&mut self, label: LabelId, key: &str) -> GDBResult<()> {
+        let field = self
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.get_vertex_schema(label))
+            .and_then(|header| header.get(key))
+            .map(|(_, field)| *field)
+            .ok_or(GDBError::FieldNotExistError)?;
+        if self.property_indices.contains(label, key) {
+            return Ok(());
+        }
 
-        let prop = Row::from(vec![json!(15), json!("John")]);
-        let new_prop = Row::from(vec![json!(16), json!("Steve")]);
+        let mut values = Vec::new();
+        for internal_id in self.index_data.get_indices_of_label(label) {
+            if let Some(global_id) = self.index_data.get_global_id(internal_id) {
+                let row = self.vertex_prop_table.get_row(internal_id.index())?;
+                if let Some(value) = row.get(field) {
+                    values.push((global_id, value.clone()));
+                }
+            }
+        }
+        self.property_indices.insert(label, key, field, values);
+        Ok(())
+    }
 
-        // Update the vertex's properties
-        assert!(graphdb.add_or_update_vertex_properties(PIDS[0], prop.clone()).unwrap().is_none());
+    fn lookup_by_property(&self, label: LabelId, key: &str, value: &ItemType) -> GDBResult<Vec<G>> {
+        self.property_indices.lookup(label, key, value)
+    }
 
-        // The vertex PIDS[1] does no exist, can not update
-        assert!(graphdb.add_or_update_vertex_properties(PIDS[1], prop.clone()).is_err());
+    fn remove_vertex_properties(&mut self, global_id: G) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        let internal_id =
+            self.index_data.get_internal_id(global_id).ok_or(GDBError::VertexNotFoundError)?;
+        let old = self.vertex_prop_table.remove(internal_id.index())?;
+        if let Some(label) = self.graph.node_weight(internal_id) {
+            self.property_indices.update(global_id, *label, old.as_ref(), None);
+        }
+        Ok(old)
+    }
 
-        // Add vertex PIDS[1]
-        assert!(graphdb
-            .add_vertex_with_properties(PIDS[1], [1, INVALID_LABEL_ID], prop.clone())
-            .unwrap()
-            .is_none());
+    // Besides the read-only graph, `ModifyReadOnlyError` is also returned if the property table
+    // is read-only, where nothing is changed either
+    fn delete_property(&mut self, element_id: ElementId<G>, key: &str) -> GDBResult<()> {
+        self.check_writable()?;
+        match element_id {
+            ElementId::Vertex(global_id) => self.delete_vertex_property(global_id, key),
+            ElementId::Edge(src_id, dst_id, label_id) => {
+                self.delete_edge_property(src_id, dst_id, label_id, key)
+            }
+        }
+    }
+}
 
-        // The vertex already exists, return the old properties
-        assert_eq!(
-            Some(prop.clone()),
-            graphdb
-                .add_vertex_with_properties(PIDS[1], [1, INVALID_LABEL_ID], new_prop.clone())
-                .unwrap()
-        );
+enum BatchOp<G> {
+    AddVertex(G, Label, Row),
+    AddEdge(G, G, LabelId, Row),
+    DeleteVertex(G),
+}
 
-        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 13));
-        // add an edge from a corner vertex
-        assert!(graphdb.add_edge(PIDS[5], PIDS[0], 12));
+/// An operation of a batch on the graph structure, whose properties are planned apart
+enum GraphOp<G> {
+    AddVertex(G, Label),
+    AddEdge(G, G, LabelId),
+    DeleteVertex(G),
+}
 
-        // PIDS[3] does not exist, therefore `false` is returned
-        assert!(!graphdb.add_edge(PIDS[0], PIDS[3], 12));
-        assert!(!graphdb.add_edge(PIDS[3], PIDS[0], 12));
+/// Where the row at an internal id comes from once a batch is applied
+#[derive(Clone)]
+enum RowSource {
+    /// The row at the given internal id before the batch
+    Base(usize),
+    /// The row written by the batch
+    New(Row),
+    /// No row, i.e., the row is removed
+    Empty,
+}
 
-        let edge_prop = Row::from(20200202_u64);
-        // add duplicate edge to the db
-        assert!(graphdb
-            .add_edge_with_properties(PIDS[0], PIDS[1], 12, edge_prop.clone())
-            .unwrap()
-            .is_none());
+impl RowSource {
+    /// Turn into the row, given the rows read for `RowSource::Base`
+    fn resolve(self, base: &HashMap<usize, Option<Row>>) -> Option<Row> {
+        match self {
+            RowSource::Base(index) => base.get(&index).cloned().flatten(),
+            RowSource::New(row) => Some(row),
+            RowSource::Empty => None,
+        }
+    }
+}
 
-        // PIDS[3] does not exist, thus return error.
-        assert!(graphdb.add_edge_with_properties(PIDS[0], PIDS[3], 12, edge_prop.clone()).is_err());
+/// The plan of a batch, which is applied to the graph structure as `ops`, and to the property
+/// tables as the rows at the internal ids after the batch
+struct BatchPlan<G> {
+    ops: Vec<GraphOp<G>>,
+    vertex_rows: HashMap<usize, RowSource>,
+    edge_rows: HashMap<usize, RowSource>,
+    /// The changes of vertices' properties in order, to update the property indices
+    vertex_changes: Vec<(G, Label, RowSource, Option<Row>)>,
+}
 
-        let schema =
-            LDBCGraphSchema::from_json_file("data/schema.json").expect("Get Schema error!");
+impl<G> Default for BatchPlan<G> {
+    fn default() -> Self {
+        BatchPlan {
+            ops: vec![],
+            vertex_rows: HashMap::new(),
+            edge_rows: HashMap::new(),
+            vertex_changes: vec![],
+        }
+    }
+}
 
-        let graph = graphdb.into_graph(schema);
+impl<G> BatchPlan<G> {
+    /// The row of the vertex at `internal_id` so far
+    fn vertex_row(&self, internal_id: usize) -> RowSource {
+        self.vertex_rows.get(&internal_id).cloned().unwrap_or(RowSource::Base(internal_id))
+    }
+}
+
+/// Plan moving the row at `from` to `to`, which removes the row at `from` if they are the same.
+fn plan_move(rows: &mut HashMap<usize, RowSource>, from: usize, to: usize) {
+    if from != to {
+        let row = rows.remove(&from).unwrap_or(RowSource::Base(from));
+        rows.insert(to, row);
+    }
+    rows.insert(from, RowSource::Empty);
+}
+
+/// Read the rows of `table` that the `sources` refer to as before the batch
+fn read_base_rows<'a, T: PropertyTableTrait>(
+    table: &T, sources: impl Iterator<Item = &'a RowSource>,
+) -> GDBResult<HashMap<usize, Option<Row>>> {
+    let indices: BTreeSet<usize> = sources
+        .filter_map(|source| if let RowSource::Base(index) = source { Some(*index) } else { None })
+        .collect();
+    let indices: Vec<usize> = indices.into_iter().collect();
+    let rows = table.get_rows(&indices)?;
+    Ok(indices.into_iter().zip(rows.into_iter().map(|row| row.into_row())).collect())
+}
+
+/// Simulate the operations of a batch on the graph structure without modifying it, to plan where
+/// the vertices and edges, and thus their rows, are after each operation, as `petgraph` moves the
+/// last vertex (edge) to the internal id of a removed one.
+///
+/// A vertex (edge) is tracked by a key, which is its internal id before the batch, or the next
+/// one after all the keys for a new vertex (edge). Only the keys and the internal ids that differ
+/// are recorded, so that the cost is of the size of the batch rather than that of the graph.
+struct BatchPlanner<'a, G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
+    graph: &'a DiGraph<Label, LabelId, I>,
+    index_data: &'a IndexData<G, I>,
+    node_count: usize,
+    edge_count: usize,
+    next_node_key: usize,
+    /// The keys of the vertices at the internal ids, where a key is the internal id by default
+    node_keys: HashMap<usize, usize>,
+    /// The internal ids of the vertices' keys, where `None` is for a removed vertex
+    node_ids: HashMap<usize, Option<usize>>,
+    /// The global ids, labels and whether corner, of the vertices added or relabelled by keys
+    nodes: HashMap<usize, (G, Label, bool)>,
+    /// The keys of the local vertices by global ids, where `None` is for a removed vertex
+    local_keys: HashMap<G, Option<usize>>,
+    /// The keys of the corner vertices by global ids, where `None` is for a removed vertex
+    corner_keys: HashMap<G, Option<usize>>,
+    edge_keys: HashMap<usize, usize>,
+    edge_ids: HashMap<usize, Option<usize>>,
+    /// The keys of the ends of the edges added, in the order of their keys
+    new_edges: Vec<(usize, usize)>,
+}
+
+impl<'a, G, I> BatchPlanner<'a, G, I>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+{
+    fn new(graph: &'a DiGraph<Label, LabelId, I>, index_data: &'a IndexData<G, I>) -> Self {
+        BatchPlanner {
+            graph,
+            index_data,
+            node_count: graph.node_count(),
+            edge_count: graph.edge_count(),
+            next_node_key: graph.node_count(),
+            node_keys: HashMap::new(),
+            node_ids: HashMap::new(),
+            nodes: HashMap::new(),
+            local_keys: HashMap::new(),
+            corner_keys: HashMap::new(),
+            edge_keys: HashMap::new(),
+            edge_ids: HashMap::new(),
+            new_edges: vec![],
+        }
+    }
+
+    fn node_key_at(&self, internal_id: usize) -> usize {
+        self.node_keys.get(&internal_id).cloned().unwrap_or(internal_id)
+    }
+
+    fn node_id_of(&self, key: usize) -> Option<usize> {
+        self.node_ids.get(&key).cloned().unwrap_or(Some(key))
+    }
+
+    fn edge_key_at(&self, internal_id: usize) -> usize {
+        self.edge_keys.get(&internal_id).cloned().unwrap_or(internal_id)
+    }
+
+    fn edge_id_of(&self, key: usize) -> Option<usize> {
+        self.edge_ids.get(&key).cloned().unwrap_or(Some(key))
+    }
+
+    /// The global id, the label and whether it is a corner vertex, of the vertex of `key`
+    fn node(&self, key: usize) -> (G, Label, bool) {
+        if let Some(node) = self.nodes.get(&key) {
+            *node
+        } else {
+            let index = NodeIndex::new(key);
+            let global_id = self.index_data.get_global_id(index).unwrap();
+            let is_corner = self.index_data.global_id_to_index.get(&global_id) != Some(&index);
+            (global_id, self.graph[index], is_corner)
+        }
+    }
+
+    fn label_at(&self, internal_id: usize) -> Label {
+        self.node(self.node_key_at(internal_id)).1
+    }
+
+    fn local_key(&self, global_id: G) -> Option<usize> {
+        match self.local_keys.get(&global_id) {
+            Some(key) => *key,
+            None => self.index_data.global_id_to_index.get(&global_id).map(|index| index.index()),
+        }
+    }
+
+    fn corner_key(&self, global_id: G) -> Option<usize> {
+        match self.corner_keys.get(&global_id) {
+            Some(key) => *key,
+            None => {
+                self.index_data.corner_global_id_to_index.get(&global_id).map(|index| index.index())
+            }
+        }
+    }
+
+    /// Get the key of a local vertex, or a corner one, as `IndexData::get_internal_id()` does
+    fn get_key(&self, global_id: G) -> Option<usize> {
+        self.local_key(global_id).or_else(|| self.corner_key(global_id))
+    }
+
+    /// Plan `MutableGraphDB::add_vertex_internal()`, and return the internal id of the vertex
+    fn add_vertex(&mut self, global_id: G, label: Label) -> usize {
+        if let Some(key) = self.local_key(global_id) {
+            if label[1] != INVALID_LABEL_ID {
+                let (_, _, is_corner) = self.node(key);
+                self.nodes.insert(key, (global_id, label, is_corner));
+            }
+            self.node_id_of(key).unwrap()
+        } else {
+            let key = self.next_node_key;
+            self.next_node_key += 1;
+            let internal_id = self.node_count;
+            self.node_count += 1;
+            self.node_keys.insert(internal_id, key);
+            self.node_ids.insert(key, Some(internal_id));
+            self.nodes.insert(key, (global_id, label, false));
+            self.local_keys.insert(global_id, Some(key));
+            internal_id
+        }
+    }
+
+    /// Plan `MutableGraphDB::add_edge_internal()`, and return the internal id of the edge, or
+    /// `None` if either end does not present
+    fn add_edge(&mut self, src_id: G, dst_id: G) -> Option<usize> {
+        let src_key = self.get_key(src_id)?;
+        let dst_key = self.get_key(dst_id)?;
+        let key = self.graph.edge_count() + self.new_edges.len();
+        self.new_edges.push((src_key, dst_key));
+        let internal_id = self.edge_count;
+        self.edge_count += 1;
+        self.edge_keys.insert(internal_id, key);
+        self.edge_ids.insert(key, Some(internal_id));
+        Some(internal_id)
+    }
+
+    /// Plan `MutableGraphDB::remove_vertex_internal()` for the vertex of `key`, where the rows
+    /// moved and removed are planned in `plan`
+    fn remove_vertex(&mut self, key: usize, plan: &mut BatchPlan<G>) {
+        // the edges of the vertex that present before the batch, and those added by it
+        let mut edges = BTreeSet::new();
+        if key < self.graph.node_count() {
+            let index = NodeIndex::new(key);
+            for edge in self
+                .graph
+                .edges_directed(index, Direction::Outgoing)
+                .chain(self.graph.edges_directed(index, Direction::Incoming))
+            {
+                edges.extend(self.edge_id_of(edge.id().index()));
+            }
+        }
+        for (offset, (src_key, dst_key)) in self.new_edges.iter().enumerate() {
+            if *src_key == key || *dst_key == key {
+                edges.extend(self.edge_id_of(self.graph.edge_count() + offset));
+            }
+        }
+        for edge_id in edges.into_iter().rev() {
+            let last_id = self.edge_count - 1;
+            self.edge_ids.insert(self.edge_key_at(edge_id), None);
+            if edge_id != last_id {
+                let moved = self.edge_key_at(last_id);
+                self.edge_keys.insert(edge_id, moved);
+                self.edge_ids.insert(moved, Some(edge_id));
+            }
+            self.edge_count = last_id;
+            plan_move(&mut plan.edge_rows, last_id, edge_id);
+        }
+
+        let internal_id = self.node_id_of(key).unwrap();
+        let (global_id, label, is_corner) = self.node(key);
+        let old = plan.vertex_row(internal_id);
+        plan.vertex_changes.push((global_id, label, old, None));
+        let last_id = self.node_count - 1;
+        self.node_ids.insert(key, None);
+        if internal_id != last_id {
+            let moved = self.node_key_at(last_id);
+            self.node_keys.insert(internal_id, moved);
+            self.node_ids.insert(moved, Some(internal_id));
+        }
+        self.node_count = last_id;
+        if is_corner {
+            self.corner_keys.insert(global_id, None);
+        } else {
+            self.local_keys.insert(global_id, None);
+        }
+        plan_move(&mut plan.vertex_rows, last_id, internal_id);
+    }
+}
+
+/// A batch of writes to a `MutableGraphDB`, which are applied as a whole on `Self::commit()`, so
+/// that a partially-failed ingestion does not leave the graph inconsistent. The writes are
+/// discarded if the batch is dropped without committing.
+pub struct WriteBatch<
+    'a,
+    G: Send + Sync + IndexType = DefaultId,
+    I: Send + Sync + IndexType = InternalId,
+    N: PropertyTableTrait = PropertyTable,
+    E: PropertyTableTrait = SingleValueTable,
+> {
+    db: &'a mut MutableGraphDB<G, I, N, E>,
+    ops: Vec<BatchOp<G>>,
+}
+
+impl<'a, G, I, N, E> WriteBatch<'a, G, I, N, E>
+where
+    G: Eq + IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+    N: PropertyTableTrait + Sync,
+    E: PropertyTableTrait + Sync,
+{
+    /// Add a vertex with its properties, or update the label and the properties of the vertex
+    /// if it already presents, see `GlobalStoreUpdate::add_vertex_with_properties()`
+    pub fn add_vertex(&mut self, global_id: G, label: Label, properties: Row) -> &mut Self {
+        self.ops.push(BatchOp::AddVertex(global_id, label, properties));
+        self
+    }
+
+    /// Add an edge with its properties, where the vertices of both ends must present, or be
+    /// added earlier in the batch
+    pub fn add_edge(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId, properties: Row,
+    ) -> &mut Self {
+        self.ops.push(BatchOp::AddEdge(global_src_id, global_dst_id, label_id, properties));
+        self
+    }
+
+    /// Delete a vertex, as well as its properties and all its incoming and outgoing edges
+    pub fn delete_vertex(&mut self, global_id: G) -> &mut Self {
+        self.ops.push(BatchOp::DeleteVertex(global_id));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Apply the writes in order, and commit the properties to the property tables atomically,
+    /// e.g. in one `WriteBatch` of RocksDB for the `RocksTable`s, see `TableBatch`. The writes are
+    /// planned against the graph structure first, and applied to it only after the properties
+    /// are committed, so that nothing is to be rolled back on failure. Return
+    /// * `Ok(())` if all the writes are applied;
+    /// * `Err` if any write fails, e.g. `VertexNotFoundError` for deleting an absent vertex,
+    ///   `EdgeNotFoundError` for an edge of absent ends, errors of validating the properties
+    ///   against the schema, `ModifyReadOnlyError` for a read-only graph, or `RocksError` for
+    ///   failing to read or write RocksDB, where the graph is left unchanged.
+    pub fn commit(self) -> GDBResult<()> {
+        let WriteBatch { db, ops } = self;
+        db.check_writable()?;
+        let plan = db.plan_batch(ops)?;
+
+        let vertex_base = read_base_rows(
+            &db.vertex_prop_table,
+            plan.vertex_rows.values().chain(plan.vertex_changes.iter().map(|change| &change.2)),
+        )?;
+        let edge_base = read_base_rows(&db.edge_prop_table, plan.edge_rows.values())?;
+        let vertex_writes: Vec<(usize, Option<Row>)> = plan
+            .vertex_rows
+            .into_iter()
+            .map(|(index, row)| (index, row.resolve(&vertex_base)))
+            .collect();
+        let edge_writes: Vec<(usize, Option<Row>)> = plan
+            .edge_rows
+            .into_iter()
+            .map(|(index, row)| (index, row.resolve(&edge_base)))
+            .collect();
+        let mut batch = TableBatch::default();
+        db.vertex_prop_table.stage_writes(&vertex_writes, &mut batch)?;
+        db.edge_prop_table.stage_writes(&edge_writes, &mut batch)?;
+        batch.commit()?;
+
+        // nothing fails from now on
+        for op in plan.ops {
+            db.apply_graph_op(op);
+        }
+        db.vertex_prop_table.apply_staged(vertex_writes);
+        db.edge_prop_table.apply_staged(edge_writes);
+        for (global_id, label, old, new) in plan.vertex_changes {
+            let old = old.resolve(&vertex_base);
+            db.property_indices.update(global_id, label, old.as_ref(), new.as_ref());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::config::{GraphDBConfig, JsonConf, PARTITION_PREFIX};
+    use crate::ldbc::*;
+    use crate::parser::DataType;
+    use crate::schema::ID_FIELD;
+    use crate::serde_json::Value as JsonValue;
+    use std::path::Path;
+
+    // person ids
+    static PIDS: [DefaultId; 9] = [
+        1 << LABEL_SHIFT_BITS | 111,
+        1 << LABEL_SHIFT_BITS | 222,
+        1 << LABEL_SHIFT_BITS | 333,
+        1 << LABEL_SHIFT_BITS | 444,
+        1 << LABEL_SHIFT_BITS | 555,
+        1 << LABEL_SHIFT_BITS | 666,
+        1 << LABEL_SHIFT_BITS | 777,
+        1 << LABEL_SHIFT_BITS | 888,
+        1 << LABEL_SHIFT_BITS | 999,
+    ];
+    // comment ids
+    static CIDS: [DefaultId; 9] = [
+        2 << LABEL_SHIFT_BITS | 111,
+        2 << LABEL_SHIFT_BITS | 222,
+        2 << LABEL_SHIFT_BITS | 333,
+        2 << LABEL_SHIFT_BITS | 444,
+        2 << LABEL_SHIFT_BITS | 555,
+        2 << LABEL_SHIFT_BITS | 666,
+        2 << LABEL_SHIFT_BITS | 777,
+        2 << LABEL_SHIFT_BITS | 888,
+        2 << LABEL_SHIFT_BITS | 999,
+    ];
+
+    fn ldbc_schema() -> LDBCGraphSchema {
+        LDBCGraphSchema::from_json_file("data/schema.json").expect("Get Schema error!")
+    }
+
+    /// An empty graph whose properties are in memory, without any schema
+    fn new_graphdb() -> MutableGraphDB<DefaultId, InternalId, PropertyTable, PropertyTable> {
+        GraphDBConfig::default().number_vertex_labels(20).new()
+    }
+
+    /// An empty graph in `dir`, e.g. for the property tables of RocksDB
+    fn new_graphdb_in<N, E>(dir: &Path) -> MutableGraphDB<DefaultId, InternalId, N, E>
+    where
+        N: PropertyTableTrait + Sync,
+        E: PropertyTableTrait + Sync,
+    {
+        GraphDBConfig::default().root_dir(dir).number_vertex_labels(20).new()
+    }
+
+    #[test]
+    fn test_graph_store_update() {
+        let root_dir = "data/simple_data";
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId> =
+            new_graphdb_in(Path::new(root_dir));
+        assert!(graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
+        // Cannot re-add a vertex
+        assert!(!graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
+        assert!(graphdb.add_vertex(PIDS[2], [1, INVALID_LABEL_ID]));
+        assert!(graphdb.add_corner_vertex(PIDS[5], 1));
+
+        let prop = Row::from(vec![json!(15), json!("John")]);
+        let new_prop = Row::from(vec![json!(16), json!("Steve")]);
+
+        // Update the vertex's properties
+        assert!(graphdb.add_or_update_vertex_properties(PIDS[0], prop.clone()).unwrap().is_none());
+
+        // The vertex PIDS[1] does no exist, can not update
+        assert!(graphdb.add_or_update_vertex_properties(PIDS[1], prop.clone()).is_err());
+
+        // Add vertex PIDS[1]
+        assert!(graphdb
+            .add_vertex_with_properties(PIDS[1], [1, INVALID_LABEL_ID], prop.clone())
+            .unwrap()
+            .is_none());
+
+        // The vertex already exists, return the old properties
+        assert_eq!(
+            Some(prop.clone()),
+            graphdb
+                .add_vertex_with_properties(PIDS[1], [1, INVALID_LABEL_ID], new_prop.clone())
+                .unwrap()
+        );
+
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 13));
+        // add an edge from a corner vertex
+        assert!(graphdb.add_edge(PIDS[5], PIDS[0], 12));
+
+        // PIDS[3] does not exist, therefore `false` is returned
+        assert!(!graphdb.add_edge(PIDS[0], PIDS[3], 12));
+        assert!(!graphdb.add_edge(PIDS[3], PIDS[0], 12));
+
+        let edge_prop = Row::from(20200202_u64);
+        // add duplicate edge to the db
+        assert!(graphdb
+            .add_edge_with_properties(PIDS[0], PIDS[1], 12, edge_prop.clone())
+            .unwrap()
+            .is_none());
+
+        // PIDS[3] does not exist, thus return error.
+        assert!(graphdb.add_edge_with_properties(PIDS[0], PIDS[3], 12, edge_prop.clone()).is_err());
+
+        let schema = ldbc_schema();
+
+        let graph = graphdb.into_graph(schema);
 
         assert_eq!(
             vec![prop.clone().get(0), prop.clone().get(1)],
@@ -945,6 +2252,919 @@ mod test {
         assert_eq!(1, graph.count_all_edges(Some(&vec![13])));
     }
 
+    #[test]
+    fn test_graph_store_schema() {
+        let schema = ldbc_schema();
+        let mut graphdb = new_graphdb();
+        // no schema, any property is accepted
+        let prop = Row::from(vec![json!("John"), json!(15)]);
+        assert!(graphdb
+            .add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], prop)
+            .unwrap()
+            .is_none());
+
+        graphdb.set_schema(schema.clone());
+        let prop = Row::from(vec![json!(15), json!("John")]);
+        assert!(graphdb
+            .add_vertex_with_properties(PIDS[1], [1, INVALID_LABEL_ID], prop)
+            .unwrap()
+            .is_none());
+        // wrong type of firstName
+        let prop = Row::from(vec![json!(16), json!(16)]);
+        assert_eq!(
+            graphdb.add_vertex_with_properties(PIDS[2], [1, INVALID_LABEL_ID], prop.clone()),
+            Err(GDBError::InvalidTypeError)
+        );
+        // the vertex is not added
+        assert!(!graphdb.is_vertex_local(PIDS[2]));
+        assert_eq!(
+            graphdb.add_or_update_vertex_properties(PIDS[1], prop),
+            Err(GDBError::InvalidTypeError)
+        );
+        // more properties than the header of PERSON
+        let prop = Row::from((0..9).map(|i| json!(i)).collect::<Vec<_>>());
+        assert_eq!(
+            graphdb.add_vertex_with_properties(PIDS[2], [1, INVALID_LABEL_ID], prop),
+            Err(GDBError::FieldNotExistError)
+        );
+        assert!(graphdb.add_vertex(PIDS[2], [1, INVALID_LABEL_ID]));
+
+        let edge_prop = Row::from(vec![json!(20200202_u64)]);
+        assert!(graphdb
+            .add_edge_with_properties(PIDS[1], PIDS[2], 12, edge_prop)
+            .unwrap()
+            .is_none());
+        let edge_prop = Row::from(vec![json!("2020-02-02")]);
+        assert_eq!(
+            graphdb.add_edge_with_properties(PIDS[1], PIDS[2], 12, edge_prop),
+            Err(GDBError::InvalidTypeError)
+        );
+        assert_eq!(1, graphdb.edge_count());
+
+        // the vertices before the invalid one are added
+        let batches = vec![
+            (PIDS[3], [1, INVALID_LABEL_ID], Row::from(vec![json!(3), json!("Jack")])),
+            (PIDS[4], [1, INVALID_LABEL_ID], Row::from(vec![json!(4), json!(4)])),
+            (PIDS[5], [1, INVALID_LABEL_ID], Row::from(vec![json!(5), json!("Tom")])),
+        ];
+        assert_eq!(
+            graphdb.add_vertex_batches(batches.into_iter()),
+            Err(GDBError::InvalidTypeError)
+        );
+        assert!(graphdb.is_vertex_local(PIDS[3]));
+        assert!(!graphdb.is_vertex_local(PIDS[4]));
+        assert!(!graphdb.is_vertex_local(PIDS[5]));
+
+        let graph = graphdb.into_graph(schema);
+        assert_eq!(
+            Some(&json!("John")),
+            graph.get_vertex(PIDS[1]).unwrap().get_property("firstName")
+        );
+        assert_eq!(
+            Some(&json!("Jack")),
+            graph.get_vertex(PIDS[3]).unwrap().get_property("firstName")
+        );
+    }
+
+    #[test]
+    fn test_property_index() {
+        // the indices are kept consistent with the writes as tested through `GlobalStoreUpdate`
+        // in `graph_db`, which are carried into the graph here
+        let schema = ldbc_schema();
+        let mut graphdb = new_graphdb();
+        graphdb
+            .add_vertex_with_properties(
+                PIDS[0],
+                [1, INVALID_LABEL_ID],
+                Row::from(vec![json!(0), json!("John")]),
+            )
+            .unwrap();
+        // the schema is required to locate the property
+        assert_eq!(graphdb.create_vertex_index(1, "firstName"), Err(GDBError::FieldNotExistError));
+        graphdb.set_schema(schema.clone());
+        graphdb.create_vertex_index(1, "firstName").unwrap();
+
+        let graph = graphdb.into_graph(schema);
+        assert_eq!(
+            graph.lookup_by_property(1, "firstName", &json!("John")).unwrap(),
+            vec![PIDS[0]]
+        );
+        assert_eq!(
+            graph.lookup_by_property(2, "content", &json!("x")),
+            Err(GDBError::IndexNotFoundError)
+        );
+    }
+
+    #[test]
+    fn test_delete_property() {
+        let schema = ldbc_schema();
+        let mut graphdb = new_graphdb();
+        graphdb
+            .add_vertex_with_properties(
+                PIDS[0],
+                [1, INVALID_LABEL_ID],
+                Row::from(vec![json!(0), json!("John"), json!("Doe")]),
+            )
+            .unwrap();
+        graphdb.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+        graphdb.add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::from(20200202_u64)).unwrap();
+        // the schema is required to locate the property
+        assert_eq!(
+            graphdb.delete_property(ElementId::Vertex(PIDS[0]), "firstName"),
+            Err(GDBError::FieldNotExistError)
+        );
+        graphdb.set_schema(schema);
+        graphdb.create_vertex_index(1, "firstName").unwrap();
+        let internal_id = graphdb.index_data.get_internal_id(PIDS[0]).unwrap();
+        let get_row =
+            |graphdb: &MutableGraphDB<DefaultId, InternalId, PropertyTable, PropertyTable>| {
+                graphdb.vertex_prop_table.get_row(internal_id.index()).unwrap().into_row()
+            };
+
+        // a property before a present one leaves a null, which is read and validated as absent
+        graphdb.delete_property(ElementId::Vertex(PIDS[0]), "firstName").unwrap();
+        let row = get_row(&graphdb).unwrap();
+        assert_eq!(row, Row::from(vec![json!(0), JsonValue::Null, json!("Doe")]));
+        let header = graphdb.schema.as_ref().unwrap().get_vertex_schema(1);
+        let properties = RowWithSchema::new(Some(RowRef::Ref(&row)), header).unwrap();
+        assert_eq!(properties.get("firstName"), None);
+        assert_eq!(properties.get("lastName"), Some(&json!("Doe")));
+        assert_eq!(properties.into_properties(), Some(json!({"id": 0, "lastName": "Doe"})));
+        assert!(graphdb.add_or_update_vertex_properties(PIDS[0], row).is_ok());
+        assert!(graphdb.lookup_by_property(1, "firstName", &json!("John")).unwrap().is_empty());
+        assert!(graphdb.lookup_by_property(1, "firstName", &JsonValue::Null).unwrap().is_empty());
+
+        // the last property is removed with the nulls before it
+        graphdb.delete_property(ElementId::Vertex(PIDS[0]), "lastName").unwrap();
+        assert_eq!(get_row(&graphdb), Some(Row::from(vec![json!(0)])));
+        // deleted already, absent in the row, or not in the schema
+        for key in ["firstName", "lastName", "gender", "noSuchKey"] {
+            assert_eq!(
+                graphdb.delete_property(ElementId::Vertex(PIDS[0]), key),
+                Err(GDBError::FieldNotExistError)
+            );
+        }
+        // the row is removed without any property
+        graphdb.delete_property(ElementId::Vertex(PIDS[0]), "id").unwrap();
+        assert!(get_row(&graphdb).map(|row| row.is_empty()).unwrap_or(true));
+        assert_eq!(
+            graphdb.delete_property(ElementId::Vertex(PIDS[8]), "firstName"),
+            Err(GDBError::VertexNotFoundError)
+        );
+
+        let edge = ElementId::Edge(PIDS[0], PIDS[1], 12);
+        graphdb.delete_property(edge, "creationDate").unwrap();
+        assert!(graphdb
+            .edge_prop_table
+            .get_row(0)
+            .unwrap()
+            .into_row()
+            .map(|row| row.is_empty())
+            .unwrap_or(true));
+        assert_eq!(
+            graphdb.delete_property(edge, "creationDate"),
+            Err(GDBError::FieldNotExistError)
+        );
+        assert_eq!(graphdb.delete_property(edge, "firstName"), Err(GDBError::FieldNotExistError));
+        for edge in [
+            ElementId::Edge(PIDS[1], PIDS[0], 12),
+            ElementId::Edge(PIDS[0], PIDS[1], 13),
+            ElementId::Edge(PIDS[0], PIDS[8], 12),
+        ] {
+            assert_eq!(
+                graphdb.delete_property(edge, "creationDate"),
+                Err(GDBError::EdgeNotFoundError)
+            );
+        }
+    }
+
+    #[test]
+    fn test_delete_parallel_edge_property() {
+        let mut graphdb = new_graphdb();
+        graphdb.set_schema(ldbc_schema());
+        graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]);
+        graphdb.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+        // two parallel edges, where only the second one has the property
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        graphdb.add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::from(20200202_u64)).unwrap();
+        let get_row = |graphdb: &MutableGraphDB<
+            DefaultId,
+            InternalId,
+            PropertyTable,
+            PropertyTable,
+        >,
+                       index: usize| {
+            graphdb.edge_prop_table.get_row(index).unwrap().into_row().filter(|row| !row.is_empty())
+        };
+        assert_eq!(get_row(&graphdb, 0), None);
+        assert_eq!(get_row(&graphdb, 1), Some(Row::from(20200202_u64)));
+
+        let edge = ElementId::Edge(PIDS[0], PIDS[1], 12);
+        graphdb.delete_property(edge, "creationDate").unwrap();
+        assert_eq!(get_row(&graphdb, 0), None);
+        assert_eq!(get_row(&graphdb, 1), None);
+        assert_eq!(graphdb.edge_count(), 2);
+        // absent in both of them now
+        assert_eq!(
+            graphdb.delete_property(edge, "creationDate"),
+            Err(GDBError::FieldNotExistError)
+        );
+    }
+
+    #[test]
+    fn test_delete_property_read_only() {
+        let dir = tempdir::TempDir::new("test_delete_property_read_only").unwrap();
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable> =
+            new_graphdb_in(dir.path());
+        graphdb.set_schema(ldbc_schema());
+        let row = Row::from(vec![json!(0), json!("John")]);
+        graphdb.add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], row.clone()).unwrap();
+        graphdb.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+        graphdb.add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::from(20200202_u64)).unwrap();
+
+        graphdb.set_read_only(true);
+        assert_eq!(
+            graphdb.delete_property(ElementId::Vertex(PIDS[0]), "firstName"),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+        assert_eq!(
+            graphdb.delete_property(ElementId::Edge(PIDS[0], PIDS[1], 12), "creationDate"),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+
+        // nothing is changed
+        let internal_id = graphdb.index_data.get_internal_id(PIDS[0]).unwrap();
+        assert_eq!(
+            graphdb.vertex_prop_table.get_row(internal_id.index()).unwrap().into_row(),
+            Some(row)
+        );
+        assert_eq!(
+            graphdb.edge_prop_table.get_row(0).unwrap().into_row(),
+            Some(Row::from(20200202_u64))
+        );
+    }
+
+    #[test]
+    fn test_read_only() {
+        // the writes through `GlobalStoreUpdate` are tested in `graph_db`, and those of the
+        // batches, which leave the graph structure unchanged, are tested here
+        let mut graphdb = new_graphdb();
+        graphdb.set_schema(ldbc_schema());
+        let row = Row::from(vec![json!(0), json!("John")]);
+        graphdb.add_vertex_with_properties(PIDS[0], [1, INVALID_LABEL_ID], row.clone()).unwrap();
+        graphdb.add_vertex(PIDS[1], [1, INVALID_LABEL_ID]);
+
+        graphdb.set_read_only(true);
+        assert!(!graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        assert_eq!(graphdb.remove_vertex_properties(PIDS[0]), Err(GDBError::ModifyReadOnlyError));
+        let mut batch = graphdb.write_batch();
+        batch.delete_vertex(PIDS[1]);
+        assert_eq!(batch.commit(), Err(GDBError::ModifyReadOnlyError));
+
+        // nothing is changed
+        assert_eq!(graphdb.node_count(), 2);
+        assert_eq!(graphdb.edge_count(), 0);
+        let internal_id = graphdb.index_data.get_internal_id(PIDS[0]).unwrap();
+        assert_eq!(
+            graphdb.vertex_prop_table.get_row(internal_id.index()).unwrap().into_row(),
+            Some(row)
+        );
+
+        graphdb.set_read_only(false);
+        let mut batch = graphdb.write_batch();
+        batch.delete_vertex(PIDS[1]);
+        assert!(batch.commit().is_ok());
+        assert_eq!(graphdb.node_count(), 1);
+    }
+
+    #[test]
+    fn test_write_batch() {
+        let dir = tempdir::TempDir::new("test_write_batch").unwrap();
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable> =
+            new_graphdb_in(dir.path());
+        fn get_row(
+            graphdb: &MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable>, id: DefaultId,
+        ) -> Option<Row> {
+            let internal_id = graphdb.index_data.get_internal_id(id)?;
+            graphdb.vertex_prop_table.get_row(internal_id.index()).unwrap().into_row()
+        }
+
+        let mut batch = graphdb.write_batch();
+        batch
+            .add_vertex(PIDS[0], [1, INVALID_LABEL_ID], Row::from(vec![json!(0), json!("John")]))
+            .add_vertex(PIDS[1], [1, INVALID_LABEL_ID], Row::from(vec![json!(1), json!("Tom")]))
+            .add_vertex(PIDS[2], [1, INVALID_LABEL_ID], Row::from(vec![json!(2), json!("Jack")]))
+            .add_edge(PIDS[0], PIDS[1], 12, Row::from(20200202_u64))
+            .add_edge(PIDS[1], PIDS[2], 12, Row::from(20200203_u64));
+        assert_eq!(batch.len(), 5);
+        batch.commit().unwrap();
+        assert_eq!(graphdb.node_count(), 3);
+        assert_eq!(graphdb.edge_count(), 2);
+        assert_eq!(get_row(&graphdb, PIDS[1]), Some(Row::from(vec![json!(1), json!("Tom")])));
+
+        // the last vertex and edge are moved to the ids of the deleted ones
+        let mut batch = graphdb.write_batch();
+        batch.delete_vertex(PIDS[0]);
+        batch.commit().unwrap();
+        assert!(!graphdb.is_vertex_local(PIDS[0]));
+        assert_eq!(get_row(&graphdb, PIDS[0]), None);
+        assert_eq!(get_row(&graphdb, PIDS[2]), Some(Row::from(vec![json!(2), json!("Jack")])));
+        assert_eq!(graphdb.node_count(), 2);
+        assert_eq!(graphdb.edge_count(), 1);
+        assert_eq!(
+            graphdb.edge_prop_table.get_row(0).unwrap().into_row(),
+            Some(Row::from(20200203_u64))
+        );
+        assert_eq!(graphdb.edge_prop_table.get_row(1).unwrap().into_row(), None);
+        let vertices: Vec<_> = graphdb
+            .index_data
+            .get_indices_of_label(1)
+            .map(|index| graphdb.index_data.get_global_id(index).unwrap())
+            .collect();
+        assert_eq!(vertices, vec![PIDS[1], PIDS[2]]);
+
+        // fail in the middle, as `PIDS[1]` is deleted before adding its edge
+        let mut batch = graphdb.write_batch();
+        batch
+            .add_vertex(PIDS[3], [1, INVALID_LABEL_ID], Row::from(vec![json!(3), json!("Mike")]))
+            .add_edge(PIDS[3], PIDS[1], 12, Row::from(20200204_u64))
+            .delete_vertex(PIDS[1])
+            .add_edge(PIDS[1], PIDS[2], 12, Row::from(20200205_u64));
+        assert_eq!(batch.commit(), Err(GDBError::EdgeNotFoundError));
+        assert!(!graphdb.is_vertex_local(PIDS[3]));
+        assert!(graphdb.is_vertex_local(PIDS[1]));
+        assert_eq!(graphdb.node_count(), 2);
+        assert_eq!(graphdb.edge_count(), 1);
+        assert_eq!(get_row(&graphdb, PIDS[1]), Some(Row::from(vec![json!(1), json!("Tom")])));
+        assert_eq!(get_row(&graphdb, PIDS[3]), None);
+        assert_eq!(graphdb.edge_prop_table.get_row(1).unwrap().into_row(), None);
+
+        let mut batch = graphdb.write_batch();
+        batch.delete_vertex(PIDS[8]);
+        assert_eq!(batch.commit(), Err(GDBError::VertexNotFoundError));
+        // dropped without committing
+        graphdb.write_batch().delete_vertex(PIDS[1]);
+        assert!(graphdb.is_vertex_local(PIDS[1]));
+    }
+
+    #[test]
+    fn test_graph_snapshot() {
+        let dir = tempdir::TempDir::new("test_graph_snapshot").unwrap();
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable> =
+            new_graphdb_in(dir.path());
+        let mut batch = graphdb.write_batch();
+        batch
+            .add_vertex(PIDS[0], [1, INVALID_LABEL_ID], Row::from(vec![json!(0), json!("John")]))
+            .add_vertex(PIDS[1], [1, INVALID_LABEL_ID], Row::from(vec![json!(1), json!("Tom")]))
+            .add_edge(PIDS[0], PIDS[1], 12, Row::from(0_u64));
+        batch.commit().unwrap();
+        let schema = ldbc_schema();
+        let graph = Arc::new(graphdb.into_graph(schema));
+
+        let snapshot = graph.snapshot();
+        let writer = {
+            let graph = graph.clone();
+            std::thread::spawn(move || {
+                // the vertex and the edge are updated together in each round
+                for round in 1..=10_u64 {
+                    let mut batch = TableBatch::default();
+                    let vertex_row = Row::from(vec![json!(0), json!(format!("John_{}", round))]);
+                    graph
+                        .vertex_prop_table
+                        .stage_writes(&[(0, Some(vertex_row))], &mut batch)
+                        .unwrap();
+                    graph
+                        .edge_prop_table
+                        .stage_writes(&[(0, Some(Row::from(round)))], &mut batch)
+                        .unwrap();
+                    batch.commit().unwrap();
+                }
+            })
+        };
+
+        // the view of the snapshot is unchanged while, and after the writes
+        let check_snapshot =
+            |snapshot: &GraphSnapshot<DefaultId, InternalId, RocksTable, RocksTable>| {
+                let vertex = snapshot.get_vertex(PIDS[0]).unwrap();
+                assert_eq!(vertex.get_property("firstName"), Some(&json!("John")));
+                let edges: Vec<LocalEdge<DefaultId, InternalId>> =
+                    snapshot.get_out_edges(PIDS[0], None).collect();
+                assert_eq!(edges.len(), 1);
+                assert_eq!(edges[0].get_property("creationDate"), Some(&json!(0)));
+                let vertices = snapshot.get_vertices(&[PIDS[1], PIDS[0]]).unwrap();
+                assert_eq!(
+                    vertices[1].as_ref().unwrap().get_property("firstName"),
+                    Some(&json!("John"))
+                );
+            };
+        for _ in 0..10 {
+            check_snapshot(&snapshot);
+        }
+        writer.join().unwrap();
+        check_snapshot(&snapshot);
+        drop(snapshot);
+
+        // the graph, and a new snapshot see the writes of the last round
+        let snapshot = graph.snapshot();
+        let vertex = graph.get_vertex(PIDS[0]).unwrap();
+        assert_eq!(vertex.get_property("firstName"), Some(&json!("John_10")));
+        let vertex = snapshot.get_vertex(PIDS[0]).unwrap();
+        assert_eq!(vertex.get_property("firstName"), Some(&json!("John_10")));
+        let edge = snapshot.get_out_edges(PIDS[0], None).next().unwrap();
+        assert_eq!(edge.get_property("creationDate"), Some(&json!(10)));
+        assert_eq!(snapshot.count_all_vertices(None), 2);
+    }
+
+    #[test]
+    fn test_write_batch_moves() {
+        let mut graphdb = new_graphdb();
+        let person = |i: usize| Row::from(vec![json!(i), json!(format!("person_{}", i))]);
+        let mut batch = graphdb.write_batch();
+        for (i, pid) in PIDS.iter().enumerate().take(6) {
+            batch.add_vertex(*pid, [1, INVALID_LABEL_ID], person(i));
+        }
+        for (src, dst, date) in
+            vec![(0, 1, 100_u64), (1, 2, 101), (2, 2, 102), (3, 0, 103), (4, 5, 104), (0, 4, 105)]
+        {
+            batch.add_edge(PIDS[src], PIDS[dst], 12, Row::from(date));
+        }
+        batch.commit().unwrap();
+        assert!(graphdb.add_corner_vertex(CIDS[0], 2));
+        assert!(graphdb.add_edge_with_properties(PIDS[5], CIDS[0], 13, Row::from(106_u64)).is_ok());
+
+        // the vertices and edges are removed in between of those added, including self-loops, a
+        // corner vertex, and a vertex added again after it is deleted
+        let mut batch = graphdb.write_batch();
+        batch
+            .delete_vertex(PIDS[2])
+            .add_vertex(PIDS[6], [1, INVALID_LABEL_ID], person(6))
+            .add_edge(PIDS[6], PIDS[0], 12, Row::from(107_u64))
+            .delete_vertex(PIDS[0])
+            .add_edge(PIDS[6], PIDS[6], 12, Row::from(108_u64))
+            .delete_vertex(CIDS[0])
+            .add_vertex(PIDS[2], [1, INVALID_LABEL_ID], person(22))
+            .add_edge(PIDS[2], PIDS[6], 12, Row::from(109_u64))
+            .add_vertex(PIDS[4], [1, INVALID_LABEL_ID], person(44));
+        batch.commit().unwrap();
+
+        let expected_vertices: HashMap<DefaultId, Row> = vec![
+            (PIDS[1], person(1)),
+            (PIDS[2], person(22)),
+            (PIDS[3], person(3)),
+            (PIDS[4], person(44)),
+            (PIDS[5], person(5)),
+            (PIDS[6], person(6)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(graphdb.node_count(), expected_vertices.len());
+        for index in graphdb.graph.node_indices() {
+            let global_id = graphdb.index_data.get_global_id(index).unwrap();
+            assert_eq!(graphdb.index_data.get_internal_id(global_id), Some(index));
+            let row = graphdb.vertex_prop_table.get_row(index.index()).unwrap().into_row();
+            assert_eq!(row.as_ref(), expected_vertices.get(&global_id));
+        }
+        assert!(graphdb.index_data.get_internal_id(PIDS[0]).is_none());
+        assert!(graphdb.index_data.get_internal_id(CIDS[0]).is_none());
+
+        let mut edges: Vec<(DefaultId, DefaultId, Option<Row>)> = graphdb
+            .graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    graphdb.index_data.get_global_id(edge.source()).unwrap(),
+                    graphdb.index_data.get_global_id(edge.target()).unwrap(),
+                    graphdb.edge_prop_table.get_row(edge.id().index()).unwrap().into_row(),
+                )
+            })
+            .collect();
+        edges.sort_by_key(|(src, dst, _)| (*src, *dst));
+        assert_eq!(
+            edges,
+            vec![
+                (PIDS[2], PIDS[6], Some(Row::from(109_u64))),
+                (PIDS[4], PIDS[5], Some(Row::from(104_u64))),
+                (PIDS[6], PIDS[6], Some(Row::from(108_u64))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_batch_storage_failure() {
+        let dir = tempdir::TempDir::new("test_write_batch_storage_failure").unwrap();
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, RocksTable, RocksTable> =
+            new_graphdb_in(dir.path());
+        let mut batch = graphdb.write_batch();
+        batch
+            .add_vertex(PIDS[0], [1, INVALID_LABEL_ID], Row::from(vec![json!(0), json!("John")]))
+            .add_vertex(PIDS[1], [1, INVALID_LABEL_ID], Row::from(vec![json!(1), json!("Tom")]))
+            .add_edge(PIDS[0], PIDS[1], 12, Row::from(20200202_u64))
+            .add_edge(PIDS[1], PIDS[0], 12, Row::from(20200203_u64));
+        batch.commit().unwrap();
+        graphdb.vertex_prop_table.flush().unwrap();
+
+        // the properties of the vertices and the edges are stored in one RocksDB, which is opened
+        // in the read-only mode of RocksDB, so that the commit fails in writing to RocksDB
+        let partition_dir =
+            dir.path().join(DIR_BINARY_DATA).join(format!("{}{}", PARTITION_PREFIX, 0));
+        assert!(!partition_dir.join(FILE_EDGE_PPT_DATA).exists());
+        let vertex_prop_table =
+            RocksTable::open_rocks_read_only(partition_dir.join(FILE_NODE_PPT_DATA)).unwrap();
+        graphdb.edge_prop_table = RocksTable::new_shared(
+            partition_dir.join(FILE_EDGE_PPT_DATA),
+            vertex_prop_table.rocks_db(),
+        );
+        graphdb.vertex_prop_table = vertex_prop_table;
+
+        let mut batch = graphdb.write_batch();
+        batch
+            .add_vertex(PIDS[2], [1, INVALID_LABEL_ID], Row::from(vec![json!(2), json!("Jack")]))
+            .add_edge(PIDS[2], PIDS[1], 12, Row::from(20200204_u64))
+            .delete_vertex(PIDS[0]);
+        match batch.commit() {
+            Err(GDBError::RocksError(_)) => {}
+            other => panic!("expect RocksError, got {:?}", other),
+        }
+
+        // both the graph structure and the properties are unchanged
+        assert_eq!(graphdb.node_count(), 2);
+        assert_eq!(graphdb.edge_count(), 2);
+        assert!(graphdb.is_vertex_local(PIDS[0]));
+        assert!(!graphdb.is_vertex_local(PIDS[2]));
+        for (i, pid) in PIDS.iter().enumerate().take(2) {
+            let internal_id = graphdb.index_data.get_internal_id(*pid).unwrap();
+            assert_eq!(internal_id.index(), i);
+            assert_eq!(
+                graphdb
+                    .vertex_prop_table
+                    .get_row(i)
+                    .unwrap()
+                    .into_row()
+                    .and_then(|row| row.get(0).cloned()),
+                Some(json!(i))
+            );
+        }
+        assert_eq!(graphdb.vertex_prop_table.get_row(2).unwrap(), RowRef::None);
+        assert_eq!(
+            graphdb.edge_prop_table.get_rows(&[0, 1, 2]).unwrap(),
+            vec![
+                RowRef::Owned(Row::from(20200202_u64)),
+                RowRef::Owned(Row::from(20200203_u64)),
+                RowRef::None
+            ]
+        );
+        let edges: Vec<(usize, usize)> = graphdb
+            .graph
+            .edge_references()
+            .map(|edge| (edge.source().index(), edge.target().index()))
+            .collect();
+        assert_eq!(edges, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_scan_vertices_by_label() {
+        let mut graphdb = new_graphdb();
+        // add the vertices of labels in interleaved and descending order of ids
+        for i in (0..5).rev() {
+            let prop = Row::from(vec![json!(i), json!(format!("person_{}", i))]);
+            graphdb.add_vertex_with_properties(PIDS[i], [1, INVALID_LABEL_ID], prop).unwrap();
+            let label = if i % 2 == 0 { [2, 3] } else { [2, INVALID_LABEL_ID] };
+            assert!(graphdb.add_vertex(CIDS[i], label));
+        }
+        let schema = ldbc_schema();
+        let graph = graphdb.into_graph(schema);
+
+        let persons: Vec<LocalVertex<DefaultId>> =
+            graph.scan_vertices_by_label(1).map(|v| v.unwrap()).collect();
+        assert_eq!(PIDS[0..5].to_vec(), persons.iter().map(|v| v.get_id()).collect::<Vec<_>>());
+        for (i, v) in persons.iter().enumerate() {
+            assert_eq!(1, v.get_label()[0]);
+            assert_eq!(Some(&json!(format!("person_{}", i))), v.get_property("firstName"));
+        }
+
+        let comments: Vec<DefaultId> =
+            graph.scan_vertices_by_label(2).map(|v| v.unwrap().get_id()).collect();
+        assert_eq!(CIDS[0..5].to_vec(), comments);
+        // the vertices of a secondary label
+        let secondary: Vec<DefaultId> =
+            graph.scan_vertices_by_label(3).map(|v| v.unwrap().get_id()).collect();
+        assert_eq!(vec![CIDS[0], CIDS[2], CIDS[4]], secondary);
+        // no vertex of the label
+        assert_eq!(0, graph.scan_vertices_by_label(5).count());
+        assert_eq!(0, graph.scan_vertices_by_label(100).count());
+    }
+
+    #[test]
+    fn test_scan_vertices_stale_index() {
+        let mut graphdb = new_graphdb();
+        assert!(graphdb.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
+        let schema = ldbc_schema();
+        let mut graph = graphdb.into_graph(schema);
+        // an index of the label whose vertex is absent in the topology
+        graph.index_data.add_vertex(PIDS[1], [1, INVALID_LABEL_ID], NodeIndex::new(10), false);
+
+        let scanned: Vec<GDBResult<DefaultId>> =
+            graph.scan_vertices_by_label(1).map(|v| v.map(|v| v.get_id())).collect();
+        assert_eq!(vec![Ok(PIDS[0]), Err(GDBError::VertexNotFoundError)], scanned);
+    }
+
+    #[test]
+    fn test_get_vertices_stale_index() {
+        let mut graphdb = new_graphdb();
+        for pid in PIDS.iter().take(2) {
+            let prop = Row::from(vec![json!(*pid), json!("person")]);
+            graphdb.add_vertex_with_properties(*pid, [1, INVALID_LABEL_ID], prop).unwrap();
+        }
+        let schema = ldbc_schema();
+        let mut graph = graphdb.into_graph(schema);
+        // an index whose vertex is absent in the topology
+        graph.index_data.add_vertex(PIDS[2], [1, INVALID_LABEL_ID], NodeIndex::new(10), false);
+
+        let vertices = graph.get_vertices(&[PIDS[2], PIDS[1], PIDS[0]]).unwrap();
+        assert!(vertices[0].is_none());
+        // the rows of the following vertices are kept in position
+        for (vertex, id) in vertices[1..].iter().zip(vec![PIDS[1], PIDS[0]]) {
+            let vertex = vertex.as_ref().unwrap();
+            assert_eq!(id, vertex.get_id());
+            assert_eq!(Some(&json!(id)), vertex.get_property(ID_FIELD));
+        }
+    }
+
+    #[test]
+    fn test_adj_directions() {
+        let mut graphdb = new_graphdb();
+        for pid in PIDS.iter().take(3) {
+            assert!(graphdb.add_vertex(*pid, [1, INVALID_LABEL_ID]));
+        }
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        assert!(graphdb.add_edge(PIDS[2], PIDS[0], 12));
+        assert!(graphdb.add_edge(PIDS[1], PIDS[0], 13));
+        // a self-loop
+        assert!(graphdb.add_edge(PIDS[0], PIDS[0], 12));
+        let schema = ldbc_schema();
+        let graph = graphdb.into_graph(schema);
+
+        let sorted = |mut ids: Vec<DefaultId>| {
+            ids.sort();
+            ids
+        };
+        let out_vertices = graph.get_out_vertices(PIDS[0], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[0], PIDS[1]], sorted(out_vertices));
+        let in_vertices = graph.get_in_vertices(PIDS[0], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[0], PIDS[1], PIDS[2]], sorted(in_vertices));
+        // the self-loop is not counted twice
+        let both_vertices = graph.get_both_vertices(PIDS[0], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[0], PIDS[1], PIDS[1], PIDS[2]], sorted(both_vertices));
+        let both_vertices =
+            graph.get_both_vertices(PIDS[0], Some(&vec![12])).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[0], PIDS[1], PIDS[2]], sorted(both_vertices));
+
+        let edges = |iter: Iter<LocalEdge<DefaultId, InternalId>>| {
+            let mut edges: Vec<(DefaultId, DefaultId, LabelId)> =
+                iter.map(|e| (e.get_src_id(), e.get_dst_id(), e.get_label())).collect();
+            edges.sort();
+            edges
+        };
+        assert_eq!(
+            vec![(PIDS[0], PIDS[0], 12), (PIDS[0], PIDS[1], 12)],
+            edges(graph.get_out_edges(PIDS[0], None))
+        );
+        assert_eq!(
+            vec![(PIDS[0], PIDS[0], 12), (PIDS[1], PIDS[0], 13), (PIDS[2], PIDS[0], 12)],
+            edges(graph.get_in_edges(PIDS[0], None))
+        );
+        assert_eq!(
+            vec![
+                (PIDS[0], PIDS[0], 12),
+                (PIDS[0], PIDS[1], 12),
+                (PIDS[1], PIDS[0], 13),
+                (PIDS[2], PIDS[0], 12)
+            ],
+            edges(graph.get_both_edges(PIDS[0], None))
+        );
+        assert_eq!(
+            vec![(PIDS[1], PIDS[0], 13)],
+            edges(graph.get_both_edges(PIDS[0], Some(&vec![13])))
+        );
+        // the edges of opposite directions between two vertices are not self-loops
+        let both_vertices = graph.get_both_vertices(PIDS[1], None).map(|v| v.get_id()).collect();
+        assert_eq!(vec![PIDS[0], PIDS[0]], sorted(both_vertices));
+    }
+
+    #[test]
+    fn test_degree() {
+        let mut graphdb = new_graphdb();
+        for pid in PIDS.iter().take(3) {
+            assert!(graphdb.add_vertex(*pid, [1, INVALID_LABEL_ID]));
+        }
+        // parallel edges are counted respectively
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        assert!(graphdb.add_edge(PIDS[2], PIDS[0], 12));
+        assert!(graphdb.add_edge(PIDS[1], PIDS[0], 13));
+        // a self-loop
+        assert!(graphdb.add_edge(PIDS[0], PIDS[0], 12));
+        let schema = ldbc_schema();
+        let graph = graphdb.into_graph(schema);
+
+        let degrees = |global_id: DefaultId, labels: Option<&Vec<LabelId>>| {
+            (
+                graph.out_degree(global_id, labels).unwrap(),
+                graph.in_degree(global_id, labels).unwrap(),
+                graph.both_degree(global_id, labels).unwrap(),
+            )
+        };
+        // the self-loop is counted once in both directions
+        assert_eq!((3, 3, 5), degrees(PIDS[0], None));
+        assert_eq!((3, 2, 4), degrees(PIDS[0], Some(&vec![12])));
+        assert_eq!((0, 1, 1), degrees(PIDS[0], Some(&vec![13])));
+        assert_eq!((3, 3, 5), degrees(PIDS[0], Some(&vec![12, 13])));
+        assert_eq!((1, 2, 3), degrees(PIDS[1], None));
+        assert_eq!((1, 0, 1), degrees(PIDS[2], None));
+        assert_eq!((0, 0, 0), degrees(PIDS[2], Some(&vec![14])));
+        assert_eq!((0, 0, 0), degrees(PIDS[8], None));
+
+        for pid in PIDS.iter().take(3) {
+            for labels in vec![None, Some(vec![12]), Some(vec![13])] {
+                assert_eq!(
+                    graph.get_both_edges(*pid, labels.as_ref()).count(),
+                    graph.both_degree(*pid, labels.as_ref()).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighborhood() {
+        let mut graphdb = new_graphdb();
+        for pid in PIDS.iter().take(6) {
+            assert!(graphdb.add_vertex(*pid, [1, INVALID_LABEL_ID]));
+        }
+        // a chain 0 -> 1 -> 2 -> 3 of label 12, with a shortcut 0 -> 2 of label 13
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 12));
+        assert!(graphdb.add_edge(PIDS[1], PIDS[2], 12));
+        assert!(graphdb.add_edge(PIDS[2], PIDS[3], 12));
+        assert!(graphdb.add_edge(PIDS[0], PIDS[2], 13));
+        // a cycle 4 -> 5 -> 4, with a self-loop on 5
+        assert!(graphdb.add_edge(PIDS[4], PIDS[5], 12));
+        assert!(graphdb.add_edge(PIDS[5], PIDS[4], 12));
+        assert!(graphdb.add_edge(PIDS[5], PIDS[5], 12));
+        let schema = ldbc_schema();
+        let graph = graphdb.into_graph(schema);
+
+        let sorted = |mut ids: Vec<DefaultId>| {
+            ids.sort();
+            ids
+        };
+        let out = ExpandDirection::Out;
+        // 1-hop
+        assert_eq!(
+            vec![PIDS[1], PIDS[2]],
+            sorted(graph.neighborhood(PIDS[0], 1, out, None).unwrap())
+        );
+        assert_eq!(vec![PIDS[1]], graph.neighborhood(PIDS[0], 1, out, Some(&vec![12])).unwrap());
+        assert_eq!(
+            vec![PIDS[0], PIDS[1]],
+            sorted(graph.neighborhood(PIDS[2], 2, ExpandDirection::In, None).unwrap())
+        );
+        // 2-hop, where PIDS[2] is returned once at the first hop it is reached
+        assert_eq!(
+            vec![PIDS[1], PIDS[2], PIDS[3]],
+            sorted(graph.neighborhood(PIDS[0], 2, out, None).unwrap())
+        );
+        assert_eq!(
+            vec![PIDS[1], PIDS[2]],
+            graph.neighborhood(PIDS[0], 2, out, Some(&vec![12])).unwrap()
+        );
+        assert_eq!(
+            vec![PIDS[1], PIDS[2], PIDS[3]],
+            graph.neighborhood(PIDS[0], 5, out, Some(&vec![12])).unwrap()
+        );
+        // a cycle neither returns the start vertex, nor expands forever
+        assert_eq!(vec![PIDS[5]], graph.neighborhood(PIDS[4], 1, out, None).unwrap());
+        assert_eq!(vec![PIDS[5]], graph.neighborhood(PIDS[4], 10, out, None).unwrap());
+        assert_eq!(vec![PIDS[4]], graph.neighborhood(PIDS[5], 10, out, None).unwrap());
+
+        assert!(graph.neighborhood(PIDS[0], 0, out, None).unwrap().is_empty());
+        assert!(graph.neighborhood(PIDS[3], 2, out, None).unwrap().is_empty());
+        assert!(graph.neighborhood(PIDS[8], 2, out, None).unwrap().is_empty());
+
+        // both directions, where the outgoing neighbors are reached before the incoming ones
+        let both = ExpandDirection::Both;
+        assert_eq!(vec![PIDS[2], PIDS[0]], graph.neighborhood(PIDS[1], 1, both, None).unwrap());
+        assert_eq!(vec![PIDS[2]], graph.neighborhood(PIDS[3], 1, both, None).unwrap());
+        assert_eq!(
+            vec![PIDS[0], PIDS[1], PIDS[2]],
+            sorted(graph.neighborhood(PIDS[3], 2, both, None).unwrap())
+        );
+        assert_eq!(
+            vec![PIDS[2], PIDS[1], PIDS[0]],
+            graph.neighborhood(PIDS[3], 3, both, Some(&vec![12])).unwrap()
+        );
+        // the neighbor by edges of both directions and the self-loop are returned once
+        assert_eq!(vec![PIDS[4]], graph.neighborhood(PIDS[5], 10, both, None).unwrap());
+    }
+
+    #[test]
+    fn test_scan_all_edges() {
+        let dir = tempdir::TempDir::new("test_scan_all_edges").unwrap();
+        let mut graphdb: MutableGraphDB<DefaultId, InternalId, PropertyTable, RocksTable> =
+            new_graphdb_in(dir.path());
+        for pid in PIDS.iter().take(4) {
+            assert!(graphdb.add_vertex(*pid, [1, INVALID_LABEL_ID]));
+        }
+        // edges between every pair of vertices, with parallel edges and self-loops
+        let mut expected = vec![];
+        for (i, src_id) in PIDS.iter().take(4).enumerate() {
+            for (j, dst_id) in PIDS.iter().take(4).enumerate() {
+                let date = (i * 10 + j) as u64;
+                graphdb
+                    .add_edge_with_properties(*src_id, *dst_id, 12, Row::from(vec![json!(date)]))
+                    .unwrap();
+                expected.push((*src_id, *dst_id, 12, Some(json!(date))));
+            }
+            assert!(graphdb.add_edge(*src_id, PIDS[0], 13));
+            expected.push((*src_id, PIDS[0], 13, None));
+        }
+        // an edge from a corner vertex, which is not local
+        assert!(graphdb.add_corner_vertex(PIDS[8], 1));
+        assert!(graphdb.add_edge(PIDS[8], PIDS[0], 12));
+        let schema = ldbc_schema();
+        let graph: LargeGraphDB<DefaultId, InternalId, PropertyTable, RocksTable> =
+            graphdb.into_graph(schema);
+
+        let edges: Vec<(DefaultId, DefaultId, LabelId, Option<JsonValue>)> = graph
+            .scan_all_edges()
+            .map(|e| {
+                let e = e.unwrap();
+                let date = e.get_property("creationDate").cloned();
+                (e.get_src_id(), e.get_dst_id(), e.get_label(), date)
+            })
+            .collect();
+        assert_eq!(20, edges.len());
+        assert_eq!(graph.count_all_edges(None), edges.len());
+        assert_eq!(expected, edges);
+    }
+
+    #[test]
+    fn test_edges_between() {
+        let mut graphdb = new_graphdb();
+        for pid in PIDS.iter().take(3) {
+            assert!(graphdb.add_vertex(*pid, [1, INVALID_LABEL_ID]));
+        }
+        // parallel edges between PIDS[0] and PIDS[1]
+        for date in vec![20200202_u64, 20210101_u64] {
+            graphdb
+                .add_edge_with_properties(PIDS[0], PIDS[1], 12, Row::from(vec![json!(date)]))
+                .unwrap();
+        }
+        assert!(graphdb.add_edge(PIDS[0], PIDS[1], 13));
+        assert!(graphdb.add_edge(PIDS[1], PIDS[0], 12));
+        let schema = ldbc_schema();
+        let graph = graphdb.into_graph(schema);
+
+        let edges = |src_id: DefaultId, dst_id: DefaultId, labels: Option<&Vec<LabelId>>| {
+            let mut edges: Vec<(DefaultId, DefaultId, LabelId, Option<JsonValue>)> = graph
+                .get_edges_between(src_id, dst_id, labels)
+                .unwrap()
+                .into_iter()
+                .map(|e| {
+                    let date = e.get_property("creationDate").cloned();
+                    (e.get_src_id(), e.get_dst_id(), e.get_label(), date)
+                })
+                .collect();
+            edges.sort_by_key(|e| (e.2, e.3.as_ref().and_then(|date| date.as_u64())));
+            edges
+        };
+        // all labels
+        assert_eq!(
+            vec![
+                (PIDS[0], PIDS[1], 12, Some(json!(20200202_u64))),
+                (PIDS[0], PIDS[1], 12, Some(json!(20210101_u64))),
+                (PIDS[0], PIDS[1], 13, None)
+            ],
+            edges(PIDS[0], PIDS[1], None)
+        );
+        assert_eq!(
+            vec![
+                (PIDS[0], PIDS[1], 12, Some(json!(20200202_u64))),
+                (PIDS[0], PIDS[1], 12, Some(json!(20210101_u64)))
+            ],
+            edges(PIDS[0], PIDS[1], Some(&vec![12]))
+        );
+        assert_eq!(
+            vec![(PIDS[0], PIDS[1], 13, None)],
+            edges(PIDS[0], PIDS[1], Some(&vec![13, 14]))
+        );
+        // the edges of the opposite direction are not included
+        assert_eq!(vec![(PIDS[1], PIDS[0], 12, None)], edges(PIDS[1], PIDS[0], None));
+        // no such edge
+        assert!(edges(PIDS[0], PIDS[1], Some(&vec![14])).is_empty());
+        assert!(edges(PIDS[0], PIDS[2], None).is_empty());
+        assert!(edges(PIDS[0], PIDS[8], None).is_empty());
+        assert!(edges(PIDS[8], PIDS[0], None).is_empty());
+    }
+
     #[test]
     fn test_graph_query() {
         let data_dir = "data/more_data/graph_data";
@@ -956,6 +3176,23 @@ mod test {
         loader.load().expect("Load graph error!");
         let graphdb = loader.into_graph();
 
+        // test get_vertices..
+        let absent_id = 1 << LABEL_SHIFT_BITS | 1000;
+        let vertices = graphdb
+            .get_vertices(&[PIDS[2], absent_id, PIDS[0], PIDS[2]])
+            .expect("Get vertices error!");
+        assert_eq!(4, vertices.len());
+        assert!(vertices[1].is_none());
+        for (vertex, id) in vertices.iter().zip(vec![PIDS[2], absent_id, PIDS[0], PIDS[2]]) {
+            if let Some(vertex) = vertex {
+                let expected = graphdb.get_vertex(id).unwrap();
+                assert_eq!(expected.get_id(), vertex.get_id());
+                assert_eq!(expected.get_property(ID_FIELD), vertex.get_property(ID_FIELD));
+            }
+        }
+        assert_eq!(vertices[0].as_ref().unwrap().get_id(), PIDS[2]);
+        assert_eq!(vertices[2].as_ref().unwrap().get_id(), PIDS[0]);
+
         // test get_in_vertices..
         let mut in_vertices: Vec<(DefaultId, LabelId)> = graphdb
             .get_adj_vertices(PIDS[1], None, Direction::Incoming)
@@ -513,8 +513,10 @@ impl<G: FromStr + Send + Sync + IndexType, I: Send + Sync + IndexType> GraphLoad
 }
 
 fn get_fname_from_path(path: &PathBuf) -> GDBResult<&str> {
-    let fname =
-        path.file_name().ok_or(GDBError::UnknownError)?.to_str().ok_or(GDBError::UnknownError)?;
+    let fname = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| GDBError::InvalidPathError(path.display().to_string()))?;
 
     Ok(fname)
 }
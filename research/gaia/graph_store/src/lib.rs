@@ -15,11 +15,13 @@
 
 pub mod common;
 pub mod config;
+pub mod csv_loader;
 pub mod error;
 pub mod graph_db;
 pub mod graph_db_impl;
 pub mod io;
 pub mod ldbc;
+pub mod mem_store;
 pub mod parser;
 pub mod prelude;
 pub mod schema;
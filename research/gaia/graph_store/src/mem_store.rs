@@ -0,0 +1,598 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::common::*;
+use crate::error::{GDBError, GDBResult};
+use crate::graph_db::*;
+use crate::graph_db_impl::{remove_field, PropertyIndices};
+use crate::schema::{LDBCGraphSchema, Schema};
+use crate::table::{ItemType, Row, RowRef};
+use crate::utils::Iter;
+use petgraph::graph::{EdgeIndex, IndexType};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A local vertex maintained in `MemGraphStore`
+struct MemVertex {
+    label: Label,
+    properties: Option<Row>,
+}
+
+/// An edge maintained in `MemGraphStore`, identified by its position in `MemGraphStore::edges`
+struct MemEdge<G> {
+    src: G,
+    dst: G,
+    label: LabelId,
+    properties: Option<Row>,
+}
+
+/// A graph store that maintains the vertices, edges and their properties in hash maps, which
+/// is both readable and writable through `GraphStore`. Unlike `MutableGraphDB`, which writes the
+/// graph to be turned into a `LargeGraphDB` for reading, it never touches the disk-based storage,
+/// e.g. for unit tests and small graphs that are built and queried in place. As is in
+/// `MutableGraphDB`, the writes can be rejected by `GlobalStoreUpdate::set_read_only()`, and the
+/// vertices can be looked up by the indices on their properties.
+///
+/// As is in `LargeGraphDB`, a vertex is either local to the partition, or a corner vertex
+/// introduced by an edge, which has neither properties nor a secondary label.
+pub struct MemGraphStore<G: IndexType = DefaultId, I: IndexType = InternalId> {
+    /// Which partition of this part of data
+    partition: usize,
+    /// The schema to validate the properties written, and to read them by names
+    graph_schema: Arc<LDBCGraphSchema>,
+    /// The local vertices by their global ids
+    vertices: HashMap<G, MemVertex>,
+    /// The corner vertices by their global ids, with their primary labels
+    corner_vertices: HashMap<G, LabelId>,
+    /// The edges in the order of insertion, whose positions are their internal ids
+    edges: Vec<MemEdge<G>>,
+    /// The positions of the outgoing edges of each vertex
+    out_edges: HashMap<G, Vec<usize>>,
+    /// The positions of the incoming edges of each vertex
+    in_edges: HashMap<G, Vec<usize>>,
+    /// The secondary indices on vertex properties, which are kept consistent with the writes
+    property_indices: PropertyIndices<G>,
+    /// Whether the writes are rejected, see `GlobalStoreUpdate::set_read_only()`
+    read_only: bool,
+    _ph: PhantomData<I>,
+}
+
+impl<G: IndexType, I: IndexType> MemGraphStore<G, I> {
+    /// Create an empty store of the `partition`, whose properties are validated against
+    /// `schema`, which is trimmed as `MutableGraphDB::into_graph()` does.
+    pub fn new(partition: usize, mut schema: LDBCGraphSchema) -> Self {
+        schema.trim();
+        MemGraphStore {
+            partition,
+            graph_schema: Arc::new(schema),
+            vertices: HashMap::new(),
+            corner_vertices: HashMap::new(),
+            edges: vec![],
+            out_edges: HashMap::new(),
+            in_edges: HashMap::new(),
+            property_indices: PropertyIndices::default(),
+            read_only: false,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Get the label of a vertex, either local or corner, as `IndexData::get_internal_id()`
+    /// looks up the local vertices first
+    fn get_label(&self, id: G) -> Option<Label> {
+        if let Some(vertex) = self.vertices.get(&id) {
+            Some(vertex.label)
+        } else {
+            self.corner_vertices.get(&id).map(|label_id| [*label_id, INVALID_LABEL_ID])
+        }
+    }
+
+    fn to_local_vertex<'a>(&'a self, id: G, vertex: &'a MemVertex) -> LocalVertex<'a, G> {
+        LocalVertex::with_property(
+            id,
+            vertex.label,
+            RowWithSchema::new(
+                vertex.properties.as_ref().map(RowRef::Ref),
+                self.graph_schema.get_vertex_schema(vertex.label[0]),
+            ),
+        )
+    }
+
+    fn to_local_edge(&self, index: usize) -> LocalEdge<G, I> {
+        let edge = &self.edges[index];
+        LocalEdge::with_property(
+            edge.src,
+            edge.dst,
+            edge.label,
+            EdgeIndex::new(index),
+            RowWithSchema::new(
+                edge.properties.as_ref().map(RowRef::Ref),
+                self.graph_schema.get_edge_schema(edge.label),
+            ),
+        )
+    }
+
+    /// Get the local vertices of the label `label_id`, either primary or secondary, in ascending
+    /// order of their global ids
+    fn vertices_of_label(&self, label_id: LabelId) -> Vec<LocalVertex<G>> {
+        let mut vertices: Vec<(&G, &MemVertex)> =
+            self.vertices.iter().filter(|(_, vertex)| vertex.label.contains(&label_id)).collect();
+        vertices.sort_by_key(|(id, _)| **id);
+        vertices.into_iter().map(|(id, vertex)| self.to_local_vertex(*id, vertex)).collect()
+    }
+
+    /// Get the positions of the edges of `src_id` with the direction `dir`, that satisfy the
+    /// edge labels `edge_labels`, where the latest added edge comes first, as is in `LargeGraphDB`
+    fn adj_edge_indices(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Vec<usize> {
+        let adj = if dir == Direction::Outgoing { &self.out_edges } else { &self.in_edges };
+        adj.get(&src_id)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .rev()
+                    .cloned()
+                    .filter(|index| {
+                        edge_labels
+                            .map(|labels| labels.contains(&self.edges[*index].label))
+                            .unwrap_or(true)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The positions of the edges whose start vertices are local to current partition
+    fn local_edge_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.edges.len())
+            .filter(move |index| self.vertices.contains_key(&self.edges[*index].src))
+    }
+
+    fn check_writable(&self) -> GDBResult<()> {
+        if self.read_only {
+            Err(GDBError::ModifyReadOnlyError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the position of the property `key` in the rows of the vertex label `label_id`
+    fn vertex_field(&self, label_id: LabelId, key: &str) -> GDBResult<usize> {
+        self.graph_schema
+            .get_vertex_schema(label_id)
+            .and_then(|header| header.get(key))
+            .map(|(_, field)| *field)
+            .ok_or(GDBError::FieldNotExistError)
+    }
+
+    fn delete_vertex_property(&mut self, global_id: G, key: &str) -> GDBResult<()> {
+        let label = self.get_label(global_id).ok_or(GDBError::VertexNotFoundError)?;
+        let field = self.vertex_field(label[0], key)?;
+        // a corner vertex has no properties
+        let vertex = self.vertices.get_mut(&global_id).ok_or(GDBError::FieldNotExistError)?;
+        let old = vertex.properties.take().ok_or(GDBError::FieldNotExistError)?;
+        match remove_field(&old, field) {
+            Some(new) => {
+                let new = if new.is_empty() { None } else { Some(new) };
+                self.property_indices.update(global_id, label, Some(&old), new.as_ref());
+                vertex.properties = new;
+                Ok(())
+            }
+            None => {
+                vertex.properties = Some(old);
+                Err(GDBError::FieldNotExistError)
+            }
+        }
+    }
+
+    fn delete_edge_property(
+        &mut self, src_id: G, dst_id: G, label_id: LabelId, key: &str,
+    ) -> GDBResult<()> {
+        let edges: Vec<usize> = self
+            .adj_edge_indices(src_id, Some(&vec![label_id]), Direction::Outgoing)
+            .into_iter()
+            .filter(|index| self.edges[*index].dst == dst_id)
+            .collect();
+        if edges.is_empty() {
+            return Err(GDBError::EdgeNotFoundError);
+        }
+        let field = self
+            .graph_schema
+            .get_edge_schema(label_id)
+            .and_then(|header| header.get(key))
+            .map(|(_, field)| *field)
+            .ok_or(GDBError::FieldNotExistError)?;
+
+        let mut deleted = false;
+        for index in edges {
+            let edge = &mut self.edges[index];
+            if let Some(new) = edge.properties.as_ref().and_then(|old| remove_field(old, field)) {
+                edge.properties = if new.is_empty() { None } else { Some(new) };
+                deleted = true;
+            }
+        }
+        if deleted {
+            Ok(())
+        } else {
+            Err(GDBError::FieldNotExistError)
+        }
+    }
+
+    fn add_edge_internal(
+        &mut self, src: G, dst: G, label: LabelId, properties: Option<Row>,
+    ) -> bool {
+        if self.get_label(src).is_some() && self.get_label(dst).is_some() {
+            let index = self.edges.len();
+            self.edges.push(MemEdge { src, dst, label, properties });
+            self.out_edges.entry(src).or_default().push(index);
+            self.in_edges.entry(dst).or_default().push(index);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<G, I> GlobalStoreTrait<G, I> for MemGraphStore<G, I>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+{
+    fn get_adj_vertices(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalVertex<G>> {
+        let iter =
+            self.adj_edge_indices(src_id, edge_labels, dir).into_iter().filter_map(move |index| {
+                let edge = &self.edges[index];
+                let id = if dir == Direction::Outgoing { edge.dst } else { edge.src };
+                self.get_label(id).map(|label| LocalVertex::new(id, label))
+            });
+        Iter::from_iter(iter)
+    }
+
+    fn get_adj_edges(
+        &self, src_id: G, edge_labels: Option<&Vec<LabelId>>, dir: Direction,
+    ) -> Iter<LocalEdge<G, I>> {
+        let iter = self
+            .adj_edge_indices(src_id, edge_labels, dir)
+            .into_iter()
+            .map(move |index| self.to_local_edge(index));
+        Iter::from_iter(iter)
+    }
+
+    fn get_vertex(&self, id: G) -> Option<LocalVertex<G>> {
+        if let Some(vertex) = self.vertices.get(&id) {
+            Some(self.to_local_vertex(id, vertex))
+        } else {
+            self.get_label(id).map(|label| LocalVertex::new(id, label))
+        }
+    }
+
+    fn get_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalVertex<G>> {
+        if let Some(labels) = labels {
+            let vertices: Vec<LocalVertex<G>> =
+                labels.iter().flat_map(|label_id| self.vertices_of_label(*label_id)).collect();
+            Iter::from_iter(vertices.into_iter())
+        } else {
+            let mut ids: Vec<&G> = self.vertices.keys().collect();
+            ids.sort();
+            let iter = ids.into_iter().map(move |id| self.to_local_vertex(*id, &self.vertices[id]));
+            Iter::from_iter(iter)
+        }
+    }
+
+    fn scan_vertices_by_label(&self, label_id: LabelId) -> Iter<GDBResult<LocalVertex<G>>> {
+        // the properties are in memory, which never fail to be read
+        Iter::from_iter(self.vertices_of_label(label_id).into_iter().map(Ok))
+    }
+
+    fn scan_all_edges(&self) -> Iter<GDBResult<LocalEdge<G, I>>> {
+        Iter::from_iter(self.local_edge_indices().map(move |index| Ok(self.to_local_edge(index))))
+    }
+
+    fn get_all_edges(&self, labels: Option<&Vec<LabelId>>) -> Iter<LocalEdge<G, I>> {
+        let labels = labels.cloned();
+        let iter = self
+            .local_edge_indices()
+            .filter(move |index| {
+                labels
+                    .as_ref()
+                    .map(|labels| labels.contains(&self.edges[*index].label))
+                    .unwrap_or(true)
+            })
+            .map(move |index| self.to_local_edge(index));
+        Iter::from_iter(iter)
+    }
+
+    fn count_all_vertices(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        if let Some(labels) = labels {
+            labels
+                .iter()
+                .map(|label_id| {
+                    self.vertices.values().filter(|vertex| vertex.label.contains(label_id)).count()
+                })
+                .sum()
+        } else {
+            self.vertices.len()
+        }
+    }
+
+    fn count_all_edges(&self, labels: Option<&Vec<LabelId>>) -> usize {
+        self.local_edge_indices()
+            .filter(|index| {
+                labels.map(|labels| labels.contains(&self.edges[*index].label)).unwrap_or(true)
+            })
+            .count()
+    }
+
+    fn get_schema(&self) -> Arc<dyn Schema> {
+        self.graph_schema.clone()
+    }
+
+    fn get_current_partition(&self) -> usize {
+        self.partition
+    }
+}
+
+impl<G, I> GlobalStoreUpdate<G, I> for MemGraphStore<G, I>
+where
+    G: IndexType + Send + Sync,
+    I: IndexType + Send + Sync,
+{
+    fn add_vertex(&mut self, global_id: G, label: Label) -> bool {
+        self.try_add_vertex(global_id, label).unwrap_or(false)
+    }
+
+    fn add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> bool {
+        self.try_add_corner_vertex(global_id, label_id).unwrap_or(false)
+    }
+
+    fn try_add_vertex(&mut self, global_id: G, label: Label) -> GDBResult<bool> {
+        self.check_writable()?;
+        if let Some(vertex) = self.vertices.get_mut(&global_id) {
+            // update a more fine-grained label, as `MutableGraphDB` does
+            if label[1] != INVALID_LABEL_ID {
+                vertex.label = label;
+            }
+            Ok(false)
+        } else {
+            self.vertices.insert(global_id, MemVertex { label, properties: None });
+            Ok(true)
+        }
+    }
+
+    fn try_add_corner_vertex(&mut self, global_id: G, label_id: LabelId) -> GDBResult<bool> {
+        self.check_writable()?;
+        if let Entry::Vacant(entry) = self.corner_vertices.entry(global_id) {
+            entry.insert(label_id);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn add_or_update_vertex_properties(
+        &mut self, global_id: G, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        let label = self.vertices.get(&global_id).ok_or(GDBError::VertexNotFoundError)?.label;
+        self.graph_schema.validate_vertex_row(label[0], &properties)?;
+        let vertex = self.vertices.get_mut(&global_id).unwrap();
+        let old = vertex.properties.replace(properties);
+        self.property_indices.update(global_id, label, old.as_ref(), vertex.properties.as_ref());
+        Ok(old)
+    }
+
+    fn add_vertex_with_properties(
+        &mut self, global_id: G, label: Label, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        // validate before adding, so that no vertex is added without its properties
+        self.graph_schema.validate_vertex_row(label[0], &properties)?;
+        self.try_add_vertex(global_id, label)?;
+        self.add_or_update_vertex_properties(global_id, properties)
+    }
+
+    fn add_edge(&mut self, global_src_id: G, global_dst_id: G, label_id: LabelId) -> bool {
+        self.try_add_edge(global_src_id, global_dst_id, label_id).unwrap_or(false)
+    }
+
+    fn try_add_edge(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId,
+    ) -> GDBResult<bool> {
+        self.check_writable()?;
+        Ok(self.add_edge_internal(global_src_id, global_dst_id, label_id, None))
+    }
+
+    fn add_edge_with_properties(
+        &mut self, global_src_id: G, global_dst_id: G, label_id: LabelId, properties: Row,
+    ) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        self.graph_schema.validate_edge_row(label_id, &properties)?;
+        if self.add_edge_internal(global_src_id, global_dst_id, label_id, Some(properties)) {
+            // an edge is always newly added, which has no old properties
+            Ok(None)
+        } else {
+            Err(GDBError::EdgeNotFoundError)
+        }
+    }
+
+    fn add_vertex_batches<Iter: Iterator<Item = (G, Label, Row)>>(
+        &mut self, iter: Iter,
+    ) -> GDBResult<usize> {
+        self.check_writable()?;
+        let mut count = 0;
+        for (global_id, label, properties) in iter {
+            // stop at an invalid vertex, while the vertices before it are added
+            self.graph_schema.validate_vertex_row(label[0], &properties)?;
+            if self.add_vertex(global_id, label) {
+                count += 1;
+            }
+            // only non-empty properties will be added
+            if !properties.is_empty() {
+                self.add_or_update_vertex_properties(global_id, properties)?;
+            }
+        }
+        Ok(count)
+    }
+
+    fn add_edge_batches<Iter: Iterator<Item = (G, G, LabelId, Row)>>(
+        &mut self, iter: Iter,
+    ) -> GDBResult<usize> {
+        self.check_writable()?;
+        let mut count = 0;
+        for (src_id, dst_id, label_id, properties) in iter {
+            // stop at an invalid edge, while the edges before it are added
+            self.graph_schema.validate_edge_row(label_id, &properties)?;
+            let properties = if properties.is_empty() { None } else { Some(properties) };
+            if self.add_edge_internal(src_id, dst_id, label_id, properties) {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn create_vertex_index(&mut self, label: LabelId, key: &str) -> GDBResult<()> {
+        let field = self.vertex_field(label, key)?;
+        if self.property_indices.contains(label, key) {
+            return Ok(());
+        }
+
+        let values = self
+            .vertices
+            .iter()
+            .filter(|(_, vertex)| vertex.label.contains(&label))
+            .filter_map(|(id, vertex)| {
+                vertex.properties.as_ref().and_then(|row| row.get(field)).map(|v| (*id, v.clone()))
+            });
+        self.property_indices.insert(label, key, field, values);
+        Ok(())
+    }
+
+    fn lookup_by_property(&self, label: LabelId, key: &str, value: &ItemType) -> GDBResult<Vec<G>> {
+        self.property_indices.lookup(label, key, value)
+    }
+
+    fn remove_vertex_properties(&mut self, global_id: G) -> GDBResult<Option<Row>> {
+        self.check_writable()?;
+        if let Some(vertex) = self.vertices.get_mut(&global_id) {
+            let old = vertex.properties.take();
+            self.property_indices.update(global_id, vertex.label, old.as_ref(), None);
+            Ok(old)
+        } else if self.corner_vertices.contains_key(&global_id) {
+            Ok(None)
+        } else {
+            Err(GDBError::VertexNotFoundError)
+        }
+    }
+
+    fn delete_property(&mut self, element_id: ElementId<G>, key: &str) -> GDBResult<()> {
+        self.check_writable()?;
+        match element_id {
+            ElementId::Vertex(global_id) => self.delete_vertex_property(global_id, key),
+            ElementId::Edge(src_id, dst_id, label_id) => {
+                self.delete_edge_property(src_id, dst_id, label_id, key)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::JsonConf;
+    use crate::ldbc::LABEL_SHIFT_BITS;
+
+    static PIDS: [DefaultId; 4] = [
+        1 << LABEL_SHIFT_BITS | 111,
+        1 << LABEL_SHIFT_BITS | 222,
+        1 << LABEL_SHIFT_BITS | 333,
+        1 << LABEL_SHIFT_BITS | 444,
+    ];
+
+    fn new_store() -> MemGraphStore {
+        let schema =
+            LDBCGraphSchema::from_json_file("data/schema.json").expect("Get Schema error!");
+        MemGraphStore::new(0, schema)
+    }
+
+    #[test]
+    fn test_mem_graph_store_update() {
+        let mut store = new_store();
+        assert!(store.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
+        assert!(!store.add_vertex(PIDS[0], [1, INVALID_LABEL_ID]));
+        let old = store
+            .add_or_update_vertex_properties(PIDS[0], Row::from(vec![json!(0), json!("john")]))
+            .unwrap();
+        assert!(old.is_none());
+        let old = store
+            .add_or_update_vertex_properties(PIDS[0], Row::from(vec![json!(0), json!("jane")]))
+            .unwrap();
+        assert_eq!(Some(&json!("john")), old.as_ref().and_then(|row| row.get(1)));
+
+        // a missing or corner vertex has no properties to update
+        store.add_corner_vertex(PIDS[1], 1);
+        for pid in &PIDS[1..3] {
+            match store.add_or_update_vertex_properties(*pid, Row::from(vec![json!(1)])) {
+                Err(GDBError::VertexNotFoundError) => {}
+                other => panic!("expect VertexNotFoundError, got {:?}", other),
+            }
+        }
+        // an edge requires both ends to present
+        assert!(!store.add_edge(PIDS[0], PIDS[2], 12));
+        match store.add_edge_with_properties(PIDS[0], PIDS[2], 12, Row::from(vec![json!(1_u64)])) {
+            Err(GDBError::EdgeNotFoundError) => {}
+            other => panic!("expect EdgeNotFoundError, got {:?}", other),
+        }
+        // an unknown property is rejected by the schema
+        let long_row = Row::from(vec![json!(0); 64]);
+        assert!(store
+            .add_vertex_with_properties(PIDS[2], [1, INVALID_LABEL_ID], long_row)
+            .is_err());
+        assert!(store.get_vertex(PIDS[2]).is_none());
+
+        let count = store
+            .add_vertex_batches(
+                vec![
+                    (PIDS[2], [1, INVALID_LABEL_ID], Row::from(vec![json!(2)])),
+                    (PIDS[3], [1, INVALID_LABEL_ID], Row::default()),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+        assert_eq!(2, count);
+        let count = store
+            .add_edge_batches(
+                vec![
+                    (PIDS[0], PIDS[2], 12, Row::default()),
+                    (PIDS[0], 1 << LABEL_SHIFT_BITS | 555, 12, Row::default()),
+                ]
+                .into_iter(),
+            )
+            .unwrap();
+        assert_eq!(1, count);
+        assert!(store.get_vertex(PIDS[3]).unwrap().clone_all_properties().is_none());
+    }
+}
@@ -31,6 +31,10 @@ pub enum DataType {
     Date,
     ID,
     LABEL,
+    /// An ISO-8601 date time, stored as the milliseconds since the epoch in UTC;
+    DateTime,
+    /// A double-precision float, which is parsed as `f64`, while a `Float` is parsed as `f32`;
+    Double,
 }
 
 impl<'a> From<&'a str> for DataType {
@@ -43,8 +47,14 @@ impl<'a> From<&'a str> for DataType {
             DataType::Long
         } else if token == "INT" {
             DataType::Integer
+        } else if token == "FLOAT" {
+            DataType::Float
+        } else if token == "DOUBLE" {
+            DataType::Double
         } else if token == "DATE" {
             DataType::Date
+        } else if token == "DATETIME" {
+            DataType::DateTime
         } else if token == "ID" {
             DataType::ID
         } else if token == "LABEL" {
@@ -61,13 +71,13 @@ impl<'a> From<&'a str> for DataType {
 ///
 /// Return
 ///     * succeed: the parsed data of type `T` succeed
-///     * parsing error: `GDBError::ParseError`
+///     * parsing error: `GDBError::ParseError` with the item failed to parse
 ///     * empty iterator: `GDBError::OutOfBoundError`
 fn _parse_one_item_to<'a, T: FromStr, Iter: Iterator<Item = &'a str>>(
     iter: &mut Iter,
 ) -> GDBResult<T> {
     if let Some(item) = iter.next() {
-        item.parse::<T>().map_err(|_| GDBError::ParseError)
+        item.parse::<T>().map_err(|_| GDBError::ParseError(format!("can't parse {:?}", item)))
     } else {
         Err(GDBError::OutOfBoundError)
     }
@@ -95,6 +105,129 @@ fn is_time_splitter(c: char) -> bool {
     c == '-' || c == ':' || c == ' ' || c == 'T' || c == 'Z'
 }
 
+/// Parse exactly `len` ascii digits into a number;
+fn parse_digits(s: &str, len: usize) -> Option<i64> {
+    if s.len() == len && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days from 1970-01-01 to the date of a non-negative year in the proleptic
+/// Gregorian calendar, where the year is counted from March so that the leap day comes last;
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Parse the zone of a date time into the offset in minutes, e.g. "Z", "+08", "+0800" or "-08:00",
+/// where an empty zone is UTC;
+fn parse_zone_offset(zone: &str) -> Option<i64> {
+    if zone.is_empty() || zone == "Z" {
+        return Some(0);
+    }
+    let sign = match zone.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let offset = zone[1..].replace(':', "");
+    if !offset.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hour, minute) = match offset.len() {
+        2 => (parse_digits(&offset, 2)?, 0),
+        4 => (parse_digits(&offset[..2], 2)?, parse_digits(&offset[2..], 2)?),
+        _ => return None,
+    };
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    Some(sign * (hour * 60 + minute))
+}
+
+/// Parse an ISO-8601 date or date time into the milliseconds since the epoch in UTC, e.g.
+/// "2010-02-14", "2010-02-14T15:32:10Z" or "2010-02-14T15:32:10.447+0800". A date time without
+/// a zone is taken as UTC, and the fraction of a second is truncated to milliseconds. Return
+/// `None` if the value is not a valid date time;
+pub fn parse_date_time(val: &str) -> Option<i64> {
+    let date = val.get(..10)?;
+    let year = parse_digits(date.get(..4)?, 4)?;
+    let month = parse_digits(date.get(5..7)?, 2)?;
+    let day = parse_digits(date.get(8..)?, 2)?;
+    if &date[4..5] != "-" || &date[7..8] != "-" || month < 1 || month > 12 {
+        return None;
+    }
+    if day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    let mut millis = days_from_civil(year, month, day) * 86_400_000;
+    let rest = &val[10..];
+    if rest.is_empty() {
+        return Some(millis);
+    }
+
+    let rest = rest.strip_prefix(|c| c == 'T' || c == ' ')?;
+    let zone_pos = rest.find(|c| c == 'Z' || c == '+' || c == '-').unwrap_or(rest.len());
+    let (time, zone) = rest.split_at(zone_pos);
+    let (time, fraction) = match time.find('.') {
+        Some(pos) => (&time[..pos], &time[pos + 1..]),
+        None => (time, ""),
+    };
+    let mut fields = time.split(':');
+    let hour = parse_digits(fields.next()?, 2)?;
+    let minute = parse_digits(fields.next()?, 2)?;
+    let second = match fields.next() {
+        Some(second) => parse_digits(second, 2)?,
+        None => 0,
+    };
+    if fields.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    millis += ((hour * 60 + minute) * 60 + second) * 1000;
+    if !fraction.is_empty() {
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let len = fraction.len().min(3);
+        // e.g. ".4" is 400 millis;
+        millis += parse_digits(&fraction[..len], len)? * 10_i64.pow(3 - len as u32);
+    }
+    Some(millis - parse_zone_offset(zone)? * 60_000)
+}
+
+fn type_mismatch(val: &str, field: &str, ty: &DataType) -> GDBError {
+    GDBError::SchemaTypeMismatch {
+        field: field.to_string(),
+        expected: format!("{:?}", ty),
+        got: val.to_string(),
+    }
+}
+
+/// Parse the value of the field according to its type in the schema, return
+/// `GDBError::SchemaTypeMismatch` if the value can't be parsed into the type.
+fn parse_field_value<T: FromStr>(val: &str, field: &str, ty: &DataType) -> GDBResult<T> {
+    val.parse::<T>().map_err(|_| type_mismatch(val, field, ty))
+}
+
 pub fn parse_properties<'a, Iter: Iterator<Item = &'a str>>(
     mut record_iter: Iter, _header: Option<&[(String, DataType)]>,
 ) -> GDBResult<Row> {
@@ -112,11 +245,13 @@ pub fn parse_properties<'a, Iter: Iterator<Item = &'a str>>(
             if ty == &DataType::String {
                 properties.push(json!(val.to_string()));
             } else if ty == &DataType::Integer {
-                properties.push(json!(val.parse::<i32>()?));
+                properties.push(json!(parse_field_value::<i32>(val, field, ty)?));
             } else if ty == &DataType::Long {
-                properties.push(json!(val.parse::<i64>()?));
+                properties.push(json!(parse_field_value::<i64>(val, field, ty)?));
             } else if ty == &DataType::Float {
-                properties.push(json!(val.parse::<f32>()?));
+                properties.push(json!(parse_field_value::<f32>(val, field, ty)?));
+            } else if ty == &DataType::Double {
+                properties.push(json!(parse_field_value::<f64>(val, field, ty)?));
             } else if ty == &DataType::Date {
                 let mut _date = String::with_capacity(val.len());
                 for c in val.chars() {
@@ -129,17 +264,25 @@ pub fn parse_properties<'a, Iter: Iterator<Item = &'a str>>(
                         _date.push(c);
                     }
                 }
-                properties.push(json!(_date.parse::<u64>()?));
+                let date = _date.parse::<u64>().map_err(|_| type_mismatch(val, field, ty))?;
+                properties.push(json!(date));
+            } else if ty == &DataType::DateTime {
+                let date_time =
+                    parse_date_time(val).ok_or_else(|| type_mismatch(val, field, ty))?;
+                properties.push(json!(date_time));
             } else if ty == &DataType::ID {
                 // do not record the starting (ldbc) id and end id of an edge
                 if field != START_ID_FIELD && field != END_ID_FIELD {
-                    properties.push(json!(val.parse::<usize>()?));
+                    properties.push(json!(parse_field_value::<usize>(val, field, ty)?));
                 }
             } else if ty == &DataType::LABEL {
                 // do not further record the label of a vertex
                 continue;
             } else {
-                return GDBResult::Err(GDBError::ParseError);
+                return GDBResult::Err(GDBError::ParseError(format!(
+                    "unsupported data type {:?} of field {:?}",
+                    ty, field
+                )));
             }
         }
     }
@@ -167,3 +310,75 @@ pub trait ParserTrait<G: FromStr + PartialEq> {
         &self, record_iter: Iter,
     ) -> GDBResult<EdgeMeta<G>>;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_properties() {
+        let header = vec![
+            ("name".to_string(), DataType::String),
+            ("age".to_string(), DataType::Integer),
+            ("creationDate".to_string(), DataType::Date),
+        ];
+        let record = vec!["marko", "29", "2010-02-14T15:32:10.447+0000"];
+        let properties = parse_properties(record.into_iter(), Some(&header)).unwrap();
+        assert_eq!(properties.len(), 3);
+        assert_eq!(properties.get(1), Some(&json!(29)));
+        assert_eq!(properties.get(2), Some(&json!(20100214153210_u64)));
+
+        let header = vec![("creationDate".to_string(), DataType::DateTime)];
+        let record = vec!["2010-02-14T15:32:10.447+0000"];
+        let properties = parse_properties(record.into_iter(), Some(&header)).unwrap();
+        assert_eq!(properties.get(0), Some(&json!(1266161530447_i64)));
+
+        // a double keeps the precision, and the range, which a float doesn't
+        let header = vec![
+            ("weight".to_string(), DataType::Double),
+            ("distance".to_string(), DataType::Double),
+            ("score".to_string(), DataType::Float),
+        ];
+        let record = vec!["0.1", "1e300", "0.1"];
+        let properties = parse_properties(record.into_iter(), Some(&header)).unwrap();
+        assert_eq!(properties.get(0).and_then(|v| v.as_f64()), Some(0.1_f64));
+        assert_eq!(properties.get(1).and_then(|v| v.as_f64()), Some(1e300_f64));
+        assert_ne!(properties.get(2).and_then(|v| v.as_f64()), Some(0.1_f64));
+        assert_eq!(DataType::from("double"), DataType::Double);
+        assert_eq!(DataType::from("float"), DataType::Float);
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        assert_eq!(parse_date_time("1970-01-01"), Some(0));
+        assert_eq!(parse_date_time("1970-01-01T00:00:00.001Z"), Some(1));
+        assert_eq!(parse_date_time("2010-02-14"), Some(1266105600000));
+        assert_eq!(parse_date_time("2010-02-14T15:32:10.447+0000"), Some(1266161530447));
+        assert_eq!(parse_date_time("2010-02-14 15:32:10.447"), Some(1266161530447));
+        assert_eq!(parse_date_time("2010-02-14T23:32:10.447+08:00"), Some(1266161530447));
+        assert_eq!(parse_date_time("2010-02-14T15:32:10.4-00"), Some(1266161530400));
+        assert_eq!(parse_date_time("2010-02-14T15:32"), Some(1266161520000));
+        // the earlier date time is less, crossing a leap day;
+        assert!(parse_date_time("2012-02-29T23:59:59Z") < parse_date_time("2012-03-01T00:00:00Z"));
+        assert_eq!(parse_date_time("1969-12-31T23:59:59.999Z"), Some(-1));
+        for invalid in vec!["2010-2-14", "2010-02-30", "2011-02-29", "2010-02-14T25:00", "tomorrow"]
+        {
+            assert_eq!(parse_date_time(invalid), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_properties_type_mismatch() {
+        let header =
+            vec![("name".to_string(), DataType::String), ("age".to_string(), DataType::Integer)];
+        let record = vec!["marko", "twenty-nine"];
+        match parse_properties(record.into_iter(), Some(&header)) {
+            Err(GDBError::SchemaTypeMismatch { field, expected, got }) => {
+                assert_eq!(field, "age");
+                assert_eq!(expected, "Integer");
+                assert_eq!(got, "twenty-nine");
+            }
+            _ => panic!("expect schema type mismatch"),
+        }
+    }
+}
@@ -17,10 +17,12 @@ pub use crate::common::{DefaultId, InternalId, Label, LabelId, INVALID_LABEL_ID,
 pub use crate::config::GraphDBConfig;
 pub use crate::error::{GDBError, GDBResult};
 pub use crate::graph_db::{
-    Direction, GlobalStoreTrait, GlobalStoreUpdate, LocalAdjEdge, LocalEdge, LocalVertex,
+    Direction, ElementId, ExpandDirection, GlobalStoreTrait, GlobalStoreUpdate, GraphStore,
+    LocalAdjEdge, LocalEdge, LocalVertex,
 };
-pub use crate::graph_db_impl::{LargeGraphDB, MutableGraphDB};
+pub use crate::graph_db_impl::{GraphSnapshot, LargeGraphDB, MutableGraphDB, WriteBatch};
+pub use crate::mem_store::MemGraphStore;
 pub use crate::schema::{LDBCGraphSchema, Schema};
 pub use crate::table::{
-    PropertyTable, PropertyTableTrait, RocksTable, Row, RowRef, SingleValueTable,
+    PropertyTable, PropertyTableTrait, RocksTable, Row, RowRef, SingleValueTable, TableBatch,
 };
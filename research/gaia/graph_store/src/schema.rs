@@ -15,7 +15,9 @@
 
 use crate::common::LabelId;
 use crate::config::JsonConf;
+use crate::error::{GDBError, GDBResult};
 use crate::parser::DataType;
+use crate::table::{ItemType, Row};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
@@ -80,6 +82,87 @@ pub trait Schema {
 
     /// Get a certain edge type's id, together with its start- and edge- vertices's type ids if any
     fn get_edge_label_id(&self, edge_type: &str) -> Option<EdgeLabelTuple>;
+
+    /// Get all the vertex types, as pairs of the type's name and id
+    fn get_vertex_types(&self) -> Vec<(&str, LabelId)>;
+
+    /// Get all the edge types, as pairs of the type's name and id tuple
+    fn get_edge_types(&self) -> Vec<(&str, EdgeLabelTuple)>;
+
+    /// Validate the property `key` of `value` to write to a vertex of `label`. Return
+    /// * `FieldNotExistError` if the property is not registered for the label, or the label is not
+    /// registered at all.
+    /// * `InvalidTypeError` if the value is not of the data type registered.
+    fn validate_vertex_property(
+        &self, label: LabelId, key: &str, value: &ItemType,
+    ) -> GDBResult<()> {
+        validate_property(self.get_vertex_schema(label), key, value)
+    }
+
+    /// Validate the property `key` of `value` to write to an edge of `label`, see
+    /// `Self::validate_vertex_property()`.
+    fn validate_edge_property(&self, label: LabelId, key: &str, value: &ItemType) -> GDBResult<()> {
+        validate_property(self.get_edge_schema(label), key, value)
+    }
+
+    /// Validate a row of properties to write to a vertex of `label`, where the properties are in
+    /// the order of the vertex header. A row longer than the header contains unknown properties,
+    /// which fails with `FieldNotExistError`, while a shorter one is valid as the rest are absent.
+    /// A null property is absent as well, e.g. one deleted before a present one.
+    fn validate_vertex_row(&self, label: LabelId, properties: &Row) -> GDBResult<()> {
+        validate_row(self.get_vertex_header(label), properties)
+    }
+
+    /// Validate a row of properties to write to an edge of `label`, see
+    /// `Self::validate_vertex_row()`.
+    fn validate_edge_row(&self, label: LabelId, properties: &Row) -> GDBResult<()> {
+        validate_row(self.get_edge_header(label), properties)
+    }
+}
+
+/// Check if `value` is of the data type `ty`, in the way `parse_properties()` stores a value of
+/// the type. A label is never stored as a property, and is always invalid.
+fn is_of_type(value: &ItemType, ty: &DataType) -> bool {
+    match ty {
+        DataType::NULL => value.is_null(),
+        DataType::String => value.is_string(),
+        DataType::Integer => value
+            .as_i64()
+            .map(|v| v >= std::i32::MIN as i64 && v <= std::i32::MAX as i64)
+            .unwrap_or(false),
+        DataType::Long | DataType::DateTime => value.is_i64(),
+        DataType::Float | DataType::Double => value.is_number(),
+        DataType::Date | DataType::ID => value.is_u64(),
+        DataType::LABEL => false,
+    }
+}
+
+fn validate_property(
+    schema: Option<&HashMap<String, (DataType, usize)>>, key: &str, value: &ItemType,
+) -> GDBResult<()> {
+    let (ty, _) = schema.and_then(|s| s.get(key)).ok_or(GDBError::FieldNotExistError)?;
+    if is_of_type(value, ty) {
+        Ok(())
+    } else {
+        Err(GDBError::InvalidTypeError)
+    }
+}
+
+fn validate_row(header: Option<&[(String, DataType)]>, properties: &Row) -> GDBResult<()> {
+    let header = header.unwrap_or(&[]);
+    if properties.len() > header.len() {
+        return Err(GDBError::FieldNotExistError);
+    }
+    for (index, (_, ty)) in header.iter().enumerate().take(properties.len()) {
+        if !properties
+            .get(index)
+            .map(|value| value.is_null() || is_of_type(value, ty))
+            .unwrap_or(false)
+        {
+            return Err(GDBError::InvalidTypeError);
+        }
+    }
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -295,6 +378,14 @@ impl Schema for LDBCGraphSchema {
     fn get_edge_label_id(&self, edge_type: &str) -> Option<EdgeLabelTuple> {
         self.edge_type_to_id.get(edge_type).cloned()
     }
+
+    fn get_vertex_types(&self) -> Vec<(&str, LabelId)> {
+        self.vertex_type_to_id.iter().map(|(name, id)| (name.as_str(), *id)).collect()
+    }
+
+    fn get_edge_types(&self) -> Vec<(&str, EdgeLabelTuple)> {
+        self.edge_type_to_id.iter().map(|(name, tuple)| (name.as_str(), *tuple)).collect()
+    }
 }
 
 impl JsonConf<LDBCGraphSchemaJson> for LDBCGraphSchemaJson {}
@@ -329,6 +420,87 @@ impl JsonConf<LDBCGraphSchema> for LDBCGraphSchema {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_validate_property() {
+        let mut schema =
+            LDBCGraphSchema::from_json_file("data/schema.json").expect("Get schema error");
+        schema.trim();
+        let person = schema.get_vertex_label_id("PERSON").unwrap();
+        assert!(schema.validate_vertex_property(person, "firstName", &json!("John")).is_ok());
+        assert!(schema.validate_vertex_property(person, "birthday", &json!(19900101_u64)).is_ok());
+        assert_eq!(
+            schema.validate_vertex_property(person, "email", &json!("john@x.com")),
+            Err(GDBError::FieldNotExistError)
+        );
+        assert_eq!(
+            schema.validate_vertex_property(person, "firstName", &json!(1)),
+            Err(GDBError::InvalidTypeError)
+        );
+        assert_eq!(
+            schema.validate_vertex_property(person, "birthday", &json!("1990-01-01")),
+            Err(GDBError::InvalidTypeError)
+        );
+        // an unknown label has no property
+        assert_eq!(
+            schema.validate_vertex_property(100, "firstName", &json!("John")),
+            Err(GDBError::FieldNotExistError)
+        );
+
+        let knows = schema.get_edge_label_id("PERSON_KNOWS_PERSON").unwrap().edge_label;
+        assert!(schema.validate_edge_property(knows, "creationDate", &json!(20200202_u64)).is_ok());
+        assert_eq!(
+            schema.validate_edge_property(knows, "creationDate", &json!(-1)),
+            Err(GDBError::InvalidTypeError)
+        );
+        // the same key of a vertex label doesn't exist on an edge label
+        assert_eq!(
+            schema.validate_edge_property(knows, "firstName", &json!("John")),
+            Err(GDBError::FieldNotExistError)
+        );
+    }
+
+    #[test]
+    fn test_validate_row() {
+        let mut schema =
+            LDBCGraphSchema::from_json_file("data/schema.json").expect("Get schema error");
+        schema.trim();
+        let person = schema.get_vertex_label_id("PERSON").unwrap();
+        // a prefix of the header is valid
+        assert!(schema
+            .validate_vertex_row(person, &Row::from(vec![json!(1), json!("John")]))
+            .is_ok());
+        assert!(schema.validate_vertex_row(person, &Row::default()).is_ok());
+        // a null property is absent
+        assert!(schema
+            .validate_vertex_row(person, &Row::from(vec![json!(1), ItemType::Null, json!("Doe")]))
+            .is_ok());
+        assert_eq!(
+            schema.validate_vertex_row(person, &Row::from(vec![json!("1"), json!("John")])),
+            Err(GDBError::InvalidTypeError)
+        );
+        let too_long = Row::from((0..9).map(|i| json!(i)).collect::<Vec<_>>());
+        assert_eq!(
+            schema.validate_vertex_row(person, &too_long),
+            Err(GDBError::FieldNotExistError)
+        );
+        assert_eq!(
+            schema.validate_vertex_row(100, &Row::from(vec![json!(1)])),
+            Err(GDBError::FieldNotExistError)
+        );
+    }
+
+    #[test]
+    fn test_get_types() {
+        let schema = LDBCGraphSchema::from_json_file("data/schema.json").expect("Get schema error");
+        let vertex_types = schema.get_vertex_types();
+        assert_eq!(vertex_types.len(), 13);
+        assert!(vertex_types.contains(&("PERSON", 1)));
+
+        let edge_types = schema.get_edge_types();
+        assert_eq!(edge_types.len(), 23);
+        assert!(edge_types.contains(&("PERSON_KNOWS_PERSON", EdgeLabelTuple::from((12, 1, 1)))));
+    }
+
     #[test]
     fn test_trim_schema() {
         let mut schema =
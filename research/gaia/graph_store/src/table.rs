@@ -14,7 +14,7 @@
 //! limitations under the License.
 
 use rocksdb::DB as Tree;
-use rocksdb::{Options, WriteBatch};
+use rocksdb::{DBRawIterator, Options, Snapshot, WriteBatch};
 use serde::de::Error as DeError;
 use serde::ser::Error as SerError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -23,6 +23,7 @@ use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::fs::create_dir_all;
 use std::path::Path;
+use std::sync::Arc;
 
 use crate::error::{GDBError, GDBResult};
 
@@ -51,6 +52,17 @@ impl Row {
     pub fn push(&mut self, val: ItemType) {
         self.data.push(val)
     }
+
+    /// Replace the item at `index` with `val`, and return the old item, or `None` if the index
+    /// is out of bound, in which case the row is unchanged
+    pub fn replace(&mut self, index: usize, val: ItemType) -> Option<ItemType> {
+        self.data.get_mut(index).map(|item| std::mem::replace(item, val))
+    }
+
+    /// Shorten the row to its first `len` items, which has no effect if the row is not longer
+    pub fn truncate(&mut self, len: usize) {
+        self.data.truncate(len)
+    }
 }
 
 impl From<String> for Row {
@@ -91,11 +103,20 @@ impl From<Vec<ItemType>> for Row {
 impl TryInto<Vec<u8>> for Row {
     type Error = GDBError;
 
+    fn try_into(self) -> GDBResult<Vec<u8>> {
+        (&self).try_into()
+    }
+}
+
+/// Serialize a `Row` of data into a byte array by reference
+impl TryInto<Vec<u8>> for &Row {
+    type Error = GDBError;
+
     fn try_into(self) -> GDBResult<Vec<u8>> {
         let mut raw_data = Vec::with_capacity(self.len());
 
-        for item in self.data.into_iter() {
-            raw_data.push(serde_cbor::to_vec(&item)?);
+        for item in self.data.iter() {
+            raw_data.push(serde_cbor::to_vec(item)?);
         }
 
         Ok(bincode::serialize(&raw_data)?)
@@ -162,6 +183,16 @@ impl<'a> RowRef<'a> {
             RowRef::None => None,
         }
     }
+
+    /// Turn into an owned row, which is `None` if the row does not present
+    pub fn into_row(self) -> Option<Row> {
+        match self {
+            RowRef::Ref(row) => Some(row.clone()),
+            RowRef::Owned(row) => Some(row),
+            RowRef::Single(val) => Some(Row::from(vec![val])),
+            RowRef::None => None,
+        }
+    }
 }
 
 /// The table structure, which maintain a couple of rows
@@ -179,6 +210,12 @@ pub trait PropertyTableTrait {
     /// To get a row from the table at the give index
     fn get_row(&self, index: usize) -> GDBResult<RowRef>;
 
+    /// Get the rows at the given indices in order, where the row of an absent index is
+    /// `RowRef::None`
+    fn get_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>> {
+        indices.iter().map(|index| self.get_row(*index)).collect()
+    }
+
     /// Inserts a key-value pair into the table.
     ///
     /// If the table did not have this key present, [`Ok(None)`] is returned.
@@ -189,6 +226,11 @@ pub trait PropertyTableTrait {
     /// `GDBError` will be thrown out in case of error
     fn insert(&mut self, index: usize, row: Row) -> GDBResult<Option<Row>>;
 
+    /// Removes the row at the given index from the table, returning the row if it presents.
+    ///
+    /// `GDBError` will be thrown out in case of error
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>>;
+
     /// Batch inserting a certain number of items
     /// Return the number of data that is successfully inserted
     fn insert_batches<Iter: Iterator<Item = (usize, Row)>>(
@@ -204,8 +246,86 @@ pub trait PropertyTableTrait {
         Ok(count)
     }
 
+    /// Atomically apply a batch of writes, each of which inserts the row at the index, or removes
+    /// the row at the index if the row is `None`. Either all the writes are applied, or none of
+    /// them is if any write fails, and the error is returned.
+    ///
+    /// By default, the applied writes are undone in reverse order on failure.
+    fn write_batch(&mut self, writes: Vec<(usize, Option<Row>)>) -> GDBResult<()> {
+        let mut applied = Vec::with_capacity(writes.len());
+        for (index, row) in writes {
+            let result = match row {
+                Some(row) => self.insert(index, row),
+                None => self.remove(index),
+            };
+            match result {
+                Ok(old) => applied.push((index, old)),
+                Err(e) => {
+                    // an undo only writes back a row that was written before
+                    for (index, old) in applied.into_iter().rev() {
+                        let _ = match old {
+                            Some(row) => self.insert(index, row),
+                            None => self.remove(index),
+                        };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stage the `writes`, as those of `Self::write_batch()`, into `batch` rather than applying
+    /// them, so that they are committed along with the writes of other tables by
+    /// `TableBatch::commit()`, after which `Self::apply_staged()` must be called with the same
+    /// writes. A table must reject here any write that it fails to apply, so that the writes
+    /// either fail as a whole before committing, or all succeed.
+    ///
+    /// By default, nothing is staged, as the writes are applied in memory by
+    /// `Self::apply_staged()`.
+    fn stage_writes(
+        &self, _writes: &[(usize, Option<Row>)], _batch: &mut TableBatch,
+    ) -> GDBResult<()> {
+        Ok(())
+    }
+
+    /// Apply the `writes` staged by `Self::stage_writes()` once the batch is committed, which
+    /// never fails. By default, the rows are inserted or removed one by one.
+    fn apply_staged(&mut self, writes: Vec<(usize, Option<Row>)>) {
+        for (index, row) in writes {
+            let _ = match row {
+                Some(row) => self.insert(index, row),
+                None => self.remove(index),
+            };
+        }
+    }
+
+    /// The RocksDB that the table is stored in if any, for another table to be stored in it
+    /// through `Self::new_shared()`.
+    fn rocks_db(&self) -> Option<Arc<Tree>> {
+        None
+    }
+
+    /// Take a snapshot of the table if it supports, which is a consistent read view of the table
+    /// at this moment, see `RocksSnapshot`. The snapshot `shared` of another table, if it is of the
+    /// same RocksDB, is reused, so that both snapshots are of the same moment.
+    fn snapshot<'a>(&'a self, _shared: Option<&RocksSnapshot<'a>>) -> Option<RocksSnapshot<'a>> {
+        None
+    }
+
     fn new<P: AsRef<Path>>(_path: P) -> Self;
 
+    /// Create a table like `Self::new()`, but in the RocksDB `shared` of another table if it is
+    /// given and the table is RocksDB-based, so that the writes to both tables can be staged into
+    /// one `TableBatch`. By default, `shared` is ignored.
+    fn new_shared<P: AsRef<Path>>(path: P, _shared: Option<Arc<Tree>>) -> Self
+    where
+        Self: std::marker::Sized,
+    {
+        Self::new(path)
+    }
+
     /// Export `Self`'s binary file to the given file
     fn export<P: AsRef<Path>>(&self, path: P, fname: &str) -> GDBResult<()>;
 
@@ -213,6 +333,36 @@ pub trait PropertyTableTrait {
     fn import<P: AsRef<Path>>(path: P, fname: &str) -> GDBResult<Self>
     where
         Self: std::marker::Sized;
+
+    /// Import like `Self::import()`, but from the RocksDB `shared` of another table if it is
+    /// given, for a table created by `Self::new_shared()`. By default, `shared` is ignored.
+    fn import_shared<P: AsRef<Path>>(
+        path: P, fname: &str, _shared: Option<Arc<Tree>>,
+    ) -> GDBResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        Self::import(path, fname)
+    }
+}
+
+/// The writes to one or more property tables to commit at once, as staged by
+/// `PropertyTableTrait::stage_writes()`. The writes to the `RocksTable`s, which must be stored in
+/// one RocksDB, see `PropertyTableTrait::new_shared()`, are committed in one `WriteBatch` of
+/// RocksDB, i.e., either all of them are written, or none of them is.
+#[derive(Default)]
+pub struct TableBatch {
+    rocks: Option<(Arc<Tree>, WriteBatch)>,
+}
+
+impl TableBatch {
+    /// Commit the staged writes, which are left unwritten on error
+    pub fn commit(self) -> GDBResult<()> {
+        if let Some((db, batch)) = self.rocks {
+            db.write(batch)?;
+        }
+        Ok(())
+    }
 }
 
 /// A memory-based table to store the properties of an entity (vertex or edge)
@@ -280,6 +430,17 @@ impl PropertyTableTrait for PropertyTable {
         }
     }
 
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>> {
+        match &mut self.properties {
+            Table::Sparse(data) => Ok(data.remove(&index)),
+            // the rows after the index keep their positions, so the row is only cleared
+            Table::Dense(data) => Ok(data
+                .get_mut(index)
+                .map(|row| std::mem::replace(row, Row::default()))
+                .filter(|row| !row.is_empty())),
+        }
+    }
+
     fn new<P: AsRef<Path>>(_path: P) -> Self {
         // By default use the dense table
         PropertyTable::new_dense()
@@ -329,12 +490,32 @@ impl PropertyTableTrait for SingleValueTable {
         if let Some(number) = item.as_u64() {
             _ret_val = self.property.insert(index, number).map(|num| Row::from(num));
         } else {
-            return GDBResult::Err(GDBError::ParseError);
+            return GDBResult::Err(GDBError::ParseError(format!("{:?} is not a u64", item)));
         }
 
         Ok(_ret_val)
     }
 
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>> {
+        Ok(self.property.remove(&index).map(|num| Row::from(num)))
+    }
+
+    // Check the rows as `Self::insert()` does, so that they are then applied without failure
+    fn stage_writes(
+        &self, writes: &[(usize, Option<Row>)], _batch: &mut TableBatch,
+    ) -> GDBResult<()> {
+        for row in writes.iter().filter_map(|(_, row)| row.as_ref()) {
+            match row.get(0) {
+                None => return GDBResult::Err(GDBError::OutOfBoundError),
+                Some(item) if item.as_u64().is_none() => {
+                    return GDBResult::Err(GDBError::ParseError(format!("{:?} is not a u64", item)))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     fn new<P: AsRef<Path>>(_path: P) -> Self {
         Self { property: HashMap::new() }
     }
@@ -354,7 +535,10 @@ impl PropertyTableTrait for SingleValueTable {
 
 /// A property table based on `RocksDB`
 pub struct RocksTable {
-    property: Tree,
+    property: Arc<Tree>,
+    /// The prefix of the keys, which keeps the rows apart from those of the other tables stored in
+    /// the same RocksDB, see `PropertyTableTrait::new_shared()`
+    prefix: Vec<u8>,
     read_only: bool,
 }
 
@@ -369,7 +553,7 @@ impl RocksTable {
         opts.create_if_missing(true);
 
         let tree = Tree::open(&opts, path)?;
-        Ok(RocksTable { property: tree, read_only: false })
+        Ok(RocksTable { property: Arc::new(tree), prefix: vec![], read_only: false })
     }
 
     pub fn open<P: AsRef<Path>>(path: P, read_only: bool) -> GDBResult<Self> {
@@ -384,10 +568,23 @@ impl RocksTable {
 
             let tree = Tree::open(&opts, path)?;
 
-            Ok(RocksTable { property: tree, read_only })
+            Ok(RocksTable { property: Arc::new(tree), prefix: vec![], read_only })
         }
     }
 
+    /// A table stored in the RocksDB `db` of another table, whose rows are keyed with the prefix
+    /// of `name`, e.g., the file name of the table.
+    fn shared(db: Arc<Tree>, name: &str, read_only: bool) -> Self {
+        let mut prefix = name.as_bytes().to_vec();
+        prefix.push(b'/');
+        RocksTable { property: db, prefix, read_only }
+    }
+
+    /// The key of the row at `index`
+    fn key(&self, index: usize) -> GDBResult<Vec<u8>> {
+        row_key(&self.prefix, index)
+    }
+
     pub fn flush(&self) -> GDBResult<()> {
         if self.read_only {
             panic!("Trying to modify a read-only db.");
@@ -429,8 +626,7 @@ impl RocksTable {
 
         let mut batch = WriteBatch::default();
         for (id, prop) in props {
-            let id_bytes = bincode::serialize(&id)?;
-            batch.put(id_bytes, prop);
+            batch.put(self.key(id)?, prop);
             count += 1;
         }
 
@@ -440,8 +636,86 @@ impl RocksTable {
     }
 
     pub fn get_raw_data(&self, index: usize) -> GDBResult<Option<Vec<u8>>> {
-        let index_bytes = bincode::serialize(&index)?;
-        Ok(self.property.get(&index_bytes)?)
+        Ok(self.property.get(&self.key(index)?)?)
+    }
+
+    /// Atomically write the rows in one `WriteBatch` of RocksDB, where a `None` row removes the
+    /// row at the index. Unlike `PropertyTableTrait::write_batch()`, it can be called while the
+    /// table is shared with the readers, e.g. those reading through a `RocksSnapshot`.
+    pub fn write_rows(&self, writes: Vec<(usize, Option<Row>)>) -> GDBResult<()> {
+        let mut batch = TableBatch::default();
+        self.stage_writes(&writes, &mut batch)?;
+        batch.commit()
+    }
+}
+
+#[cfg(test)]
+impl RocksTable {
+    /// Open the table in the read-only mode of RocksDB, while the table itself accepts the
+    /// writes, so that the writes fail in RocksDB
+    pub(crate) fn open_rocks_read_only<P: AsRef<Path>>(path: P) -> GDBResult<Self> {
+        let tree = Tree::open_for_read_only(&Options::default(), path, false)?;
+        Ok(RocksTable { property: Arc::new(tree), prefix: vec![], read_only: false })
+    }
+}
+
+/// The key of the row at `index` of a table whose keys are of `prefix`, see `RocksTable::prefix`
+fn row_key(prefix: &[u8], index: usize) -> GDBResult<Vec<u8>> {
+    let mut key = prefix.to_vec();
+    key.extend(bincode::serialize(&index)?);
+    Ok(key)
+}
+
+/// As `multi_get` is not available in the current version of RocksDB, the rows are fetched
+/// through one raw iterator, which seeks the keys in order and thus only moves forward
+fn get_rows_by_iter<'a>(
+    mut iter: DBRawIterator, prefix: &[u8], indices: &[usize],
+) -> GDBResult<Vec<RowRef<'a>>> {
+    let mut keys = Vec::with_capacity(indices.len());
+    for (pos, index) in indices.iter().enumerate() {
+        keys.push((row_key(prefix, *index)?, pos));
+    }
+    keys.sort();
+
+    let mut rows = vec![RowRef::None; indices.len()];
+    for (key, pos) in keys {
+        iter.seek(&key);
+        if iter.valid() && iter.key() == Some(key.as_slice()) {
+            if let Some(raw_data) = iter.value() {
+                rows[pos] = RowRef::Owned(Row::try_from(raw_data.to_vec())?);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// A snapshot of a `RocksTable`, through which the reads see the rows as of the moment the
+/// snapshot is taken, regardless of the writes afterwards, so that a long-running reader, e.g.
+/// a traversal, sees a stable view of the properties while writes happen. The snapshot borrows
+/// the table, and is released once dropped, after which RocksDB can compact the old versions of
+/// the rows kept for it. Thus a snapshot is expected to be held for the duration of a reader,
+/// rather than for long.
+pub struct RocksSnapshot<'a> {
+    /// The snapshot of the RocksDB, which is shared by the tables stored in it
+    snapshot: Arc<Snapshot<'a>>,
+    db: &'a Tree,
+    prefix: &'a [u8],
+}
+
+impl<'a> RocksSnapshot<'a> {
+    /// To get a row of the snapshot at the given index
+    pub fn get_row(&self, index: usize) -> GDBResult<RowRef> {
+        if let Some(raw_data) = self.snapshot.get(&row_key(self.prefix, index)?)? {
+            Ok(RowRef::Owned(Row::try_from(raw_data)?))
+        } else {
+            Ok(RowRef::None)
+        }
+    }
+
+    /// Get the rows of the snapshot at the given indices in order, where the row of an absent
+    /// index is `RowRef::None`
+    pub fn get_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>> {
+        get_rows_by_iter(self.snapshot.raw_iterator(), self.prefix, indices)
     }
 }
 
@@ -459,13 +733,19 @@ impl PropertyTableTrait for RocksTable {
         }
     }
 
+    fn get_rows(&self, indices: &[usize]) -> GDBResult<Vec<RowRef>> {
+        get_rows_by_iter(self.property.raw_iterator(), &self.prefix, indices)
+    }
+
     // Careful, calling insertion to RocksDB will be very slow, use `insert_batchs` instead
     fn insert(&mut self, index: usize, row: Row) -> GDBResult<Option<Row>> {
+        if self.read_only {
+            return Err(GDBError::ModifyReadOnlyError);
+        }
         let old_val = self.get_row(index);
 
-        let id_bytes = bincode::serialize(&index)?;
         let raw_data: Vec<u8> = row.try_into()?;
-        self.property.put(id_bytes, raw_data)?;
+        self.property.put(self.key(index)?, raw_data)?;
 
         old_val.map(|x| match x {
             RowRef::Owned(row) => Some(row),
@@ -473,16 +753,84 @@ impl PropertyTableTrait for RocksTable {
         })
     }
 
+    fn remove(&mut self, index: usize) -> GDBResult<Option<Row>> {
+        if self.read_only {
+            return Err(GDBError::ModifyReadOnlyError);
+        }
+        let old_val = match self.get_row(index)? {
+            RowRef::Owned(row) => Some(row),
+            _ => None,
+        };
+        self.property.delete(self.key(index)?)?;
+
+        Ok(old_val)
+    }
+
     fn insert_batches<Iter: Iterator<Item = (usize, Row)>>(
         &mut self, iter: Iter,
     ) -> GDBResult<usize> {
         self.extend_batches(iter)
     }
 
+    // The writes are committed in one atomic `WriteBatch` of RocksDB
+    fn write_batch(&mut self, writes: Vec<(usize, Option<Row>)>) -> GDBResult<()> {
+        self.write_rows(writes)
+    }
+
+    fn stage_writes(
+        &self, writes: &[(usize, Option<Row>)], batch: &mut TableBatch,
+    ) -> GDBResult<()> {
+        if self.read_only {
+            return Err(GDBError::ModifyReadOnlyError);
+        }
+        let (db, rocks_batch) =
+            batch.rocks.get_or_insert_with(|| (self.property.clone(), WriteBatch::default()));
+        // the writes to different RocksDBs can not be committed atomically
+        if !Arc::ptr_eq(db, &self.property) {
+            return Err(GDBError::InvalidFunctionCallError);
+        }
+        for (index, row) in writes {
+            let key = row_key(&self.prefix, *index)?;
+            match row {
+                Some(row) => {
+                    let raw_data: Vec<u8> = row.try_into()?;
+                    rocks_batch.put(key, raw_data);
+                }
+                None => rocks_batch.delete(key),
+            }
+        }
+        Ok(())
+    }
+
+    // The rows are already written to RocksDB by `TableBatch::commit()`
+    fn apply_staged(&mut self, _writes: Vec<(usize, Option<Row>)>) {}
+
+    fn rocks_db(&self) -> Option<Arc<Tree>> {
+        Some(self.property.clone())
+    }
+
+    fn snapshot<'a>(&'a self, shared: Option<&RocksSnapshot<'a>>) -> Option<RocksSnapshot<'a>> {
+        let snapshot = match shared {
+            Some(shared) if std::ptr::eq(shared.db, self.property.as_ref()) => {
+                shared.snapshot.clone()
+            }
+            _ => Arc::new(self.property.snapshot()),
+        };
+        Some(RocksSnapshot { snapshot, db: &self.property, prefix: &self.prefix })
+    }
+
     fn new<P: AsRef<Path>>(path: P) -> Self {
         Self::new_table(path).unwrap()
     }
 
+    // The table is keyed by its file name in the shared RocksDB
+    fn new_shared<P: AsRef<Path>>(path: P, shared: Option<Arc<Tree>>) -> Self {
+        match (shared, path.as_ref().file_name().and_then(|name| name.to_str())) {
+            (Some(db), Some(name)) => Self::shared(db, name, false),
+            _ => Self::new(path),
+        }
+    }
+
     fn export<P: AsRef<Path>>(&self, _path: P, _fname: &str) -> GDBResult<()> {
         self.flush()
     }
@@ -490,6 +838,17 @@ impl PropertyTableTrait for RocksTable {
     fn import<P: AsRef<Path>>(path: P, fname: &str) -> GDBResult<Self> {
         Self::open(path.as_ref().join(&fname), true)
     }
+
+    // A table that was exported in a RocksDB of its own, e.g. before the tables were stored in
+    // one RocksDB, is still imported from there
+    fn import_shared<P: AsRef<Path>>(
+        path: P, fname: &str, shared: Option<Arc<Tree>>,
+    ) -> GDBResult<Self> {
+        match shared {
+            Some(db) if !path.as_ref().join(fname).exists() => Ok(Self::shared(db, fname, true)),
+            _ => Self::import(path, fname),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -534,6 +893,27 @@ mod test {
         assert_eq!(table.get_row(2).unwrap(), RowRef::Ref(&Row::default()));
     }
 
+    #[test]
+    fn test_property_table_remove() {
+        let mut tables = vec![PropertyTable::new_dense(), PropertyTable::new_sparse()];
+        for table in tables.iter_mut() {
+            assert!(table.insert(2, Row::from("abc".to_string())).unwrap().is_none());
+            assert_eq!(table.remove(2).unwrap(), Some(Row::from("abc".to_string())));
+            assert_eq!(table.remove(2).unwrap(), None);
+            assert_eq!(table.remove(5).unwrap(), None);
+            assert!(table
+                .insert(2, Row::from("def".to_string()))
+                .unwrap()
+                .map_or(true, |row| row.is_empty()));
+        }
+
+        let mut table = SingleValueTable::new("");
+        assert!(table.insert(1, Row::from(7_u64)).unwrap().is_none());
+        assert_eq!(table.remove(1).unwrap(), Some(Row::from(7_u64)));
+        assert_eq!(table.get_row(1).unwrap(), RowRef::None);
+    }
+
+    use std::sync::Arc;
     use tempdir::TempDir;
 
     #[test]
@@ -562,5 +942,190 @@ mod test {
         for index in 3_usize..6 {
             assert_eq!(table.get_row(index).unwrap(), RowRef::Owned(Row::default()))
         }
+
+        // The row is removed
+        assert_eq!(table.remove(2).unwrap(), Some(Row::default()));
+        assert_eq!(table.get_row(2).unwrap(), RowRef::None);
+        assert_eq!(table.remove(2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_property_table_write_batch() {
+        let mut table = SingleValueTable::new("");
+        table.insert(1, Row::from(1_u64)).unwrap();
+        table.insert(2, Row::from(2_u64)).unwrap();
+        let writes = vec![(1, Some(Row::from(10_u64))), (2, None), (3, Some(Row::from(3_u64)))];
+        table.write_batch(writes).unwrap();
+        assert_eq!(table.get_row(1).unwrap(), RowRef::Single(json!(10_u64)));
+        assert_eq!(table.get_row(2).unwrap(), RowRef::None);
+        assert_eq!(table.get_row(3).unwrap(), RowRef::Single(json!(3_u64)));
+
+        // fail in the middle, where the writes before are undone
+        let writes = vec![
+            (1, Some(Row::from(100_u64))),
+            (3, None),
+            (4, Some(Row::from(4_u64))),
+            (5, Some(Row::from("abc".to_string()))),
+            (6, Some(Row::from(6_u64))),
+        ];
+        assert!(table.write_batch(writes).is_err());
+        assert_eq!(table.get_row(1).unwrap(), RowRef::Single(json!(10_u64)));
+        assert_eq!(table.get_row(3).unwrap(), RowRef::Single(json!(3_u64)));
+        assert_eq!(table.get_row(4).unwrap(), RowRef::None);
+        assert_eq!(table.get_row(6).unwrap(), RowRef::None);
+    }
+
+    #[test]
+    fn test_rocks_property_table_write_batch() {
+        let dir = TempDir::new("test_rocksdb_write_batch").unwrap();
+        let items = (0_usize..3).map(|index| (index, Row::from(index as u64)));
+        let mut table = RocksTable::with_data(dir.path(), items).unwrap();
+
+        let writes =
+            vec![(0, None), (1, Some(Row::from("abc".to_string()))), (5, Some(Row::default()))];
+        table.write_batch(writes).unwrap();
+        assert_eq!(table.get_row(0).unwrap(), RowRef::None);
+        assert_eq!(table.get_row(1).unwrap(), RowRef::Owned(Row::from("abc".to_string())));
+        assert_eq!(table.get_row(2).unwrap(), RowRef::Owned(Row::from(2_u64)));
+        assert_eq!(table.get_row(5).unwrap(), RowRef::Owned(Row::default()));
+
+        // a read-only table rejects the whole batch
+        drop(table);
+        let mut table = RocksTable::open(dir.path(), true).unwrap();
+        assert_eq!(
+            table.write_batch(vec![(2, None), (6, Some(Row::default()))]),
+            Err(GDBError::ModifyReadOnlyError)
+        );
+        assert_eq!(table.get_row(2).unwrap(), RowRef::Owned(Row::from(2_u64)));
+        assert_eq!(table.get_row(6).unwrap(), RowRef::None);
+    }
+
+    #[test]
+    fn test_rocks_property_table_shared() {
+        let dir = TempDir::new("test_rocksdb_shared").unwrap();
+        let vertex_path = dir.path().join("node_property");
+        let mut vertex_table = RocksTable::new(&vertex_path);
+        let mut edge_table =
+            RocksTable::new_shared(dir.path().join("edge_property"), vertex_table.rocks_db());
+        vertex_table.insert(0, Row::from(1_u64)).unwrap();
+        edge_table.insert(0, Row::from(2_u64)).unwrap();
+        assert_eq!(vertex_table.get_row(0).unwrap(), RowRef::Owned(Row::from(1_u64)));
+        assert_eq!(edge_table.get_row(0).unwrap(), RowRef::Owned(Row::from(2_u64)));
+
+        // the writes to both tables are committed in one batch
+        let mut batch = TableBatch::default();
+        vertex_table.stage_writes(&[(0, None), (1, Some(Row::from(3_u64)))], &mut batch).unwrap();
+        edge_table.stage_writes(&[(1, Some(Row::from(4_u64)))], &mut batch).unwrap();
+        assert_eq!(vertex_table.get_row(1).unwrap(), RowRef::None);
+        batch.commit().unwrap();
+        assert_eq!(
+            vertex_table.get_rows(&[0, 1]).unwrap(),
+            vec![RowRef::None, RowRef::Owned(Row::from(3_u64))]
+        );
+        assert_eq!(
+            edge_table.get_rows(&[0, 1]).unwrap(),
+            vec![RowRef::Owned(Row::from(2_u64)), RowRef::Owned(Row::from(4_u64))]
+        );
+
+        // a batch can not span two RocksDBs
+        let other_dir = TempDir::new("test_rocksdb_shared_other").unwrap();
+        let other_table = RocksTable::new(other_dir.path());
+        let mut batch = TableBatch::default();
+        vertex_table.stage_writes(&[(2, Some(Row::from(5_u64)))], &mut batch).unwrap();
+        assert_eq!(
+            other_table.stage_writes(&[(2, Some(Row::from(6_u64)))], &mut batch),
+            Err(GDBError::InvalidFunctionCallError)
+        );
+
+        // the edge table is imported from the RocksDB of the vertex table
+        drop(batch);
+        drop(edge_table);
+        drop(vertex_table);
+        let vertex_table = RocksTable::import(dir.path(), "node_property").unwrap();
+        let edge_table =
+            RocksTable::import_shared(dir.path(), "edge_property", vertex_table.rocks_db())
+                .unwrap();
+        assert!(!dir.path().join("edge_property").exists());
+        assert_eq!(vertex_table.get_row(1).unwrap(), RowRef::Owned(Row::from(3_u64)));
+        assert_eq!(edge_table.get_row(1).unwrap(), RowRef::Owned(Row::from(4_u64)));
+    }
+
+    #[test]
+    fn test_rocks_snapshot() {
+        let dir = TempDir::new("test_rocksdb_snapshot").unwrap();
+        let items = (0_usize..10).map(|index| (index, Row::from(index as u64)));
+        let table = Arc::new(RocksTable::with_data(dir.path(), items).unwrap());
+
+        let snapshot = table.snapshot(None).unwrap();
+        let writer = {
+            let table = table.clone();
+            std::thread::spawn(move || {
+                for round in 1..=10_u64 {
+                    let writes = (0_usize..10)
+                        .map(|index| {
+                            // remove the even rows and add new ones in the last round
+                            if round == 10 && index % 2 == 0 {
+                                (index, None)
+                            } else {
+                                (index, Some(Row::from(index as u64 + round * 100)))
+                            }
+                        })
+                        .chain(std::iter::once((10 + round as usize, Some(Row::default()))))
+                        .collect();
+                    table.write_rows(writes).unwrap();
+                }
+            })
+        };
+
+        // the view of the snapshot is unchanged while, and after the writes
+        let indices: Vec<usize> = (0..21).collect();
+        let expected: Vec<RowRef> = (0..21)
+            .map(
+                |index| {
+                    if index < 10 {
+                        RowRef::Owned(Row::from(index as u64))
+                    } else {
+                        RowRef::None
+                    }
+                },
+            )
+            .collect();
+        for _ in 0..10 {
+            assert_eq!(snapshot.get_rows(&indices).unwrap(), expected);
+        }
+        writer.join().unwrap();
+        assert_eq!(snapshot.get_rows(&indices).unwrap(), expected);
+        assert_eq!(snapshot.get_row(3).unwrap(), RowRef::Owned(Row::from(3_u64)));
+        assert_eq!(snapshot.get_row(20).unwrap(), RowRef::None);
+        drop(snapshot);
+
+        // the table, and a new snapshot see the writes
+        assert_eq!(table.get_row(0).unwrap(), RowRef::None);
+        assert_eq!(table.get_row(3).unwrap(), RowRef::Owned(Row::from(1003_u64)));
+        let snapshot = table.snapshot(None).unwrap();
+        assert_eq!(snapshot.get_row(3).unwrap(), RowRef::Owned(Row::from(1003_u64)));
+        assert_eq!(snapshot.get_row(20).unwrap(), RowRef::Owned(Row::default()));
+    }
+
+    #[test]
+    fn test_rocks_property_table_get_rows() {
+        let dir = TempDir::new("test_rocksdb_get_rows").unwrap();
+        let items = (0_usize..10).map(|index| (index * 2, Row::from(index as u64)));
+        let table = RocksTable::with_data(dir.path(), items).unwrap();
+
+        // the rows are returned in the order of the given indices, with absent rows as `None`
+        let rows = table.get_rows(&[18, 1, 0, 300, 6, 6]).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                RowRef::Owned(Row::from(9_u64)),
+                RowRef::None,
+                RowRef::Owned(Row::from(0_u64)),
+                RowRef::None,
+                RowRef::Owned(Row::from(3_u64)),
+                RowRef::Owned(Row::from(3_u64)),
+            ]
+        );
+        assert!(table.get_rows(&[]).unwrap().is_empty());
     }
 }
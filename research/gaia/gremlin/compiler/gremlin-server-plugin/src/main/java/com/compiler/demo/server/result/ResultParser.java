@@ -43,6 +43,37 @@ public class ResultParser {
             resultPB.getTagProperties().getItemList().forEach(e -> {
                 result.add(parseTagPropertyValue(e));
             });
+        } else if (resultPB.getInnerCase() == GremlinResult.Result.InnerCase.COUNTS) {
+            result.addAll(resultPB.getCounts().getItemList());
+        } else if (resultPB.getInnerCase() == GremlinResult.Result.InnerCase.GROUP_COUNTS) {
+            resultPB.getGroupCounts().getItemList().forEach(e -> {
+                result.add(parseGroupCount(e));
+            });
+        } else if (resultPB.getInnerCase() == GremlinResult.Result.InnerCase.VALUES) {
+            result.addAll(resultPB.getValues().getItemList());
+        } else if (resultPB.getInnerCase() == GremlinResult.Result.InnerCase.COLLECTIONS) {
+            resultPB.getCollections().getItemList().forEach(c -> {
+                result.add(parseCollection(c));
+            });
+        } else if (resultPB.getInnerCase() == GremlinResult.Result.InnerCase.MIXED) {
+            GremlinResult.MixedResult mixed = resultPB.getMixed();
+            mixed.getElements().getItemList().forEach(e -> {
+                result.add(parseElement(e));
+            });
+            mixed.getPaths().getItemList().forEach(p -> {
+                result.add(parsePath(p));
+            });
+            mixed.getTagProperties().getItemList().forEach(e -> {
+                result.add(parseTagPropertyValue(e));
+            });
+            result.addAll(mixed.getCounts().getItemList());
+            mixed.getGroupCounts().getItemList().forEach(e -> {
+                result.add(parseGroupCount(e));
+            });
+            result.addAll(mixed.getValues().getItemList());
+            mixed.getCollections().getItemList().forEach(c -> {
+                result.add(parseCollection(c));
+            });
         } else {
             throw new UnsupportedOperationException("");
         }
@@ -65,11 +96,38 @@ public class ResultParser {
     protected static List<Object> parsePath(GremlinResult.Path pathPB) {
         List<Object> path = new ArrayList<>();
         pathPB.getPathList().forEach(p -> {
-            path.add(parseElement(p));
+            if (p.getInnerCase() == GremlinResult.GraphElement.InnerCase.VALUE) {
+                path.add(p.getValue());
+            } else {
+                path.add(parseElement(p));
+            }
         });
         return path;
     }
 
+    protected static List<Object> parseCollection(GremlinResult.Collection collectionPB) {
+        List<Object> collection = new ArrayList<>();
+        collectionPB.getItemList().forEach(e -> {
+            if (e.getInnerCase() == GremlinResult.GraphElement.InnerCase.VALUE) {
+                collection.add(e.getValue());
+            } else {
+                collection.add(parseElement(e));
+            }
+        });
+        return collection;
+    }
+
+    protected static Map<Object, Long> parseGroupCount(GremlinResult.GroupCount groupCount) {
+        Map<Object, Long> result = new HashMap<>();
+        GremlinResult.GraphElement key = groupCount.getKey();
+        if (key.getInnerCase() == GremlinResult.GraphElement.InnerCase.VALUE) {
+            result.put(key.getValue(), groupCount.getCount());
+        } else {
+            result.put(parseElement(key), groupCount.getCount());
+        }
+        return result;
+    }
+
     protected static Map<String, Map<String, Object>> parseTagPropertyValue(GremlinResult.TagProperties tagProperties) {
         Map<String, Map<String, Object>> result = new HashMap<>();
         tagProperties.getItemList().forEach(iterm -> {
@@ -13,7 +13,9 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use gremlin_core::{create_demo_graph, GremlinJobFactory, Partitioner, ProtoReflect};
+use gremlin_core::{
+    create_demo_graph, get_demo_schema, GremlinJobFactory, Partitioner, ProtoReflect,
+};
 use pegasus::Configuration;
 use pegasus_server::rpc::start_debug_rpc_server;
 use pegasus_server::service::Service;
@@ -25,7 +27,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     pegasus::startup(Configuration::singleton()).unwrap();
     println!("try to start rpc server;");
     let partition = Partition;
-    let factory = GremlinJobFactory::new(partition);
+    let mut factory = GremlinJobFactory::new(partition);
+    factory.set_schema(&*get_demo_schema());
     let mut service = Service::new(factory);
     service.register_preprocess(ProtoReflect);
     start_debug_rpc_server("0.0.0.0:1234".parse().unwrap(), service).await?;
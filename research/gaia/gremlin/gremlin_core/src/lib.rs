@@ -35,8 +35,9 @@ use std::sync::Arc;
 
 use crate::process::traversal::step::*;
 use crate::process::traversal::traverser::Traverser;
-pub use crate::structure::{get_graph, register_graph};
+pub use crate::structure::{get_graph, register_graph, snapshot_graph};
 pub use crate::structure::{Element, GraphProxy, ID};
+use graph_store::schema::Schema;
 use pegasus::api::accum::{AccumFactory, Accumulator};
 use pegasus::api::function::*;
 use pegasus_common::collections::{Collection, CollectionFactory, DrainSet, DrainSetFactory};
@@ -52,9 +53,14 @@ mod pb_request;
 mod result_process;
 mod storage;
 use crate::result_process::result_to_pb;
+pub use crate::result_process::{
+    register_value_encoder, DictResolver, EncodeError, IdentityResolver, LabelMap, LabelResolver,
+    ResultEncoder, ValueEncoder, DEFAULT_MAX_BLOB_SIZE,
+};
+pub use generated::common as common_pb;
 pub use object::Object;
 pub use pb_request::ProtoReflect;
-pub use storage::create_demo_graph;
+pub use storage::{create_demo_graph, get_demo_schema};
 
 #[cfg(feature = "proto_inplace")]
 mod generated {
@@ -91,22 +97,52 @@ pub trait Partitioner: Send + Sync + 'static {
 
 pub struct GremlinJobFactory {
     partitioner: Arc<dyn Partitioner>,
+    labels: Arc<LabelMap>,
+    max_blob_size: usize,
 }
 
 impl GremlinJobFactory {
     pub fn new<D: Partitioner>(partitioner: D) -> Self {
-        GremlinJobFactory { partitioner: Arc::new(partitioner) }
+        GremlinJobFactory {
+            partitioner: Arc::new(partitioner),
+            labels: Arc::new(LabelMap::default()),
+            max_blob_size: DEFAULT_MAX_BLOB_SIZE,
+        }
+    }
+
+    /// Resolve the id-typed labels in the results to their names registered in the graph schema;
+    pub fn set_schema(&mut self, schema: &dyn Schema) {
+        self.labels = Arc::new(LabelMap::from_schema(schema));
+    }
+
+    /// Set the max size in bytes of a blob value in the results sent to the client; Default is
+    /// [`DEFAULT_MAX_BLOB_SIZE`];
+    pub fn set_max_blob_size(&mut self, max_blob_size: usize) {
+        self.max_blob_size = max_blob_size;
     }
 }
 
-pub struct TraverserSinkEncoder;
+pub struct TraverserSinkEncoder {
+    labels: Arc<LabelMap>,
+    max_blob_size: usize,
+}
+
+impl TraverserSinkEncoder {
+    pub fn new(labels: Arc<LabelMap>, max_blob_size: usize) -> Self {
+        TraverserSinkEncoder { labels, max_blob_size }
+    }
+}
 
 impl EncodeFunction<Traverser> for TraverserSinkEncoder {
-    fn encode(&self, data: Vec<Traverser>) -> Vec<u8> {
-        let result_pb = result_to_pb(data);
+    fn encode(&self, data: Vec<Traverser>) -> FnResult<Vec<u8>> {
+        let result_pb =
+            result_to_pb(data, self.labels.clone(), self.max_blob_size).map_err(|e| {
+                error!("encode result error: {}", e);
+                Box::new(e) as Box<dyn std::error::Error + Send>
+            })?;
         let mut bytes = vec![];
         result_pb.encode_raw(&mut bytes);
-        bytes
+        Ok(bytes)
     }
 }
 
@@ -129,11 +165,15 @@ impl JobCompiler<Traverser> for GremlinJobFactory {
     fn source(
         &self, worker_index: u32, src: &dyn Resource,
     ) -> CompileResult<Box<dyn Iterator<Item = Traverser> + Send>> {
-        Ok(src
-            .as_any_ref()
-            .downcast_ref::<GraphVertexStep>()
-            .ok_or("Downcast `GraphVertexStep` error")?
-            .gen_source(worker_index))
+        let src = src.as_any_ref();
+        if let Some(step) = src.downcast_ref::<InjectStep>() {
+            Ok(step.gen_source(worker_index))
+        } else {
+            Ok(src
+                .downcast_ref::<GraphVertexStep>()
+                .ok_or("Downcast `GraphVertexStep` error")?
+                .gen_source(worker_index))
+        }
     }
 
     fn map(&self, res: &dyn Resource) -> CompileResult<Box<dyn MapFunction<Traverser, Traverser>>> {
@@ -214,6 +254,68 @@ impl JobCompiler<Traverser> for GremlinJobFactory {
     }
 
     fn sink(&self, _res: &dyn Resource) -> CompileResult<Box<dyn EncodeFunction<Traverser>>> {
-        Ok(Box::new(TraverserSinkEncoder))
+        Ok(Box::new(TraverserSinkEncoder::new(self.labels.clone(), self.max_blob_size)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structure::{DefaultDetails, Label, Vertex};
+    use pegasus_server::AnyData;
+
+    #[test]
+    fn test_sink_encode_error() {
+        #[derive(Clone, Debug)]
+        struct Unregistered;
+
+        let encoder = TraverserSinkEncoder::new(Arc::default(), DEFAULT_MAX_BLOB_SIZE);
+        let bytes = encoder.encode(vec![Traverser::with(6u64)]).expect("encode count error");
+        assert!(!bytes.is_empty());
+        // the error is returned to be sent to the client, rather than an empty result
+        let data = vec![Traverser::from_object(Object::UnknownOwned(Box::new(Unregistered)))];
+        match encoder.encode(data) {
+            Err(e) => assert!(e.to_string().starts_with("can't encode unknown type")),
+            Ok(_) => panic!("expect encode error"),
+        }
+    }
+
+    struct SinglePartition;
+
+    impl Partitioner for SinglePartition {
+        fn get_partition(&self, _id: &ID) -> u64 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_sink_label_map() {
+        let mut factory = GremlinJobFactory::new(SinglePartition);
+        factory.set_schema(&*get_demo_schema());
+        let encoder = TraverserSinkEncoder::new(factory.labels.clone(), factory.max_blob_size);
+        let v = Vertex::new(1, Some(Label::Id(0)), DefaultDetails::new(1, Label::Id(0)));
+        let bytes = encoder.encode(vec![Traverser::new(v)]).expect("encode vertex error");
+        let result_pb = generated::protobuf::Result::decode(bytes.as_slice()).unwrap();
+        match result_pb.inner {
+            Some(generated::protobuf::result::Inner::Elements(e)) => match &e.item[0].inner {
+                Some(generated::protobuf::graph_element::Inner::Vertex(v_pb)) => {
+                    assert_eq!(v_pb.label, "PERSON")
+                }
+                _ => panic!("expect vertex"),
+            },
+            _ => panic!("expect element result"),
+        }
+    }
+
+    #[test]
+    fn test_sink_max_blob_size() {
+        let mut factory = GremlinJobFactory::new(SinglePartition);
+        factory.set_max_blob_size(7);
+        let encoder = TraverserSinkEncoder::new(factory.labels.clone(), factory.max_blob_size);
+        assert!(encoder.encode(vec![Traverser::from_object(vec![0u8; 7])]).is_ok());
+        match encoder.encode(vec![Traverser::from_object(vec![0u8; 8])]) {
+            Err(e) => assert!(e.to_string().ends_with("larger than 7 bytes")),
+            Ok(_) => panic!("expect encode error"),
+        }
     }
 }
@@ -28,24 +28,36 @@ use std::ops::Deref;
 
 #[derive(Debug, Clone, Copy)]
 pub enum RawType {
+    Boolean,
     Byte,
     Integer,
     Long,
     Float,
+    DateTime,
     String,
     Blob(usize),
+    Null,
     Unknown,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum Primitives {
+    Boolean(bool),
     Byte(i8),
     Integer(i32),
     Long(i64),
+    /// A double precision float, an `f32` is widened into it losslessly, and it is encoded as
+    /// `F64` as is, so there is no single precision float to be distinguished from;
     Float(f64),
+    /// A date time as the milliseconds since the epoch in UTC, which is cast and compared with
+    /// numbers as the milliseconds, e.g. with the date times loaded as longs;
+    DateTime(i64),
 }
 
+const F64_EXACT_INT: i64 = 1 << 53;
+
 lazy_static! {
+    static ref BOOL: TypeId = TypeId::of::<bool>();
     static ref I8: TypeId = TypeId::of::<i8>();
     static ref U8: TypeId = TypeId::of::<u8>();
     static ref I16: TypeId = TypeId::of::<i16>();
@@ -74,9 +86,11 @@ impl Primitives {
     #[inline]
     pub fn raw_type(&self) -> RawType {
         match self {
+            Primitives::Boolean(_) => RawType::Boolean,
             Primitives::Byte(_) => RawType::Byte,
             Primitives::Integer(_) => RawType::Integer,
             Primitives::Long(_) => RawType::Long,
+            Primitives::DateTime(_) => RawType::DateTime,
             Primitives::Float(_) => RawType::Float,
         }
     }
@@ -88,10 +102,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 i8::try_from(*v).map_err(|_| CastError::new::<i8>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                i8::try_from(*v).map_err(|_| CastError::new::<i8>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                i8::try_from(*v).map_err(|_| CastError::new::<i8>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<i8>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<i8>(RawType::Boolean)),
         }
     }
 
@@ -102,10 +117,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 i16::try_from(*v).map_err(|_| CastError::new::<i16>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                i16::try_from(*v).map_err(|_| CastError::new::<i16>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                i16::try_from(*v).map_err(|_| CastError::new::<i16>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<i16>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<i16>(RawType::Boolean)),
         }
     }
 
@@ -114,10 +130,11 @@ impl Primitives {
         match self {
             Primitives::Byte(v) => Ok(*v as i32),
             Primitives::Integer(v) => Ok(*v),
-            Primitives::Long(v) => {
-                i32::try_from(*v).map_err(|_| CastError::new::<i32>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                i32::try_from(*v).map_err(|_| CastError::new::<i32>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<i32>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<i32>(RawType::Boolean)),
         }
     }
 
@@ -126,8 +143,9 @@ impl Primitives {
         match self {
             Primitives::Byte(v) => Ok(*v as i64),
             Primitives::Integer(v) => Ok(*v as i64),
-            Primitives::Long(v) => Ok(*v),
+            Primitives::Long(v) | Primitives::DateTime(v) => Ok(*v),
             Primitives::Float(_) => Err(CastError::new::<i64>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<i64>(RawType::Boolean)),
         }
     }
 
@@ -136,8 +154,9 @@ impl Primitives {
         match self {
             Primitives::Byte(v) => Ok(*v as i128),
             Primitives::Integer(v) => Ok(*v as i128),
-            Primitives::Long(v) => Ok(*v as i128),
+            Primitives::Long(v) | Primitives::DateTime(v) => Ok(*v as i128),
             Primitives::Float(_) => Err(CastError::new::<i128>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<i128>(RawType::Boolean)),
         }
     }
 
@@ -150,10 +169,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 u8::try_from(*v).map_err(|_| CastError::new::<u8>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                u8::try_from(*v).map_err(|_| CastError::new::<u8>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                u8::try_from(*v).map_err(|_| CastError::new::<u8>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<u8>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<u8>(RawType::Boolean)),
         }
     }
 
@@ -166,10 +186,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 u16::try_from(*v).map_err(|_| CastError::new::<u16>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                u16::try_from(*v).map_err(|_| CastError::new::<u16>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                u16::try_from(*v).map_err(|_| CastError::new::<u16>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<u16>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<u16>(RawType::Boolean)),
         }
     }
 
@@ -182,10 +203,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 u32::try_from(*v).map_err(|_| CastError::new::<u32>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                u32::try_from(*v).map_err(|_| CastError::new::<u32>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                u32::try_from(*v).map_err(|_| CastError::new::<u32>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<u32>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<u32>(RawType::Boolean)),
         }
     }
 
@@ -198,10 +220,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 u64::try_from(*v).map_err(|_| CastError::new::<u64>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                u64::try_from(*v).map_err(|_| CastError::new::<u64>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                u64::try_from(*v).map_err(|_| CastError::new::<u64>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<u64>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<u64>(RawType::Boolean)),
         }
     }
 
@@ -214,10 +237,11 @@ impl Primitives {
             Primitives::Integer(v) => {
                 u128::try_from(*v).map_err(|_| CastError::new::<u128>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                u128::try_from(*v).map_err(|_| CastError::new::<u128>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                u128::try_from(*v).map_err(|_| CastError::new::<u128>(self.raw_type()))
             }
             Primitives::Float(_) => Err(CastError::new::<u128>(RawType::Float)),
+            Primitives::Boolean(_) => Err(CastError::new::<u128>(RawType::Boolean)),
         }
     }
 
@@ -230,17 +254,44 @@ impl Primitives {
             Primitives::Integer(v) => {
                 f64::try_from(*v).map_err(|_| CastError::new::<f64>(RawType::Integer))
             }
-            Primitives::Long(v) => {
-                let t = i16::try_from(*v).map_err(|_| CastError::new::<f64>(RawType::Long))?;
-                f64::try_from(t).map_err(|_| CastError::new::<f64>(RawType::Long))
+            Primitives::Long(v) | Primitives::DateTime(v) => {
+                // only the longs in [-2^53, 2^53] can be represented by f64 exactly;
+                if (-F64_EXACT_INT..=F64_EXACT_INT).contains(v) {
+                    Ok(*v as f64)
+                } else {
+                    Err(CastError::new::<f64>(self.raw_type()))
+                }
             }
             Primitives::Float(v) => Ok(*v),
+            Primitives::Boolean(_) => Err(CastError::new::<f64>(RawType::Boolean)),
+        }
+    }
+
+    /// Cast into bool, where an integer 0 is false and 1 is true, as bool was stored as `Byte`
+    /// before `Boolean` was added;
+    #[inline]
+    pub fn as_bool(&self) -> Result<bool, CastError> {
+        match self {
+            Primitives::Boolean(v) => Ok(*v),
+            Primitives::DateTime(_) => Err(CastError::new::<bool>(RawType::DateTime)),
+            _ => match self.as_i64() {
+                Ok(0) => Ok(false),
+                Ok(1) => Ok(true),
+                _ => Err(CastError::new::<bool>(self.raw_type())),
+            },
         }
     }
 
     #[inline]
     pub fn get<T: 'static + Clone>(&self) -> Result<T, CastError> {
         let type_id = TypeId::of::<T>();
+        if type_id == *BOOL {
+            return self.as_bool().map(|v| {
+                let t: &T = unsafe { std::mem::transmute(&v) };
+                t.clone()
+            });
+        }
+
         if type_id == *I8 {
             return self.as_i8().map(|v| {
                 let t: &T = unsafe { std::mem::transmute(&v) };
@@ -329,32 +380,68 @@ impl Primitives {
     }
 }
 
-impl PartialEq for Primitives {
-    fn eq(&self, other: &Self) -> bool {
+impl Primitives {
+    /// Promote into f64 for comparing with floats, which may lose precision for large longs; A
+    /// boolean is promoted as 0 or 1, while it is never compared with floats;
+    #[inline]
+    fn promote_f64(&self) -> f64 {
         match self {
-            Primitives::Byte(v) => other.as_i8().map(|o| o == *v).unwrap_or(false),
-            Primitives::Integer(v) => other.as_i32().map(|o| o == *v).unwrap_or(false),
-            Primitives::Long(v) => other.as_i64().map(|o| o == *v).unwrap_or(false),
-            Primitives::Float(v) => other.as_f64().map(|o| o == *v).unwrap_or(false),
+            Primitives::Boolean(v) => *v as i32 as f64,
+            Primitives::Byte(v) => *v as f64,
+            Primitives::Integer(v) => *v as f64,
+            Primitives::Long(v) | Primitives::DateTime(v) => *v as f64,
+            Primitives::Float(v) => *v,
         }
     }
 }
 
+/// Consistent with `PartialOrd`, e.g. `Integer(30) == Long(30) == Float(30.0)`, while a boolean
+/// only equals the same boolean;
+impl PartialEq for Primitives {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Primitives are compared numerically regardless of their widths: integers are compared as i64,
+/// and compared with floats as f64, where a date time is taken as its milliseconds, so date times
+/// are ordered temporally; Booleans are only comparable with booleans, where `false` is
+/// less than `true`; Return `None` if a float is NaN, or a boolean is compared with a number;
 impl PartialOrd for Primitives {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self {
-            Primitives::Byte(v) => other.as_i8().map(|o| v.cmp(&o)).ok(),
-            Primitives::Integer(v) => other.as_i32().map(|o| v.cmp(&o)).ok(),
-            Primitives::Long(v) => other.as_i64().map(|o| v.cmp(&o)).ok(),
-            Primitives::Float(v) => other.as_f64().map(|o| v.partial_cmp(&o)).unwrap_or(None),
+        match (self, other) {
+            (Primitives::Boolean(l), Primitives::Boolean(r)) => Some(l.cmp(r)),
+            (Primitives::Boolean(_), _) | (_, Primitives::Boolean(_)) => None,
+            (Primitives::Float(_), _) | (_, Primitives::Float(_)) => {
+                self.promote_f64().partial_cmp(&other.promote_f64())
+            }
+            _ => {
+                let left = self.as_i64().ok()?;
+                let right = other.as_i64().ok()?;
+                Some(left.cmp(&right))
+            }
         }
     }
 }
 
 #[clonable]
-pub trait DynType: Any + Send + Sync + Clone + Debug {}
+pub trait DynType: Any + Send + Sync + Clone + Debug {
+    /// Get the name of the concrete type, which is used for diagnostics;
+    fn type_name(&self) -> &'static str;
+
+    /// View the value as `Any`, which can be downcast to the concrete type;
+    fn as_any(&self) -> &dyn Any;
+}
 
-impl<T: Any + Send + Sync + Clone + Debug> DynType for T {}
+impl<T: Any + Send + Sync + Clone + Debug> DynType for T {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
 
 /// copy from std::any::Any;
 impl dyn DynType {
@@ -432,6 +519,8 @@ pub enum Object {
     Primitive(Primitives),
     String(String),
     Blob(Box<[u8]>),
+    /// The missing or null value, e.g. the value of a property a vertex doesn't have;
+    Null,
     UnknownOwned(Box<dyn DynType>),
     UnknownRef(&'static dyn DynType),
 }
@@ -440,15 +529,53 @@ pub enum BorrowObject<'a> {
     Primitive(Primitives),
     String(&'a str),
     Blob(&'a [u8]),
+    Null,
     Unknown(&'a dyn DynType),
 }
 
 impl Object {
+    #[inline]
+    fn kind_order(&self) -> u8 {
+        match self {
+            Object::Null => 0,
+            Object::Primitive(_) => 1,
+            Object::String(_) => 2,
+            Object::Blob(_) => 3,
+            Object::UnknownOwned(_) | Object::UnknownRef(_) => 4,
+        }
+    }
+
+    /// A total order of objects, e.g. for `order()` over values of mixed kinds: objects of the
+    /// same kind are ordered as `partial_cmp`, and objects of different kinds are ordered by kinds
+    /// as `Null < Primitive < String < Blob < Unknown`; Booleans are less than numbers, NaN is
+    /// greater than any other primitive, and all nulls, as well as all unknown objects, are equal;
+    pub fn total_cmp(&self, other: &Object) -> Ordering {
+        match (self, other) {
+            (Object::Primitive(l), Object::Primitive(r)) => match l.partial_cmp(r) {
+                Some(ord) => ord,
+                // booleans are always comparable with each other;
+                None => match (l, r) {
+                    (Primitives::Boolean(_), _) => Ordering::Less,
+                    (_, Primitives::Boolean(_)) => Ordering::Greater,
+                    _ => {
+                        let l_nan = l.promote_f64().is_nan();
+                        let r_nan = r.promote_f64().is_nan();
+                        l_nan.cmp(&r_nan)
+                    }
+                },
+            },
+            (Object::String(l), Object::String(r)) => l.cmp(r),
+            (Object::Blob(l), Object::Blob(r)) => l.cmp(r),
+            _ => self.kind_order().cmp(&other.kind_order()),
+        }
+    }
+
     pub fn raw_type(&self) -> RawType {
         match self {
             Object::Primitive(p) => p.raw_type(),
             Object::String(_) => RawType::String,
             Object::Blob(b) => RawType::Blob(b.len()),
+            Object::Null => RawType::Null,
             Object::UnknownOwned(_) => RawType::Unknown,
             Object::UnknownRef(_) => RawType::Unknown,
         }
@@ -459,6 +586,7 @@ impl Object {
             Object::Primitive(p) => BorrowObject::Primitive(*p),
             Object::String(v) => BorrowObject::String(v.as_str()),
             Object::Blob(v) => BorrowObject::Blob(v.as_ref()),
+            Object::Null => BorrowObject::Null,
             Object::UnknownOwned(v) => BorrowObject::Unknown(v.deref()),
             Object::UnknownRef(v) => BorrowObject::Unknown(*v),
         }
@@ -469,6 +597,7 @@ impl Object {
             Object::Primitive(p) => Ok(*p),
             Object::String(_) => Err(CastError::new::<Primitives>(RawType::String)),
             Object::Blob(v) => Err(CastError::new::<Primitives>(RawType::Blob(v.len()))),
+            Object::Null => Err(CastError::new::<Primitives>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, Primitives),
             Object::UnknownRef(x) => try_downcast!(x, Primitives),
         }
@@ -487,6 +616,7 @@ impl Object {
             Object::Primitive(p) => p.as_i32(),
             Object::String(_) => Err(CastError::new::<i32>(RawType::String)),
             Object::Blob(v) => Err(CastError::new::<i32>(RawType::Blob(v.len()))),
+            Object::Null => Err(CastError::new::<i32>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, i32),
             Object::UnknownRef(x) => try_downcast!(x, i32),
         }
@@ -497,6 +627,7 @@ impl Object {
             Object::Primitive(p) => p.as_i64(),
             Object::String(_) => Err(CastError::new::<i64>(RawType::String)),
             Object::Blob(v) => Err(CastError::new::<i64>(RawType::Blob(v.len()))),
+            Object::Null => Err(CastError::new::<i64>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, i64),
             Object::UnknownRef(x) => try_downcast!(x, i64),
         }
@@ -507,6 +638,7 @@ impl Object {
             Object::Primitive(p) => p.as_i128(),
             Object::String(_) => Err(CastError::new::<i128>(RawType::String)),
             Object::Blob(v) => Err(CastError::new::<i128>(RawType::Blob(v.len()))),
+            Object::Null => Err(CastError::new::<i128>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, i128),
             Object::UnknownRef(x) => try_downcast!(x, i128),
         }
@@ -517,6 +649,7 @@ impl Object {
             Object::Primitive(p) => p.as_u128(),
             Object::String(_) => Err(CastError::new::<u128>(RawType::String)),
             Object::Blob(v) => Err(CastError::new::<u128>(RawType::Blob(v.len()))),
+            Object::Null => Err(CastError::new::<u128>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, u128),
             Object::UnknownRef(x) => try_downcast!(x, u128),
         }
@@ -532,12 +665,22 @@ impl Object {
         }
     }
 
+    pub fn as_bool(&self) -> Result<bool, CastError> {
+        match self {
+            Object::Primitive(p) => p.as_bool(),
+            Object::UnknownOwned(x) => try_downcast!(x, bool),
+            Object::UnknownRef(x) => try_downcast!(x, bool),
+            _ => Err(CastError::new::<bool>(self.raw_type())),
+        }
+    }
+
     pub fn as_str(&self) -> Result<Cow<'_, str>, CastError> {
         match self {
             Object::String(str) => Ok(Cow::Borrowed(str.as_str())),
             Object::Blob(b) => Ok(String::from_utf8_lossy(b)),
             Object::UnknownOwned(x) => try_downcast!(x, String, as_str).map(|r| Cow::Borrowed(r)),
             Object::UnknownRef(x) => try_downcast!(x, String, as_str).map(|r| Cow::Borrowed(r)),
+            Object::Null => Err(CastError::new::<String>(RawType::Null)),
             Object::Primitive(p) => Err(CastError::new::<String>(p.raw_type())),
         }
     }
@@ -547,6 +690,7 @@ impl Object {
             Object::Primitive(p) => Err(CastError::new::<&[u8]>(p.raw_type())),
             Object::String(str) => Ok(str.as_bytes()),
             Object::Blob(v) => Ok(v.as_ref()),
+            Object::Null => Err(CastError::new::<&[u8]>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast!(x, Vec<u8>, as_slice),
             Object::UnknownRef(x) => try_downcast!(x, Vec<u8>, as_slice),
         }
@@ -562,6 +706,7 @@ impl Object {
             Object::Blob(x) => {
                 try_transmute!(x, T, RawType::Blob(x.len())).map(|v| OwnedOrRef::Ref(v))
             }
+            Object::Null => Err(CastError::new::<T>(RawType::Null)),
             Object::UnknownOwned(x) => try_downcast_ref!(x, T).map(|v| OwnedOrRef::Ref(v)),
             Object::UnknownRef(x) => try_downcast_ref!(x, T).map(|v| OwnedOrRef::Ref(v)),
         }
@@ -580,6 +725,7 @@ impl Object {
             Object::UnknownRef(x) => try_downcast!(x, String, to_owned),
             Object::Primitive(p) => Err(CastError::new::<String>(p.raw_type())),
             Object::Blob(_) => unimplemented!(),
+            Object::Null => Err(CastError::new::<String>(RawType::Null)),
         }
     }
 }
@@ -590,6 +736,7 @@ impl<'a> BorrowObject<'a> {
             BorrowObject::Primitive(p) => p.raw_type(),
             BorrowObject::String(_) => RawType::String,
             BorrowObject::Blob(b) => RawType::Blob(b.len()),
+            BorrowObject::Null => RawType::Null,
             BorrowObject::Unknown(_) => RawType::Unknown,
         }
     }
@@ -599,6 +746,7 @@ impl<'a> BorrowObject<'a> {
             BorrowObject::Primitive(p) => Ok(*p),
             BorrowObject::String(_) => Err(CastError::new::<Primitives>(RawType::String)),
             BorrowObject::Blob(v) => Err(CastError::new::<Primitives>(RawType::Blob(v.len()))),
+            BorrowObject::Null => Err(CastError::new::<Primitives>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, Primitives),
         }
     }
@@ -616,6 +764,7 @@ impl<'a> BorrowObject<'a> {
                     Err(CastError::new::<i32>(RawType::Blob(v.len())))
                 }
             }
+            BorrowObject::Null => Err(CastError::new::<i32>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, i32),
         }
     }
@@ -633,6 +782,7 @@ impl<'a> BorrowObject<'a> {
                     Err(CastError::new::<i64>(RawType::Blob(v.len())))
                 }
             }
+            BorrowObject::Null => Err(CastError::new::<i64>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, i64),
         }
     }
@@ -650,6 +800,7 @@ impl<'a> BorrowObject<'a> {
                     Err(CastError::new::<i128>(RawType::Blob(v.len())))
                 }
             }
+            BorrowObject::Null => Err(CastError::new::<i128>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, i128),
         }
     }
@@ -667,6 +818,7 @@ impl<'a> BorrowObject<'a> {
                     Err(CastError::new::<i128>(RawType::Blob(v.len())))
                 }
             }
+            BorrowObject::Null => Err(CastError::new::<u128>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, u128),
         }
     }
@@ -684,6 +836,7 @@ impl<'a> BorrowObject<'a> {
                     Err(CastError::new::<f64>(RawType::Blob(v.len())))
                 }
             }
+            BorrowObject::Null => Err(CastError::new::<f64>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, f64),
         }
     }
@@ -692,6 +845,7 @@ impl<'a> BorrowObject<'a> {
         match self {
             BorrowObject::String(str) => Ok(Cow::Borrowed(*str)),
             BorrowObject::Blob(b) => Ok(String::from_utf8_lossy(b)),
+            BorrowObject::Null => Err(CastError::new::<String>(RawType::Null)),
             BorrowObject::Unknown(x) => try_downcast!(x, String, as_str).map(|r| Cow::Borrowed(r)),
             BorrowObject::Primitive(p) => Err(CastError::new::<String>(p.raw_type())),
         }
@@ -702,6 +856,7 @@ impl<'a> BorrowObject<'a> {
             BorrowObject::Primitive(p) => Err(CastError::new::<&[u8]>(p.raw_type())),
             BorrowObject::String(v) => Ok(v.as_bytes()),
             BorrowObject::Blob(v) => Ok(*v),
+            BorrowObject::Null => Err(CastError::new::<&[u8]>(RawType::Null)),
             BorrowObject::Unknown(v) => try_downcast!(v, Vec<u8>, as_slice),
         }
     }
@@ -711,6 +866,7 @@ impl<'a> BorrowObject<'a> {
             BorrowObject::Primitive(p) => Some(Object::Primitive(*p)),
             BorrowObject::String(s) => Some(Object::String((*s).to_owned())),
             BorrowObject::Blob(b) => Some(Object::Blob(b.to_vec().into_boxed_slice())),
+            BorrowObject::Null => Some(Object::Null),
             BorrowObject::Unknown(_) => None,
         }
     }
@@ -718,7 +874,22 @@ impl<'a> BorrowObject<'a> {
 
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
+        self.value_eq(other)
+    }
+}
+
+impl Object {
+    /// Value equality of objects, e.g. for `has("age", 30)`, where:
+    /// * primitives are compared numerically regardless of their widths, e.g.
+    ///   `Integer(30) == Long(30) == Float(30.0)`; floats are compared exactly without tolerance,
+    ///   as property values are stored as they are written, and NaN equals nothing;
+    /// * booleans only equal booleans, but not the integers 0 or 1;
+    /// * strings and blobs are compared by their bytes;
+    /// * null only equals null;
+    /// * unknown objects equal nothing;
+    pub fn value_eq(&self, other: &Object) -> bool {
         match self {
+            Object::Null => matches!(other, Object::Null),
             Object::Primitive(p) => other.as_primitive().map(|o| p == &o).unwrap_or(false),
             Object::Blob(v) => other.as_bytes().map(|o| o.eq(v.as_ref())).unwrap_or(false),
             Object::String(v) => other.as_str().map(|o| o.eq(v.as_str())).unwrap_or(false),
@@ -728,14 +899,24 @@ impl PartialEq for Object {
     }
 }
 
+/// Objects of the same kind are compared by value, while objects of different kinds, e.g. a
+/// primitive and a string, are incomparable, see [`Object::total_cmp`] for a total order; Null is
+/// less than any other object, so that nulls come first in `order()` ascending;
 impl PartialOrd for Object {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Object::Null, Object::Null) => return Some(Ordering::Equal),
+            (Object::Null, _) => return Some(Ordering::Less),
+            (_, Object::Null) => return Some(Ordering::Greater),
+            _ => (),
+        }
         match self {
             Object::Primitive(p) => other.as_primitive().map(|o| p.partial_cmp(&o)).unwrap_or(None),
             Object::Blob(v) => other.as_bytes().map(|o| v.as_ref().partial_cmp(o)).unwrap_or(None),
             Object::String(v) => {
                 other.as_str().map(|o| v.as_str().partial_cmp(o.as_ref())).unwrap_or(None)
             }
+            Object::Null => None,
             Object::UnknownOwned(_) => None,
             Object::UnknownRef(_) => None,
         }
@@ -748,6 +929,7 @@ impl<'a> PartialEq for BorrowObject<'a> {
             BorrowObject::Primitive(p) => other.as_primitive().map(|o| p == &o).unwrap_or(false),
             BorrowObject::String(v) => other.as_str().map(|o| o.eq(*v)).unwrap_or(false),
             BorrowObject::Blob(v) => other.as_bytes().map(|o| *v == o).unwrap_or(false),
+            BorrowObject::Null => matches!(other, BorrowObject::Null),
             BorrowObject::Unknown(_) => false,
         }
     }
@@ -755,6 +937,12 @@ impl<'a> PartialEq for BorrowObject<'a> {
 
 impl<'a> PartialOrd for BorrowObject<'a> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (BorrowObject::Null, BorrowObject::Null) => return Some(Ordering::Equal),
+            (BorrowObject::Null, _) => return Some(Ordering::Less),
+            (_, BorrowObject::Null) => return Some(Ordering::Greater),
+            _ => (),
+        }
         match self {
             BorrowObject::Primitive(p) => {
                 other.as_primitive().map(|o| p.partial_cmp(&o)).unwrap_or(None)
@@ -763,6 +951,7 @@ impl<'a> PartialOrd for BorrowObject<'a> {
                 other.as_str().map(|o| (*v).partial_cmp(o.as_ref())).unwrap_or(None)
             }
             BorrowObject::Blob(v) => other.as_bytes().map(|o| (*v).partial_cmp(o)).unwrap_or(None),
+            BorrowObject::Null => None,
             BorrowObject::Unknown(_) => None,
         }
     }
@@ -788,12 +977,15 @@ impl CastError {
 impl Display for CastError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self.kind {
+            RawType::Boolean => write!(f, "can't cast bool into {}", self.target),
             RawType::Byte => write!(f, "can't cast i8 into {}", self.target),
             RawType::Integer => write!(f, "can't cast i32 into {}", self.target),
             RawType::Long => write!(f, "can't cast i64 into {}", self.target),
             RawType::Float => write!(f, "can't cast f64 into {}", self.target),
+            RawType::DateTime => write!(f, "can't cast date time into {}", self.target),
             RawType::Blob(len) => write!(f, "can't cast Blob({}) into {}", len, self.target),
             RawType::String => write!(f, "can't cast String into {}", self.target),
+            RawType::Null => write!(f, "can't cast null into {}", self.target),
             RawType::Unknown => write!(f, "can't cast unknown dyn type into {}", self.target),
         }
     }
@@ -815,24 +1007,18 @@ impl<'a> From<i8> for BorrowObject<'a> {
 
 impl From<bool> for Object {
     fn from(v: bool) -> Self {
-        if v {
-            Object::Primitive(Primitives::Byte(1))
-        } else {
-            Object::Primitive(Primitives::Byte(0))
-        }
+        Object::Primitive(Primitives::Boolean(v))
     }
 }
 
 impl<'a> From<bool> for BorrowObject<'a> {
     fn from(v: bool) -> Self {
-        if v {
-            BorrowObject::Primitive(Primitives::Byte(1))
-        } else {
-            BorrowObject::Primitive(Primitives::Byte(0))
-        }
+        BorrowObject::Primitive(Primitives::Boolean(v))
     }
 }
 
+/// An `i32` is always an `Integer` and an `i64` always a `Long`, even if the `i64` fits in 32
+/// bits, so the type of a value is kept as is, e.g. `count()` output as a `Long`;
 impl From<i32> for Object {
     fn from(i: i32) -> Self {
         Object::Primitive(Primitives::Integer(i))
@@ -869,6 +1055,12 @@ impl<'a> From<f64> for BorrowObject<'a> {
     }
 }
 
+impl From<f32> for Object {
+    fn from(i: f32) -> Self {
+        Object::Primitive(Primitives::Float(i as f64))
+    }
+}
+
 impl From<u128> for Object {
     fn from(i: u128) -> Self {
         if i <= (i64::MAX as u128) {
@@ -934,6 +1126,9 @@ impl From<&pb::Value> for Option<Object> {
             Some(pb::value::Item::I32(item)) => Some((*item).into()),
             Some(pb::value::Item::I64(item)) => Some((*item).into()),
             Some(pb::value::Item::F64(item)) => Some((*item).into()),
+            Some(pb::value::Item::DateTime(item)) => {
+                Some(Object::Primitive(Primitives::DateTime(*item)))
+            }
             Some(pb::value::Item::Str(item)) => Some(item.as_str().into()),
             Some(pb::value::Item::I32Array(_)) => unimplemented!(),
             Some(pb::value::Item::I64Array(_)) => unimplemented!(),
@@ -948,7 +1143,7 @@ impl From<&pb::Value> for Option<Object> {
 impl From<&serde_json::Value> for Object {
     fn from(val: &serde_json::Value) -> Self {
         match val {
-            serde_json::Value::Bool(item) => Object::from(*item as i8),
+            serde_json::Value::Bool(item) => Object::from(*item),
             Value::Number(num) => {
                 if num.is_i64() {
                     Object::from(num.as_i64().unwrap())
@@ -959,9 +1154,9 @@ impl From<&serde_json::Value> for Object {
                 }
             }
             Value::String(item) => Object::from(item.as_str()),
+            Value::Null => Object::Null,
             Value::Array(_) => unimplemented!(),
             Value::Object(_) => unimplemented!(),
-            _ => unimplemented!(),
         }
     }
 }
@@ -1034,4 +1229,164 @@ mod test {
         assert_eq!(right.partial_cmp(&left), Some(Ordering::Greater));
         assert_eq!(*&*right, 8u128);
     }
+
+    #[test]
+    fn test_object_from() {
+        assert!(matches!(Object::from(8i32), Object::Primitive(Primitives::Integer(8))));
+        assert!(matches!(Object::from(8i64), Object::Primitive(Primitives::Long(8))));
+        assert!(matches!(Object::from(8.5), Object::Primitive(Primitives::Float(f)) if f == 8.5));
+        assert!(matches!(Object::from("marko"), Object::String(s) if s == "marko"));
+        assert!(matches!(Object::from("marko".to_owned()), Object::String(s) if s == "marko"));
+        assert!(matches!(Object::from(vec![1u8, 2]), Object::Blob(b) if &*b == &[1u8, 2]));
+        assert!(matches!(Object::from(8i32).raw_type(), RawType::Integer));
+        assert!(matches!(Object::from(8i64).raw_type(), RawType::Long));
+    }
+
+    #[test]
+    fn test_object_from_f32() {
+        // widened as is, which differs from the double closest to the decimal `0.1`;
+        assert_eq!(Object::from(0.1f32).as_f64().unwrap(), 0.1f32 as f64);
+        assert_ne!(Object::from(0.1f32).as_f64().unwrap(), 0.1);
+        assert!(matches!(Object::from(0.5f32).raw_type(), RawType::Float));
+    }
+
+    #[test]
+    fn test_object_as_i64() {
+        assert_eq!(Object::from(8i8).as_i64().unwrap(), 8);
+        assert_eq!(Object::from(8i32).as_i64().unwrap(), 8);
+        assert_eq!(Object::from(8i64).as_i64().unwrap(), 8);
+        assert!(Object::from(8.0).as_i64().is_err());
+        assert!(Object::from("8").as_i64().is_err());
+        assert!(Object::from(vec![8u8]).as_i64().is_err());
+    }
+
+    #[test]
+    fn test_object_as_f64() {
+        assert_eq!(Object::from(8i8).as_f64().unwrap(), 8.0);
+        assert_eq!(Object::from(8i32).as_f64().unwrap(), 8.0);
+        assert_eq!(Object::from(1i64 << 40).as_f64().unwrap(), (1i64 << 40) as f64);
+        assert!(Object::from(i64::max_value()).as_f64().is_err());
+        assert_eq!(Object::from(8.5).as_f64().unwrap(), 8.5);
+        assert!(Object::from("8.5").as_f64().is_err());
+    }
+
+    #[test]
+    fn test_object_as_str() {
+        assert_eq!(Object::from("marko").as_str().unwrap(), "marko");
+        assert_eq!(Object::from("marko".as_bytes().to_vec()).as_str().unwrap(), "marko");
+        assert!(Object::from(8i32).as_str().is_err());
+    }
+
+    #[test]
+    fn test_object_as_bool() {
+        assert_eq!(Object::from(true).as_bool().unwrap(), true);
+        assert_eq!(Object::from(false).as_bool().unwrap(), false);
+        assert_eq!(Object::from(1i64).as_bool().unwrap(), true);
+        assert!(Object::from(2i32).as_bool().is_err());
+        assert!(Object::from(1.0).as_bool().is_err());
+        assert!(Object::from("true").as_bool().is_err());
+    }
+
+    #[test]
+    fn test_boolean_object() {
+        let t = Object::from(true);
+        let f = Object::from(false);
+        assert!(matches!(t.raw_type(), RawType::Boolean));
+        assert_eq!(t, Object::from(true));
+        assert_ne!(t, f);
+        assert_eq!(f.partial_cmp(&t), Some(Ordering::Less));
+        // booleans are not numbers;
+        assert_ne!(t, Object::from(1i32));
+        assert_eq!(t.partial_cmp(&Object::from(1i32)), None);
+        assert!(t.as_i64().is_err());
+        assert!(t.as_f64().is_err());
+        assert_eq!(t.total_cmp(&Object::from(0i32)), Ordering::Less);
+        assert_eq!(Object::from(f64::NAN).total_cmp(&t), Ordering::Greater);
+        assert_eq!(t.get::<bool>().map(|v| *v).unwrap(), true);
+        assert_eq!(Object::from(&serde_json::Value::Bool(false)), f);
+    }
+
+    #[test]
+    fn test_date_time_object() {
+        // 2010-02-14T15:32:10.447Z and a second later;
+        let earlier = Object::Primitive(Primitives::DateTime(1266161530447));
+        let later = Object::Primitive(Primitives::DateTime(1266161531447));
+        assert!(matches!(earlier.raw_type(), RawType::DateTime));
+        assert_eq!(earlier.partial_cmp(&later), Some(Ordering::Less));
+        assert_eq!(later.total_cmp(&earlier), Ordering::Greater);
+        assert_eq!(earlier.as_i64().unwrap(), 1266161530447);
+        // compared with the longs as the milliseconds;
+        assert_eq!(earlier, Object::from(1266161530447i64));
+        assert!(earlier.as_i32().is_err());
+        assert_ne!(earlier, Object::from(true));
+    }
+
+    #[test]
+    fn test_primitives_cmp_int_long() {
+        let int = Primitives::Integer(30);
+        let long = Primitives::Long(1 << 40);
+        assert_eq!(int.partial_cmp(&long), Some(Ordering::Less));
+        assert_eq!(long.partial_cmp(&int), Some(Ordering::Greater));
+        assert_eq!(Primitives::Integer(30), Primitives::Long(30));
+        assert_eq!(Primitives::Byte(30).partial_cmp(&Primitives::Long(30)), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_primitives_cmp_int_float() {
+        let int = Primitives::Integer(30);
+        assert_eq!(int.partial_cmp(&Primitives::Float(30.5)), Some(Ordering::Less));
+        assert_eq!(Primitives::Float(30.5).partial_cmp(&int), Some(Ordering::Greater));
+        assert_eq!(int, Primitives::Float(30.0));
+        assert_eq!(Primitives::Float(30.0), int);
+        assert_eq!(int.partial_cmp(&Primitives::Float(f64::NAN)), None);
+    }
+
+    #[test]
+    fn test_object_cmp_cross_kind() {
+        let num = Object::from(30i32);
+        let str = Object::from("30");
+        assert_eq!(num.partial_cmp(&str), None);
+        assert_ne!(num, str);
+        assert_eq!(num.total_cmp(&str), Ordering::Less);
+        assert_eq!(str.total_cmp(&num), Ordering::Greater);
+        assert_eq!(Object::from(vec![0u8]).total_cmp(&str), Ordering::Greater);
+        assert_eq!(num.total_cmp(&Object::from(30i64)), Ordering::Equal);
+        assert_eq!(Object::from(f64::NAN).total_cmp(&num), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_value_eq_numeric() {
+        assert!(Object::from(30i32).value_eq(&Object::from(30i64)));
+        assert!(Object::from(30i64).value_eq(&Object::from(30i32)));
+        assert!(Object::from(30i32).value_eq(&Object::from(30.0)));
+        assert!(Object::from(30.0).value_eq(&Object::from(30i8)));
+        assert!(!Object::from(30i32).value_eq(&Object::from(30.000001)));
+        assert!(!Object::from(f64::NAN).value_eq(&Object::from(f64::NAN)));
+        assert!(!Object::from(30i32).value_eq(&Object::from("30")));
+    }
+
+    #[test]
+    fn test_value_eq_string_blob() {
+        assert!(Object::from("marko").value_eq(&Object::from("marko")));
+        assert!(!Object::from("marko").value_eq(&Object::from("vadas")));
+        assert!(Object::from(vec![1u8, 2]).value_eq(&Object::from(vec![1u8, 2])));
+        assert!(!Object::from(vec![1u8, 2]).value_eq(&Object::from(vec![1u8])));
+        assert!(Object::from("marko").value_eq(&Object::from("marko".as_bytes().to_vec())));
+    }
+
+    #[test]
+    fn test_null_object() {
+        let num = Object::from(30i32);
+        assert_eq!(Object::Null, Object::Null);
+        assert_ne!(Object::Null, num);
+        assert_ne!(num, Object::Null);
+        assert_eq!(Object::Null.partial_cmp(&num), Some(Ordering::Less));
+        assert_eq!(num.partial_cmp(&Object::Null), Some(Ordering::Greater));
+        assert_eq!(Object::Null.partial_cmp(&Object::Null), Some(Ordering::Equal));
+        assert_eq!(Object::Null.total_cmp(&Object::from("")), Ordering::Less);
+        assert_eq!(Object::from(f64::NAN).total_cmp(&Object::Null), Ordering::Greater);
+        assert!(Object::Null.as_i64().is_err());
+        assert!(Object::Null.as_str().is_err());
+        assert_eq!(Object::from(&serde_json::Value::Null), Object::Null);
+    }
 }
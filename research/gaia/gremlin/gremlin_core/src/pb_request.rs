@@ -49,7 +49,11 @@ impl JobPreprocess for ProtoReflect {
 fn reflect_source(job: &mut JobDesc) -> Result<(), BuildJobError> {
     let pb = job.source.get().as_any_ref().downcast_ref::<Vec<u8>>().unwrap();
     let mut gremlin_step = pb::GremlinStep::decode(&pb[0..]).unwrap();
-    let step = graph_step_from(&mut gremlin_step)?;
+    let mut step = graph_step_from(&mut gremlin_step)?;
+    // the job reads one snapshot of the graph taken as it's built, held by its source step
+    if let Some(graph) = crate::snapshot_graph(job.job_id()) {
+        step.set_graph(graph);
+    }
     job.source = SharedResource::new(step);
     Ok(())
 }
@@ -77,6 +77,12 @@ impl Path {
         Path { history: vec![first], head: 0, tags: RefCell::new(HashMap::new()) }
     }
 
+    /// Create a path whose first item is a detached value, other than a graph element;
+    pub fn from_detached<T: Into<Object>>(first: T) -> Self {
+        let first = PathItem::Detached(first.into());
+        Path { history: vec![first], head: 0, tags: RefCell::new(HashMap::new()) }
+    }
+
     pub fn size(&self) -> usize {
         self.history.len()
     }
@@ -105,7 +105,8 @@ impl KeyFunction<Traverser> for GroupBy {
         let mut state = DefaultHasher::new();
         group_key.hash(&mut state);
         let hash = state.finish();
-        let traverser = if group_by_self { item.clone() } else { Traverser::Unknown(group_key) };
+        let traverser =
+            if group_by_self { item.clone() } else { Traverser::from_object(group_key) };
         let hash_key = HashKey::new(hash, traverser);
         Cow::Owned(hash_key)
     }
@@ -17,9 +17,10 @@ use crate::process::traversal::step::map::MapFuncGen;
 use crate::process::traversal::step::util::StepSymbol;
 use crate::process::traversal::step::Step;
 use crate::process::traversal::traverser::Traverser;
-use crate::structure::{QueryParams, Vertex, VertexOrEdge};
+use crate::structure::{GraphProxy, QueryParams, Vertex, VertexOrEdge};
 use pegasus::api::function::{FnResult, MapFunction};
 use std::collections::HashSet;
+use std::sync::Arc;
 
 enum Kind {
     OutV,
@@ -65,6 +66,7 @@ struct EdgeVertexFunc {
     labels: HashSet<String>,
     params: QueryParams<Vertex>,
     get_src: bool,
+    graph: Option<Arc<dyn GraphProxy>>,
 }
 
 impl MapFunction<Traverser, Traverser> for EdgeVertexFunc {
@@ -73,7 +75,7 @@ impl MapFunction<Traverser, Traverser> for EdgeVertexFunc {
             match elem.get() {
                 VertexOrEdge::E(e) => {
                     let id = if self.get_src { e.src_id } else { e.dst_id };
-                    let graph = crate::get_graph().unwrap();
+                    let graph = self.graph.as_ref().expect("graph not found");
                     let mut r = graph.get_vertex(&[id], &self.params).expect("xxx");
                     if let Some(v) = r.next() {
                         return Ok(input.split(v, &self.labels));
@@ -93,9 +95,10 @@ impl MapFuncGen for EdgeVertexStep {
     fn gen(&self) -> Box<dyn MapFunction<Traverser, Traverser>> {
         let labels = self.get_tags();
         let params = self.params.clone();
+        let graph = crate::get_graph();
         match self.kind {
-            Kind::OutV => Box::new(EdgeVertexFunc { labels, params, get_src: true }),
-            Kind::InV => Box::new(EdgeVertexFunc { labels, params, get_src: false }),
+            Kind::OutV => Box::new(EdgeVertexFunc { labels, params, get_src: true, graph }),
+            Kind::InV => Box::new(EdgeVertexFunc { labels, params, get_src: false, graph }),
             Kind::OtherV => unimplemented!(),
         }
     }
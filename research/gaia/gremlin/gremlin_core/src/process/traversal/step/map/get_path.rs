@@ -40,7 +40,7 @@ impl MapFuncGen for GetPathStep {
     fn gen(&self) -> Box<dyn MapFunction<Traverser, Traverser>> {
         let func = map!(|item: Traverser| {
             let path = item.take_path();
-            Ok(Traverser::Unknown(Object::UnknownOwned(Box::new(path))))
+            Ok(Traverser::from_object(Object::UnknownOwned(Box::new(path))))
         });
         Box::new(func)
     }
@@ -74,7 +74,7 @@ impl MapFuncGen for GetPropertyStep {
             let mut result = ResultProperty::new();
             let mut get_keys_opt = false;
             let mut get_values_opt = false;
-            let mut get_keys_trav = Traverser::Unknown(0.into());
+            let mut get_keys_trav = Traverser::from_object(0);
             let mut get_values_trav_vec = vec![];
             for tag_key in tag_keys.iter() {
                 let (tag, by_key) = (tag_key.tag.as_ref(), tag_key.by_key.as_ref());
@@ -176,7 +176,7 @@ impl MapFuncGen for GetPropertyStep {
             } else if get_values_opt {
                 Ok(Traverser::with(get_values_trav_vec))
             } else {
-                Ok(Traverser::Unknown(Object::UnknownOwned(Box::new(result))))
+                Ok(Traverser::from_object(Object::UnknownOwned(Box::new(result))))
             }
         });
         Box::new(func)
@@ -16,9 +16,10 @@
 use crate::process::traversal::step::util::StepSymbol;
 use crate::process::traversal::step::{MapFuncGen, Step};
 use crate::process::traversal::traverser::Traverser;
-use crate::structure::{QueryParams, Vertex, VertexOrEdge};
+use crate::structure::{GraphProxy, QueryParams, Vertex, VertexOrEdge};
 use pegasus::api::function::{FnResult, MapFunction};
 use std::collections::HashSet;
+use std::sync::Arc;
 
 pub struct IdentityStep {
     pub params: QueryParams<Vertex>,
@@ -36,6 +37,7 @@ impl IdentityStep {
 struct IdentityFunc {
     pub params: QueryParams<Vertex>,
     labels: HashSet<String>,
+    graph: Option<Arc<dyn GraphProxy>>,
 }
 
 impl Step for IdentityStep {
@@ -62,7 +64,7 @@ impl MapFunction<Traverser, Traverser> for IdentityFunc {
                     VertexOrEdge::V(v) => {
                         // the case of preserving properties on demand for vertex
                         let id = v.id;
-                        let graph = crate::get_graph().unwrap();
+                        let graph = self.graph.as_ref().expect("graph not found");
                         let mut r = graph.get_vertex(&[id], &self.params).expect("failure");
                         if let Some(v) = r.next() {
                             Ok(input.modify_head(v, &self.labels))
@@ -95,6 +97,7 @@ impl MapFuncGen for IdentityStep {
     fn gen(&self) -> Box<dyn MapFunction<Traverser, Traverser>> {
         let labels = self.get_tags();
         let params = self.params.clone();
-        Box::new(IdentityFunc { params, labels })
+        let graph = crate::get_graph();
+        Box::new(IdentityFunc { params, labels, graph })
     }
 }
@@ -52,8 +52,11 @@ pub use map::ResultProperty;
 pub use map::{MapFuncGen, MapStep};
 pub use order_by::{CompareFunctionGen, OrderStep};
 pub use sink::SinkFuncGen;
-pub use source::GraphVertexStep;
-pub use sub_traversal::{BySubJoin, HasAnyJoin, JoinFuncGen};
+pub use source::{GraphVertexStep, InjectStep};
+pub use sub_traversal::{
+    BySubJoin, CoalesceJoin, CountSubJoin, Extreme, HasAnyJoin, JoinFuncGen, NotExistsJoin,
+    ReduceSubJoin, SelectCompareJoin, SelectKeyJoin,
+};
 
 #[enum_dispatch(Step)]
 pub enum GremlinStep {
@@ -16,9 +16,11 @@
 use crate::process::traversal::step::util::StepSymbol;
 use crate::process::traversal::step::Step;
 use crate::process::traversal::traverser::{Requirement, Traverser};
-use crate::structure::{QueryParams, Vertex, ID};
+use crate::structure::{GraphProxy, QueryParams, Vertex, ID};
+use crate::Object;
 use crossbeam_queue::ArrayQueue;
 use pegasus_common::downcast::*;
+use std::sync::Arc;
 
 /// V(),
 pub struct GraphVertexStep {
@@ -27,6 +29,7 @@ pub struct GraphVertexStep {
     src: Option<ArrayQueue<Vec<ID>>>,
     as_labels: Vec<String>,
     requirement: Requirement,
+    graph: Option<Arc<dyn GraphProxy>>,
 }
 
 impl_as_any!(GraphVertexStep);
@@ -39,9 +42,20 @@ impl GraphVertexStep {
             as_labels: vec![],
             requirement: req,
             params: QueryParams::new(),
+            graph: None,
         }
     }
 
+    /// Read the graph of the job, e.g. the snapshot taken by `snapshot_graph()`, which is held
+    /// by the step for as long as the job holds it; The registered graph is read if not set;
+    pub fn set_graph(&mut self, graph: Arc<dyn GraphProxy>) {
+        self.graph = Some(graph);
+    }
+
+    fn get_graph(&self) -> Arc<dyn GraphProxy> {
+        self.graph.clone().or_else(crate::get_graph).expect("graph not found")
+    }
+
     pub fn set_src(&mut self, ids: Vec<ID>, workers: u32) {
         let mut partition = Vec::with_capacity(workers as usize);
         for _ in 0..workers {
@@ -79,16 +93,14 @@ impl GraphVertexStep {
         let source = if let Some(ref seeds) = self.src {
             let src = seeds.pop().unwrap_or_else(|_| vec![]);
             if !src.is_empty() {
-                let graph = crate::get_graph().unwrap();
-                graph.get_vertex(&src, &self.params).expect("xxx")
+                self.get_graph().get_vertex(&src, &self.params).expect("xxx")
             } else {
                 // return an emtpy iterator;
                 Box::new(std::iter::empty())
             }
         } else {
             if worker_index == 0 {
-                let graph = crate::get_graph().unwrap();
-                graph.scan_vertex(&self.params).expect("xxx")
+                self.get_graph().scan_vertex(&self.params).expect("xxx")
             } else {
                 // return an emtpy iterator;
                 Box::new(std::iter::empty())
@@ -105,3 +117,115 @@ impl GraphVertexStep {
         }
     }
 }
+
+/// inject(1, 2, 3), or constant("x") as the start of a traversal;
+///
+/// Each value is injected as a traverser carrying the value; All the values are injected on the
+/// worker 0 by default, or distributed among the workers in round-robin by [`partition`];
+///
+/// [`partition`]: InjectStep::partition
+pub struct InjectStep {
+    pub symbol: StepSymbol,
+    /// the values injected on each worker, indexed by the worker index;
+    src: Vec<Vec<Object>>,
+    as_labels: Vec<String>,
+    requirement: Requirement,
+}
+
+impl_as_any!(InjectStep);
+
+impl InjectStep {
+    pub fn new(values: Vec<Object>, req: Requirement) -> Self {
+        InjectStep {
+            symbol: StepSymbol::Inject,
+            src: vec![values],
+            as_labels: vec![],
+            requirement: req,
+        }
+    }
+
+    /// Distribute the values among `workers` workers, the i-th value is injected on the worker
+    /// `i % workers`;
+    pub fn partition(&mut self, workers: u32) {
+        let workers = std::cmp::max(workers, 1) as usize;
+        let mut partition = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            partition.push(vec![]);
+        }
+        let values = self.src.drain(..).flatten();
+        for (i, value) in values.enumerate() {
+            partition[i % workers].push(value);
+        }
+        self.src = partition;
+    }
+}
+
+impl Step for InjectStep {
+    fn get_symbol(&self) -> StepSymbol {
+        self.symbol
+    }
+
+    fn add_tag(&mut self, label: String) {
+        self.as_labels.push(label);
+    }
+
+    fn tags(&self) -> &[String] {
+        self.as_labels.as_slice()
+    }
+}
+
+impl InjectStep {
+    pub fn gen_source(&self, worker_index: u32) -> Box<dyn Iterator<Item = Traverser> + Send> {
+        let values = self.src.get(worker_index as usize).cloned().unwrap_or_else(|| vec![]);
+        if self.requirement.contains(Requirement::PATH)
+            || self.requirement.contains(Requirement::LABELEDPATH)
+        {
+            let labels = self.get_tags();
+            Box::new(values.into_iter().map(move |v| Traverser::object_with_path(v, &labels)))
+        } else {
+            Box::new(values.into_iter().map(|v| Traverser::from_object(v)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn injected(step: &InjectStep, worker_index: u32) -> Vec<Object> {
+        step.gen_source(worker_index)
+            .map(|t| t.get_object().expect("should carry an object").clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_inject_step() {
+        let values = vec![Object::from(1i64), Object::from("x"), Object::from(3i64)];
+        let step = InjectStep::new(values.clone(), Requirement::empty());
+        assert_eq!(injected(&step, 0), values);
+        assert!(injected(&step, 1).is_empty());
+    }
+
+    #[test]
+    fn test_inject_step_with_path() {
+        let mut step = InjectStep::new(vec![Object::from("x")], Requirement::PATH);
+        step.add_tag("a".to_string());
+        let traversers = step.gen_source(0).collect::<Vec<_>>();
+        assert_eq!(traversers.len(), 1);
+        assert_eq!(traversers[0].get_object(), Some(&Object::from("x")));
+        assert_eq!(traversers[0].select_as_value(&"a".to_string()), Some(&Object::from("x")));
+    }
+
+    #[test]
+    fn test_inject_step_partition() {
+        let values = (0..5i64).map(|i| Object::from(i)).collect::<Vec<_>>();
+        let mut step = InjectStep::new(values, Requirement::empty());
+        step.partition(2);
+        assert_eq!(
+            injected(&step, 0),
+            vec![Object::from(0i64), Object::from(2i64), Object::from(4i64)]
+        );
+        assert_eq!(injected(&step, 1), vec![Object::from(1i64), Object::from(3i64)]);
+        assert!(injected(&step, 2).is_empty());
+    }
+}
@@ -13,24 +13,44 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use crate::object::Object;
 use crate::process::traversal::traverser::Traverser;
+use crate::structure::{BiPredicate, Details, Element, EqCmp, Tag, Token};
 use pegasus::api::function::LeftJoinFunction;
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+enum JoinFunc {
+    Shared(Arc<dyn LeftJoinFunction<Traverser> + Sync>),
+    Stateful(Box<dyn Fn() -> Box<dyn LeftJoinFunction<Traverser>> + Send + Sync>),
+}
+
 pub struct JoinFuncGen {
-    func: Arc<dyn LeftJoinFunction<Traverser> + Sync>,
+    func: JoinFunc,
 }
 
 impl JoinFuncGen {
+    /// The `func` is shared by all the joins generated, which should be stateless;
     pub fn new(func: Arc<dyn LeftJoinFunction<Traverser> + Sync>) -> Self {
-        JoinFuncGen { func }
+        JoinFuncGen { func: JoinFunc::Shared(func) }
+    }
+
+    /// Each join generated is a fresh instance built by `factory`, whose state is isolated from
+    /// the others;
+    pub fn stateful<F>(factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn LeftJoinFunction<Traverser>> + Send + Sync + 'static,
+    {
+        JoinFuncGen { func: JoinFunc::Stateful(Box::new(factory)) }
     }
 }
 
 impl JoinFuncGen {
     pub fn gen(&self) -> Box<dyn LeftJoinFunction<Traverser>> {
-        let func = self.func.clone();
-        Box::new(func)
+        match &self.func {
+            JoinFunc::Shared(func) => Box::new(func.clone()),
+            JoinFunc::Stateful(factory) => factory(),
+        }
     }
 }
 
@@ -43,17 +63,581 @@ impl LeftJoinFunction<Traverser> for HasAnyJoin {
     }
 }
 
+// for e.g., where(not(out().out())), the negation of `HasAnyJoin`
+//
+// A parent is kept only if its sub traversal produces nothing, which is known only after all
+// the sub traversers are observed; So it is a fold join, which is invoked with each sub traverser
+// by `fold`, and produces the parent by `end` once the sub traversal is ended. Invoked per sub
+// traverser by `exec`, which means the parent has a match, the parent is always dropped;
+pub struct NotExistsJoin;
+
+impl LeftJoinFunction<Traverser> for NotExistsJoin {
+    fn exec(&self, _parent: &Traverser, _sub: Traverser) -> Option<Traverser> {
+        None
+    }
+
+    fn is_fold(&self) -> bool {
+        true
+    }
+
+    fn end(&self, parent: Traverser, folded: usize) -> Option<Traverser> {
+        if folded == 0 {
+            Some(parent)
+        } else {
+            None
+        }
+    }
+}
+
 // for e.g., order().by(out().out().count())
+//
+// The parent is dropped if it is not an element, or the sub traverser carries no object,
+// as there is nothing to attach or order by;
 pub struct BySubJoin;
 
-// TODO: throw error
 impl LeftJoinFunction<Traverser> for BySubJoin {
     fn exec(&self, parent: &Traverser, sub: Traverser) -> Option<Traverser> {
-        let mut parent = parent.clone();
-        parent
-            .get_element_mut()
-            .expect("parent should be element")
-            .attach(sub.get_object().expect("object").clone());
-        Some(parent)
+        if parent.get_element().is_none() {
+            warn!("drop parent {:?} as it is not an element;", parent);
+            None
+        } else if let Some(obj) = sub.get_object() {
+            Some(parent.with_object(obj.clone()))
+        } else {
+            warn!("drop parent {:?} as sub traverser {:?} has no object;", parent, sub);
+            None
+        }
+    }
+}
+
+// for e.g., order().by(out().out().count()), with the count fused into the join
+//
+// The number of the sub traversers of a parent is attached to the parent as a `Long`, which is 0
+// if its sub traversal produces nothing; The parent is dropped if it is not an element;
+pub struct CountSubJoin;
+
+impl CountSubJoin {
+    fn attach_count(parent: &Traverser, count: usize) -> Option<Traverser> {
+        if parent.get_element().is_some() {
+            Some(parent.with_object(Object::from(count as i64)))
+        } else {
+            warn!("drop parent {:?} as it is not an element;", parent);
+            None
+        }
+    }
+}
+
+impl LeftJoinFunction<Traverser> for CountSubJoin {
+    fn exec(&self, parent: &Traverser, _sub: Traverser) -> Option<Traverser> {
+        CountSubJoin::attach_count(parent, 1)
+    }
+
+    fn is_fold(&self) -> bool {
+        true
+    }
+
+    fn end(&self, parent: Traverser, folded: usize) -> Option<Traverser> {
+        CountSubJoin::attach_count(&parent, folded)
+    }
+}
+
+/// The extreme of sub results selected by [`ReduceSubJoin`];
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Extreme {
+    Min,
+    Max,
+}
+
+// for e.g., order().by(out().values("age").max())
+//
+// The objects of all the sub traversers of a parent are folded into the extreme one, which is
+// attached to the parent. The parent is dropped if its sub traversal produces nothing, as there is
+// nothing to order by; Sub traversers without object, and objects incomparable with the current
+// extreme are ignored;
+pub struct ReduceSubJoin {
+    extreme: Extreme,
+}
+
+impl ReduceSubJoin {
+    pub fn new(extreme: Extreme) -> Self {
+        ReduceSubJoin { extreme }
+    }
+
+    pub fn max() -> Self {
+        ReduceSubJoin::new(Extreme::Max)
+    }
+
+    pub fn min() -> Self {
+        ReduceSubJoin::new(Extreme::Min)
+    }
+}
+
+impl LeftJoinFunction<Traverser> for ReduceSubJoin {
+    fn exec(&self, parent: &Traverser, sub: Traverser) -> Option<Traverser> {
+        BySubJoin.exec(parent, sub)
+    }
+
+    fn is_fold(&self) -> bool {
+        true
+    }
+
+    fn fold(&self, parent: &mut Traverser, folded: usize, sub: Traverser) {
+        let element = match parent.get_element_mut() {
+            Some(element) => element,
+            None => return,
+        };
+        // the object attached to the parent before the join is not an extreme of the sub results,
+        // so the parent has an extreme only once one is attached by the join;
+        if folded == 0 {
+            element.take_attached();
+        }
+        let obj = match sub.get_object() {
+            Some(obj) => obj,
+            None => {
+                warn!("ignore sub traverser {:?} without object;", sub);
+                return;
+            }
+        };
+        let replace = match element.get_attached() {
+            None => true,
+            Some(cur) => match (obj.partial_cmp(cur), self.extreme) {
+                (Some(Ordering::Greater), Extreme::Max) => true,
+                (Some(Ordering::Less), Extreme::Min) => true,
+                _ => false,
+            },
+        };
+        if replace {
+            element.attach(obj.clone());
+        }
+    }
+
+    fn end(&self, parent: Traverser, folded: usize) -> Option<Traverser> {
+        match parent.get_element() {
+            None => {
+                warn!("drop parent {:?} as it is not an element;", parent);
+                None
+            }
+            // no sub result, or none of them has an object
+            Some(element) if folded == 0 || element.get_attached().is_none() => None,
+            Some(_) => Some(parent),
+        }
+    }
+}
+
+// for e.g., where(out().out().where(neq("a"))), where the element reached by the sub traversal
+// is compared with the element tagged as "a" in the path of the parent
+//
+// The parent is kept if the ids of the two elements satisfy `cmp`; The parent is dropped if no
+// element is tagged by `tag` in its path, or the sub traverser is not an element;
+pub struct SelectCompareJoin {
+    tag: Tag,
+    cmp: EqCmp,
+}
+
+impl SelectCompareJoin {
+    pub fn new(tag: Tag, cmp: EqCmp) -> Self {
+        SelectCompareJoin { tag, cmp }
+    }
+
+    pub fn eq(tag: Tag) -> Self {
+        SelectCompareJoin::new(tag, EqCmp::Eq)
+    }
+
+    pub fn neq(tag: Tag) -> Self {
+        SelectCompareJoin::new(tag, EqCmp::NotEq)
+    }
+}
+
+impl LeftJoinFunction<Traverser> for SelectCompareJoin {
+    fn exec(&self, parent: &Traverser, sub: Traverser) -> Option<Traverser> {
+        let tagged = match parent.select_as_element(&self.tag) {
+            Some(tagged) => tagged,
+            None => {
+                warn!("drop parent {:?} as no element is tagged by {:?};", parent, self.tag);
+                return None;
+            }
+        };
+        let current = match sub.get_element() {
+            Some(current) => current,
+            None => {
+                warn!("drop parent {:?} as sub traverser {:?} is not an element;", parent, sub);
+                return None;
+            }
+        };
+        if self.cmp.test(&tagged.id(), &current.id()).unwrap_or(false) {
+            Some(parent.clone())
+        } else {
+            None
+        }
+    }
+}
+
+// for e.g., group().by(select("a").values("name")), where the group key is selected from the
+// tag "a" in the path of the sub traverser, or in the path of the parent if absent from the sub
+//
+// The key is attached to the parent, to be grouped by the attached object as that of
+// `ByStepOption::OptSubtraversal`: it is the `key` of the element tagged by `tag`, i.e. its id,
+// label or property, which is the id by default; Or the value tagged by `tag`, regardless of
+// `key`. The parent is dropped if nothing is tagged by `tag`, the tagged element has no such
+// property, or the parent is not an element;
+pub struct SelectKeyJoin {
+    tag: Tag,
+    key: Option<Token>,
+}
+
+impl SelectKeyJoin {
+    pub fn new(tag: Tag, key: Option<Token>) -> Self {
+        SelectKeyJoin { tag, key }
+    }
+
+    fn select_key(&self, traverser: &Traverser) -> Option<Object> {
+        if let Some(element) = traverser.select_as_element(&self.tag) {
+            match &self.key {
+                None | Some(Token::Id) => Some(element.id().into()),
+                Some(Token::Label) => Some(element.label().into()),
+                Some(Token::Property(name)) => {
+                    element.details().get_property(name).and_then(|value| value.try_to_owned())
+                }
+            }
+        } else {
+            traverser.select_as_value(&self.tag).cloned()
+        }
+    }
+}
+
+impl LeftJoinFunction<Traverser> for SelectKeyJoin {
+    fn exec(&self, parent: &Traverser, sub: Traverser) -> Option<Traverser> {
+        if parent.get_element().is_none() {
+            warn!("drop parent {:?} as it is not an element;", parent);
+            return None;
+        }
+        match self.select_key(&sub).or_else(|| self.select_key(parent)) {
+            Some(key) => Some(parent.with_object(key)),
+            None => {
+                warn!("drop parent {:?} as no key is selected by tag {:?};", parent, self.tag);
+                None
+            }
+        }
+    }
+}
+
+// for e.g., coalesce(out("knows").as("b0"), out("created").as("b1")), where the sub traversal is
+// the union of the branches, each of which tags its sub traversers by the tag of the branch
+//
+// Only the sub traversers of the first branch producing any are kept, which is known only after
+// all the sub traversers are observed; So it is a collect join, which produces them by `collect`
+// once the sub traversal is ended, and a sub traverser tagged by none of the branches is dropped.
+// The branch kept depends only on the order of the branches, no matter which workers produce the
+// sub traversers; While the sub traversers kept are in the order they are received from the
+// workers, which is not deterministic. Invoked per sub traverser by `exec`, the sub traverser is
+// always kept, as if there is only one branch;
+pub struct CoalesceJoin {
+    branches: Vec<Tag>,
+}
+
+impl CoalesceJoin {
+    pub fn new(branches: Vec<Tag>) -> Self {
+        CoalesceJoin { branches }
+    }
+
+    fn branch_of(&self, sub: &Traverser) -> Option<usize> {
+        self.branches.iter().position(|tag| sub.select(tag).is_some())
+    }
+}
+
+impl LeftJoinFunction<Traverser> for CoalesceJoin {
+    fn exec(&self, _parent: &Traverser, sub: Traverser) -> Option<Traverser> {
+        Some(sub)
+    }
+
+    fn is_collect(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, _parent: Traverser, subs: Vec<Traverser>) -> Vec<Traverser> {
+        let mut first = None;
+        let mut result = vec![];
+        for sub in subs {
+            match (self.branch_of(&sub), first) {
+                (None, _) => warn!("drop sub {:?} as it is of none of the branches;", sub),
+                (Some(branch), Some(f)) if branch == f => result.push(sub),
+                (Some(branch), Some(f)) if branch > f => (),
+                (Some(branch), _) => {
+                    first = Some(branch);
+                    result.clear();
+                    result.push(sub);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structure::{DefaultDetails, Label, Vertex};
+
+    fn vertex_traverser(id: u128) -> Traverser {
+        let label = Label::Str("person".to_string());
+        Traverser::new(Vertex::new(id, Some(label.clone()), DefaultDetails::new(id, label)))
+    }
+
+    #[test]
+    fn test_by_sub_join() {
+        let parent = vertex_traverser(1);
+        let sub = Traverser::from_object(Object::from(3i64));
+        let joined = BySubJoin.exec(&parent, sub).expect("should be joined");
+        let attached = joined.get_element().and_then(|e| e.get_attached());
+        assert_eq!(attached, Some(&Object::from(3i64)));
+    }
+
+    #[test]
+    fn test_by_sub_join_parent_not_element() {
+        let parent = Traverser::from_object(Object::from(1i64));
+        let sub = Traverser::from_object(Object::from(3i64));
+        assert!(BySubJoin.exec(&parent, sub).is_none());
+    }
+
+    #[test]
+    fn test_by_sub_join_sub_without_object() {
+        let parent = vertex_traverser(1);
+        let sub = vertex_traverser(2);
+        assert!(BySubJoin.exec(&parent, sub).is_none());
+    }
+
+    fn reduce(join: &ReduceSubJoin, values: Vec<i64>) -> Option<Traverser> {
+        let mut parent = vertex_traverser(1);
+        let len = values.len();
+        for (i, v) in values.into_iter().enumerate() {
+            join.fold(&mut parent, i, Traverser::from_object(Object::from(v)));
+        }
+        join.end(parent, len)
+    }
+
+    fn tagged_traverser(id: u128, tag: &str) -> Traverser {
+        let mut labels = std::collections::HashSet::new();
+        labels.insert(tag.to_string());
+        let label = Label::Str("person".to_string());
+        Traverser::with_path(
+            Vertex::new(id, Some(label.clone()), DefaultDetails::new(id, label)),
+            &labels,
+        )
+    }
+
+    #[test]
+    fn test_select_compare_join_eq() {
+        let join = SelectCompareJoin::eq("a".to_string());
+        let parent = tagged_traverser(1, "a");
+        assert_eq!(join.exec(&parent, vertex_traverser(1)), Some(parent.clone()));
+        assert!(join.exec(&parent, vertex_traverser(2)).is_none());
+    }
+
+    #[test]
+    fn test_select_compare_join_neq() {
+        let join = SelectCompareJoin::neq("a".to_string());
+        let parent = tagged_traverser(1, "a");
+        assert!(join.exec(&parent, vertex_traverser(1)).is_none());
+        assert_eq!(join.exec(&parent, vertex_traverser(2)), Some(parent.clone()));
+    }
+
+    #[test]
+    fn test_select_compare_join_absent() {
+        // the tag is absent from the path, or the parent has no path;
+        let join = SelectCompareJoin::neq("b".to_string());
+        assert!(join.exec(&tagged_traverser(1, "a"), vertex_traverser(2)).is_none());
+        assert!(join.exec(&vertex_traverser(1), vertex_traverser(2)).is_none());
+        // the sub traverser is not an element;
+        let join = SelectCompareJoin::neq("a".to_string());
+        let sub = Traverser::from_object(Object::from(2i64));
+        assert!(join.exec(&tagged_traverser(1, "a"), sub).is_none());
+    }
+
+    fn person(id: u128, name: &str) -> Vertex {
+        let label = Label::Str("person".to_string());
+        let mut properties = std::collections::HashMap::new();
+        properties.insert("name".to_string(), Object::from(name));
+        Vertex::new(id, Some(label.clone()), DefaultDetails::new_with_prop(id, label, properties))
+    }
+
+    #[test]
+    fn test_select_key_join_group_by_property() {
+        // group the parents by the name of the person tagged as "a" in their sub traversals;
+        let join = SelectKeyJoin::new("a".to_string(), Some(Token::Property("name".to_string())));
+        let mut labels = std::collections::HashSet::new();
+        labels.insert("a".to_string());
+        let no_labels = std::collections::HashSet::new();
+        let mut groups: Vec<(Object, Vec<u128>)> = vec![];
+        for (parent_id, friend) in
+            vec![(1, person(11, "marko")), (2, person(12, "vadas")), (3, person(13, "marko"))]
+        {
+            let parent = Traverser::with_path(person(parent_id, "parent"), &no_labels);
+            let sub = parent.split(friend, &labels).split(person(100, "josh"), &no_labels);
+            let joined = join.exec(&parent, sub).expect("should be joined");
+            let key = attached(&joined).expect("should attach the key").clone();
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, ids)) => ids.push(parent_id),
+                None => groups.push((key, vec![parent_id])),
+            }
+        }
+        assert_eq!(
+            groups,
+            vec![(Object::from("marko"), vec![1, 3]), (Object::from("vadas"), vec![2])]
+        );
+    }
+
+    #[test]
+    fn test_select_key_join() {
+        let mut labels = std::collections::HashSet::new();
+        labels.insert("a".to_string());
+        let parent = Traverser::with_path(person(1, "marko"), &labels);
+        let sub = vertex_traverser(2);
+        // select from the parent if the tag is absent from the sub traverser;
+        let joined = SelectKeyJoin::new("a".to_string(), None).exec(&parent, sub.clone());
+        assert_eq!(joined.as_ref().and_then(attached), Some(&Object::from(1u128)));
+        let joined =
+            SelectKeyJoin::new("a".to_string(), Some(Token::Label)).exec(&parent, sub.clone());
+        assert_eq!(joined.as_ref().and_then(attached), Some(&Object::from("person")));
+
+        // the tag doesn't exist, or the property doesn't exist;
+        assert!(SelectKeyJoin::new("b".to_string(), None).exec(&parent, sub.clone()).is_none());
+        let join = SelectKeyJoin::new("a".to_string(), Some(Token::Property("age".to_string())));
+        assert!(join.exec(&parent, sub.clone()).is_none());
+        // the parent is not an element;
+        let parent = Traverser::from_object(Object::from(1i64));
+        assert!(SelectKeyJoin::new("a".to_string(), None).exec(&parent, sub).is_none());
+    }
+
+    fn branch_sub(parent: &Traverser, id: u128, branch: &str) -> Traverser {
+        let mut labels = std::collections::HashSet::new();
+        labels.insert(branch.to_string());
+        parent.split(person(id, "sub"), &labels)
+    }
+
+    fn sub_ids(subs: &[Traverser]) -> Vec<u128> {
+        subs.iter().map(|t| t.get_element().unwrap().id()).collect()
+    }
+
+    #[test]
+    fn test_coalesce_join_first_branch_empty() {
+        let join = CoalesceJoin::new(vec!["b0".to_string(), "b1".to_string(), "b2".to_string()]);
+        let parent = Traverser::with_path(person(1, "marko"), &std::collections::HashSet::new());
+        // nothing from the branch "b0", so the results of the branch "b1" are kept;
+        let subs = vec![
+            branch_sub(&parent, 12, "b2"),
+            branch_sub(&parent, 10, "b1"),
+            branch_sub(&parent, 13, "b2"),
+            branch_sub(&parent, 11, "b1"),
+        ];
+        assert_eq!(sub_ids(&join.collect(parent.clone(), subs)), vec![10, 11]);
+    }
+
+    #[test]
+    fn test_coalesce_join() {
+        let join = CoalesceJoin::new(vec!["b0".to_string(), "b1".to_string()]);
+        let parent = Traverser::with_path(person(1, "marko"), &std::collections::HashSet::new());
+        // the first branch is kept though its results are received later;
+        let subs = vec![
+            branch_sub(&parent, 10, "b1"),
+            branch_sub(&parent, 20, "b0"),
+            branch_sub(&parent, 30, "none"),
+        ];
+        assert_eq!(sub_ids(&join.collect(parent.clone(), subs)), vec![20]);
+        // no results if all the branches are empty;
+        assert!(join.collect(parent.clone(), vec![]).is_empty());
+        assert!(join.collect(parent.clone(), vec![branch_sub(&parent, 30, "none")]).is_empty());
+    }
+
+    fn attached(t: &Traverser) -> Option<&Object> {
+        t.get_element().and_then(|e| e.get_attached())
+    }
+
+    #[test]
+    fn test_reduce_sub_join_max() {
+        let join = ReduceSubJoin::max();
+        let joined = reduce(&join, vec![3, 7, 1, 5]).expect("should be joined");
+        assert_eq!(attached(&joined), Some(&Object::from(7i64)));
+    }
+
+    #[test]
+    fn test_reduce_sub_join_min() {
+        let join = ReduceSubJoin::min();
+        let joined = reduce(&join, vec![3, 7, 1, 5]).expect("should be joined");
+        assert_eq!(attached(&joined), Some(&Object::from(1i64)));
+    }
+
+    #[test]
+    fn test_reduce_sub_join_empty() {
+        assert!(reduce(&ReduceSubJoin::max(), vec![]).is_none());
+    }
+
+    #[test]
+    fn test_reduce_sub_join_first_without_object() {
+        for (join, expected) in [(ReduceSubJoin::max(), 3i64), (ReduceSubJoin::min(), 1i64)] {
+            // the object attached before the join is never taken as the extreme
+            let mut parent = vertex_traverser(1);
+            parent.get_element_mut().unwrap().attach(100i64);
+            join.fold(&mut parent, 0, vertex_traverser(2));
+            join.fold(&mut parent, 1, Traverser::from_object(Object::from(3i64)));
+            join.fold(&mut parent, 2, Traverser::from_object(Object::from(1i64)));
+            let joined = join.end(parent, 3).expect("should be joined");
+            assert_eq!(attached(&joined), Some(&Object::from(expected)));
+
+            let mut parent = vertex_traverser(1);
+            parent.get_element_mut().unwrap().attach(100i64);
+            join.fold(&mut parent, 0, vertex_traverser(2));
+            assert!(join.end(parent, 1).is_none());
+        }
+    }
+
+    #[test]
+    fn test_not_exists_join() {
+        let join = NotExistsJoin;
+        let mut matched = vertex_traverser(1);
+        join.fold(&mut matched, 0, vertex_traverser(2));
+        join.fold(&mut matched, 1, vertex_traverser(3));
+        assert!(join.end(matched, 2).is_none());
+
+        let unmatched = vertex_traverser(4);
+        assert_eq!(join.end(unmatched.clone(), 0), Some(unmatched));
+    }
+
+    #[test]
+    fn test_count_sub_join() {
+        let join = CountSubJoin;
+        for count in 0..3 {
+            let mut parent = vertex_traverser(1);
+            for i in 0..count {
+                join.fold(&mut parent, i, vertex_traverser(i as u128 + 2));
+            }
+            let joined = join.end(parent, count).expect("should be joined");
+            assert_eq!(attached(&joined), Some(&Object::from(count as i64)));
+        }
+    }
+
+    #[derive(Default)]
+    struct StatefulCount {
+        count: std::cell::Cell<usize>,
+    }
+
+    impl LeftJoinFunction<Traverser> for StatefulCount {
+        fn exec(&self, parent: &Traverser, _sub: Traverser) -> Option<Traverser> {
+            self.count.set(self.count.get() + 1);
+            CountSubJoin::attach_count(parent, self.count.get())
+        }
+    }
+
+    #[test]
+    fn test_stateful_join_func_gen() {
+        let gen = JoinFuncGen::stateful(|| Box::new(StatefulCount::default()));
+        let first = gen.gen();
+        let second = gen.gen();
+        let parent = vertex_traverser(1);
+        for i in 1..4 {
+            let joined = first.exec(&parent, vertex_traverser(2)).expect("should be joined");
+            assert_eq!(attached(&joined), Some(&Object::from(i as i64)));
+        }
+        // the state of `second` is isolated from `first`;
+        let joined = second.exec(&parent, vertex_traverser(2)).expect("should be joined");
+        assert_eq!(attached(&joined), Some(&Object::from(1i64)));
     }
 }
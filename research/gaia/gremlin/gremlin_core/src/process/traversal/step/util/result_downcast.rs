@@ -35,6 +35,15 @@ pub fn try_downcast_count(obj: &Object) -> Option<&ShadeSync<u64>> {
     }
 }
 
+/// downcast result of fold(), where all traversers of a scope are collected into one list
+pub fn try_downcast_fold(obj: &Object) -> Option<&ShadeSync<Vec<Traverser>>> {
+    if let Object::UnknownOwned(object) = obj {
+        object.try_downcast_ref::<ShadeSync<Vec<Traverser>>>()
+    } else {
+        None
+    }
+}
+
 /// downcast result of group().by() and get key where key is a Traverser
 pub fn try_downcast_group_key(obj: &Object) -> Option<Traverser> {
     if let Some(group_count_pair) = try_downcast_group_count_pair(obj) {
@@ -41,103 +41,202 @@ bitflags! {
 }
 
 #[derive(Clone, Debug)]
-pub enum Traverser {
+enum Inner {
     Path(Path),
     NoPath(GraphElement),
     Unknown(Object),
 }
 
+/// A traverser with a `bulk`, which represents `bulk` identical traversers, so that identical
+/// traversers can be represented once; The traversers split from it inherit its bulk;
+#[derive(Clone, Debug)]
+pub struct Traverser {
+    inner: Inner,
+    bulk: u64,
+}
+
 impl Traverser {
     pub fn new<E: Into<GraphElement>>(e: E) -> Self {
-        Traverser::NoPath(e.into())
+        Traverser { inner: Inner::NoPath(e.into()), bulk: 1 }
     }
 
     pub fn with_path<E: Into<GraphElement>>(e: E, labels: &HashSet<String>) -> Self {
         let path = Path::new(e.into());
         path.extend(labels);
-        Traverser::Path(path)
+        Traverser { inner: Inner::Path(path), bulk: 1 }
+    }
+
+    /// Create a traverser carrying an object, other than a graph element;
+    pub fn from_object<T: Into<Object>>(obj: T) -> Self {
+        Traverser { inner: Inner::Unknown(obj.into()), bulk: 1 }
+    }
+
+    /// Create a traverser carrying an object as [`from_object`] does, but with its path tracked,
+    /// where the object is tagged by `labels`;
+    ///
+    /// [`from_object`]: Traverser::from_object
+    pub fn object_with_path<T: Into<Object>>(obj: T, labels: &HashSet<String>) -> Self {
+        let path = Path::from_detached(obj);
+        path.extend(labels);
+        Traverser { inner: Inner::Path(path), bulk: 1 }
+    }
+
+    #[inline]
+    fn split_inner(&self, inner: Inner) -> Self {
+        Traverser { inner, bulk: self.bulk }
+    }
+
+    #[inline]
+    pub fn bulk(&self) -> u64 {
+        self.bulk
+    }
+
+    #[inline]
+    pub fn set_bulk(&mut self, bulk: u64) {
+        self.bulk = bulk;
+    }
+
+    #[inline]
+    pub fn add_bulk(&mut self, bulk: u64) {
+        self.bulk += bulk;
+    }
+
+    /// Merge an equal traverser `other` into this one by adding up their bulks, return `other`
+    /// back if they are not equal;
+    pub fn merge(&mut self, other: Traverser) -> Result<(), Traverser> {
+        if *self == other {
+            self.add_bulk(other.bulk);
+            Ok(())
+        } else {
+            Err(other)
+        }
     }
 
     pub fn get_element(&self) -> Option<&GraphElement> {
-        match self {
-            Traverser::Path(p) => p.head().as_element(),
-            Traverser::NoPath(e) => Some(e),
-            Traverser::Unknown(_) => None,
+        match &self.inner {
+            Inner::Path(p) => p.head().as_element(),
+            Inner::NoPath(e) => Some(e),
+            Inner::Unknown(_) => None,
         }
     }
 
     pub fn get_element_mut(&mut self) -> Option<&mut GraphElement> {
-        match self {
-            Traverser::Path(p) => p.head_mut().as_mut_element(),
-            Traverser::NoPath(e) => Some(e),
-            Traverser::Unknown(_) => None,
+        match &mut self.inner {
+            Inner::Path(p) => p.head_mut().as_mut_element(),
+            Inner::NoPath(e) => Some(e),
+            Inner::Unknown(_) => None,
         }
     }
 
     pub fn get_object(&self) -> Option<&Object> {
-        match self {
-            Traverser::Path(p) => p.head().as_detached(),
-            Traverser::NoPath(_) => None,
-            Traverser::Unknown(o) => Some(o),
+        match &self.inner {
+            Inner::Path(p) => p.head().as_detached(),
+            Inner::NoPath(_) => None,
+            Inner::Unknown(o) => Some(o),
+        }
+    }
+
+    /// Clone the traverser with its carried object replaced by `obj`; If the traverser holds an
+    /// element, `obj` is attached to the element, which is kept; Otherwise the object it holds is
+    /// replaced;
+    pub fn with_object<T: Into<Object>>(&self, obj: T) -> Traverser {
+        let mut traverser = self.clone();
+        match &mut traverser.inner {
+            Inner::Path(p) => match p.head_mut() {
+                PathItem::OnGraph(e) => e.attach(obj),
+                head => *head = PathItem::Detached(obj.into()),
+            },
+            Inner::NoPath(e) => e.attach(obj),
+            Inner::Unknown(o) => *o = obj.into(),
         }
+        traverser
     }
 
     pub fn split<E: Into<GraphElement>>(&self, e: E, labels: &HashSet<String>) -> Traverser {
-        match self {
-            Traverser::Path(p) => {
+        match &self.inner {
+            Inner::Path(p) => {
                 let mut path = p.clone();
                 path.extend_with(e, labels);
-                Traverser::Path(path)
+                self.split_inner(Inner::Path(path))
             }
-            Traverser::NoPath(_) => Traverser::NoPath(e.into()),
-            Traverser::Unknown(_) => Traverser::NoPath(e.into()),
+            Inner::NoPath(_) => self.split_inner(Inner::NoPath(e.into())),
+            Inner::Unknown(_) => self.split_inner(Inner::NoPath(e.into())),
         }
     }
 
     pub fn split_with_value<T: Into<Object>>(&self, o: T, labels: &HashSet<String>) -> Traverser {
-        match self {
-            Traverser::Path(p) => {
+        match &self.inner {
+            Inner::Path(p) => {
                 let mut path = p.clone();
                 path.add_detached(o, labels);
-                Traverser::Path(path)
+                self.split_inner(Inner::Path(path))
             }
-            Traverser::NoPath(e) => {
+            Inner::NoPath(e) => {
                 let mut e = e.clone();
                 e.attach(o);
-                Traverser::NoPath(e)
+                self.split_inner(Inner::NoPath(e))
             }
-            Traverser::Unknown(_) => Traverser::Unknown(o.into()),
+            Inner::Unknown(_) => self.split_inner(Inner::Unknown(o.into())),
         }
     }
 
     pub fn modify_head<E: Into<GraphElement>>(&self, e: E, labels: &HashSet<String>) -> Traverser {
-        match self {
-            Traverser::Path(p) => {
+        match &self.inner {
+            Inner::Path(p) => {
                 let mut path = p.clone();
                 path.modify_head_with(e, labels);
-                Traverser::Path(path)
+                self.split_inner(Inner::Path(path))
             }
-            _ => Traverser::NoPath(e.into()),
+            _ => self.split_inner(Inner::NoPath(e.into())),
         }
     }
 
     pub fn add_labels(&self, labels: &HashSet<String>) {
-        match self {
-            Traverser::Path(p) => p.extend(labels),
+        match &self.inner {
+            Inner::Path(p) => p.extend(labels),
             _ => (),
         }
     }
 
-    pub fn is_simple(&self) -> bool {
-        match self {
-            Traverser::Path(p) => p.is_simple(),
+    /// Tag the current head by `tag`, so that it can be selected back by [`select`] after the
+    /// traverser moves on; The tag refers to the head by its position in the path history, which is
+    /// not copied, and the tags are copied along with the path to the traversers split from this
+    /// one; If the path is not tracked, the tracking starts from the current head, as the history
+    /// before it is already lost;
+    ///
+    /// [`select`]: Traverser::select
+    pub fn tag(&mut self, tag: Tag) {
+        let mut labels = HashSet::new();
+        labels.insert(tag);
+        let path = match &self.inner {
+            Inner::Path(p) => {
+                p.extend(&labels);
+                return;
+            }
+            Inner::NoPath(e) => Path::new(e.clone()),
+            Inner::Unknown(o) => Path::from_detached(o.clone()),
+        };
+        path.extend(&labels);
+        self.inner = Inner::Path(path);
+    }
+
+    /// Return `true` if no graph element repeats in the path, compared by element id; Detached
+    /// items in the path are ignored, and a traverser without path tracking is always simple;
+    pub fn is_simple_path(&self) -> bool {
+        match &self.inner {
+            Inner::Path(p) => p.is_simple(),
             _ => true,
         }
     }
 
+    #[deprecated(note = "use `is_simple_path` instead")]
+    pub fn is_simple(&self) -> bool {
+        self.is_simple_path()
+    }
+
     pub fn select(&self, label: &Tag) -> Option<&PathItem> {
-        match self {
-            Traverser::Path(p) => p.select_first(label),
+        match &self.inner {
+            Inner::Path(p) => p.select_first(label),
             _ => None,
         }
     }
@@ -152,8 +251,8 @@ impl Traverser {
 
     // TODO: select_pop
     pub fn select_pop(&self, pop: Pop, label: &Tag) -> Option<&PathItem> {
-        match self {
-            Traverser::Path(p) => match pop {
+        match &self.inner {
+            Inner::Path(p) => match pop {
                 Pop::First => p.select_first(label),
                 Pop::Last => p.select_last(label),
                 _ => unimplemented!(),
@@ -180,24 +279,46 @@ impl Traverser {
         }
     }
 
+    /// The complement of [`is_simple_path`];
+    ///
+    /// [`is_simple_path`]: Traverser::is_simple_path
+    pub fn is_cyclic_path(&self) -> bool {
+        !self.is_simple_path()
+    }
+
+    #[deprecated(note = "use `is_cyclic_path` instead")]
     pub fn has_cyclic_path(&self) -> bool {
-        match self {
-            Traverser::Path(p) => !p.is_simple(),
-            _ => false,
-        }
+        self.is_cyclic_path()
     }
 
     pub fn take_path(self) -> ResultPath {
-        match self {
-            Traverser::Path(p) => p.finalize(),
-            Traverser::NoPath(e) => ResultPath::new(vec![PathItem::OnGraph(e)]),
-            Traverser::Unknown(e) => ResultPath::new(vec![PathItem::Detached(e)]),
+        match self.inner {
+            Inner::Path(p) => p.finalize(),
+            Inner::NoPath(e) => ResultPath::new(vec![PathItem::OnGraph(e)]),
+            Inner::Unknown(e) => ResultPath::new(vec![PathItem::Detached(e)]),
         }
     }
 
+    /// Get the path history of the traverser, return `None` if the traverser is created without
+    /// path tracking; The path is finalized into a [`ResultPath`] by [`take_path`];
+    ///
+    /// [`take_path`]: Traverser::take_path
+    pub fn path(&self) -> Option<&Path> {
+        match &self.inner {
+            Inner::Path(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Get the length of the path history, return `None` if the traverser is created without path
+    /// tracking;
+    pub fn path_len(&self) -> Option<usize> {
+        self.path().map(|p| p.length())
+    }
+
     pub fn get_path_len(&self) -> usize {
-        match self {
-            Traverser::Path(p) => p.length(),
+        match &self.inner {
+            Inner::Path(p) => p.length(),
             _ => 0,
         }
     }
@@ -215,42 +336,43 @@ impl Decode for Traverser {
     }
 }
 
+/// The bulks are not compared, as they are the multiplicities of the traversers;
 impl PartialEq for Traverser {
     fn eq(&self, other: &Self) -> bool {
-        match self {
-            Traverser::Path(p) => match p.head() {
-                PathItem::OnGraph(e) => match other {
-                    Traverser::Path(other_p) => match other_p.head() {
+        match &self.inner {
+            Inner::Path(p) => match p.head() {
+                PathItem::OnGraph(e) => match &other.inner {
+                    Inner::Path(other_p) => match other_p.head() {
                         PathItem::OnGraph(o) => e == o,
                         PathItem::Detached(_) => false,
                     },
-                    Traverser::NoPath(o) => e == o,
-                    Traverser::Unknown(_) => false,
+                    Inner::NoPath(o) => e == o,
+                    Inner::Unknown(_) => false,
                 },
-                PathItem::Detached(obj) => match other {
-                    Traverser::Path(other_p) => match other_p.head() {
+                PathItem::Detached(obj) => match &other.inner {
+                    Inner::Path(other_p) => match other_p.head() {
                         PathItem::OnGraph(_) => false,
                         PathItem::Detached(other_obj) => obj == other_obj,
                     },
-                    Traverser::NoPath(_) => false,
-                    Traverser::Unknown(other_obj) => obj == other_obj,
+                    Inner::NoPath(_) => false,
+                    Inner::Unknown(other_obj) => obj == other_obj,
                 },
             },
-            Traverser::NoPath(e) => match other {
-                Traverser::Path(p) => match p.head() {
+            Inner::NoPath(e) => match &other.inner {
+                Inner::Path(p) => match p.head() {
                     PathItem::OnGraph(o) => e == o,
                     PathItem::Detached(_) => false,
                 },
-                Traverser::NoPath(o) => e == o,
-                Traverser::Unknown(_) => false,
+                Inner::NoPath(o) => e == o,
+                Inner::Unknown(_) => false,
             },
-            Traverser::Unknown(obj) => match other {
-                Traverser::Path(p) => match p.head() {
+            Inner::Unknown(obj) => match &other.inner {
+                Inner::Path(p) => match p.head() {
                     PathItem::OnGraph(_) => false,
                     PathItem::Detached(other_obj) => obj == other_obj,
                 },
-                Traverser::NoPath(_) => false,
-                Traverser::Unknown(other_obj) => obj == other_obj,
+                Inner::NoPath(_) => false,
+                Inner::Unknown(other_obj) => obj == other_obj,
             },
         }
     }
@@ -285,7 +407,15 @@ impl<T: Debug + Send> Debug for ShadeSync<T> {
 impl AnyData for Traverser {
     fn with<T: Data + Eq>(raw: T) -> Self {
         let v = ShadeSync { inner: raw };
-        Traverser::Unknown(Object::UnknownOwned(Box::new(v)))
+        Traverser::from_object(Object::UnknownOwned(Box::new(v)))
+    }
+
+    fn bulk(&self) -> u64 {
+        self.bulk
+    }
+
+    fn reset_bulk(&mut self) {
+        self.bulk = 1;
     }
 }
 
@@ -312,3 +442,172 @@ impl<E: Into<GraphElement>> Iterator for TraverserSplitIter<E> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structure::{DefaultDetails, Element, Label, Vertex};
+
+    fn vertex(id: u128) -> Vertex {
+        let label = Label::Str("person".to_string());
+        Vertex::new(id, Some(label.clone()), DefaultDetails::new(id, label))
+    }
+
+    #[test]
+    fn test_path_history() {
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels);
+        let t = t.split(vertex(2), &labels);
+        let t = t.split_with_value(3i64, &labels);
+        assert_eq!(t.path_len(), Some(3));
+        let history = t.path().expect("should have path").objects();
+        assert_eq!(history[0].as_element().map(|e| e.id()), Some(1));
+        assert_eq!(history[1].as_element().map(|e| e.id()), Some(2));
+        assert_eq!(history[2].as_detached(), Some(&Object::from(3i64)));
+    }
+
+    #[test]
+    fn test_path_untracked() {
+        let t = Traverser::new(vertex(1));
+        assert!(t.path().is_none());
+        assert_eq!(t.path_len(), None);
+        assert!(Traverser::from_object(1i64).path().is_none());
+    }
+
+    #[test]
+    fn test_simple_path() {
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels).split(vertex(2), &labels);
+        let t = t.split(vertex(3), &labels);
+        assert!(t.is_simple_path());
+        assert!(!t.is_cyclic_path());
+        assert!(Traverser::new(vertex(1)).is_simple_path());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_cyclic_path() {
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels).split(vertex(2), &labels);
+        let t = t.split(vertex(1), &labels);
+        assert!(!t.is_simple_path());
+        assert!(t.is_cyclic_path());
+        // the old names forward to the new ones
+        assert!(!t.is_simple());
+        assert!(t.has_cyclic_path());
+    }
+
+    #[test]
+    fn test_path_with_detached() {
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels).split_with_value(1i64, &labels);
+        let t = t.split_with_value(1i64, &labels).split(vertex(2), &labels);
+        assert!(t.is_simple_path());
+        let t = t.split_with_value(2i64, &labels).split(vertex(1), &labels);
+        assert!(t.is_cyclic_path());
+    }
+
+    #[test]
+    fn test_with_object_on_element() {
+        let t = Traverser::new(vertex(1));
+        let replaced = t.with_object(3i64);
+        let e = replaced.get_element().expect("should be element");
+        assert_eq!(e.id(), 1);
+        assert_eq!(e.get_attached(), Some(&Object::from(3i64)));
+        assert_eq!(t.get_element().and_then(|e| e.get_attached()), None);
+
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels).with_object(3i64);
+        assert_eq!(t.get_element().and_then(|e| e.get_attached()), Some(&Object::from(3i64)));
+    }
+
+    #[test]
+    fn test_with_object_on_object() {
+        let t = Traverser::from_object(1i64).with_object(2i64);
+        assert_eq!(t.get_object(), Some(&Object::from(2i64)));
+
+        let labels = HashSet::new();
+        let t = Traverser::with_path(vertex(1), &labels).split_with_value(1i64, &labels);
+        let t = t.with_object(2i64);
+        assert_eq!(t.get_object(), Some(&Object::from(2i64)));
+        assert_eq!(t.path_len(), Some(2));
+    }
+
+    #[test]
+    fn test_tag_and_select() {
+        let labels = HashSet::new();
+        let mut t = Traverser::with_path(vertex(1), &labels);
+        t.tag("a".to_string());
+        let mut t = t.split(vertex(2), &labels).split_with_value(3i64, &labels);
+        t.tag("b".to_string());
+        let t = t.split(vertex(4), &labels);
+        assert_eq!(t.select_as_element(&"a".to_string()).map(|e| e.id()), Some(1));
+        assert_eq!(t.select_as_value(&"b".to_string()), Some(&Object::from(3i64)));
+        // a tagged element can't be selected as a value, and vice versa;
+        assert!(t.select_as_value(&"a".to_string()).is_none());
+        assert!(t.select_as_element(&"b".to_string()).is_none());
+        assert!(t.select(&"c".to_string()).is_none());
+        assert_eq!(t.path_len(), Some(4));
+    }
+
+    #[test]
+    fn test_tag_untracked() {
+        let labels = HashSet::new();
+        let mut t = Traverser::new(vertex(1));
+        t.tag("a".to_string());
+        assert_eq!(t.path_len(), Some(1));
+        let t = t.split(vertex(2), &labels);
+        assert_eq!(t.select_as_element(&"a".to_string()).map(|e| e.id()), Some(1));
+
+        let mut t = Traverser::from_object(1i64);
+        t.tag("a".to_string());
+        assert_eq!(t.get_object(), Some(&Object::from(1i64)));
+        assert_eq!(t.select_as_value(&"a".to_string()), Some(&Object::from(1i64)));
+    }
+
+    #[test]
+    fn test_bulk() {
+        let labels = HashSet::new();
+        let mut t = Traverser::with_path(vertex(1), &labels);
+        assert_eq!(t.bulk(), 1);
+        t.set_bulk(3);
+        t.add_bulk(2);
+        assert_eq!(t.bulk(), 5);
+        assert_eq!(AnyData::bulk(&t), 5);
+        // the traversers split from a bulked traverser inherit its bulk;
+        assert_eq!(t.split(vertex(2), &labels).bulk(), 5);
+        assert_eq!(t.split_with_value(1i64, &labels).bulk(), 5);
+    }
+
+    #[test]
+    fn test_count_by_bulk() {
+        let mut bulked = Traverser::new(vertex(1));
+        bulked.set_bulk(3);
+        let mut merged = Traverser::new(vertex(2));
+        merged.merge(Traverser::new(vertex(2))).unwrap();
+        // a traverser without bulk set is counted as 1;
+        let traversers =
+            vec![bulked.clone(), Traverser::new(vertex(3)), merged, Traverser::with(1u64)];
+        let count: u64 = traversers.iter().map(AnyData::bulk).sum();
+        assert_eq!(count, 7);
+
+        // dedup keeps one of the identical traversers, which is counted once;
+        let mut deduped = bulked;
+        deduped.reset_bulk();
+        assert_eq!(AnyData::bulk(&deduped), 1);
+    }
+
+    #[test]
+    fn test_merge_bulk() {
+        let mut t = Traverser::new(vertex(1));
+        let mut other = Traverser::new(vertex(1));
+        other.set_bulk(2);
+        assert!(t.merge(other).is_ok());
+        assert_eq!(t.bulk(), 3);
+
+        let different = Traverser::new(vertex(2));
+        let back = t.merge(different).expect_err("should not be merged");
+        assert_eq!(back.bulk(), 1);
+        assert_eq!(t.bulk(), 3);
+    }
+}
@@ -15,78 +15,320 @@
 
 use crate::generated::common as common_pb;
 use crate::generated::protobuf as result_pb;
-use crate::object::Primitives;
+use crate::object::{DynType, Primitives, RawType};
 use crate::process::traversal::path::{PathItem, ResultPath};
 use crate::process::traversal::step::ResultProperty;
 use crate::process::traversal::traverser::{ShadeSync, Traverser};
-use crate::structure::{Edge, GraphElement, Label, Vertex, VertexOrEdge};
+use crate::structure::{
+    Details, DynDetails, Edge, Element, GraphElement, Label, Vertex, VertexOrEdge,
+};
 use crate::Object;
+use graph_store::common::LabelId;
+use graph_store::schema::Schema;
 use pegasus_server::factory::HashKey;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Deref;
+use std::sync::{Arc, RwLock};
 
-fn vertex_to_pb(v: &Vertex) -> result_pb::Vertex {
+#[derive(Debug)]
+pub enum EncodeError {
+    /// The dynamic typed object can't be recognized, with the name of its type;
+    UnknownType(&'static str),
+    /// The value of the raw type is not supported to encode yet;
+    UnsupportedValue(RawType),
+    /// The blob is larger than the limit, with the size of the blob and the limit;
+    BlobTooLarge(usize, usize),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EncodeError::UnknownType(name) => write!(f, "can't encode unknown type {}", name),
+            EncodeError::UnsupportedValue(raw_type) => {
+                write!(f, "can't encode value of type {:?}", raw_type)
+            }
+            EncodeError::BlobTooLarge(size, limit) => {
+                write!(f, "can't encode blob of {} bytes larger than {} bytes", size, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// The default max size in bytes of a blob value to be encoded;
+pub const DEFAULT_MAX_BLOB_SIZE: usize = 64 * 1024 * 1024;
+
+/// Encode the value of a dynamic type into pb value, return `None` if the value can't be encoded;
+pub type ValueEncoder = fn(&dyn Any) -> Option<common_pb::Value>;
+
+lazy_static! {
+    static ref VALUE_ENCODERS: RwLock<HashMap<TypeId, ValueEncoder>> = RwLock::new(HashMap::new());
+}
+
+/// Register the encoder of the values of type `T`, which is consulted to encode the
+/// `Object::UnknownOwned` or `Object::UnknownRef` of type `T`; The encoder registered before for
+/// the same type is replaced;
+pub fn register_value_encoder<T: DynType>(encoder: ValueEncoder) {
+    let mut encoders = VALUE_ENCODERS.write().expect("value encoders poisoned");
+    encoders.insert(TypeId::of::<T>(), encoder);
+}
+
+fn dyn_value_to_pb(value: &dyn DynType) -> Result<common_pb::Value, EncodeError> {
+    let any = value.as_any();
+    let encoder =
+        VALUE_ENCODERS.read().expect("value encoders poisoned").get(&any.type_id()).copied();
+    encoder
+        .and_then(|encode| encode(any))
+        .ok_or_else(|| EncodeError::UnknownType(value.type_name()))
+}
+
+/// Resolve the label into the string to be encoded;
+pub trait LabelResolver {
+    fn resolve<'a>(&self, label: &'a Label) -> Cow<'a, str>;
+}
+
+/// Keep the string-typed label as it is, and turn the id-typed label into its numeric form;
+pub struct IdentityResolver;
+
+impl LabelResolver for IdentityResolver {
+    fn resolve<'a>(&self, label: &'a Label) -> Cow<'a, str> {
+        match label {
+            Label::Str(s) => Cow::Borrowed(s.as_str()),
+            Label::Id(id) => Cow::Owned(id.to_string()),
+        }
+    }
+}
+
+/// Turn the id-typed label back to its name by a dictionary, and fall back to the numeric form
+/// if the id is not found;
+#[derive(Clone, Debug, Default)]
+pub struct DictResolver {
+    names: HashMap<u32, String>,
+}
+
+impl DictResolver {
+    pub fn new(names: HashMap<u32, String>) -> Self {
+        DictResolver { names }
+    }
+
+    pub fn add<S: Into<String>>(&mut self, id: u32, name: S) {
+        self.names.insert(id, name.into());
+    }
+}
+
+impl LabelResolver for DictResolver {
+    fn resolve<'a>(&self, label: &'a Label) -> Cow<'a, str> {
+        match label {
+            Label::Str(s) => Cow::Borrowed(s.as_str()),
+            Label::Id(id) => {
+                if let Some(name) = self.names.get(&(*id as u32)) {
+                    Cow::Owned(name.clone())
+                } else {
+                    debug!("label id {} is not found in label dictionary", id);
+                    Cow::Owned(id.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// The dictionaries to turn the id-typed labels of vertices and edges back to their names;
+#[derive(Clone, Debug, Default)]
+pub struct LabelMap {
+    vertex_labels: DictResolver,
+    edge_labels: DictResolver,
+}
+
+impl LabelMap {
+    /// Build the label map of all the vertex and edge types registered in the graph schema;
+    pub fn from_schema(schema: &dyn Schema) -> Self {
+        let mut labels = LabelMap::default();
+        for (name, id) in schema.get_vertex_types() {
+            labels.add_vertex_label(id, name);
+        }
+        for (name, tuple) in schema.get_edge_types() {
+            labels.add_edge_label(tuple.edge_label, name);
+        }
+        labels
+    }
+
+    pub fn add_vertex_label<S: Into<String>>(&mut self, id: LabelId, name: S) {
+        self.vertex_labels.add(id as u32, name);
+    }
+
+    pub fn add_edge_label<S: Into<String>>(&mut self, id: LabelId, name: S) {
+        self.edge_labels.add(id as u32, name);
+    }
+}
+
+/// Encode the properties in `details` into pb. If `keys` is given, only the properties of the
+/// given keys are encoded, otherwise all the properties are encoded. The blob properties larger
+/// than `max_blob_size` are skipped.
+fn details_to_pb(
+    details: &DynDetails, keys: Option<&[String]>, max_blob_size: usize,
+) -> Vec<result_pb::Property> {
+    let mut props_pb = vec![];
+    if let Some(keys) = keys {
+        for key in keys {
+            if let Some(value) = details.get_property(key).and_then(|v| v.try_to_owned()) {
+                if let Some(property) = property_to_pb(key, &value, max_blob_size) {
+                    props_pb.push(property);
+                }
+            }
+        }
+    } else if let Some(properties) = details.get_all_properties() {
+        for (key, value) in properties.iter() {
+            if let Some(property) = property_to_pb(key, value, max_blob_size) {
+                props_pb.push(property);
+            }
+        }
+    }
+    props_pb
+}
+
+fn property_to_pb(key: &str, value: &Object, max_blob_size: usize) -> Option<result_pb::Property> {
+    match object_to_pb_value(value, max_blob_size) {
+        Ok(pb_value) => Some(result_pb::Property { key: key.to_string(), value: Some(pb_value) }),
+        Err(e) => {
+            warn!("skip property {:?}: {}", key, e);
+            None
+        }
+    }
+}
+
+fn label_to_pb(label: Option<&Label>, resolver: &dyn LabelResolver) -> String {
+    if let Some(label) = label {
+        resolver.resolve(label).into_owned()
+    } else {
+        String::new()
+    }
+}
+
+fn vertex_to_pb(
+    v: &Vertex, keys: Option<&[String]>, resolver: &dyn LabelResolver, max_blob_size: usize,
+) -> result_pb::Vertex {
     result_pb::Vertex {
         id: v.id as i64,
-        label: if let Some(label) = v.label.clone() {
-            match label {
-                Label::Str(s) => s,
-                // TODO(longbin) should turn back to its actual string
-                Label::Id(id) => id.to_string(),
-            }
-        } else {
-            String::new()
-        },
-        properties: vec![],
+        label: label_to_pb(v.label.as_ref(), resolver),
+        properties: details_to_pb(v.details(), keys, max_blob_size),
     }
 }
-fn edge_to_pb(e: &Edge) -> result_pb::Edge {
+
+/// Encode the edge into pb, where `resolver` resolves the label of the edge, and `v_resolver`
+/// resolves the labels of its source and destination vertices; The properties are encoded as
+/// `details_to_pb`, and are left empty if the edge has no properties, or its properties can't be
+/// enumerated;
+fn edge_to_pb(
+    e: &Edge, keys: Option<&[String]>, resolver: &dyn LabelResolver,
+    v_resolver: &dyn LabelResolver, max_blob_size: usize,
+) -> result_pb::Edge {
     result_pb::Edge {
         id: e.id as i64,
-        label: if let Some(label) = e.label.clone() {
-            match label {
-                Label::Str(s) => s,
-                // TODO(longbin) should turn back to its actual string
-                Label::Id(id) => id.to_string(),
-            }
-        } else {
-            String::new()
-        },
+        label: label_to_pb(e.label.as_ref(), resolver),
         src_id: e.src_id as i64,
-        src_label: "".to_string(),
+        src_label: label_to_pb(e.src_label(), v_resolver),
         dst_id: e.dst_id as i64,
-        dst_label: "".to_string(),
-        properties: vec![],
+        dst_label: label_to_pb(e.dst_label(), v_resolver),
+        properties: details_to_pb(e.details(), keys, max_blob_size),
     }
 }
 
-fn element_to_pb(g: &GraphElement) -> result_pb::GraphElement {
+fn element_to_pb(
+    g: &GraphElement, labels: &LabelMap, max_blob_size: usize,
+) -> result_pb::GraphElement {
     let inner = match g.get() {
-        VertexOrEdge::V(v) => result_pb::graph_element::Inner::Vertex(vertex_to_pb(v)),
-        VertexOrEdge::E(e) => result_pb::graph_element::Inner::Edge(edge_to_pb(e)),
+        VertexOrEdge::V(v) => result_pb::graph_element::Inner::Vertex(vertex_to_pb(
+            v,
+            None,
+            &labels.vertex_labels,
+            max_blob_size,
+        )),
+        VertexOrEdge::E(e) => result_pb::graph_element::Inner::Edge(edge_to_pb(
+            e,
+            None,
+            &labels.edge_labels,
+            &labels.vertex_labels,
+            max_blob_size,
+        )),
     };
     result_pb::GraphElement { inner: Some(inner) }
 }
 
-fn path_to_pb(path: &ResultPath) -> result_pb::Path {
+/// Encode the detached item of a path, which is usually a projected value (e.g., via `by()`)
+/// rather than a vertex or an edge, into a value-typed `GraphElement`. A detached element is
+/// still encoded as vertex or edge. The unsupported value is encoded as `None` to keep the
+/// position of items in the path.
+fn detached_to_pb(
+    obj: &Object, labels: &LabelMap, max_blob_size: usize,
+) -> result_pb::GraphElement {
+    if let Object::UnknownOwned(x) = obj {
+        if let Some(element) = x.try_downcast_ref::<GraphElement>() {
+            return element_to_pb(element, labels, max_blob_size);
+        }
+    }
+    let value = object_to_pb_value(obj, max_blob_size).unwrap_or_else(|e| {
+        warn!("encode detached path item {:?} as none: {}", obj, e);
+        common_pb::Value { item: Some(common_pb::value::Item::None(common_pb::None {})) }
+    });
+    result_pb::GraphElement { inner: Some(result_pb::graph_element::Inner::Value(value)) }
+}
+
+fn traverser_to_pb(
+    t: &Traverser, labels: &LabelMap, max_blob_size: usize,
+) -> result_pb::GraphElement {
+    if let Some(e) = t.get_element() {
+        element_to_pb(e, labels, max_blob_size)
+    } else if let Some(o) = t.get_object() {
+        detached_to_pb(o, labels, max_blob_size)
+    } else {
+        result_pb::GraphElement { inner: None }
+    }
+}
+
+fn group_count_to_pb(
+    group_count: &(HashKey<Traverser>, u64), labels: &LabelMap, max_blob_size: usize,
+) -> result_pb::GroupCount {
+    let (key, count) = group_count;
+    let key = traverser_to_pb(key, labels, max_blob_size);
+    result_pb::GroupCount { key: Some(key), count: *count as i64 }
+}
+
+fn path_to_pb(path: &ResultPath, labels: &LabelMap, max_blob_size: usize) -> result_pb::Path {
     let mut path_pb = vec![];
     for item in path.iter() {
         match item {
             PathItem::OnGraph(graph_element) => {
-                path_pb.push(element_to_pb(graph_element));
+                path_pb.push(element_to_pb(graph_element, labels, max_blob_size));
+            }
+            PathItem::Detached(obj) => {
+                path_pb.push(detached_to_pb(obj, labels, max_blob_size));
             }
-            PathItem::Detached(_) => unimplemented!(),
         }
     }
     result_pb::Path { path: path_pb }
 }
 
-fn property_to_pb(result_property: &ResultProperty) -> result_pb::TagProperties {
+fn collection_to_pb(
+    items: &[Traverser], labels: &LabelMap, max_blob_size: usize,
+) -> result_pb::Collection {
+    let item = items.iter().map(|t| traverser_to_pb(t, labels, max_blob_size)).collect();
+    result_pb::Collection { item }
+}
+
+fn tag_properties_to_pb(
+    result_property: &ResultProperty, max_blob_size: usize,
+) -> result_pb::TagProperties {
     let mut tag_props_pb = vec![];
     for (tag, props_key_value) in result_property.properties.iter() {
         let mut props_pb = vec![];
         for (key, value) in props_key_value {
-            let pb_value = object_to_pb_value(value);
-            let property = result_pb::Property { key: key.to_string(), value: Some(pb_value) };
-            props_pb.push(property);
+            if let Some(property) = property_to_pb(key, value, max_blob_size) {
+                props_pb.push(property);
+            }
         }
         let tag_property = result_pb::TagProperty { tag: tag.to_string(), props: props_pb };
         tag_props_pb.push(tag_property);
@@ -94,71 +336,761 @@ fn property_to_pb(result_property: &ResultProperty) -> result_pb::TagProperties
     result_pb::TagProperties { item: tag_props_pb }
 }
 
-fn object_to_pb_value(value: &Object) -> common_pb::Value {
+/// Encode the object into pb value, where the blob larger than `max_blob_size` is rejected, other
+/// than copied;
+fn object_to_pb_value(
+    value: &Object, max_blob_size: usize,
+) -> Result<common_pb::Value, EncodeError> {
     let item = match value {
         Object::Primitive(v) => {
             match v {
                 Primitives::Byte(_) => {
                     // TODO: check
-                    unimplemented!()
+                    return Err(EncodeError::UnsupportedValue(RawType::Byte));
                 }
+                Primitives::Boolean(v) => common_pb::value::Item::Boolean(*v),
                 Primitives::Integer(v) => common_pb::value::Item::I32(*v),
                 Primitives::Long(v) => common_pb::value::Item::I64(*v),
                 Primitives::Float(v) => common_pb::value::Item::F64(*v),
+                Primitives::DateTime(v) => common_pb::value::Item::DateTime(*v),
             }
         }
         Object::String(s) => common_pb::value::Item::Str(s.clone()),
-        Object::Blob(b) => common_pb::value::Item::Blob(b.to_vec()),
-        Object::UnknownOwned(_) => unimplemented!(),
-        Object::UnknownRef(_) => unimplemented!(),
+        Object::Blob(b) => {
+            if b.len() > max_blob_size {
+                return Err(EncodeError::BlobTooLarge(b.len(), max_blob_size));
+            }
+            common_pb::value::Item::Blob(b.to_vec())
+        }
+        Object::Null => common_pb::value::Item::None(common_pb::None {}),
+        Object::UnknownOwned(x) => return dyn_value_to_pb(x.deref()),
+        Object::UnknownRef(x) => return dyn_value_to_pb(*x),
     };
-    common_pb::Value { item: Some(item) }
+    Ok(common_pb::Value { item: Some(item) })
 }
 
-pub fn result_to_pb(data: Vec<Traverser>) -> result_pb::Result {
-    let mut paths_encode = vec![];
-    let mut elements_encode = vec![];
-    let mut properties_encode = vec![];
-    for t in data {
+/// Encode traversers into `result_pb::Result` incrementally, and flush the encoded results
+/// to `sink` in chunks of at most `batch_size` traversers.
+pub struct ResultEncoder<F: FnMut(result_pb::Result)> {
+    batch_size: usize,
+    len: usize,
+    flushed: bool,
+    sink: F,
+    labels: Arc<LabelMap>,
+    max_blob_size: usize,
+    paths_encode: Vec<result_pb::Path>,
+    elements_encode: Vec<result_pb::GraphElement>,
+    properties_encode: Vec<result_pb::TagProperties>,
+    counts_encode: Vec<i64>,
+    group_counts_encode: Vec<result_pb::GroupCount>,
+    values_encode: Vec<common_pb::Value>,
+    collections_encode: Vec<result_pb::Collection>,
+}
+
+impl<F: FnMut(result_pb::Result)> ResultEncoder<F> {
+    pub fn new(batch_size: usize, sink: F) -> Self {
+        assert!(batch_size > 0, "batch size should be positive");
+        ResultEncoder {
+            batch_size,
+            len: 0,
+            flushed: false,
+            sink,
+            labels: Arc::new(LabelMap::default()),
+            max_blob_size: DEFAULT_MAX_BLOB_SIZE,
+            paths_encode: vec![],
+            elements_encode: vec![],
+            properties_encode: vec![],
+            counts_encode: vec![],
+            group_counts_encode: vec![],
+            values_encode: vec![],
+            collections_encode: vec![],
+        }
+    }
+
+    /// Set the label map to turn the id-typed labels back to their names while encoding;
+    pub fn set_label_map<L: Into<Arc<LabelMap>>>(&mut self, labels: L) {
+        self.labels = labels.into();
+    }
+
+    /// Set the max size in bytes of a blob value to be encoded, the larger blob properties are
+    /// skipped, and the larger detached blob values are encoded as `None`; Default is
+    /// [`DEFAULT_MAX_BLOB_SIZE`];
+    pub fn set_max_blob_size(&mut self, max_blob_size: usize) {
+        self.max_blob_size = max_blob_size;
+    }
+
+    pub fn push(&mut self, t: Traverser) -> Result<(), EncodeError> {
         if let Some(e) = t.get_element() {
-            println!("element: {:?}", e);
-            elements_encode.push(element_to_pb(e));
+            trace!("element: {:?}", e);
+            self.elements_encode.push(element_to_pb(e, &self.labels, self.max_blob_size));
         } else if let Some(o) = t.get_object() {
             match o {
-                Object::Primitive(p) => println!("object result {:?}", p),
-                Object::String(s) => println!("object result {:?}", s),
-                Object::Blob(b) => println!("object result {:?}", b),
+                Object::Primitive(_) | Object::String(_) | Object::Blob(_) | Object::Null => {
+                    trace!("value result {:?}", o);
+                    self.values_encode.push(object_to_pb_value(o, self.max_blob_size)?);
+                }
                 Object::UnknownOwned(x) => {
                     if let Some(p) = x.try_downcast_ref::<ResultPath>() {
-                        println!("path: {:?}", p);
-                        paths_encode.push(path_to_pb(p));
+                        trace!("path: {:?}", p);
+                        self.paths_encode.push(path_to_pb(p, &self.labels, self.max_blob_size));
                     } else if let Some(result_prop) = x.try_downcast_ref::<ResultProperty>() {
-                        println!("property: {:?}", result_prop);
-                        properties_encode.push(property_to_pb(result_prop));
+                        trace!("property: {:?}", result_prop);
+                        self.properties_encode
+                            .push(tag_properties_to_pb(result_prop, self.max_blob_size));
                     } else if let Some(result_prop) =
                         x.try_downcast_ref::<ShadeSync<(HashKey<Traverser>, u64)>>()
                     {
-                        println!("group count result {:?}", result_prop);
+                        trace!("group count result {:?}", result_prop);
+                        self.group_counts_encode.push(group_count_to_pb(
+                            &result_prop.inner,
+                            &self.labels,
+                            self.max_blob_size,
+                        ));
                     } else if let Some(result_prop) = x.try_downcast_ref::<ShadeSync<u64>>() {
-                        println!("count result {:?}", result_prop);
+                        trace!("count result {:?}", result_prop);
+                        self.counts_encode.push(result_prop.inner as i64);
+                    } else if let Some(list) = x.try_downcast_ref::<ShadeSync<Vec<Traverser>>>() {
+                        trace!("fold result {:?}", list);
+                        self.collections_encode.push(collection_to_pb(
+                            &list.inner,
+                            &self.labels,
+                            self.max_blob_size,
+                        ));
                     } else {
-                        println!("object result {:?}", x);
+                        return Err(EncodeError::UnknownType(x.deref().type_name()));
                     }
                 }
-                _ => unreachable!(),
+                Object::UnknownRef(x) => return Err(EncodeError::UnknownType((**x).type_name())),
             }
         } else {
-            println!("object result is none!");
+            debug!("object result is none!");
         };
+        self.len += 1;
+        if self.len >= self.batch_size {
+            self.flush();
+        }
+        Ok(())
     }
-    if !elements_encode.is_empty() {
-        let elements = result_pb::GraphElementArray { item: elements_encode };
-        result_pb::Result { inner: Some(result_pb::result::Inner::Elements(elements)) }
-    } else if !paths_encode.is_empty() {
-        let paths = result_pb::PathArray { item: paths_encode };
-        result_pb::Result { inner: Some(result_pb::result::Inner::Paths(paths)) }
-    } else {
-        let properties = result_pb::TagPropertiesArray { item: properties_encode };
-        result_pb::Result { inner: Some(result_pb::result::Inner::TagProperties(properties)) }
+
+    /// Flush the results encoded so far to the sink, even if there is nothing encoded;
+    pub fn flush(&mut self) {
+        let paths_encode = std::mem::replace(&mut self.paths_encode, vec![]);
+        let elements_encode = std::mem::replace(&mut self.elements_encode, vec![]);
+        let properties_encode = std::mem::replace(&mut self.properties_encode, vec![]);
+        let counts_encode = std::mem::replace(&mut self.counts_encode, vec![]);
+        let group_counts_encode = std::mem::replace(&mut self.group_counts_encode, vec![]);
+        let values_encode = std::mem::replace(&mut self.values_encode, vec![]);
+        let collections_encode = std::mem::replace(&mut self.collections_encode, vec![]);
+        let kinds = [
+            !elements_encode.is_empty(),
+            !paths_encode.is_empty(),
+            !properties_encode.is_empty(),
+            !counts_encode.is_empty(),
+            !group_counts_encode.is_empty(),
+            !values_encode.is_empty(),
+            !collections_encode.is_empty(),
+        ];
+        let result = if kinds.iter().filter(|k| **k).count() > 1 {
+            let mixed = result_pb::MixedResult {
+                elements: Some(result_pb::GraphElementArray { item: elements_encode }),
+                paths: Some(result_pb::PathArray { item: paths_encode }),
+                tag_properties: Some(result_pb::TagPropertiesArray { item: properties_encode }),
+                counts: Some(common_pb::I64Array { item: counts_encode }),
+                group_counts: Some(result_pb::GroupCountArray { item: group_counts_encode }),
+                values: Some(result_pb::ValueArray { item: values_encode }),
+                collections: Some(result_pb::CollectionArray { item: collections_encode }),
+            };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Mixed(mixed)) }
+        } else if !elements_encode.is_empty() {
+            let elements = result_pb::GraphElementArray { item: elements_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Elements(elements)) }
+        } else if !paths_encode.is_empty() {
+            let paths = result_pb::PathArray { item: paths_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Paths(paths)) }
+        } else if !counts_encode.is_empty() {
+            let counts = common_pb::I64Array { item: counts_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Counts(counts)) }
+        } else if !group_counts_encode.is_empty() {
+            let group_counts = result_pb::GroupCountArray { item: group_counts_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::GroupCounts(group_counts)) }
+        } else if !values_encode.is_empty() {
+            let values = result_pb::ValueArray { item: values_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Values(values)) }
+        } else if !collections_encode.is_empty() {
+            let collections = result_pb::CollectionArray { item: collections_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::Collections(collections)) }
+        } else {
+            let properties = result_pb::TagPropertiesArray { item: properties_encode };
+            result_pb::Result { inner: Some(result_pb::result::Inner::TagProperties(properties)) }
+        };
+        self.len = 0;
+        self.flushed = true;
+        (self.sink)(result);
+    }
+
+    /// Flush the remaining results. At least one result is flushed to the sink during the
+    /// whole encoding, even if there is no traverser pushed;
+    pub fn finish(mut self) {
+        if self.len > 0 || !self.flushed {
+            self.flush();
+        }
+    }
+}
+
+pub fn result_to_pb(
+    data: Vec<Traverser>, labels: Arc<LabelMap>, max_blob_size: usize,
+) -> Result<result_pb::Result, EncodeError> {
+    let mut result = None;
+    let mut encoder = ResultEncoder::new(usize::MAX, |r| result = Some(r));
+    encoder.set_label_map(labels);
+    encoder.set_max_blob_size(max_blob_size);
+    for t in data {
+        encoder.push(t)?;
+    }
+    encoder.finish();
+    Ok(result.expect("result should be flushed when finish"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::structure::DefaultDetails;
+    use pegasus_server::AnyData;
+    use std::collections::HashMap;
+
+    fn build_vertex() -> Vertex {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), Object::from("marko"));
+        properties.insert("age".to_string(), Object::from(29));
+        let details =
+            DefaultDetails::new_with_prop(1, Label::Str("person".to_string()), properties);
+        Vertex::new(1, Some(Label::Str("person".to_string())), details)
+    }
+
+    fn get_pb_property<'a>(
+        props: &'a [result_pb::Property], key: &str,
+    ) -> Option<&'a common_pb::value::Item> {
+        props
+            .iter()
+            .find(|p| p.key == key)
+            .and_then(|p| p.value.as_ref())
+            .and_then(|v| v.item.as_ref())
+    }
+
+    #[test]
+    fn test_vertex_to_pb() {
+        let v = build_vertex();
+        let v_pb = vertex_to_pb(&v, None, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(v_pb.id, 1);
+        assert_eq!(v_pb.label, "person");
+        assert_eq!(v_pb.properties.len(), 2);
+        assert_eq!(
+            get_pb_property(&v_pb.properties, "name"),
+            Some(&common_pb::value::Item::Str("marko".to_string()))
+        );
+        assert_eq!(
+            get_pb_property(&v_pb.properties, "age"),
+            Some(&common_pb::value::Item::I32(29))
+        );
+    }
+
+    #[test]
+    fn test_vertex_to_pb_with_keys() {
+        let v = build_vertex();
+        let keys = vec!["age".to_string(), "unknown".to_string()];
+        let v_pb = vertex_to_pb(&v, Some(&keys), &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(v_pb.properties.len(), 1);
+        assert_eq!(
+            get_pb_property(&v_pb.properties, "age"),
+            Some(&common_pb::value::Item::I32(29))
+        );
+    }
+
+    #[test]
+    fn test_edge_to_pb() {
+        let details = DefaultDetails::new(1, Label::Str("knows".to_string()));
+        let mut e =
+            Edge::new(1, Some(Label::Str("knows".to_string())), 2, 3, DynDetails::new(details));
+        let e_pb =
+            edge_to_pb(&e, None, &IdentityResolver, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(e_pb.src_label, "");
+        assert_eq!(e_pb.dst_label, "");
+
+        e.set_src_label(Label::Str("person".to_string()));
+        e.set_dst_label(Label::Str("software".to_string()));
+        let e_pb =
+            edge_to_pb(&e, None, &IdentityResolver, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(e_pb.id, 1);
+        assert_eq!(e_pb.label, "knows");
+        assert_eq!(e_pb.src_id, 2);
+        assert_eq!(e_pb.src_label, "person");
+        assert_eq!(e_pb.dst_id, 3);
+        assert_eq!(e_pb.dst_label, "software");
+    }
+
+    #[test]
+    fn test_edge_to_pb_with_properties() {
+        let mut properties = HashMap::new();
+        properties.insert("weight".to_string(), Object::from(0.5));
+        properties.insert("since".to_string(), Object::from(2010));
+        let details = DefaultDetails::new_with_prop(1, Label::Str("knows".to_string()), properties);
+        let e = Edge::new(1, Some(Label::Str("knows".to_string())), 2, 3, DynDetails::new(details));
+        let e_pb =
+            edge_to_pb(&e, None, &IdentityResolver, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(e_pb.properties.len(), 2);
+        assert_eq!(
+            get_pb_property(&e_pb.properties, "weight"),
+            Some(&common_pb::value::Item::F64(0.5))
+        );
+        assert_eq!(
+            get_pb_property(&e_pb.properties, "since"),
+            Some(&common_pb::value::Item::I32(2010))
+        );
+
+        let keys = vec!["since".to_string()];
+        let e_pb = edge_to_pb(
+            &e,
+            Some(&keys),
+            &IdentityResolver,
+            &IdentityResolver,
+            DEFAULT_MAX_BLOB_SIZE,
+        );
+        assert_eq!(e_pb.properties.len(), 1);
+        assert!(get_pb_property(&e_pb.properties, "since").is_some());
+
+        let e2 = Edge::from_pb(&e_pb).unwrap();
+        assert_eq!(e2.details().get_property("since").unwrap().as_i32().unwrap(), 2010);
+
+        let element_pb = element_to_pb(&e.into(), &LabelMap::default(), DEFAULT_MAX_BLOB_SIZE);
+        match element_pb.inner {
+            Some(result_pb::graph_element::Inner::Edge(e_pb)) => {
+                assert_eq!(e_pb.properties.len(), 2)
+            }
+            _ => panic!("expect edge"),
+        }
+    }
+
+    #[test]
+    fn test_vertex_pb_round_trip() {
+        let v = build_vertex();
+        let v_pb = vertex_to_pb(&v, None, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        let v2 = Vertex::from_pb(&v_pb).unwrap();
+        assert_eq!(v2.id, 1);
+        assert_eq!(v2.label, Some(Label::Str("person".to_string())));
+        assert_eq!(v2.property("name"), Some(Object::from("marko")));
+        assert_eq!(v2.property("age"), Some(Object::from(29)));
+        assert_eq!(v2.details().get_all_properties().unwrap().len(), 2);
+        // the properties are encoded in the order of a `HashMap`, so compare them by key
+        let v2_pb = vertex_to_pb(&v2, None, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!((v2_pb.id, &v2_pb.label), (v_pb.id, &v_pb.label));
+        assert_eq!(v2_pb.properties.len(), v_pb.properties.len());
+        for p in v_pb.properties.iter() {
+            assert_eq!(
+                get_pb_property(&v2_pb.properties, &p.key),
+                p.value.as_ref().unwrap().item.as_ref()
+            );
+        }
+
+        let v_pb = result_pb::Vertex { id: 2, label: String::new(), properties: vec![] };
+        let v = Vertex::from_pb(&v_pb).unwrap();
+        assert_eq!(v.label, None);
+    }
+
+    #[test]
+    fn test_edge_pb_round_trip() {
+        let details = DefaultDetails::new(1, Label::Str("knows".to_string()));
+        let mut e =
+            Edge::new(1, Some(Label::Str("knows".to_string())), 2, 3, DynDetails::new(details));
+        e.set_src_label(Label::Str("person".to_string()));
+        let e_pb =
+            edge_to_pb(&e, None, &IdentityResolver, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE);
+        let e2 = Edge::from_pb(&e_pb).unwrap();
+        assert_eq!(e2.id, 1);
+        assert_eq!(e2.label, Some(Label::Str("knows".to_string())));
+        assert_eq!(e2.src_id, 2);
+        assert_eq!(e2.src_label(), Some(&Label::Str("person".to_string())));
+        assert_eq!(e2.dst_id, 3);
+        assert_eq!(e2.dst_label(), None);
+        assert_eq!(
+            edge_to_pb(&e2, None, &IdentityResolver, &IdentityResolver, DEFAULT_MAX_BLOB_SIZE),
+            e_pb
+        );
+    }
+
+    #[test]
+    fn test_vertex_from_pb_with_array_value() {
+        let value = common_pb::Value {
+            item: Some(common_pb::value::Item::I64Array(common_pb::I64Array { item: vec![1] })),
+        };
+        let v_pb = result_pb::Vertex {
+            id: 1,
+            label: "person".to_string(),
+            properties: vec![result_pb::Property { key: "ids".to_string(), value: Some(value) }],
+        };
+        assert!(Vertex::from_pb(&v_pb).is_err());
+    }
+
+    #[test]
+    fn test_path_to_pb() {
+        let v: GraphElement = build_vertex().into();
+        let path = ResultPath::new(vec![
+            PathItem::OnGraph(v.clone()),
+            PathItem::Detached(Object::from("marko")),
+            PathItem::Detached(Object::UnknownOwned(Box::new(v))),
+        ]);
+        let path_pb = path_to_pb(&path, &LabelMap::default(), DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(path_pb.path.len(), 3);
+        match &path_pb.path[0].inner {
+            Some(result_pb::graph_element::Inner::Vertex(v_pb)) => assert_eq!(v_pb.id, 1),
+            _ => panic!("expect vertex in path"),
+        }
+        match &path_pb.path[1].inner {
+            Some(result_pb::graph_element::Inner::Value(value)) => {
+                assert_eq!(value.item, Some(common_pb::value::Item::Str("marko".to_string())))
+            }
+            _ => panic!("expect value in path"),
+        }
+        match &path_pb.path[2].inner {
+            Some(result_pb::graph_element::Inner::Vertex(v_pb)) => assert_eq!(v_pb.id, 1),
+            _ => panic!("expect vertex in path"),
+        }
+    }
+
+    #[test]
+    fn test_count_result_to_pb() {
+        let result = vec![Traverser::with(6u64)];
+        let result_pb = result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap();
+        match result_pb.inner {
+            Some(result_pb::result::Inner::Counts(counts)) => assert_eq!(counts.item, vec![6]),
+            _ => panic!("expect count result"),
+        }
+    }
+
+    #[test]
+    fn test_group_count_result_to_pb() {
+        let v_key = HashKey::new(1, Traverser::new(build_vertex()));
+        let o_key = HashKey::new(2, Traverser::from_object(Object::from("marko")));
+        let result = vec![Traverser::with((v_key, 2u64)), Traverser::with((o_key, 3u64))];
+        let result_pb = result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap();
+        match result_pb.inner {
+            Some(result_pb::result::Inner::GroupCounts(group_counts)) => {
+                assert_eq!(group_counts.item.len(), 2);
+                match group_counts.item[0].key.as_ref().and_then(|k| k.inner.as_ref()) {
+                    Some(result_pb::graph_element::Inner::Vertex(v_pb)) => assert_eq!(v_pb.id, 1),
+                    _ => panic!("expect vertex key"),
+                }
+                assert_eq!(group_counts.item[0].count, 2);
+                match group_counts.item[1].key.as_ref().and_then(|k| k.inner.as_ref()) {
+                    Some(result_pb::graph_element::Inner::Value(value)) => assert_eq!(
+                        value.item,
+                        Some(common_pb::value::Item::Str("marko".to_string()))
+                    ),
+                    _ => panic!("expect value key"),
+                }
+                assert_eq!(group_counts.item[1].count, 3);
+            }
+            _ => panic!("expect group count result"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_result_to_pb() {
+        #[derive(Clone, Debug)]
+        struct Unregistered;
+
+        let result = vec![Traverser::from_object(Object::UnknownOwned(Box::new(Unregistered)))];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE) {
+            Err(EncodeError::UnknownType(name)) => assert!(name.ends_with("Unregistered")),
+            _ => panic!("expect unknown type error"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_ref_result_to_pb() {
+        #[derive(Clone, Debug)]
+        struct Unregistered;
+        static UNREGISTERED: Unregistered = Unregistered;
+
+        let result = vec![Traverser::from_object(Object::UnknownRef(&UNREGISTERED))];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE) {
+            Err(EncodeError::UnknownType(name)) => assert!(name.ends_with("Unregistered")),
+            _ => panic!("expect unknown type error"),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_object_to_pb_value() {
+        match object_to_pb_value(&Object::from(1i8), DEFAULT_MAX_BLOB_SIZE) {
+            Err(EncodeError::UnsupportedValue(RawType::Byte)) => (),
+            _ => panic!("expect unsupported value error"),
+        }
+        match object_to_pb_value(&Object::UnknownOwned(Box::new(1u32)), DEFAULT_MAX_BLOB_SIZE) {
+            Err(e) => assert_eq!(e.to_string(), "can't encode unknown type u32"),
+            _ => panic!("expect unknown type error"),
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    fn point_to_pb(value: &dyn Any) -> Option<common_pb::Value> {
+        let point = value.downcast_ref::<Point>()?;
+        let item = common_pb::value::Item::Str(format!("({}, {})", point.x, point.y));
+        Some(common_pb::Value { item: Some(item) })
+    }
+
+    #[test]
+    fn test_registered_object_to_pb_value() {
+        static ORIGIN: Point = Point { x: 0, y: 0 };
+        register_value_encoder::<Point>(point_to_pb);
+
+        let owned = Object::UnknownOwned(Box::new(Point { x: 1, y: 2 }));
+        let value = object_to_pb_value(&owned, DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(value.item, Some(common_pb::value::Item::Str("(1, 2)".to_string())));
+        let value =
+            object_to_pb_value(&Object::UnknownRef(&ORIGIN), DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(value.item, Some(common_pb::value::Item::Str("(0, 0)".to_string())));
+
+        let property = property_to_pb("location", &owned, DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(property.key, "location");
+    }
+
+    #[test]
+    fn test_null_to_pb_value() {
+        let value = object_to_pb_value(&Object::Null, DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(value.item, Some(common_pb::value::Item::None(common_pb::None {})));
+        let property = property_to_pb("age", &Object::Null, DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(property.value, Some(value));
+    }
+
+    #[test]
+    fn test_boolean_to_pb_value() {
+        for v in vec![true, false] {
+            let value = object_to_pb_value(&Object::from(v), DEFAULT_MAX_BLOB_SIZE).unwrap();
+            assert_eq!(value.item, Some(common_pb::value::Item::Boolean(v)));
+            let decoded: Option<Object> = (&value).into();
+            assert_eq!(decoded.unwrap().as_bool().unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_date_time_to_pb_value() {
+        let date_time = Object::Primitive(Primitives::DateTime(1266161530447));
+        let value = object_to_pb_value(&date_time, DEFAULT_MAX_BLOB_SIZE).unwrap();
+        assert_eq!(value.item, Some(common_pb::value::Item::DateTime(1266161530447)));
+        // the type is kept after decoding, rather than a long;
+        let decoded: Option<Object> = (&value).into();
+        assert!(matches!(decoded, Some(Object::Primitive(Primitives::DateTime(1266161530447)))));
+    }
+
+    #[test]
+    fn test_double_to_pb_value() {
+        // neither can be represented by a single precision float;
+        for v in vec![std::f64::consts::PI, 1.0 + f64::EPSILON, f64::MAX] {
+            let value = object_to_pb_value(&Object::from(v), DEFAULT_MAX_BLOB_SIZE).unwrap();
+            assert_eq!(value.item, Some(common_pb::value::Item::F64(v)));
+            let decoded: Option<Object> = (&value).into();
+            assert_eq!(decoded.unwrap().as_f64().unwrap().to_bits(), v.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_blob_size_guard() {
+        let blob = Object::from(vec![0u8; 8]);
+        match object_to_pb_value(&blob, 8) {
+            Ok(value) => assert_eq!(value.item, Some(common_pb::value::Item::Blob(vec![0u8; 8]))),
+            Err(e) => panic!("unexpected error {}", e),
+        }
+        match object_to_pb_value(&blob, 7) {
+            Err(EncodeError::BlobTooLarge(8, 7)) => (),
+            _ => panic!("expect blob too large error"),
+        }
+        assert!(property_to_pb("content", &blob, 7).is_none());
+        assert!(property_to_pb("content", &blob, 8).is_some());
+    }
+
+    #[test]
+    fn test_mixed_result_to_pb() {
+        let path = ResultPath::new(vec![PathItem::OnGraph(build_vertex().into())]);
+        let result = vec![
+            Traverser::new(build_vertex()),
+            Traverser::from_object(Object::UnknownOwned(Box::new(path))),
+        ];
+        let result_pb = result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap();
+        match result_pb.inner {
+            Some(result_pb::result::Inner::Mixed(mixed)) => {
+                assert_eq!(mixed.elements.map(|e| e.item.len()), Some(1));
+                assert_eq!(mixed.paths.map(|p| p.item.len()), Some(1));
+                assert_eq!(mixed.tag_properties.map(|p| p.item.len()), Some(0));
+            }
+            _ => panic!("expect mixed result"),
+        }
+    }
+
+    #[test]
+    fn test_fold_result_to_pb() {
+        // the list is emitted by fold() as `AnyData::with(Vec<Traverser>)`
+        let list = vec![Traverser::new(build_vertex()), Traverser::from_object(Object::from(29))];
+        let result = vec![Traverser::with(list), Traverser::with(Vec::<Traverser>::new())];
+        let result_pb = result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap();
+        match result_pb.inner {
+            Some(result_pb::result::Inner::Collections(collections)) => {
+                assert_eq!(collections.item.len(), 2);
+                let items = &collections.item[0].item;
+                assert_eq!(items.len(), 2);
+                match &items[0].inner {
+                    Some(result_pb::graph_element::Inner::Vertex(v)) => assert_eq!(v.id, 1),
+                    _ => panic!("expect vertex"),
+                }
+                match &items[1].inner {
+                    Some(result_pb::graph_element::Inner::Value(v)) => {
+                        assert_eq!(v.item, Some(common_pb::value::Item::I32(29)))
+                    }
+                    _ => panic!("expect value"),
+                }
+                assert!(collections.item[1].item.is_empty());
+            }
+            _ => panic!("expect collection result"),
+        }
+
+        let result = vec![Traverser::with(6u64), Traverser::with(vec![Traverser::with(6u64)])];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap().inner {
+            Some(result_pb::result::Inner::Mixed(mixed)) => {
+                assert_eq!(mixed.counts.map(|c| c.item), Some(vec![6]));
+                assert_eq!(mixed.collections.map(|c| c.item.len()), Some(1));
+            }
+            _ => panic!("expect mixed result"),
+        }
+    }
+
+    #[test]
+    fn test_id_result_to_pb() {
+        let result = vec![
+            Traverser::from_object(Object::from(1i64)),
+            Traverser::from_object(Object::from(2i64)),
+        ];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap().inner {
+            Some(result_pb::result::Inner::Values(values)) => {
+                let items = values.item.into_iter().map(|v| v.item).collect::<Vec<_>>();
+                assert_eq!(
+                    items,
+                    vec![
+                        Some(common_pb::value::Item::I64(1)),
+                        Some(common_pb::value::Item::I64(2))
+                    ]
+                );
+            }
+            _ => panic!("expect value result"),
+        }
+    }
+
+    #[test]
+    fn test_values_result_to_pb() {
+        let result = vec![
+            Traverser::from_object(Object::from("marko")),
+            Traverser::from_object(Object::Null),
+        ];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap().inner {
+            Some(result_pb::result::Inner::Values(values)) => {
+                let items = values.item.into_iter().map(|v| v.item).collect::<Vec<_>>();
+                assert_eq!(
+                    items,
+                    vec![
+                        Some(common_pb::value::Item::Str("marko".to_string())),
+                        Some(common_pb::value::Item::None(common_pb::None {})),
+                    ]
+                );
+            }
+            _ => panic!("expect value result"),
+        }
+
+        // values mixed with elements are encoded in a separate branch of the mixed result
+        let result =
+            vec![Traverser::new(build_vertex()), Traverser::from_object(Object::from("marko"))];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap().inner {
+            Some(result_pb::result::Inner::Mixed(mixed)) => {
+                assert_eq!(mixed.elements.map(|e| e.item.len()), Some(1));
+                assert_eq!(mixed.values.map(|v| v.item.len()), Some(1));
+            }
+            _ => panic!("expect mixed result"),
+        }
+
+        let result = vec![Traverser::from_object(Object::from(1i8))];
+        match result_to_pb(result, Arc::default(), DEFAULT_MAX_BLOB_SIZE) {
+            Err(EncodeError::UnsupportedValue(RawType::Byte)) => (),
+            _ => panic!("expect unsupported value error"),
+        }
+    }
+
+    #[test]
+    fn test_result_encoder() {
+        let data: Vec<Traverser> = (0..5).map(|_| Traverser::new(build_vertex())).collect();
+        let mut chunks = vec![];
+        let mut encoder = ResultEncoder::new(2, |r| chunks.push(r));
+        for t in data.clone() {
+            encoder.push(t).unwrap();
+        }
+        encoder.finish();
+
+        let mut elements = vec![];
+        let mut chunk_sizes = vec![];
+        for chunk in chunks {
+            match chunk.inner {
+                Some(result_pb::result::Inner::Elements(e)) => {
+                    chunk_sizes.push(e.item.len());
+                    elements.extend(e.item);
+                }
+                _ => panic!("expect element result"),
+            }
+        }
+        assert_eq!(chunk_sizes, vec![2, 2, 1]);
+        match result_to_pb(data, Arc::default(), DEFAULT_MAX_BLOB_SIZE).unwrap().inner {
+            Some(result_pb::result::Inner::Elements(e)) => assert_eq!(e.item, elements),
+            _ => panic!("expect element result"),
+        }
+    }
+
+    #[test]
+    fn test_label_id_to_pb() {
+        let mut labels = LabelMap::default();
+        labels.add_vertex_label(3, "person");
+        labels.add_edge_label(3, "knows");
+        let details = DefaultDetails::new(1, Label::Id(3));
+        let v = Vertex::new(1, Some(Label::Id(3)), details);
+        assert_eq!(
+            vertex_to_pb(&v, None, &labels.vertex_labels, DEFAULT_MAX_BLOB_SIZE).label,
+            "person"
+        );
+
+        let details = DefaultDetails::new(2, Label::Id(3));
+        let mut e = Edge::new(2, Some(Label::Id(3)), 1, 4, DynDetails::new(details));
+        e.set_src_label(Label::Id(3));
+        e.set_dst_label(Label::Id(4));
+        let e_pb =
+            edge_to_pb(&e, None, &labels.edge_labels, &labels.vertex_labels, DEFAULT_MAX_BLOB_SIZE);
+        assert_eq!(e_pb.label, "knows");
+        assert_eq!(e_pb.src_label, "person");
+        // fall back to the numeric form if the label id is not found
+        assert_eq!(e_pb.dst_label, "4");
+    }
+
+    #[test]
+    fn test_identity_resolver() {
+        let resolver = IdentityResolver;
+        assert_eq!(resolver.resolve(&Label::Str("person".to_string())), "person");
+        assert_eq!(resolver.resolve(&Label::Id(3)), "3");
+    }
+
+    #[test]
+    fn test_dict_resolver() {
+        let mut names = HashMap::new();
+        names.insert(3, "person".to_string());
+        let resolver = DictResolver::new(names);
+        assert_eq!(resolver.resolve(&Label::Str("software".to_string())), "software");
+        assert_eq!(resolver.resolve(&Label::Id(3)), "person");
+        assert_eq!(resolver.resolve(&Label::Id(4)), "4");
     }
 }
@@ -21,8 +21,9 @@ use crate::{register_graph, DynResult, GraphProxy, Object, ID};
 use graph_store::config::{JsonConf, DIR_GRAPH_SCHEMA, FILE_SCHEMA};
 use graph_store::ldbc::LDBCVertexParser;
 use graph_store::prelude::{
-    DefaultId, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId, LDBCGraphSchema,
-    LabelId, LargeGraphDB, LocalEdge, LocalVertex, MutableGraphDB, Row, INVALID_LABEL_ID,
+    DefaultId, GDBError, GDBResult, GlobalStoreTrait, GlobalStoreUpdate, GraphDBConfig, InternalId,
+    LDBCGraphSchema, LabelId, LargeGraphDB, LocalEdge, LocalVertex, MemGraphStore, Row, Schema,
+    INVALID_LABEL_ID,
 };
 use pegasus::api::function::DynIter;
 use serde_json::{json, Value};
@@ -33,71 +34,47 @@ use std::sync::Arc;
 
 lazy_static! {
     pub static ref DATA_PATH: String = configure_with_default!(String, "DATA_PATH", "".to_string());
-    pub static ref GRAPH: LargeGraphDB<DefaultId, InternalId> = _init_graph();
+    pub static ref GRAPH: Arc<DemoStore> = _init_graph();
+    static ref LARGE_GRAPH: Arc<LargeGraphDB<DefaultId, InternalId>> = _open_large_graph();
     static ref GRAPH_PROXY: Arc<DemoGraph> = initialize();
 }
 
+/// The store of the demo graph, which is either the modern graph in memory, or the graph read
+/// from `DATA_PATH`, or a snapshot of the latter taken for a job
+pub type DemoStore = dyn GlobalStoreTrait<DefaultId, InternalId> + Send + Sync;
+
 pub struct DemoGraph {
-    store: &'static LargeGraphDB<DefaultId, InternalId>,
+    store: Arc<DemoStore>,
+    /// The graph read from `DATA_PATH` to take the snapshots of, which is `None` for the modern
+    /// graph, which is never written once built, and for a snapshot itself
+    db: Option<&'static LargeGraphDB<DefaultId, InternalId>>,
 }
 
 fn initialize() -> Arc<DemoGraph> {
-    lazy_static::initialize(&GRAPH);
-    Arc::new(DemoGraph { store: &GRAPH })
+    let db = if DATA_PATH.is_empty() { None } else { Some(LARGE_GRAPH.as_ref()) };
+    Arc::new(DemoGraph { store: GRAPH.clone(), db })
 }
 
-fn _init_graph() -> LargeGraphDB<DefaultId, InternalId> {
+fn _init_graph() -> Arc<DemoStore> {
     if DATA_PATH.is_empty() {
         info!("Create and use the modern graph for demo.");
         _init_modern_graph()
     } else {
         info!("Read the graph data from {:?} for demo.", *DATA_PATH);
-        GraphDBConfig::default()
-            .root_dir(&(*DATA_PATH))
-            .schema_file(&(DATA_PATH.as_ref() as &Path).join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA))
-            .open()
-            .expect("Open graph error")
+        LARGE_GRAPH.clone()
     }
 }
 
-fn _init_modern_graph() -> LargeGraphDB<DefaultId, InternalId> {
-    let mut mut_graph: MutableGraphDB<DefaultId, InternalId> = GraphDBConfig::default().new();
-
-    let v1: DefaultId = LDBCVertexParser::to_global_id(1, 0);
-    let v2: DefaultId = LDBCVertexParser::to_global_id(2, 0);
-    let v3: DefaultId = LDBCVertexParser::to_global_id(3, 1);
-    let v4: DefaultId = LDBCVertexParser::to_global_id(4, 0);
-    let v5: DefaultId = LDBCVertexParser::to_global_id(5, 1);
-    let v6: DefaultId = LDBCVertexParser::to_global_id(6, 0);
-
-    mut_graph.add_vertex(v1, [0, INVALID_LABEL_ID]);
-    mut_graph.add_vertex(v2, [0, INVALID_LABEL_ID]);
-    mut_graph.add_vertex(v3, [1, INVALID_LABEL_ID]);
-    mut_graph.add_vertex(v4, [0, INVALID_LABEL_ID]);
-    mut_graph.add_vertex(v5, [1, INVALID_LABEL_ID]);
-    mut_graph.add_vertex(v6, [0, INVALID_LABEL_ID]);
-
-    mut_graph.add_edge(v1, v2, 0);
-    mut_graph.add_edge(v1, v3, 1);
-    mut_graph.add_edge(v1, v4, 0);
-    mut_graph.add_edge(v4, v3, 1);
-    mut_graph.add_edge(v4, v5, 1);
-    mut_graph.add_edge(v6, v3, 1);
-
-    let prop1 = Row::from(vec![json!(1), json!("marko"), json!(29)]);
-    let prop2 = Row::from(vec![json!(2), json!("vadas"), json!(27)]);
-    let prop3 = Row::from(vec![json!(3), json!("lop"), json!("java")]);
-    let prop4 = Row::from(vec![json!(4), json!("josh"), json!(32)]);
-    let prop5 = Row::from(vec![json!(5), json!("ripple"), json!("java")]);
-    let prop6 = Row::from(vec![json!(6), json!("peter"), json!(35)]);
-
-    mut_graph.add_or_update_vertex_properties(v1, prop1).unwrap();
-    mut_graph.add_or_update_vertex_properties(v2, prop2).unwrap();
-    mut_graph.add_or_update_vertex_properties(v3, prop3).unwrap();
-    mut_graph.add_or_update_vertex_properties(v4, prop4).unwrap();
-    mut_graph.add_or_update_vertex_properties(v5, prop5).unwrap();
-    mut_graph.add_or_update_vertex_properties(v6, prop6).unwrap();
+fn _open_large_graph() -> Arc<LargeGraphDB<DefaultId, InternalId>> {
+    let graph = GraphDBConfig::default()
+        .root_dir(&(*DATA_PATH))
+        .schema_file(&(DATA_PATH.as_ref() as &Path).join(DIR_GRAPH_SCHEMA).join(FILE_SCHEMA))
+        .open()
+        .expect("Open graph error");
+    Arc::new(graph)
+}
 
+fn _init_modern_graph() -> Arc<DemoStore> {
     let modern_graph_schema = r#"
     {
         "vertex_type_map": {
@@ -165,7 +142,44 @@ fn _init_modern_graph() -> LargeGraphDB<DefaultId, InternalId> {
     let schema =
         LDBCGraphSchema::from_json(modern_graph_schema.to_string()).expect("Parse schema error!");
 
-    mut_graph.into_graph(schema)
+    let mut graph: MemGraphStore<DefaultId, InternalId> = MemGraphStore::new(0, schema);
+
+    let v1: DefaultId = LDBCVertexParser::to_global_id(1, 0);
+    let v2: DefaultId = LDBCVertexParser::to_global_id(2, 0);
+    let v3: DefaultId = LDBCVertexParser::to_global_id(3, 1);
+    let v4: DefaultId = LDBCVertexParser::to_global_id(4, 0);
+    let v5: DefaultId = LDBCVertexParser::to_global_id(5, 1);
+    let v6: DefaultId = LDBCVertexParser::to_global_id(6, 0);
+
+    graph.add_vertex(v1, [0, INVALID_LABEL_ID]);
+    graph.add_vertex(v2, [0, INVALID_LABEL_ID]);
+    graph.add_vertex(v3, [1, INVALID_LABEL_ID]);
+    graph.add_vertex(v4, [0, INVALID_LABEL_ID]);
+    graph.add_vertex(v5, [1, INVALID_LABEL_ID]);
+    graph.add_vertex(v6, [0, INVALID_LABEL_ID]);
+
+    graph.add_edge(v1, v2, 0);
+    graph.add_edge(v1, v3, 1);
+    graph.add_edge(v1, v4, 0);
+    graph.add_edge(v4, v3, 1);
+    graph.add_edge(v4, v5, 1);
+    graph.add_edge(v6, v3, 1);
+
+    let prop1 = Row::from(vec![json!(1), json!("marko"), json!(29)]);
+    let prop2 = Row::from(vec![json!(2), json!("vadas"), json!(27)]);
+    let prop3 = Row::from(vec![json!(3), json!("lop"), json!("java")]);
+    let prop4 = Row::from(vec![json!(4), json!("josh"), json!(32)]);
+    let prop5 = Row::from(vec![json!(5), json!("ripple"), json!("java")]);
+    let prop6 = Row::from(vec![json!(6), json!("peter"), json!(35)]);
+
+    graph.add_or_update_vertex_properties(v1, prop1).unwrap();
+    graph.add_or_update_vertex_properties(v2, prop2).unwrap();
+    graph.add_or_update_vertex_properties(v3, prop3).unwrap();
+    graph.add_or_update_vertex_properties(v4, prop4).unwrap();
+    graph.add_or_update_vertex_properties(v5, prop5).unwrap();
+    graph.add_or_update_vertex_properties(v6, prop6).unwrap();
+
+    Arc::new(graph)
 }
 
 macro_rules! limit_n {
@@ -197,11 +211,12 @@ impl GraphProxy for DemoGraph {
         &self, params: &QueryParams<Vertex>,
     ) -> DynResult<Box<dyn Iterator<Item = Vertex> + Send>> {
         let label_ids = encode_storage_vertex_label(&params.labels);
-        let store = self.store;
-        let result = self.store.get_all_vertices(label_ids.as_ref()).map(move |v| {
+        let store = self.store.clone();
+        let vertices = SharedRead::new(&self.store, |s| s.get_all_vertices(label_ids.as_ref()));
+        let result = vertices.map(move |v| {
             // TODO: Only process label[0] for now
             // TODO: change to  to_runtime_vertex_with_property
-            to_runtime_vertex(v, store)
+            to_runtime_vertex(v, store.clone())
             //  to_runtime_vertex_with_property(v, params.props.as_ref())
         });
 
@@ -240,16 +255,18 @@ impl GraphProxy for DemoGraph {
         let edge_label_ids = encode_storage_edge_label(params.labels.as_ref());
         let filter = params.filter.clone();
         let limit = params.limit.clone();
-        let graph = self.store;
+        let graph = self.store.clone();
 
         let stmt = from_fn(move |v: ID| {
-            let iter = match direction {
-                Direction::Out => graph.get_out_vertices(v as DefaultId, edge_label_ids.as_ref()),
-                Direction::In => graph.get_in_vertices(v as DefaultId, edge_label_ids.as_ref()),
-                Direction::Both => graph.get_both_vertices(v as DefaultId, edge_label_ids.as_ref()),
-            }
+            let labels = edge_label_ids.as_ref();
+            let store = graph.clone();
+            let iter = SharedRead::new(&graph, |g| match direction {
+                Direction::Out => g.get_out_vertices(v as DefaultId, labels),
+                Direction::In => g.get_in_vertices(v as DefaultId, labels),
+                Direction::Both => g.get_both_vertices(v as DefaultId, labels),
+            })
             // TODO: change to to_runtime_vertex_with_property
-            .map(move |v| to_runtime_vertex(v, graph));
+            .map(move |v| to_runtime_vertex(v, store.clone()));
             Ok(filter_limit_ok!(iter, filter, limit))
         });
         Ok(stmt)
@@ -261,18 +278,27 @@ impl GraphProxy for DemoGraph {
         let edge_label_ids = encode_storage_edge_label(&params.labels);
         let filter = params.filter.clone();
         let limit = params.limit.clone();
-        let graph = self.store;
+        let graph = self.store.clone();
         let stmt = from_fn(move |v: ID| {
-            let iter = match direction {
-                Direction::Out => graph.get_out_edges(v as DefaultId, edge_label_ids.as_ref()),
-                Direction::In => graph.get_in_edges(v as DefaultId, edge_label_ids.as_ref()),
-                Direction::Both => graph.get_both_edges(v as DefaultId, edge_label_ids.as_ref()),
-            }
-            .map(move |e| to_runtime_edge(e, graph));
+            let labels = edge_label_ids.as_ref();
+            let iter = SharedRead::new(&graph, |g| match direction {
+                Direction::Out => g.get_out_edges(v as DefaultId, labels),
+                Direction::In => g.get_in_edges(v as DefaultId, labels),
+                Direction::Both => g.get_both_edges(v as DefaultId, labels),
+            })
+            .map(|e| to_runtime_edge(e));
             Ok(filter_limit_ok!(iter, filter, limit))
         });
         Ok(stmt)
     }
+
+    fn snapshot(&self) -> Arc<dyn GraphProxy> {
+        let store = match self.db {
+            Some(db) => Arc::new(db.snapshot()) as Arc<DemoStore>,
+            None => self.store.clone(),
+        };
+        Arc::new(DemoGraph { store, db: None })
+    }
 }
 
 #[allow(dead_code)]
@@ -281,10 +307,42 @@ pub fn create_demo_graph() {
     register_graph(GRAPH_PROXY.clone());
 }
 
+/// Get the schema of the demo graph, e.g., to resolve the id-typed labels of the results;
+#[allow(dead_code)]
+pub fn get_demo_schema() -> Arc<dyn Schema> {
+    GRAPH.get_schema()
+}
+
+/// Borrow the store shared by `Arc` for `'static`, which is only sound if the returned reference,
+/// and the reads borrowing it, are dropped before the `Arc` held alongside them, see `SharedRead`;
+fn borrow_static(store: &Arc<DemoStore>) -> &'static DemoStore {
+    unsafe { &*(store.as_ref() as *const DemoStore) }
+}
+
+/// A read of a store shared by `Arc`, e.g. a snapshot held by a job, which borrows the store and
+/// holds it alongside to keep it alive for as long as the read;
+struct SharedRead<R> {
+    read: R,
+    // dropped after `read` in the order of declaration
+    _store: Arc<DemoStore>,
+}
+
+impl<R> SharedRead<R> {
+    fn new<F: FnOnce(&'static DemoStore) -> R>(store: &Arc<DemoStore>, read: F) -> Self {
+        SharedRead { read: read(borrow_static(store)), _store: store.clone() }
+    }
+}
+
+impl<R: Iterator> Iterator for SharedRead<R> {
+    type Item = R::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read.next()
+    }
+}
+
 #[inline]
-fn to_runtime_vertex(
-    v: LocalVertex<DefaultId>, store: &'static LargeGraphDB<DefaultId, InternalId>,
-) -> Vertex {
+fn to_runtime_vertex(v: LocalVertex<DefaultId>, store: Arc<DemoStore>) -> Vertex {
     // For vertices, we query properties via vid
     let details = LazyVertexDetails::new(v.get_id(), store);
     let id = encode_runtime_v_id(&v);
@@ -323,19 +381,11 @@ fn to_runtime_vertex_with_property(
 }
 
 #[inline]
-fn to_runtime_edge(
-    e: LocalEdge<DefaultId, InternalId>, _store: &'static LargeGraphDB<DefaultId, InternalId>,
-) -> Edge {
+fn to_runtime_edge(e: LocalEdge<DefaultId, InternalId>) -> Edge {
     // TODO: For edges, we clone all properties by default for now. But we'd better get properties on demand
     let id = encode_runtime_e_id(&e);
     let label = encode_runtime_e_label(&e);
-    let mut properties = HashMap::new();
-    if let Some(prop_vals) = e.clone_all_properties() {
-        let prop_val_map = prop_vals.as_object().expect("all properties should be stored in a map");
-        for (prop, val) in prop_val_map {
-            properties.insert(prop.clone(), Object::from(val));
-        }
-    }
+    let properties = to_runtime_properties(e.clone_all_properties());
     Edge::new(
         id,
         label.clone(),
@@ -345,24 +395,34 @@ fn to_runtime_edge(
     )
 }
 
+/// Convert the properties of a vertex or an edge, where a malformed row, which is not a map,
+/// is taken as no property;
+fn to_runtime_properties(prop_vals: Option<Value>) -> HashMap<String, Object> {
+    let mut properties = HashMap::new();
+    if let Some(prop_val_map) = prop_vals.as_ref().and_then(|p| p.as_object()) {
+        for (prop, val) in prop_val_map {
+            properties.insert(prop.clone(), Object::from(val));
+        }
+    }
+    properties
+}
+
 #[allow(dead_code)]
 struct LazyVertexDetails {
     pub id: DefaultId,
     inner: AtomicPtr<LocalVertex<'static, DefaultId>>,
-    store: &'static LargeGraphDB<DefaultId, InternalId>,
+    store: Arc<DemoStore>,
 }
 
 impl LazyVertexDetails {
-    pub fn new(id: DefaultId, store: &'static LargeGraphDB<DefaultId, InternalId>) -> Self {
+    pub fn new(id: DefaultId, store: Arc<DemoStore>) -> Self {
         LazyVertexDetails { id, inner: AtomicPtr::default(), store }
     }
-}
 
-impl Details for LazyVertexDetails {
-    fn get_property(&self, key: &str) -> Option<BorrowObject> {
+    fn get_vertex_ptr(&self) -> Option<*mut LocalVertex<'static, DefaultId>> {
         let mut ptr = self.inner.load(Ordering::SeqCst);
         if ptr.is_null() {
-            if let Some(v) = self.store.get_vertex(self.id) {
+            if let Some(v) = borrow_static(&self.store).get_vertex(self.id) {
                 let v = Box::new(v);
                 let new_ptr = Box::into_raw(v);
                 let swapped = self.inner.swap(new_ptr, Ordering::SeqCst);
@@ -378,7 +438,13 @@ impl Details for LazyVertexDetails {
                 return None;
             }
         }
+        Some(ptr)
+    }
+}
 
+impl Details for LazyVertexDetails {
+    fn get_property(&self, key: &str) -> Option<BorrowObject> {
+        let ptr = self.get_vertex_ptr()?;
         unsafe { (*ptr).get_property(key) }.and_then(|v| match v {
             Value::Null => None,
             Value::Bool(b) => Some((*b).into()),
@@ -399,6 +465,27 @@ impl Details for LazyVertexDetails {
         })
     }
 
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+        self.read_all_properties().ok()
+    }
+
+    fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+        let ptr = self.inner.load(Ordering::SeqCst);
+        let prop_vals = if ptr.is_null() {
+            // read from the store rather than `get_vertex()`, to tell a read error from absence
+            let v = self
+                .store
+                .get_vertices(&[self.id])?
+                .pop()
+                .flatten()
+                .ok_or(GDBError::VertexNotFoundError)?;
+            v.clone_all_properties()
+        } else {
+            unsafe { (*ptr).clone_all_properties() }
+        };
+        Ok(to_runtime_properties(prop_vals))
+    }
+
     fn get_id(&self) -> u128 {
         unreachable!()
     }
@@ -421,7 +508,17 @@ impl Drop for LazyVertexDetails {
 
 #[allow(dead_code)]
 struct LazyEdgeDetails {
-    store: &'static LargeGraphDB<DefaultId, InternalId>,
+    src: DefaultId,
+    dst: DefaultId,
+    label: LabelId,
+    store: Arc<DemoStore>,
+}
+
+#[allow(dead_code)]
+impl LazyEdgeDetails {
+    pub fn new(src: DefaultId, dst: DefaultId, label: LabelId, store: Arc<DemoStore>) -> Self {
+        LazyEdgeDetails { src, dst, label, store }
+    }
 }
 
 impl Details for LazyEdgeDetails {
@@ -429,6 +526,20 @@ impl Details for LazyEdgeDetails {
         unimplemented!()
     }
 
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+        self.read_all_properties().ok()
+    }
+
+    fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+        let e = self
+            .store
+            .get_edges_between(self.src, self.dst, Some(&vec![self.label]))?
+            .into_iter()
+            .next()
+            .ok_or(GDBError::EdgeNotFoundError)?;
+        Ok(to_runtime_properties(e.clone_all_properties()))
+    }
+
     fn get_id(&self) -> u128 {
         unimplemented!()
     }
@@ -498,9 +609,11 @@ fn encode_storage_edge_label(labels: &Vec<Label>) -> Option<Vec<LabelId>> {
 
 #[cfg(test)]
 mod tests {
-    use super::GRAPH;
+    use super::{LazyEdgeDetails, LazyVertexDetails, GRAPH};
+    use crate::structure::{Details, Element, Label, Vertex};
+    use crate::Object;
     use graph_store::ldbc::LDBCVertexParser;
-    use graph_store::prelude::{DefaultId, GlobalStoreTrait};
+    use graph_store::prelude::{DefaultId, GDBError};
 
     #[test]
     fn it_works() {
@@ -512,4 +625,27 @@ mod tests {
         let out: Vec<DefaultId> = out_iter.map(|v| v.get_id()).collect();
         assert_eq!(out, vec![v4, v2]);
     }
+
+    #[test]
+    fn test_lazy_details_read_all() {
+        let v1: DefaultId = LDBCVertexParser::to_global_id(1, 0);
+        let v2: DefaultId = LDBCVertexParser::to_global_id(2, 0);
+        let v3: DefaultId = LDBCVertexParser::to_global_id(3, 1);
+
+        let details = LazyVertexDetails::new(v1, GRAPH.clone());
+        let properties = details.read_all_properties().expect("should be read");
+        assert_eq!(properties.get("name"), Some(&Object::from("marko")));
+        let detached = Vertex::new(v1 as u128, Some(Label::Id(0)), details)
+            .detach()
+            .expect("should be detached");
+        assert_eq!(detached.property("name"), Some(Object::from("marko")));
+
+        let absent = LazyVertexDetails::new(LDBCVertexParser::to_global_id(100, 0), GRAPH.clone());
+        assert_eq!(absent.read_all_properties().err(), Some(GDBError::VertexNotFoundError));
+
+        let details = LazyEdgeDetails::new(v1, v2, 0, GRAPH.clone());
+        assert!(details.read_all_properties().is_ok());
+        let absent = LazyEdgeDetails::new(v1, v3, 0, GRAPH.clone());
+        assert_eq!(absent.read_all_properties().err(), Some(GDBError::EdgeNotFoundError));
+    }
 }
@@ -13,9 +13,16 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::structure::element::{Element, Label, ID};
+use crate::generated::protobuf as result_pb;
+use crate::structure::element::{
+    detach_details, label_from_pb, missing_field, properties_from_pb, Element, Label,
+    PropertiesPreview, ID,
+};
 use crate::structure::property::DynDetails;
-use crate::structure::Details;
+use crate::structure::{DefaultDetails, Details};
+use graph_store::prelude::{GDBError, GDBResult};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone)]
 pub struct Edge {
@@ -46,6 +53,33 @@ impl Element for Edge {
     }
 }
 
+impl Debug for Edge {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Edge")
+            .field("id", &self.id)
+            .field("label", self.label())
+            .field("src_id", &self.src_id)
+            .field("dst_id", &self.dst_id)
+            .field("properties", &PropertiesPreview(&self.properties))
+            .finish()
+    }
+}
+
+/// Edges are compared by `id` only, regardless of the labels and properties;
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Edge {}
+
+impl Hash for Edge {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
 impl Edge {
     pub fn new(
         id: u128, label: Option<Label>, src: u128, dst: u128, properties: DynDetails,
@@ -60,62 +94,235 @@ impl Edge {
     pub fn set_dst_label(&mut self, label: Label) {
         self.dst_label = Some(label);
     }
+
+    /// Decode the edge from pb, the labels are decoded as `Label::Str`, or `None` if empty;
+    pub fn from_pb(pb: &result_pb::Edge) -> GDBResult<Edge> {
+        let id = pb.id as ID;
+        let label = label_from_pb(&pb.label);
+        let properties = properties_from_pb(&pb.properties)?;
+        let details_label = label.clone().unwrap_or_else(|| Label::Str(String::new()));
+        let mut builder = EdgeBuilder::new();
+        builder.set_id(id).set_src_id(pb.src_id as ID).set_dst_id(pb.dst_id as ID).set_properties(
+            DynDetails::new(DefaultDetails::new_with_prop(id, details_label, properties)),
+        );
+        if let Some(label) = label {
+            builder.set_label(label);
+        }
+        if let Some(src_label) = label_from_pb(&pb.src_label) {
+            builder.set_src_label(src_label);
+        }
+        if let Some(dst_label) = label_from_pb(&pb.dst_label) {
+            builder.set_dst_label(dst_label);
+        }
+        builder.build()
+    }
+
+    /// Clone the edge with all its properties read into owned details, which no longer refer to
+    /// the store; Return the error of reading the properties, e.g. `EdgeNotFoundError` if the
+    /// edge is no longer found in the store, or the error of the store itself;
+    pub fn detach(&self) -> GDBResult<Edge> {
+        let properties = detach_details(self.id, &self.label, &self.properties)?;
+        Ok(Edge { properties, ..self.clone() })
+    }
+
+    pub fn src_label(&self) -> Option<&Label> {
+        self.src_label.as_ref()
+    }
+
+    pub fn dst_label(&self) -> Option<&Label> {
+        self.dst_label.as_ref()
+    }
+
+    /// Get the reversed edge, with source and destination swapped, which shares the same
+    /// id, label and properties with this edge;
+    pub fn reverse(&self) -> Edge {
+        Edge {
+            id: self.id,
+            src_id: self.dst_id,
+            dst_id: self.src_id,
+            label: self.label.clone(),
+            src_label: self.dst_label.clone(),
+            dst_label: self.src_label.clone(),
+            properties: self.properties.clone(),
+        }
+    }
 }
 
-// #[derive(Default)]
-// pub struct EdgeBuilder {
-//     id          : Option<u128>,
-//     label       : Option<String>,
-//     src_id      : Option<u128>,
-//     src_label   : Option<String>,
-//     dst_id      : Option<u128>,
-//     dst_label   : Option<String>,
-//     properties  : Option<DynProperties>
-// }
-//
-// impl EdgeBuilder {
-//     pub fn new() -> Self {
-//         EdgeBuilder::default()
-//     }
-//
-//     pub fn set_id(&mut self, id: u128) -> &mut Self {
-//         self.id = Some(id);
-//         self
-//     }
-//
-//     pub fn set_label(&mut self, label: String) -> &mut Self {
-//         self.label = Some(label);
-//         self
-//     }
-//
-//     pub fn set_src_id(&mut self, id: u128) -> &mut Self {
-//         self.src_id = Some(id);
-//         self
-//     }
-//
-//     pub fn set_src_label(&mut self, label: String) -> &mut Self {
-//         self.src_label = Some(label);
-//         self
-//     }
-//
-//     pub fn set_dst_id(&mut self, id: u128) -> &mut Self {
-//         self.dst_id = Some(id);
-//         self
-//     }
-//
-//     pub fn set_dst_label(&mut self, label: String) -> &mut Self {
-//         self.dst_label = Some(label);
-//         self
-//     }
-//
-//     pub fn set_properties(&mut self, p: DynProperties) -> &mut Self {
-//         self.properties = Some(p);
-//         self
-//     }
-//
-//     pub fn build(self) -> Option<Edge> {
-//         unimplemented!()
-//     }
-//
-//
-// }
+#[derive(Default)]
+pub struct EdgeBuilder {
+    id: Option<ID>,
+    label: Option<Label>,
+    src_id: Option<ID>,
+    src_label: Option<Label>,
+    dst_id: Option<ID>,
+    dst_label: Option<Label>,
+    properties: Option<DynDetails>,
+}
+
+impl EdgeBuilder {
+    pub fn new() -> Self {
+        EdgeBuilder::default()
+    }
+
+    pub fn set_id(&mut self, id: ID) -> &mut Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn set_label(&mut self, label: Label) -> &mut Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn set_src_id(&mut self, id: ID) -> &mut Self {
+        self.src_id = Some(id);
+        self
+    }
+
+    pub fn set_src_label(&mut self, label: Label) -> &mut Self {
+        self.src_label = Some(label);
+        self
+    }
+
+    pub fn set_dst_id(&mut self, id: ID) -> &mut Self {
+        self.dst_id = Some(id);
+        self
+    }
+
+    pub fn set_dst_label(&mut self, label: Label) -> &mut Self {
+        self.dst_label = Some(label);
+        self
+    }
+
+    pub fn set_properties(&mut self, p: DynDetails) -> &mut Self {
+        self.properties = Some(p);
+        self
+    }
+
+    /// Build the edge, which requires the id, src_id and dst_id to be set. If the properties
+    /// are not set, the label is required to create empty properties.
+    pub fn build(&self) -> GDBResult<Edge> {
+        let id = self.id.ok_or_else(|| missing_field("id"))?;
+        let src_id = self.src_id.ok_or_else(|| missing_field("src_id"))?;
+        let dst_id = self.dst_id.ok_or_else(|| missing_field("dst_id"))?;
+        let properties = if let Some(ref properties) = self.properties {
+            properties.clone()
+        } else {
+            let label = self.label.clone().ok_or_else(|| missing_field("label"))?;
+            DynDetails::new(DefaultDetails::new(id, label))
+        };
+        Ok(Edge {
+            id,
+            src_id,
+            dst_id,
+            label: self.label.clone(),
+            src_label: self.src_label.clone(),
+            dst_label: self.dst_label.clone(),
+            properties,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Object;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_reverse_edge() {
+        let mut properties = HashMap::new();
+        properties.insert("weight".to_string(), Object::from(0.5));
+        let details = DefaultDetails::new_with_prop(1, Label::Str("knows".to_string()), properties);
+        let mut e =
+            Edge::new(1, Some(Label::Str("knows".to_string())), 2, 3, DynDetails::new(details));
+        e.set_src_label(Label::Str("person".to_string()));
+        e.set_dst_label(Label::Str("software".to_string()));
+
+        let r = e.reverse();
+        assert_eq!(r.id, 1);
+        assert_eq!(r.label, Some(Label::Str("knows".to_string())));
+        assert_eq!(r.src_id, 3);
+        assert_eq!(r.dst_id, 2);
+        assert_eq!(r.src_label(), Some(&Label::Str("software".to_string())));
+        assert_eq!(r.dst_label(), Some(&Label::Str("person".to_string())));
+        assert!(r.details().get_property("weight") == e.details().get_property("weight"));
+        assert!(r.details().get_property("weight") == Some(Object::from(0.5).as_borrow()));
+    }
+
+    #[test]
+    fn test_edge_builder() {
+        let mut builder = EdgeBuilder::new();
+        builder
+            .set_id(1)
+            .set_label(Label::Str("knows".to_string()))
+            .set_src_id(2)
+            .set_src_label(Label::Str("person".to_string()))
+            .set_dst_id(3)
+            .set_dst_label(Label::Str("software".to_string()));
+        let e = builder.build().unwrap();
+        assert_eq!(e.id, 1);
+        assert_eq!(e.label(), &Label::Str("knows".to_string()));
+        assert_eq!(e.src_id, 2);
+        assert_eq!(e.src_label(), Some(&Label::Str("person".to_string())));
+        assert_eq!(e.dst_id, 3);
+        assert_eq!(e.dst_label(), Some(&Label::Str("software".to_string())));
+    }
+
+    #[test]
+    fn test_edge_builder_missing_fields() {
+        let details = DynDetails::new(DefaultDetails::new(1, Label::Str("knows".to_string())));
+        let mut builder = EdgeBuilder::new();
+        builder.set_src_id(2).set_dst_id(3).set_properties(details.clone());
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("id".to_string())));
+
+        let mut builder = EdgeBuilder::new();
+        builder.set_id(1).set_dst_id(3).set_properties(details.clone());
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("src_id".to_string())));
+
+        let mut builder = EdgeBuilder::new();
+        builder.set_id(1).set_src_id(2).set_properties(details);
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("dst_id".to_string())));
+
+        let mut builder = EdgeBuilder::new();
+        builder.set_id(1).set_src_id(2).set_dst_id(3);
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("label".to_string())));
+    }
+
+    #[test]
+    fn test_edge_dedup_by_id() {
+        let details = DynDetails::new(DefaultDetails::new(1, Label::Str("knows".to_string())));
+        let e1 = Edge::new(1, None, 2, 3, details.clone());
+        let e2 = e1.clone();
+        let e3 = Edge::new(1, None, 3, 2, details.clone());
+        let e4 = Edge::new(4, None, 2, 3, details);
+        assert!(e1 == e3);
+        assert!(e1 != e4);
+        let mut set = HashSet::new();
+        set.insert(e1);
+        set.insert(e2);
+        assert_eq!(set.len(), 1);
+        set.insert(e3);
+        assert_eq!(set.len(), 1);
+        set.insert(e4);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_debug_edge() {
+        let mut properties = HashMap::new();
+        for i in 0..10 {
+            properties.insert(format!("p{}", i), Object::from(i));
+        }
+        let details = DefaultDetails::new_with_prop(1, Label::Str("knows".to_string()), properties);
+        let e = Edge::new(1, None, 2, 3, DynDetails::new(details));
+        let debug = format!("{:?}", e);
+        assert!(debug.contains("id: 1"));
+        assert!(debug.contains("knows"));
+        assert!(debug.contains("src_id: 2"));
+        assert!(debug.contains("dst_id: 3"));
+        assert!(debug.contains("p0"));
+        assert!(!debug.contains("p9"));
+        assert!(debug.contains("..(2 more)"));
+    }
+}
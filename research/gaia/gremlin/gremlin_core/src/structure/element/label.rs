@@ -0,0 +1,157 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::structure::element::Label;
+use graph_store::common::LabelId;
+use graph_store::prelude::{GDBError, GDBResult};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct Interned {
+    ids: HashMap<String, LabelId>,
+    names: Vec<String>,
+}
+
+/// A registry interns label names into label ids and back, which can be shared across workers;
+#[derive(Default)]
+pub struct LabelRegistry {
+    inner: RwLock<Interned>,
+}
+
+impl LabelRegistry {
+    pub fn new() -> Self {
+        LabelRegistry::default()
+    }
+
+    /// Intern the label name, the same name is always interned into the same id;
+    pub fn intern(&self, name: &str) -> GDBResult<LabelId> {
+        if let Some(id) = self.get_id(name) {
+            return Ok(id);
+        }
+        let mut interned = self.inner.write().expect("label registry poisoned");
+        // double check as the name may be interned by others before acquiring the write lock;
+        if let Some(id) = interned.ids.get(name) {
+            return Ok(*id);
+        }
+        if interned.names.len() > LabelId::max_value() as usize {
+            return Err(GDBError::OutOfBoundError);
+        }
+        let id = interned.names.len() as LabelId;
+        interned.ids.insert(name.to_string(), id);
+        interned.names.push(name.to_string());
+        Ok(id)
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<LabelId> {
+        self.inner.read().expect("label registry poisoned").ids.get(name).copied()
+    }
+
+    pub fn get_name(&self, id: LabelId) -> Option<String> {
+        self.inner.read().expect("label registry poisoned").names.get(id as usize).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().expect("label registry poisoned").names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Label {
+    /// Get the id of the label, return `None` if the name is not interned in `registry`;
+    pub fn as_id(&self, registry: &LabelRegistry) -> Option<LabelId> {
+        match self {
+            Label::Str(name) => registry.get_id(name),
+            Label::Id(id) => Some(*id),
+        }
+    }
+
+    /// Get the name of the label, return `None` if the id is not interned in `registry`;
+    pub fn as_str(&self, registry: &LabelRegistry) -> Option<Cow<'_, str>> {
+        match self {
+            Label::Str(name) => Some(Cow::Borrowed(name.as_str())),
+            Label::Id(id) => registry.get_name(*id).map(Cow::Owned),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_intern_idempotent() {
+        let registry = LabelRegistry::new();
+        let person = registry.intern("person").unwrap();
+        let software = registry.intern("software").unwrap();
+        assert_ne!(person, software);
+        assert_eq!(registry.intern("person").unwrap(), person);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_intern_out_of_bound() {
+        let registry = LabelRegistry::new();
+        for i in 0..=LabelId::max_value() as usize {
+            assert_eq!(registry.intern(&i.to_string()).unwrap() as usize, i);
+        }
+        match registry.intern("overflow") {
+            Err(GDBError::OutOfBoundError) => (),
+            _ => panic!("expect out of bound error"),
+        }
+        assert!(registry.intern("0").is_ok());
+    }
+
+    #[test]
+    fn test_label_round_trip() {
+        let registry = LabelRegistry::new();
+        let id = registry.intern("person").unwrap();
+        let label = Label::Str("person".to_string());
+        assert_eq!(label.as_id(&registry), Some(id));
+        let id_label = Label::Id(id);
+        assert_eq!(id_label.as_str(&registry).unwrap(), "person");
+        assert_eq!(
+            Label::Id(id_label.as_id(&registry).unwrap()).as_str(&registry),
+            label.as_str(&registry)
+        );
+
+        assert_eq!(Label::Str("unknown".to_string()).as_id(&registry), None);
+        assert_eq!(Label::Id(100).as_str(&registry), None);
+    }
+
+    #[test]
+    fn test_concurrent_intern() {
+        let registry = Arc::new(LabelRegistry::new());
+        let mut handles = vec![];
+        for _ in 0..4 {
+            let registry = registry.clone();
+            handles.push(std::thread::spawn(move || {
+                (0..10)
+                    .map(|i| registry.intern(&format!("label_{}", i)).unwrap())
+                    .collect::<Vec<_>>()
+            }));
+        }
+        let results = handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>();
+        assert_eq!(registry.len(), 10);
+        for r in results.iter() {
+            assert_eq!(r, &results[0]);
+        }
+    }
+}
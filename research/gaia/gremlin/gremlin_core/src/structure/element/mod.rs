@@ -13,13 +13,18 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::structure::property::DynDetails;
+use crate::generated::common as common_pb;
+use crate::generated::protobuf as result_pb;
+use crate::structure::property::{DefaultDetails, Details, DynDetails};
 use crate::Object;
-pub use edge::Edge;
+pub use edge::{Edge, EdgeBuilder};
 use graph_store::common::LabelId;
+use graph_store::prelude::{GDBError, GDBResult};
+pub use label::LabelRegistry;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
-pub use vertex::Vertex;
+pub use vertex::{Vertex, VertexBuilder};
 
 pub type ID = u128;
 
@@ -36,11 +41,115 @@ pub trait Element {
     fn label(&self) -> &Label;
 
     fn details(&self) -> &DynDetails;
+
+    /// Get an owned copy of the property of `key`, return `None` if the property is not found;
+    fn property(&self, key: &str) -> Option<Object> {
+        self.details().get_property(key).and_then(|p| p.try_to_owned())
+    }
+
+    /// Get the property of `key` as i64, return `None` if not found or it can't be coerced;
+    fn property_as_i64(&self, key: &str) -> Option<i64> {
+        self.details().get_property(key).and_then(|p| p.as_i64().ok())
+    }
+
+    /// Get the property of `key` as f64, return `None` if not found or it can't be coerced;
+    fn property_as_f64(&self, key: &str) -> Option<f64> {
+        self.details().get_property(key).and_then(|p| p.as_f64().ok())
+    }
+
+    /// Get the property of `key` as string, return `None` if not found or it isn't a string;
+    fn property_as_str(&self, key: &str) -> Option<String> {
+        self.details().get_property(key).and_then(|p| p.as_str().ok().map(|s| s.into_owned()))
+    }
+
+    /// Check if the element's label equals to `target`, labels of different kinds are never equal;
+    fn has_label(&self, target: &Label) -> bool {
+        self.label() == target
+    }
+
+    /// Check if the element's label equals to `target`, an id label is matched against `target`
+    /// parsed as a label id;
+    fn has_label_str(&self, target: &str) -> bool {
+        match self.label() {
+            Label::Str(s) => s == target,
+            Label::Id(id) => target.parse::<LabelId>().map(|t| t == *id).unwrap_or(false),
+        }
+    }
 }
 
 mod edge;
+mod label;
 mod vertex;
 
+/// Decode the label from pb, where an empty label is regarded as absent;
+fn label_from_pb(label: &str) -> Option<Label> {
+    if label.is_empty() {
+        None
+    } else {
+        Some(Label::Str(label.to_string()))
+    }
+}
+
+/// The error of building an element without setting the required `field`;
+fn missing_field(field: &str) -> GDBError {
+    GDBError::MissingFieldError(field.to_string())
+}
+
+/// Decode the properties from pb, properties with null values are skipped;
+fn properties_from_pb(props: &[result_pb::Property]) -> GDBResult<HashMap<String, Object>> {
+    let mut properties = HashMap::with_capacity(props.len());
+    for prop in props {
+        let value = prop.value.as_ref().ok_or(GDBError::FieldNotExistError)?;
+        match value.item {
+            Some(common_pb::value::Item::I32Array(_))
+            | Some(common_pb::value::Item::I64Array(_))
+            | Some(common_pb::value::Item::F64Array(_))
+            | Some(common_pb::value::Item::StrArray(_)) => return Err(GDBError::InvalidTypeError),
+            _ => (),
+        }
+        if let Some(obj) = Option::<Object>::from(value) {
+            properties.insert(prop.key.clone(), obj);
+        }
+    }
+    Ok(properties)
+}
+
+/// Read all the properties into owned details, which no longer refer to the store, return the
+/// error of reading the properties, see [`Details::read_all_properties`];
+fn detach_details(id: ID, label: &Option<Label>, details: &DynDetails) -> GDBResult<DynDetails> {
+    let properties = details.read_all_properties()?;
+    let label = label.clone().unwrap_or_else(|| Label::Str(String::new()));
+    Ok(DynDetails::new(DefaultDetails::new_with_prop(id, label, properties)))
+}
+
+/// The max number of properties shown when debugging a vertex or an edge;
+const DEBUG_PROPERTIES_LIMIT: usize = 8;
+
+/// Debug a bounded preview of the properties, sorted by key, to avoid flooding logs on wide rows;
+struct PropertiesPreview<'a>(&'a DynDetails);
+
+impl<'a> Debug for PropertiesPreview<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(properties) = self.0.get_all_properties() {
+            let mut entries = properties.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            write!(f, "{{")?;
+            for (i, (key, value)) in entries.iter().take(DEBUG_PROPERTIES_LIMIT).enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}: {:?}", key, value)?;
+            }
+            if entries.len() > DEBUG_PROPERTIES_LIMIT {
+                write!(f, ", ..({} more)", entries.len() - DEBUG_PROPERTIES_LIMIT)?;
+            }
+            write!(f, "}}")
+        } else {
+            write!(f, "{{..}}")
+        }
+    }
+}
+
 #[enum_dispatch(Element)]
 #[derive(Clone)]
 pub enum VertexOrEdge {
@@ -114,6 +223,22 @@ impl GraphElement {
     pub fn attach<O: Into<Object>>(&mut self, obj: O) {
         self.attached = Some(obj.into())
     }
+
+    #[inline]
+    pub fn take_attached(&mut self) -> Option<Object> {
+        self.attached.take()
+    }
+
+    /// Clone the element with all its properties read eagerly into owned details, so that the
+    /// copy can outlive the store, e.g. a snapshot, it is read from; The attached object is kept;
+    /// Return the error of [`Vertex::detach`] or [`Edge::detach`] if any property read fails;
+    pub fn detach(&self) -> GDBResult<GraphElement> {
+        let element = match &self.element {
+            VertexOrEdge::V(v) => VertexOrEdge::V(v.detach()?),
+            VertexOrEdge::E(e) => VertexOrEdge::E(e.detach()?),
+        };
+        Ok(GraphElement { element, attached: self.attached.clone() })
+    }
 }
 
 impl Debug for GraphElement {
@@ -169,3 +294,120 @@ impl PartialEq for GraphElement {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::object::BorrowObject;
+    use std::sync::{Arc, RwLock};
+
+    fn vertex_with_label(label: Label) -> Vertex {
+        Vertex::new(1, Some(label.clone()), DefaultDetails::new(1, label))
+    }
+
+    #[test]
+    fn test_has_label() {
+        let v = vertex_with_label(Label::Str("person".to_string()));
+        assert!(v.has_label(&Label::Str("person".to_string())));
+        assert!(!v.has_label(&Label::Str("software".to_string())));
+        assert!(!v.has_label(&Label::Id(1)));
+        assert!(v.has_label_str("person"));
+        assert!(!v.has_label_str("1"));
+
+        let v = vertex_with_label(Label::Id(1));
+        assert!(v.has_label(&Label::Id(1)));
+        assert!(!v.has_label(&Label::Id(2)));
+        assert!(!v.has_label(&Label::Str("1".to_string())));
+        assert!(v.has_label_str("1"));
+        assert!(!v.has_label_str("2"));
+        assert!(!v.has_label_str("person"));
+
+        let e: GraphElement = vertex_with_label(Label::Id(1)).into();
+        assert!(e.has_label(&Label::Id(1)));
+        assert!(e.has_label_str("1"));
+    }
+
+    /// The details reading properties from a store, which fails once the store is released;
+    struct StoreDetails {
+        label: Label,
+        store: Arc<RwLock<Option<HashMap<String, Object>>>>,
+    }
+
+    impl Details for StoreDetails {
+        fn get_property(&self, _key: &str) -> Option<BorrowObject> {
+            None
+        }
+
+        fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+            None
+        }
+
+        fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+            self.store.read().unwrap().clone().ok_or(GDBError::DBNotFoundError)
+        }
+
+        fn get_id(&self) -> ID {
+            1
+        }
+
+        fn get_label(&self) -> &Label {
+            &self.label
+        }
+    }
+
+    #[test]
+    fn test_detach() {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), Object::from("marko"));
+        properties.insert("age".to_string(), Object::from(29));
+        let store = Arc::new(RwLock::new(Some(properties)));
+        let label = Label::Str("person".to_string());
+        let details = StoreDetails { label: label.clone(), store: store.clone() };
+        let mut v: GraphElement = Vertex::new(1, Some(label), details).into();
+        v.attach(3i64);
+        let detached = v.detach().expect("should be detached");
+
+        // release the store, the properties are still read from the detached copy;
+        *store.write().unwrap() = None;
+        assert_eq!(detached.id(), 1);
+        assert!(detached.has_label_str("person"));
+        assert_eq!(detached.get_attached(), Some(&Object::from(3i64)));
+        assert_eq!(detached.property("name"), Some(Object::from("marko")));
+        assert_eq!(detached.property_as_i64("age"), Some(29));
+        // the read error is propagated;
+        assert_eq!(v.detach().err(), Some(GDBError::DBNotFoundError));
+
+        let details = DynDetails::new(DefaultDetails::new(2, Label::Id(1)));
+        let e: GraphElement = Edge::new(2, Some(Label::Id(1)), 1, 3, details).into();
+        let detached = e.detach().expect("should be detached");
+        assert_eq!(detached.id(), 2);
+        assert!(detached.details().get_all_properties().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_property_accessors() {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), Object::from("marko"));
+        properties.insert("age".to_string(), Object::from(29));
+        properties.insert("weight".to_string(), Object::from(0.5));
+        let details =
+            DefaultDetails::new_with_prop(1, Label::Str("person".to_string()), properties);
+        let v = Vertex::new(1, None, details);
+
+        assert_eq!(v.property("name"), Some(Object::from("marko")));
+        assert_eq!(v.property_as_str("name"), Some("marko".to_string()));
+        assert_eq!(v.property_as_i64("age"), Some(29));
+        assert_eq!(v.property_as_f64("age"), Some(29.0));
+        assert_eq!(v.property_as_f64("weight"), Some(0.5));
+
+        assert_eq!(v.property("unknown"), None);
+        assert_eq!(v.property_as_i64("unknown"), None);
+        assert_eq!(v.property_as_f64("unknown"), None);
+        assert_eq!(v.property_as_str("unknown"), None);
+
+        assert_eq!(v.property_as_i64("name"), None);
+        assert_eq!(v.property_as_i64("weight"), None);
+        assert_eq!(v.property_as_f64("name"), None);
+        assert_eq!(v.property_as_str("age"), None);
+    }
+}
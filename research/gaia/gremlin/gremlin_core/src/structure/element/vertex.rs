@@ -13,9 +13,15 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::structure::element::{Element, Label, ID};
+use crate::generated::protobuf as result_pb;
+use crate::structure::element::{
+    detach_details, label_from_pb, missing_field, properties_from_pb, Element, Label,
+    PropertiesPreview, ID,
+};
 use crate::structure::property::DynDetails;
-use crate::structure::Details;
+use crate::structure::{DefaultDetails, Details};
+use graph_store::prelude::{GDBError, GDBResult};
+use std::fmt::Debug;
 
 #[derive(Clone)]
 pub struct Vertex {
@@ -28,6 +34,64 @@ impl Vertex {
     pub fn new<D: Details + 'static>(id: u128, label: Option<Label>, details: D) -> Self {
         Vertex { id, label, details: DynDetails::new(details) }
     }
+
+    /// Decode the vertex from pb, the label is decoded as `Label::Str`, or `None` if it is empty;
+    pub fn from_pb(pb: &result_pb::Vertex) -> GDBResult<Vertex> {
+        let id = pb.id as ID;
+        let label = label_from_pb(&pb.label);
+        let properties = properties_from_pb(&pb.properties)?;
+        let details_label = label.clone().unwrap_or_else(|| Label::Str(String::new()));
+        Ok(Vertex::new(id, label, DefaultDetails::new_with_prop(id, details_label, properties)))
+    }
+
+    /// Clone the vertex with all its properties read into owned details, which no longer refer to
+    /// the store; Return the error of reading the properties, e.g. `VertexNotFoundError` if the
+    /// vertex is no longer found in the store, or the error of the store itself;
+    pub fn detach(&self) -> GDBResult<Vertex> {
+        let details = detach_details(self.id, &self.label, &self.details)?;
+        Ok(Vertex { id: self.id, label: self.label.clone(), details })
+    }
+}
+
+#[derive(Default)]
+pub struct VertexBuilder {
+    id: Option<ID>,
+    label: Option<Label>,
+    properties: Option<DynDetails>,
+}
+
+impl VertexBuilder {
+    pub fn new() -> Self {
+        VertexBuilder::default()
+    }
+
+    pub fn set_id(&mut self, id: ID) -> &mut Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn set_label(&mut self, label: Label) -> &mut Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn set_properties(&mut self, p: DynDetails) -> &mut Self {
+        self.properties = Some(p);
+        self
+    }
+
+    /// Build the vertex, which requires the id to be set. If the properties are not set, the
+    /// label is required to create empty properties.
+    pub fn build(&self) -> GDBResult<Vertex> {
+        let id = self.id.ok_or_else(|| missing_field("id"))?;
+        let details = if let Some(ref properties) = self.properties {
+            properties.clone()
+        } else {
+            let label = self.label.clone().ok_or_else(|| missing_field("label"))?;
+            DynDetails::new(DefaultDetails::new(id, label))
+        };
+        Ok(Vertex { id, label: self.label.clone(), details })
+    }
 }
 
 impl Element for Vertex {
@@ -47,3 +111,69 @@ impl Element for Vertex {
         &self.details
     }
 }
+
+impl Debug for Vertex {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Vertex")
+            .field("id", &self.id)
+            .field("label", self.label())
+            .field("properties", &PropertiesPreview(&self.details))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Object;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_debug_vertex() {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), Object::from("marko"));
+        let details =
+            DefaultDetails::new_with_prop(1, Label::Str("person".to_string()), properties);
+        let v = Vertex::new(1, None, details);
+        let debug = format!("{:?}", v);
+        assert!(debug.contains("id: 1"));
+        assert!(debug.contains("person"));
+        assert!(debug.contains("name"));
+    }
+
+    #[test]
+    fn test_vertex_builder() {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), Object::from("marko"));
+        let details =
+            DefaultDetails::new_with_prop(1, Label::Str("person".to_string()), properties);
+        let mut builder = VertexBuilder::new();
+        builder.set_id(1).set_properties(DynDetails::new(details));
+        let v = builder.build().unwrap();
+        assert_eq!(v.id, 1);
+        assert_eq!(v.label, None);
+        assert_eq!(v.label(), &Label::Str("person".to_string()));
+        assert_eq!(v.property_as_str("name"), Some("marko".to_string()));
+
+        // empty properties are created of the label
+        let mut builder = VertexBuilder::new();
+        builder.set_id(2).set_label(Label::Id(1));
+        let v = builder.build().unwrap();
+        assert_eq!(v.id, 2);
+        assert_eq!(v.label(), &Label::Id(1));
+        assert_eq!(v.details().get_label(), &Label::Id(1));
+        assert!(v.property("name").is_none());
+    }
+
+    #[test]
+    fn test_vertex_builder_missing_fields() {
+        let details = DynDetails::new(DefaultDetails::new(1, Label::Str("person".to_string())));
+        let mut builder = VertexBuilder::new();
+        builder.set_label(Label::Str("person".to_string())).set_properties(details);
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("id".to_string())));
+
+        let mut builder = VertexBuilder::new();
+        builder.set_id(1);
+        assert_eq!(builder.build().err(), Some(GDBError::MissingFieldError("label".to_string())));
+    }
+}
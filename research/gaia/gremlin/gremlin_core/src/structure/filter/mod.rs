@@ -45,6 +45,7 @@ mod element;
 mod traverser;
 
 use crate::structure::{GraphElement, Tag};
+pub use compare::EqCmp;
 pub use element::*;
 pub use traverser::*;
 
@@ -34,8 +34,8 @@ impl Reverse for IsSimple {
 impl Predicate<Traverser> for IsSimple {
     fn test(&self, entry: &Traverser) -> Option<bool> {
         Some(match self {
-            IsSimple::Simple => !entry.has_cyclic_path(),
-            IsSimple::Cyclic => entry.has_cyclic_path(),
+            IsSimple::Simple => entry.is_simple_path(),
+            IsSimple::Cyclic => entry.is_cyclic_path(),
         })
     }
 }
@@ -63,13 +63,20 @@ pub trait GraphProxy: Send + Sync {
     fn prepare_explore_edge(
         &self, direction: Direction, params: &QueryParams<Edge>,
     ) -> DynResult<Box<dyn Statement<ID, Edge>>>;
+
+    /// Take a snapshot of the graph, through which a job sees the graph as of the moment the job
+    /// is built, regardless of the writes during the job; A graph that can't be written returns a
+    /// proxy of itself;
+    fn snapshot(&self) -> Arc<dyn GraphProxy>;
 }
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicPtr, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock, Weak};
 
 lazy_static! {
     pub static ref GRAPH_PROXY: AtomicPtr<Arc<dyn GraphProxy>> = AtomicPtr::default();
+    static ref JOB_GRAPHS: RwLock<HashMap<u64, Weak<dyn GraphProxy>>> = RwLock::new(HashMap::new());
 }
 
 pub fn register_graph(graph: Arc<dyn GraphProxy>) {
@@ -77,7 +84,31 @@ pub fn register_graph(graph: Arc<dyn GraphProxy>) {
     GRAPH_PROXY.store(ptr, Ordering::SeqCst);
 }
 
+/// Take a snapshot of the registered graph for the job of `job_id`, which is returned by
+/// `get_graph()` to the steps of the job built on its workers. The snapshot is held by the job
+/// rather than here, e.g. by its source step and the functions reading through it, and is dropped
+/// once the job ends;
+pub fn snapshot_graph(job_id: u64) -> Option<Arc<dyn GraphProxy>> {
+    let graph = get_registered_graph()?.snapshot();
+    let mut graphs = JOB_GRAPHS.write().expect("job graphs poisoned");
+    graphs.retain(|_, graph| graph.strong_count() > 0);
+    graphs.insert(job_id, Arc::downgrade(&graph));
+    Some(graph)
+}
+
+/// Get the graph read by the job of the current worker, which is the snapshot taken by
+/// `snapshot_graph()` for the job if any, or the registered graph otherwise;
 pub fn get_graph() -> Option<Arc<dyn GraphProxy>> {
+    if let Some(worker) = pegasus::get_current_worker() {
+        let graphs = JOB_GRAPHS.read().expect("job graphs poisoned");
+        if let Some(graph) = graphs.get(&worker.job_id).and_then(|graph| graph.upgrade()) {
+            return Some(graph);
+        }
+    }
+    get_registered_graph()
+}
+
+fn get_registered_graph() -> Option<Arc<dyn GraphProxy>> {
     let ptr = GRAPH_PROXY.load(Ordering::SeqCst);
     if ptr.is_null() {
         None
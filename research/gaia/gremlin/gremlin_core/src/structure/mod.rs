@@ -18,12 +18,17 @@ pub mod filter;
 mod graph;
 mod property;
 
-pub use element::{Edge, Element, GraphElement, Label, Vertex, VertexOrEdge, ID};
+pub use element::{
+    Edge, EdgeBuilder, Element, GraphElement, Label, LabelRegistry, Vertex, VertexBuilder,
+    VertexOrEdge, ID,
+};
 pub use filter::*;
 pub use graph::*;
-pub use property::{DefaultDetails, Details, DynDetails, Token};
+pub use property::{DefaultDetails, Details, DynDetails, LazyDetails, PropertyPredicate, Token};
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+/// The direction to expand the adjacent edges of a vertex, as in the `out()`, `in()` and
+/// `both()` steps. `Both` is the union of `Out` and `In`, where a self-loop is only expanded once.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Direction {
     Out,
     In,
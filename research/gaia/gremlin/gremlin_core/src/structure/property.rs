@@ -16,9 +16,10 @@
 use crate::object::BorrowObject;
 use crate::structure::element::Label;
 use crate::{Object, ID};
+use graph_store::prelude::{GDBError, GDBResult};
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum Token {
@@ -27,12 +28,67 @@ pub enum Token {
     Property(String),
 }
 
+/// The predicate on a property value, as in `has("age", gt(30))`, where values are compared as
+/// objects, see [`Object::value_eq`] and the `PartialOrd` of `Object`;
+#[derive(Clone, Debug)]
+pub enum PropertyPredicate {
+    Eq(Object),
+    Neq(Object),
+    Gt(Object),
+    Lt(Object),
+    /// The value is in the range of `[lower, upper)`, as `between()` in gremlin;
+    Between(Object, Object),
+    /// The value equals any of the objects;
+    Within(Vec<Object>),
+    /// The property is present, whatever its value is;
+    Exists,
+}
+
 pub trait Details: Send + Sync {
     fn get_property(&self, key: &str) -> Option<BorrowObject>;
 
+    /// Clone all the properties into a map, return `None` if the properties can't be enumerated;
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>>;
+
+    /// Read all the properties into a map, e.g. to detach the details from the store, where the
+    /// details fetching properties on demand enumerate them from the store; Return the error of
+    /// the read, e.g. the element is no longer found in the store, or `InvalidFunctionCallError`
+    /// if the properties can't be enumerated;
+    fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+        self.get_all_properties().ok_or(GDBError::InvalidFunctionCallError)
+    }
+
+    /// Iterate the keys of all the properties, which is empty if the properties can't be
+    /// enumerated;
+    fn keys(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(std::iter::empty())
+    }
+
     fn get_id(&self) -> ID;
 
     fn get_label(&self) -> &Label;
+
+    /// Test the property of `key` by `pred`. As in gremlin, only the present properties can
+    /// satisfy a predicate, so every predicate, including `Neq`, is false if the property is
+    /// missing, and a property of `Null` value is seen as missing. Besides, ordering predicates
+    /// are false if the value is incomparable with the expected, e.g. a string and a number;
+    fn matches(&self, key: &str, pred: &PropertyPredicate) -> bool {
+        let value = match self.get_property(key) {
+            Some(BorrowObject::Null) | None => return false,
+            Some(value) => value,
+        };
+        match pred {
+            PropertyPredicate::Eq(expect) => value == expect.as_borrow(),
+            PropertyPredicate::Neq(expect) => value != expect.as_borrow(),
+            PropertyPredicate::Gt(expect) => value > expect.as_borrow(),
+            PropertyPredicate::Lt(expect) => value < expect.as_borrow(),
+            PropertyPredicate::Between(lower, upper) => {
+                value >= lower.as_borrow() && value < upper.as_borrow()
+            }
+            PropertyPredicate::Within(expects) => expects.iter().any(|e| value == e.as_borrow()),
+            PropertyPredicate::Exists => true,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -44,6 +100,14 @@ impl DynDetails {
     pub fn new<P: Details + 'static>(p: P) -> Self {
         DynDetails { inner: Arc::new(p) }
     }
+
+    /// Iterate all the properties as key/value pairs, without the properties of values which
+    /// can't be owned, see [`Details::keys`];
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Object)> {
+        self.keys().filter_map(move |key| {
+            self.get_property(key).and_then(|v| v.try_to_owned()).map(|v| (key, v))
+        })
+    }
 }
 
 impl Details for DynDetails {
@@ -51,6 +115,18 @@ impl Details for DynDetails {
         self.inner.get_property(key)
     }
 
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+        self.inner.get_all_properties()
+    }
+
+    fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+        self.inner.read_all_properties()
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        self.inner.keys()
+    }
+
     fn get_id(&self) -> ID {
         self.inner.get_id()
     }
@@ -97,6 +173,14 @@ impl Details for DefaultDetails {
         self.inner.get(key).map(|o| o.as_borrow())
     }
 
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+        Some(self.inner.clone())
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.inner.keys().map(|k| k.as_str()))
+    }
+
     fn get_id(&self) -> ID {
         self.id
     }
@@ -105,3 +189,231 @@ impl Details for DefaultDetails {
         &self.label
     }
 }
+
+/// The details fetching properties from the store on demand, e.g. to avoid materializing all the
+/// properties of a vertex when a query only touches one key; The property fetched is cached, so
+/// that each key, whether present or not, hits the store at most once. As details are shared
+/// across workers, `fetch` may be called concurrently from different threads; The properties can
+/// be enumerated only if `fetch_all` is given, see [`LazyDetails::with_fetch_all`];
+pub struct LazyDetails<F> {
+    id: ID,
+    label: Label,
+    fetch: F,
+    fetch_all: Option<Box<dyn Fn(ID) -> GDBResult<HashMap<String, Object>> + Send + Sync>>,
+    // the cached values are boxed and never replaced or removed, so they can be borrowed as long
+    // as the details live, even after the lock is released;
+    cache: RwLock<HashMap<String, Option<Box<Object>>>>,
+}
+
+impl<F: Fn(ID, &str) -> Option<Object> + Send + Sync> LazyDetails<F> {
+    pub fn new(id: ID, label: Label, fetch: F) -> Self {
+        LazyDetails { id, label, fetch, fetch_all: None, cache: RwLock::new(HashMap::new()) }
+    }
+
+    /// Read all the properties of the element from the store by `fetch_all` when they are
+    /// enumerated, e.g. by [`Details::read_all_properties`], which are not cached;
+    pub fn with_fetch_all<A>(mut self, fetch_all: A) -> Self
+    where
+        A: Fn(ID) -> GDBResult<HashMap<String, Object>> + Send + Sync + 'static,
+    {
+        self.fetch_all = Some(Box::new(fetch_all));
+        self
+    }
+
+    fn get_cached(&self, key: &str) -> Option<Option<&Object>> {
+        let cache = self.cache.read().expect("details cache poisoned");
+        // SAFETY: the boxed value is never moved or dropped until the details are dropped;
+        cache.get(key).map(|v| v.as_ref().map(|v| unsafe { &*(v.as_ref() as *const Object) }))
+    }
+}
+
+impl<F: Fn(ID, &str) -> Option<Object> + Send + Sync> Details for LazyDetails<F> {
+    fn get_property(&self, key: &str) -> Option<BorrowObject> {
+        if let Some(cached) = self.get_cached(key) {
+            return cached.map(|v| v.as_borrow());
+        }
+        let fetched = (self.fetch)(self.id, key).map(Box::new);
+        let mut cache = self.cache.write().expect("details cache poisoned");
+        // keep the value fetched by others first, as it may have been borrowed;
+        let value = cache.entry(key.to_string()).or_insert(fetched);
+        // SAFETY: the boxed value is never moved or dropped until the details are dropped;
+        value.as_ref().map(|v| unsafe { &*(v.as_ref() as *const Object) }.as_borrow())
+    }
+
+    fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+        self.read_all_properties().ok()
+    }
+
+    fn read_all_properties(&self) -> GDBResult<HashMap<String, Object>> {
+        match &self.fetch_all {
+            Some(fetch_all) => fetch_all(self.id),
+            None => Err(GDBError::InvalidFunctionCallError),
+        }
+    }
+
+    fn get_id(&self) -> ID {
+        self.id
+    }
+
+    fn get_label(&self) -> &Label {
+        &self.label
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct OpaqueDetails {
+        label: Label,
+    }
+
+    impl Details for OpaqueDetails {
+        fn get_property(&self, _key: &str) -> Option<BorrowObject> {
+            None
+        }
+
+        fn get_all_properties(&self) -> Option<HashMap<String, Object>> {
+            None
+        }
+
+        fn get_id(&self) -> ID {
+            1
+        }
+
+        fn get_label(&self) -> &Label {
+            &self.label
+        }
+    }
+
+    #[test]
+    fn test_lazy_details() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let details = LazyDetails::new(1, Label::Str("person".to_string()), move |id, key| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            match key {
+                "name" => Some(Object::from("marko")),
+                "id" => Some(Object::from(id as i64)),
+                _ => None,
+            }
+        });
+        assert_eq!(fetches.load(Ordering::SeqCst), 0);
+
+        let details = DynDetails::new(details);
+        assert_eq!(details.get_property("name").unwrap().as_str().unwrap(), "marko");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(details.get_property("name").unwrap().as_str().unwrap(), "marko");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        assert_eq!(details.get_property("id").unwrap().as_i64().unwrap(), 1);
+        assert!(details.get_property("age").is_none());
+        assert!(details.get_property("age").is_none());
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_lazy_details_read_all() {
+        let label = Label::Str("person".to_string());
+        let details = LazyDetails::new(1, label.clone(), |_, _| None);
+        assert!(details.get_all_properties().is_none());
+        assert_eq!(details.read_all_properties(), Err(GDBError::InvalidFunctionCallError));
+
+        // the properties are enumerated from the store, and the error of reading it is returned;
+        let store = Arc::new(RwLock::new(Some(vec![("name".to_string(), Object::from("marko"))])));
+        let reader = store.clone();
+        let details = LazyDetails::new(1, label, |_, _| None).with_fetch_all(move |_| {
+            let properties = reader.read().unwrap().clone().ok_or(GDBError::DBNotFoundError)?;
+            Ok(properties.into_iter().collect())
+        });
+        let details = DynDetails::new(details);
+        let properties = details.read_all_properties().unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties.get("name"), Some(&Object::from("marko")));
+        assert_eq!(details.get_all_properties(), Some(properties));
+
+        *store.write().unwrap() = None;
+        assert_eq!(details.read_all_properties(), Err(GDBError::DBNotFoundError));
+        assert!(details.get_all_properties().is_none());
+    }
+
+    #[test]
+    fn test_details_iter() {
+        let mut details = DefaultDetails::new(1, Label::Str("person".to_string()));
+        details.insert("name".to_string(), Object::from("marko"));
+        details.insert("age".to_string(), Object::from(29));
+        details.insert("score".to_string(), Object::from(0.5));
+        let details = DynDetails::new(details);
+
+        let mut keys = details.keys().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec!["age", "name", "score"]);
+
+        let mut pairs = details.iter().collect::<Vec<_>>();
+        pairs.sort_by(|l, r| l.0.cmp(r.0));
+        assert_eq!(
+            pairs,
+            vec![
+                ("age", Object::from(29)),
+                ("name", Object::from("marko")),
+                ("score", Object::from(0.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_details_matches() {
+        let mut details = DefaultDetails::new(1, Label::Str("person".to_string()));
+        details.insert("name".to_string(), Object::from("marko"));
+        details.insert("age".to_string(), Object::from(29));
+        details.insert("nick".to_string(), Object::Null);
+        let details = DynDetails::new(details);
+
+        assert!(details.matches("age", &PropertyPredicate::Eq(Object::from(29i64))));
+        assert!(!details.matches("age", &PropertyPredicate::Eq(Object::from(30))));
+        assert!(details.matches("age", &PropertyPredicate::Neq(Object::from(30))));
+        assert!(!details.matches("age", &PropertyPredicate::Neq(Object::from(29))));
+        // a string never equals a number;
+        assert!(details.matches("age", &PropertyPredicate::Neq(Object::from("29"))));
+        assert!(details.matches("age", &PropertyPredicate::Gt(Object::from(28))));
+        assert!(!details.matches("age", &PropertyPredicate::Gt(Object::from(29))));
+        assert!(details.matches("age", &PropertyPredicate::Lt(Object::from(29.5))));
+        assert!(!details.matches("age", &PropertyPredicate::Lt(Object::from(29))));
+        assert!(!details.matches("name", &PropertyPredicate::Gt(Object::from(0))));
+        assert!(!details.matches("name", &PropertyPredicate::Lt(Object::from(0))));
+        let between = |lower: i32, upper: i32| {
+            PropertyPredicate::Between(Object::from(lower), Object::from(upper))
+        };
+        assert!(details.matches("age", &between(29, 30)));
+        assert!(details.matches("age", &between(20, 30)));
+        assert!(!details.matches("age", &between(20, 29)));
+        let within = PropertyPredicate::Within(vec![Object::from("josh"), Object::from("marko")]);
+        assert!(details.matches("name", &within));
+        assert!(!details.matches("age", &within));
+        assert!(!details.matches("name", &PropertyPredicate::Within(vec![])));
+        assert!(details.matches("name", &PropertyPredicate::Exists));
+
+        // all predicates are false on the missing or null properties;
+        let predicates = vec![
+            PropertyPredicate::Eq(Object::from(29)),
+            PropertyPredicate::Neq(Object::from(29)),
+            PropertyPredicate::Gt(Object::from(29)),
+            PropertyPredicate::Lt(Object::from(29)),
+            between(0, 100),
+            PropertyPredicate::Within(vec![Object::Null, Object::from(29)]),
+            PropertyPredicate::Exists,
+        ];
+        for pred in predicates.iter() {
+            assert!(!details.matches("weight", pred));
+            assert!(!details.matches("nick", pred));
+        }
+    }
+
+    #[test]
+    fn test_details_iter_not_enumerable() {
+        let details = DynDetails::new(OpaqueDetails { label: Label::Id(0) });
+        assert_eq!(details.keys().count(), 0);
+        assert_eq!(details.iter().count(), 0);
+    }
+}
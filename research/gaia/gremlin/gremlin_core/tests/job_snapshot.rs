@@ -0,0 +1,175 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! The graph registered is global to a process, so the jobs reading the snapshots of it are run
+//! in a test binary of their own.
+
+use gremlin_core::process::traversal::step::{FlatMapGen, GraphVertexStep, VertexStep};
+use gremlin_core::process::traversal::traverser::{Requirement, Traverser};
+use gremlin_core::structure::{
+    DefaultDetails, Direction, Edge, Label, QueryParams, Statement, Vertex,
+};
+use gremlin_core::{
+    get_graph, register_graph, snapshot_graph, DynIter, DynResult, Element, GraphProxy, Object, ID,
+};
+use pegasus::api::{Map, ResultSet, Sink};
+use pegasus::communication::Pipeline;
+use pegasus::{BuildJobError, Configuration, JobConf, JobGuard, Worker};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+
+const VERTICES: u128 = 100;
+
+/// A complete graph of `VERTICES` vertices, which carry the version of the graph as of when they
+/// are read in the property "version"; Each write bumps the version of the graph;
+#[derive(Clone)]
+struct VersionGraph {
+    version: Arc<AtomicI64>,
+    snapshot: Option<i64>,
+}
+
+impl VersionGraph {
+    fn vertex(&self, id: ID) -> Vertex {
+        let version = self.snapshot.unwrap_or_else(|| self.version.load(Ordering::SeqCst));
+        let mut properties = HashMap::new();
+        properties.insert("version".to_string(), Object::from(version));
+        let details = DefaultDetails::new_with_prop(id, Label::Id(0), properties);
+        Vertex::new(id, Some(Label::Id(0)), details)
+    }
+}
+
+impl GraphProxy for VersionGraph {
+    fn scan_vertex(
+        &self, _params: &QueryParams<Vertex>,
+    ) -> DynResult<Box<dyn Iterator<Item = Vertex> + Send>> {
+        let graph = self.clone();
+        Ok(Box::new((0..VERTICES).map(move |id| graph.vertex(id))))
+    }
+
+    fn get_vertex(
+        &self, ids: &[ID], _params: &QueryParams<Vertex>,
+    ) -> DynResult<Box<dyn Iterator<Item = Vertex> + Send>> {
+        let vertices = ids.iter().map(|id| self.vertex(*id)).collect::<Vec<_>>();
+        Ok(Box::new(vertices.into_iter()))
+    }
+
+    fn prepare_explore_vertex(
+        &self, _direction: Direction, _params: &QueryParams<Vertex>,
+    ) -> DynResult<Box<dyn Statement<ID, Vertex>>> {
+        let graph = self.clone();
+        let stmt = move |_id: ID| -> DynResult<DynIter<Vertex>> {
+            let graph = graph.clone();
+            Ok(Box::new((0..VERTICES).map(move |id| Ok(graph.vertex(id)))))
+        };
+        Ok(Box::new(stmt))
+    }
+
+    fn prepare_explore_edge(
+        &self, _direction: Direction, _params: &QueryParams<Edge>,
+    ) -> DynResult<Box<dyn Statement<ID, Edge>>> {
+        unimplemented!()
+    }
+
+    fn snapshot(&self) -> Arc<dyn GraphProxy> {
+        let version = self.version.load(Ordering::SeqCst);
+        Arc::new(VersionGraph { version: self.version.clone(), snapshot: Some(version) })
+    }
+}
+
+/// Submit the job, retrying while the executor started by `pegasus::startup()` in the background
+/// is not started yet, during which the jobs are rejected as if it has shutdown;
+fn submit<F: Fn(&mut Worker) -> Result<(), BuildJobError>>(conf: JobConf, build: F) -> JobGuard {
+    for _ in 0..100 {
+        match pegasus::run(conf.clone(), &build) {
+            Ok(guard) => return guard.expect("job not run"),
+            Err(e) if e.to_string().contains("shutdown") => {
+                std::thread::sleep(std::time::Duration::from_millis(10))
+            }
+            Err(e) => panic!("submit job failure: {}", e),
+        }
+    }
+    panic!("executor not started")
+}
+
+fn read_version(t: &Traverser) -> i64 {
+    let v = t.get_element().expect("expect vertex");
+    v.property("version").expect("version not found").as_i64().unwrap()
+}
+
+#[test]
+fn test_job_reads_snapshot_during_writes() {
+    pegasus::startup(Configuration::singleton()).unwrap();
+    let version = Arc::new(AtomicI64::new(0));
+    register_graph(Arc::new(VersionGraph { version: version.clone(), snapshot: None }));
+    let stop = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let version = version.clone();
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                version.fetch_add(1, Ordering::SeqCst);
+                std::thread::yield_now();
+            }
+        })
+    };
+
+    // g.V().out(), with the snapshot taken as the job is built, as is by `ProtoReflect`
+    let job_id = 1;
+    let mut source = GraphVertexStep::new(Requirement::empty());
+    let snapshot = snapshot_graph(job_id).expect("graph not registered");
+    let released = Arc::downgrade(&snapshot);
+    source.set_graph(snapshot);
+    let source = Arc::new(source);
+    let out = Arc::new(VertexStep::new(Direction::Out));
+    let (tx, rx) = std::sync::mpsc::channel();
+    let conf = JobConf::new(job_id, "test_job_reads_snapshot_during_writes", 2);
+    let build = |worker: &mut Worker| {
+        let index = worker.id.index;
+        let source = source.clone();
+        let out = out.clone();
+        let tx = tx.clone();
+        worker.dataflow(move |builder| {
+            builder
+                .input_from_iter(source.gen_source(index).fuse())?
+                .flat_map(Pipeline, out.gen())?
+                .sink_by(|_| {
+                    move |_, result| {
+                        if let ResultSet::Data(data) = result {
+                            tx.send(data).unwrap();
+                        }
+                    }
+                })
+        })
+    };
+    let mut guard = submit(conf, build);
+    guard.join().expect("job failure");
+    drop(tx);
+    let versions = rx.iter().flatten().map(|t| read_version(&t)).collect::<Vec<_>>();
+    stop.store(true, Ordering::SeqCst);
+    writer.join().unwrap();
+
+    // all the vertices are read from the snapshot, regardless of the writes during the job
+    assert_eq!(versions.len(), (VERTICES * VERTICES) as usize);
+    assert!(versions.iter().all(|v| *v == versions[0]));
+    assert!(version.load(Ordering::SeqCst) > versions[0]);
+
+    // the snapshot is released with the job, out of which the registered graph is read
+    drop(source);
+    assert!(released.upgrade().is_none());
+    let graph = get_graph().expect("graph not registered");
+    let v = graph.get_vertex(&[0], &QueryParams::new()).unwrap().next().unwrap();
+    assert!(v.property("version").unwrap().as_i64().unwrap() > versions[0]);
+}
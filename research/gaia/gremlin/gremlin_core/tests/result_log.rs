@@ -0,0 +1,96 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! The logger is global to a process, so the logs of encoding the results are captured in a
+//! test binary of its own.
+
+#[macro_use]
+extern crate lazy_static;
+
+use gremlin_core::process::traversal::path::{PathItem, ResultPath};
+use gremlin_core::process::traversal::traverser::Traverser;
+use gremlin_core::structure::{DefaultDetails, Label, Vertex};
+use gremlin_core::{Object, ResultEncoder};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pegasus_server::AnyData;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Captures the level of every record it is given.
+struct CaptureLogger {
+    levels: Mutex<Vec<Level>>,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.levels.lock().unwrap().push(record.level());
+    }
+
+    fn flush(&self) {}
+}
+
+lazy_static! {
+    static ref LOGGER: CaptureLogger = CaptureLogger { levels: Mutex::new(vec![]) };
+}
+
+fn build_vertex() -> Vertex {
+    let mut properties = HashMap::new();
+    properties.insert("name".to_string(), Object::from("marko"));
+    properties.insert("age".to_string(), Object::from(29));
+    let details = DefaultDetails::new_with_prop(1, Label::Str("person".to_string()), properties);
+    Vertex::new(1, Some(Label::Str("person".to_string())), details)
+}
+
+fn encode_results() {
+    let path = ResultPath::new(vec![
+        PathItem::OnGraph(build_vertex().into()),
+        PathItem::Detached(Object::from("marko")),
+    ]);
+    let results = vec![
+        vec![Traverser::new(build_vertex())],
+        vec![Traverser::from_object(Object::UnknownOwned(Box::new(path)))],
+        vec![Traverser::with(6u64)],
+    ];
+    for result in results {
+        let mut encoded = 0;
+        let mut encoder = ResultEncoder::new(1, |_| encoded += 1);
+        for t in result {
+            encoder.push(t).expect("encode result error");
+        }
+        encoder.finish();
+        assert_eq!(encoded, 1);
+    }
+}
+
+#[test]
+fn test_result_encoder_log_levels() {
+    log::set_logger(&*LOGGER).expect("set logger error");
+
+    // nothing is printed at the default level of the server
+    log::set_max_level(LevelFilter::Info);
+    encode_results();
+    assert!(LOGGER.levels.lock().unwrap().is_empty());
+
+    // the encoded results are only traced
+    log::set_max_level(LevelFilter::Trace);
+    encode_results();
+    let levels = LOGGER.levels.lock().unwrap();
+    assert!(!levels.is_empty());
+    assert!(levels.iter().all(|level| *level >= Level::Debug));
+}
@@ -0,0 +1,70 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+#![feature(test)]
+extern crate test;
+
+use pegasus::api::function::*;
+use pegasus::api::{Limit, OrderBy, Range, ResultSet, Sink};
+use pegasus::compare;
+use pegasus::{Configuration, JobConf, Tag};
+use test::Bencher;
+
+/// cargo +nightly bench --bench bench_top;
+
+const DATA_SIZE: u32 = 100_000;
+const TOP_K: u32 = 10;
+
+fn run_top(fused: bool) -> usize {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "bench_top", 1);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            let src = (0..DATA_SIZE).map(|i| (i * 7919) % DATA_SIZE);
+            let stream = dfb.input_from_iter(src)?;
+            let cmp = compare!(|a: &u32, b: &u32| a.cmp(b));
+            let stream = if fused {
+                stream.top_by(TOP_K, Range::Global, cmp)?
+            } else {
+                stream.sort_by(Range::Global, cmp)?.limit(Range::Global, TOP_K)?
+            };
+            stream.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<u32>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data.len()).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("run job failure;");
+    std::mem::drop(tx);
+    rx.iter().sum()
+}
+
+#[bench]
+fn bench_sort_then_limit(b: &mut Bencher) {
+    pegasus::startup(Configuration::singleton()).ok();
+    b.iter(|| run_top(false));
+}
+
+#[bench]
+fn bench_top_by(b: &mut Bencher) {
+    pegasus::startup(Configuration::singleton()).ok();
+    b.iter(|| run_top(true));
+}
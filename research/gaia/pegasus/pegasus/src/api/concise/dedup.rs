@@ -17,6 +17,7 @@ use crate::api::Range;
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 use pegasus_common::collections::{Drain, DrainSet, DrainSetFactory};
+use std::hash::Hash;
 
 pub trait Dedup<D: Data + Eq> {
     fn dedup<S>(&self, range: Range) -> Result<Stream<D>, BuildJobError>
@@ -28,4 +29,18 @@ pub trait Dedup<D: Data + Eq> {
     where
         S: DrainSetFactory<D> + 'static,
         <S::Target as Drain<D>>::Target: Send;
+
+    /// Keep the first data of each distinct key extracted by `key` and discard the rest, e.g.
+    /// Gremlin `dedup().by(k)`. Unlike `dedup`, it's not blocking, each first data is output as
+    /// soon as it arrives, where:
+    /// * `Range::Local`: each worker dedups the data it received in each scope independently;
+    /// * `Range::Global`: data are deduped locally first, then exchanged by the hash of keys, so
+    ///    data of the same key meet on one worker, which keeps the first one arrived;
+    ///
+    /// The seen keys of each scope are kept in memory until the scope ends, which grows with the
+    /// number of distinct keys;
+    fn dedup_by_key<K, F>(&self, range: Range, key: F) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Hash + Eq + Send + 'static,
+        F: Fn(&D) -> K + Clone + Send + 'static;
 }
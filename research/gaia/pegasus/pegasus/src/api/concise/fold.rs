@@ -13,6 +13,7 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use crate::api::Range;
 use crate::communication::Channel;
 use crate::errors::BuildJobError;
 use crate::stream::Stream;
@@ -23,4 +24,20 @@ pub trait Fold<I: Data, O: Data> {
     where
         C: Into<Channel<I>>,
         F: Fn(&mut O, I) + Send + 'static;
+
+    /// Fold the data of each scope in `range` into a value starting from `seed` by `func`, where:
+    /// * `Range::Local`: each worker folds the data it received independently, which is the same
+    ///    as `fold` through `Pipeline`;
+    /// * `Range::Global`: each worker folds its data into a partial value first, then the partial
+    ///    values of all workers are combined into one by `combine`, starting from `seed` too;
+    ///
+    /// For the global fold, `combine` must be associative, and `seed` must be the identity of
+    /// `combine`. It's not required to be commutative, as the partial values are always combined
+    /// in the order of worker index. Nothing is output for a scope receiving no data;
+    fn fold_in_range<F, M>(
+        &self, seed: O, range: Range, func: F, combine: M,
+    ) -> Result<Stream<O>, BuildJobError>
+    where
+        F: Fn(&mut O, I) + Send + 'static,
+        M: Fn(&mut O, O) + Send + 'static;
 }
@@ -20,6 +20,15 @@ use crate::stream::Stream;
 use crate::Data;
 use std::error::Error;
 
+/// Decide how to deal with the data failed in `flat_map_result`;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OnError {
+    /// Fail the job with the error;
+    Abort,
+    /// Discard the failed data with a warning, and go on with the rest;
+    Skip,
+}
+
 pub trait Map<I: Data> {
     fn map<O, C, F>(&self, channel: C, func: F) -> Result<Stream<O>, BuildJobError>
     where
@@ -50,4 +59,36 @@ pub trait Map<I: Data> {
         C: Into<Channel<I>>,
         R: Iterator<Item = Result<O, Box<dyn Error + Send>>> + Send + 'static,
         F: Fn(I) -> R + Send + 'static;
+
+    /// Flat map each data by `func` which may fail, e.g. parsing malformed records, where the
+    /// failure of a data is dealt with by `on_error`. With `OnError::Abort`, the first failure
+    /// fails the whole job, while the output of the data before it may have been sent. Unlike
+    /// `flat_map_with_fn`, the output of a data is produced in one go without failure;
+    fn flat_map_result<O, C, R, E, F>(
+        &self, channel: C, on_error: OnError, func: F,
+    ) -> Result<Stream<O>, BuildJobError>
+    where
+        O: Data,
+        C: Into<Channel<I>>,
+        R: IntoIterator<Item = O>,
+        E: Error + Send + 'static,
+        F: Fn(I) -> Result<R, E> + Send + 'static;
+
+    /// Map each data by `func` and keep the output if it is `Some`, dropping the data otherwise,
+    /// like `Iterator::filter_map`; It fuses a filter and a map into one operator, so that each
+    /// data is moved into `func` once, without the data kept by the filter being buffered and
+    /// passed to the map. The output stays in the scope of its input data, and a scope whose data
+    /// are all dropped still ends as usual;
+    fn filter_map<O, C, F>(&self, channel: C, func: F) -> Result<Stream<O>, BuildJobError>
+    where
+        O: Data,
+        C: Into<Channel<I>>,
+        F: Fn(I) -> Option<O> + Send + 'static;
+
+    /// Call `func` on each data and pass the data through unchanged, e.g. for logging or metrics,
+    /// like `Iterator::inspect`. Data are forwarded batch by batch through `Pipeline` as they
+    /// arrive, without buffering or changing the scopes;
+    fn inspect<F>(&self, func: F) -> Result<Stream<I>, BuildJobError>
+    where
+        F: Fn(&I) + Send + 'static;
 }
@@ -14,12 +14,14 @@
 //! limitations under the License.
 
 use crate::api::function::{CompareFunction, SumFunction};
+use pegasus_common::codec::{Decode, Encode, ReadExt, WriteExt};
 use pegasus_common::collections::{Collection, CollectionFactory};
 use pegasus_common::rc::RcPointer;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashSet;
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 pub trait Accumulator<I, O>: Send + Debug {
     fn accum(&mut self, next: I);
@@ -391,6 +393,12 @@ pub struct DataSumAccum<D, A: SumFunction<D>> {
     _ph: std::marker::PhantomData<D>,
 }
 
+impl<D, A: SumFunction<D>> DataSumAccum<D, A> {
+    pub fn new(add_func: A) -> Self {
+        DataSumAccum { add_func: RcPointer::new(add_func), _ph: std::marker::PhantomData }
+    }
+}
+
 impl<D: Send + Debug, A: SumFunction<D>> AccumFactory<D, Option<D>> for DataSumAccum<D, A> {
     type Target = DataSum<D, A>;
 
@@ -403,6 +411,373 @@ impl<D: Send + Debug, A: SumFunction<D>> AccumFactory<D, Option<D>> for DataSumA
     }
 }
 
+/// The running sum and count of the data being averaged, which is the output of `AvgAccum`.
+/// The mean is taken by `value` after all data are accumulated, as partial means of workers can
+/// only be merged correctly with their counts;
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Mean {
+    sum: f64,
+    count: u64,
+}
+
+impl Mean {
+    /// Get the mean of the accumulated data, or `None` if no data is accumulated;
+    pub fn value(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f64)
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl Encode for Mean {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_f64(self.sum)?;
+        writer.write_u64(self.count)
+    }
+}
+
+impl Decode for Mean {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let sum = reader.read_f64()?;
+        let count = reader.read_u64()?;
+        Ok(Mean { sum, count })
+    }
+}
+
+pub struct Average<D> {
+    mean: Mean,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D> Debug for Average<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "avg={:?}", self.mean.value())
+    }
+}
+
+impl<D: Into<f64> + Send> Accumulator<D, Mean> for Average<D> {
+    fn accum(&mut self, next: D) {
+        self.mean.sum += next.into();
+        self.mean.count += 1;
+    }
+
+    fn merge(&mut self, other: Mean) {
+        self.mean.sum += other.sum;
+        self.mean.count += other.count;
+    }
+
+    fn finalize(&mut self) -> Mean {
+        std::mem::replace(&mut self.mean, Mean::default())
+    }
+}
+
+/// Average the numeric data, e.g. Gremlin `mean()`. The output `Mean` keeps the sum and count,
+/// use `Mean::value` to get the average, which is `None` if no data is accumulated;
+pub struct AvgAccum<D> {
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D> AvgAccum<D> {
+    pub fn new() -> Self {
+        AvgAccum { _ph: std::marker::PhantomData }
+    }
+}
+
+impl<D: Into<f64> + Send> AccumFactory<D, Mean> for AvgAccum<D> {
+    type Target = Average<D>;
+
+    fn create(&self) -> Self::Target {
+        Average { mean: Mean::default(), _ph: std::marker::PhantomData }
+    }
+
+    fn is_associative(&self) -> bool {
+        true
+    }
+}
+
+pub const MIN_HLL_PRECISION: u8 = 4;
+pub const MAX_HLL_PRECISION: u8 = 16;
+
+/// A HyperLogLog sketch estimating the number of distinct data with `2^precision` registers of
+/// one byte each. The standard error of the estimation is about `1.04 / sqrt(2^precision)`, e.g.
+/// about 1.6% with precision 12 (4KB), or 0.8% with precision 14 (16KB).
+///
+/// Data are hashed by `DefaultHasher` which is the same on all workers, so sketches of the same
+/// precision built on different workers can be merged;
+#[derive(Clone, PartialEq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an empty sketch, panic if `precision` is not in
+    /// [`MIN_HLL_PRECISION`, `MAX_HLL_PRECISION`];
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            precision >= MIN_HLL_PRECISION && precision <= MAX_HLL_PRECISION,
+            "invalid hyperloglog precision {}",
+            precision
+        );
+        HyperLogLog { precision, registers: vec![0; 1 << precision] }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn insert<D: Hash + ?Sized>(&mut self, item: &D) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - self.precision)) as usize;
+        // the sentinel bit bounds the rank if the remaining bits are all zero;
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Merge `other` into this sketch, which is as if all data of `other` are inserted into this,
+    /// panic if the precisions are different;
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert_eq!(self.precision, other.precision, "merge hyperloglog of different precision");
+        for (r, o) in self.registers.iter_mut().zip(other.registers.iter()) {
+            if *r < *o {
+                *r = *o;
+            }
+        }
+    }
+
+    /// Estimate the number of distinct data inserted;
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let mut sum = 0.0;
+        let mut zeros = 0;
+        for r in self.registers.iter() {
+            sum += 1.0 / (1u64 << *r) as f64;
+            if *r == 0 {
+                zeros += 1;
+            }
+        }
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // linear counting for small cardinality;
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl Debug for HyperLogLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "hll(precision={}, estimate={})", self.precision, self.estimate())
+    }
+}
+
+impl Encode for HyperLogLog {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u8(self.precision)?;
+        writer.write_all(&self.registers)
+    }
+}
+
+impl Decode for HyperLogLog {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let precision = reader.read_u8()?;
+        if precision < MIN_HLL_PRECISION || precision > MAX_HLL_PRECISION {
+            let msg = format!("invalid hyperloglog precision {}", precision);
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, msg));
+        }
+        let mut registers = vec![0; 1 << precision];
+        reader.read_exact(&mut registers)?;
+        Ok(HyperLogLog { precision, registers })
+    }
+}
+
+/// A uniform random sample of at most `capacity` data by reservoir sampling, where each of the
+/// `seen` data inserted is kept with the same probability `capacity / seen`. All data are kept if
+/// no more than `capacity` data are inserted.
+///
+/// Random numbers are generated by `splitmix64` from the seed, so the sample is deterministic for
+/// the same seed and the same input order;
+#[derive(Clone, Debug)]
+pub struct Reservoir<D> {
+    capacity: usize,
+    seen: u64,
+    items: Vec<D>,
+    rng: u64,
+}
+
+impl<D> Reservoir<D> {
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        Reservoir { capacity, seen: 0, items: Vec::new(), rng: seed }
+    }
+
+    /// Mix `salt` into the random seed, e.g. the worker index, so that reservoirs created with
+    /// the same seed on different workers draw different random numbers;
+    pub fn salt(&mut self, salt: u64) {
+        self.rng ^= salt.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        self.next_u64();
+    }
+
+    #[inline]
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.seen == 0
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random number in [0, bound), `bound` must be positive;
+    #[inline]
+    fn next_below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    pub fn insert(&mut self, item: D) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+        } else {
+            let index = self.next_below(self.seen);
+            if (index as usize) < self.capacity {
+                self.items[index as usize] = item;
+            }
+        }
+    }
+
+    /// Merge the sample of `other` into this, which is a uniform sample of all data inserted into
+    /// both. Each kept data is drawn from one of the two samples with the probability weighted by
+    /// the number of data not drawn yet it represents, without replacement;
+    pub fn merge(&mut self, mut other: Reservoir<D>) {
+        if other.seen == 0 {
+            return;
+        }
+        let mut left = std::mem::replace(&mut self.items, Vec::new());
+        let (mut left_seen, mut right_seen) = (self.seen, other.seen);
+        let size = std::cmp::min(self.capacity, left.len() + other.items.len());
+        let mut items = Vec::with_capacity(size);
+        // if a sample is used up, all data it represents are drawn, as it is only smaller than
+        // capacity when it keeps all its data;
+        while items.len() < size {
+            let from_left = self.next_below(left_seen + right_seen) < left_seen;
+            let source = if from_left { &mut left } else { &mut other.items };
+            let index = self.next_below(source.len() as u64) as usize;
+            items.push(source.swap_remove(index));
+            if from_left {
+                left_seen -= 1;
+            } else {
+                right_seen -= 1;
+            }
+        }
+        self.items = items;
+        self.seen += other.seen;
+    }
+
+    pub fn into_items(self) -> Vec<D> {
+        self.items
+    }
+}
+
+impl<D: Encode> Encode for Reservoir<D> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u64(self.capacity as u64)?;
+        writer.write_u64(self.seen)?;
+        writer.write_u64(self.rng)?;
+        self.items.write_to(writer)
+    }
+}
+
+impl<D: Decode> Decode for Reservoir<D> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let capacity = reader.read_u64()? as usize;
+        let seen = reader.read_u64()?;
+        let rng = reader.read_u64()?;
+        let items = Vec::<D>::read_from(reader)?;
+        Ok(Reservoir { capacity, seen, items, rng })
+    }
+}
+
+pub struct ApproxDistinct<D> {
+    sketch: HyperLogLog,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D> Debug for ApproxDistinct<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.sketch)
+    }
+}
+
+impl<D: Hash + Send> Accumulator<D, HyperLogLog> for ApproxDistinct<D> {
+    fn accum(&mut self, next: D) {
+        self.sketch.insert(&next);
+    }
+
+    fn merge(&mut self, other: HyperLogLog) {
+        self.sketch.merge(&other);
+    }
+
+    fn finalize(&mut self) -> HyperLogLog {
+        let empty = HyperLogLog::new(self.sketch.precision);
+        std::mem::replace(&mut self.sketch, empty)
+    }
+}
+
+/// Estimate the number of distinct data by `HyperLogLog` with bounded memory, instead of keeping
+/// all distinct data as `HashSetAccum`. Use `HyperLogLog::estimate` to get the estimation;
+pub struct ApproxDistinctAccum<D> {
+    precision: u8,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D> ApproxDistinctAccum<D> {
+    /// Create the accumulator factory, panic if `precision` is not in
+    /// [`MIN_HLL_PRECISION`, `MAX_HLL_PRECISION`];
+    pub fn new(precision: u8) -> Self {
+        assert!(precision >= MIN_HLL_PRECISION && precision <= MAX_HLL_PRECISION);
+        ApproxDistinctAccum { precision, _ph: std::marker::PhantomData }
+    }
+}
+
+impl<D: Hash + Send> AccumFactory<D, HyperLogLog> for ApproxDistinctAccum<D> {
+    type Target = ApproxDistinct<D>;
+
+    fn create(&self) -> Self::Target {
+        ApproxDistinct { sketch: HyperLogLog::new(self.precision), _ph: std::marker::PhantomData }
+    }
+
+    fn is_associative(&self) -> bool {
+        true
+    }
+}
+
 pub struct ToCollection<D: Send, C: Collection<D>> {
     collect: C,
     _ph: std::marker::PhantomData<D>,
@@ -452,3 +827,144 @@ where
         ToCollection { collect, _ph: std::marker::PhantomData }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn avg_accum_test() {
+        let factory = AvgAccum::<i32>::new();
+        let mut avg = factory.create();
+        assert_eq!(avg.finalize().value(), None);
+
+        for i in vec![-3, 7, 2, -2] {
+            avg.accum(i);
+        }
+        let mut other = factory.create();
+        other.accum(11);
+        avg.merge(other.finalize());
+        let mean = avg.finalize();
+        assert_eq!(mean.count(), 5);
+        assert_eq!(mean.value(), Some(3.0));
+        // accumulator is reset after finalize;
+        assert_eq!(avg.finalize().value(), None);
+    }
+
+    fn assert_estimate(estimate: u64, expected: u64, tolerance: f64) {
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < tolerance, "estimate {} of {} exceeds tolerance", estimate, expected);
+    }
+
+    #[test]
+    fn hll_estimate_test() {
+        let mut hll = HyperLogLog::new(12);
+        assert_eq!(hll.estimate(), 0);
+        for i in 0..100u32 {
+            hll.insert(&i);
+            hll.insert(&i);
+        }
+        assert_estimate(hll.estimate(), 100, 0.05);
+        for i in 0..100_000u32 {
+            hll.insert(&i);
+        }
+        // 1.6% standard error of precision 12, assert within 3 sigma;
+        assert_estimate(hll.estimate(), 100_000, 0.05);
+    }
+
+    #[test]
+    fn hll_merge_test() {
+        let factory = ApproxDistinctAccum::<u64>::new(14);
+        let mut left = factory.create();
+        let mut right = factory.create();
+        let mut all = factory.create();
+        for i in 0..50_000u64 {
+            left.accum(i);
+            all.accum(i);
+        }
+        for i in 25_000..75_000u64 {
+            right.accum(i);
+            all.accum(i);
+        }
+        left.merge(right.finalize());
+        let merged = left.finalize();
+        assert!(merged == all.finalize());
+        assert_estimate(merged.estimate(), 75_000, 0.03);
+    }
+
+    #[test]
+    fn hll_codec_test() {
+        let mut hll = HyperLogLog::new(4);
+        hll.insert("a");
+        hll.insert("b");
+        let mut bytes = vec![];
+        hll.write_to(&mut bytes).unwrap();
+        let decoded = HyperLogLog::read_from(&mut &bytes[..]).unwrap();
+        assert!(decoded == hll);
+    }
+
+    #[test]
+    fn reservoir_sample_test() {
+        let mut few = Reservoir::new(10, 7);
+        for i in 0..5u32 {
+            few.insert(i);
+        }
+        assert_eq!(few.into_items(), vec![0, 1, 2, 3, 4]);
+
+        let mut sample = Reservoir::new(10, 7);
+        let mut same = Reservoir::new(10, 7);
+        for i in 0..1000u32 {
+            sample.insert(i);
+            same.insert(i);
+        }
+        assert_eq!(sample.seen(), 1000);
+        let items = sample.into_items();
+        assert_eq!(items.len(), 10);
+        assert_eq!(items, same.into_items());
+    }
+
+    #[test]
+    fn reservoir_merge_test() {
+        let mut left = Reservoir::new(10, 1);
+        let mut right = Reservoir::new(10, 2);
+        for i in 0..3u32 {
+            left.insert(i);
+        }
+        for i in 100..1000u32 {
+            right.insert(i);
+        }
+        left.merge(right);
+        assert_eq!(left.seen(), 903);
+        let items = left.into_items();
+        assert_eq!(items.len(), 10);
+        let mut distinct = items.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 10);
+
+        let mut empty = Reservoir::new(10, 3);
+        let mut small = Reservoir::new(10, 4);
+        small.insert(1u32);
+        small.insert(2u32);
+        empty.merge(small);
+        let mut items = empty.into_items();
+        items.sort();
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[test]
+    fn reservoir_codec_test() {
+        let mut sample = Reservoir::new(3, 5);
+        for i in 0..10u32 {
+            sample.insert(i);
+        }
+        let mut bytes = vec![];
+        sample.write_to(&mut bytes).unwrap();
+        let mut decoded = Reservoir::<u32>::read_from(&mut &bytes[..]).unwrap();
+        assert_eq!(decoded.seen(), 10);
+        // the random state is kept, so both draw the same afterwards;
+        sample.insert(10);
+        decoded.insert(10);
+        assert_eq!(decoded.into_items(), sample.into_items());
+    }
+}
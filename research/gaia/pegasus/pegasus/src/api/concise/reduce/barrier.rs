@@ -15,8 +15,71 @@
 
 use crate::api::Range;
 use crate::stream::Stream;
-use crate::{BuildJobError, Data};
+use crate::{BuildJobError, Data, Tag};
+use pegasus_common::codec::{Decode, Encode, ReadExt, WriteExt};
 use pegasus_common::collections::{Collection, CollectionFactory};
+use std::time::Duration;
+
+/// The output of `barrier_with_timeout`, which tells whether all data of the scope are collected;
+#[derive(Clone, Debug, PartialEq)]
+pub enum BarrierResult<C> {
+    /// All data of the scope are collected;
+    Complete(C),
+    /// The timeout elapsed before the end of the scope, only the data arrived before are
+    /// collected, and the data arrive after are discarded;
+    Partial(C),
+}
+
+impl<C> BarrierResult<C> {
+    pub fn is_partial(&self) -> bool {
+        matches!(self, BarrierResult::Partial(_))
+    }
+
+    pub fn into_inner(self) -> C {
+        match self {
+            BarrierResult::Complete(c) => c,
+            BarrierResult::Partial(c) => c,
+        }
+    }
+}
+
+impl<C: Encode> Encode for BarrierResult<C> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            BarrierResult::Complete(c) => {
+                writer.write_u8(0)?;
+                c.write_to(writer)
+            }
+            BarrierResult::Partial(c) => {
+                writer.write_u8(1)?;
+                c.write_to(writer)
+            }
+        }
+    }
+}
+
+impl<C: Decode> Decode for BarrierResult<C> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let mode = reader.read_u8()?;
+        let c = C::read_from(reader)?;
+        if mode == 0 {
+            Ok(BarrierResult::Complete(c))
+        } else {
+            Ok(BarrierResult::Partial(c))
+        }
+    }
+}
+
+/// A snapshot of the data buffered by a barrier in a scope, see `barrier_with_metrics`;
+#[derive(Clone, Debug, PartialEq)]
+pub struct BarrierMetrics {
+    pub tag: Tag,
+    /// The number of data buffered in the scope;
+    pub count: usize,
+    /// The estimated bytes of the data buffered, which is `count` times the average encoded size
+    /// of the data sampled in the scope;
+    pub bytes: usize,
+}
 
 pub trait Barrier<D: Data> {
     fn barrier<C>(&self, range: Range) -> Result<Stream<C>, BuildJobError>
@@ -27,4 +90,33 @@ pub trait Barrier<D: Data> {
     where
         C: CollectionFactory<D> + 'static,
         C::Target: Data;
+
+    /// Same as `barrier`, but doesn't wait for the end of a scope longer than `timeout`, which is
+    /// timed from the scope opens, i.e. the start of the operator for the root scope, or the first
+    /// datum of the scope arrives for a nested scope. Once the timeout elapses, the data collected
+    /// so far are flushed as `BarrierResult::Partial`, and the upstream of the scope is canceled,
+    /// any data of the scope arrive later are discarded. Otherwise all data of the scope are output
+    /// as `BarrierResult::Complete` at the end of the scope, which is empty if the scope receives
+    /// no data.
+    ///
+    /// For `Range::Global`, the timeout is checked on the worker aggregating all data, so the data
+    /// of a slow worker may be missing in the partial result;
+    fn barrier_with_timeout<C>(
+        &self, range: Range, timeout: Duration,
+    ) -> Result<Stream<BarrierResult<C>>, BuildJobError>
+    where
+        C: Collection<D> + Data + Default + 'static;
+
+    /// Same as `barrier`, but reports the data buffered in each scope to `hook`, e.g. to find the
+    /// skewed scopes taking too much memory. The report is made every `interval` data buffered in
+    /// a scope, and once more before the scope is flushed. To bound the overhead, only the first
+    /// datum and every `interval`-th datum of a scope are encoded to sample the size of data.
+    ///
+    /// Return a build error if `interval` is 0;
+    fn barrier_with_metrics<C, M>(
+        &self, range: Range, interval: usize, hook: M,
+    ) -> Result<Stream<C>, BuildJobError>
+    where
+        C: Collection<D> + Data + Default + 'static,
+        M: Fn(&BarrierMetrics) + Send + 'static;
 }
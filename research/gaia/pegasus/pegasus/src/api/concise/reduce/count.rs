@@ -13,11 +13,53 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::concise::reduce::Range;
+use crate::api::concise::reduce::{GlobalCombine, Range};
 use crate::errors::BuildJobError;
 use crate::stream::Stream;
 use crate::Data;
+use std::hash::Hash;
 
 pub trait Count<D: Data> {
+    /// Count the number of data in the stream with the given `range`, e.g.
+    /// `stream.count(Range::Global)`, where:
+    /// * `Range::Local`: each worker counts the data it received in each scope independently, and
+    ///    outputs one count per scope;
+    /// * `Range::Global`: the local counts of all workers are aggregated into one worker, which
+    ///    outputs the total count per scope;
+    ///
+    /// Note that nothing is output for a scope that receives no data;
     fn count(&self, range: Range) -> Result<Stream<u64>, BuildJobError>;
+
+    /// Count the number of data in the stream as `count(Range::Global)` does, but the local
+    /// counts of the workers are combined by `combine`, see [`GlobalCombine`]. Return a build
+    /// error if the fanout of `GlobalCombine::Tree` is less than 2;
+    fn count_global_with(&self, combine: GlobalCombine) -> Result<Stream<u64>, BuildJobError>;
+
+    /// Count the number of data satisfying the predicate `func` in the stream, which is fused into
+    /// the counting operator, without an extra filter operator. The `range` works as in `count`,
+    /// but unlike `count`, a scope in which no data satisfies the predicate, including a scope
+    /// receiving no data at all, is counted as 0;
+    fn count_if<F>(&self, range: Range, func: F) -> Result<Stream<u64>, BuildJobError>
+    where
+        F: Fn(&D) -> bool + Send + 'static;
+
+    /// Count the data in the stream with weights, where each data is counted as `func(&data)`,
+    /// e.g. the multiplicity of the data which represents some identical data. The `range` works
+    /// as in `count`;
+    fn count_weighted<F>(&self, range: Range, func: F) -> Result<Stream<u64>, BuildJobError>
+    where
+        F: Fn(&D) -> u64 + Send + 'static;
+
+    /// Estimate the number of distinct data in the stream by `HyperLogLog` with `2^precision`
+    /// registers, which takes bounded memory regardless of the number of distinct data. The
+    /// standard error of the estimation is about `1.04 / sqrt(2^precision)`. The `range` works as
+    /// in `count`, and for `Range::Global` the sketches of all workers are merged, so the data
+    /// appear on different workers are counted once.
+    ///
+    /// Return a build error if `precision` is not in [`MIN_HLL_PRECISION`, `MAX_HLL_PRECISION`];
+    fn count_distinct_approx(
+        &self, range: Range, precision: u8,
+    ) -> Result<Stream<u64>, BuildJobError>
+    where
+        D: Hash;
 }
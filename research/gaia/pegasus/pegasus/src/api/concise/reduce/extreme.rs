@@ -0,0 +1,49 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::concise::reduce::Range;
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::Data;
+
+/// Which one of the data with the same extreme key is kept;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Tie {
+    /// Keep the first one arrived;
+    First,
+    /// Keep the last one arrived;
+    Last,
+}
+
+pub trait Extreme<D: Data> {
+    /// Output the data with the minimum key extracted by `key` of each scope, where:
+    /// * `Range::Local`: each worker outputs the minimum of the data it received;
+    /// * `Range::Global`: the minimums of all workers are combined in the order of worker index,
+    ///    and one worker outputs the minimum of all;
+    ///
+    /// Of the data with the same minimum key, the first or the last one is kept by `tie`, where
+    /// the data on a worker of smaller index are regarded as arriving earlier for the global
+    /// range. Nothing is output for a scope receiving no data;
+    fn min_by_key<K, F>(&self, range: Range, key: F, tie: Tie) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Ord,
+        F: Fn(&D) -> K + Clone + Send + 'static;
+
+    /// Output the data with the maximum key extracted by `key` of each scope, see `min_by_key`;
+    fn max_by_key<K, F>(&self, range: Range, key: F, tie: Tie) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Ord,
+        F: Fn(&D) -> K + Clone + Send + 'static;
+}
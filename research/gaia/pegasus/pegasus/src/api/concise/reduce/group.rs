@@ -15,11 +15,16 @@
 
 use crate::api::accum::AccumFactory;
 use crate::api::function::{KeyFunction, Pair};
-use crate::api::Range;
+use crate::api::OrderDirect;
+use crate::api::{GlobalCombine, Range, SpillConf};
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 
 pub trait Group<D: Data> {
+    /// Group the data by `key`, and aggregate the data of each group into a value by the
+    /// accumulator created by `accum_gen`, e.g. `CountAccum`, `DataSumAccum`, `MaxAccum`, etc.
+    /// Each datum is folded into its group's accumulator once received, so the members of groups
+    /// are not retained unless the accumulator keeps them, e.g. `ToListAccum`;
     fn group_by_with_accum<F, A, O>(
         &self, range: Range, key: F, accum_gen: A,
     ) -> Result<Stream<Pair<F::Target, O>>, BuildJobError>
@@ -29,9 +34,57 @@ pub trait Group<D: Data> {
         A: AccumFactory<D, O> + 'static,
         A::Target: 'static;
 
+    /// Group the data globally as `group_by_with_accum(Range::Global, ..)` does, but the partial
+    /// groups of the workers are merged by `combine`, see [`GlobalCombine`]. Only the partial
+    /// groups of an associative accumulator can be merged, so the data of a non-associative one
+    /// are always sent to one worker to be grouped, as `GlobalCombine::Linear`. Return a build
+    /// error if the fanout of `GlobalCombine::Tree` is less than 2;
+    fn group_by_global_with<F, A, O>(
+        &self, key: F, accum_gen: A, combine: GlobalCombine,
+    ) -> Result<Stream<Pair<F::Target, O>>, BuildJobError>
+    where
+        O: Data,
+        F: KeyFunction<D>,
+        A: AccumFactory<D, O> + 'static,
+        A::Target: 'static;
+
+    /// Group the data by `key`, and collect the members of each group into a list;
     fn group_by<F>(
         &self, range: Range, key: F,
     ) -> Result<Stream<Pair<F::Target, Vec<D>>>, BuildJobError>
     where
         F: KeyFunction<D>;
+
+    /// Same as `group_by`, but the members of a group are spilled to disk once too many of them
+    /// are buffered, e.g. the skewed groups of `group().by(country)`. Memory is accounted per key:
+    /// each group keeps at most `conf.threshold` members in memory, and spills them as a new file
+    /// once reaching, so only the oversized groups are spilled no matter how many keys there are.
+    ///
+    /// The members are read back in the order they arrive when their group is output, and the
+    /// groups are output one by one, so only one spilled group is loaded at a time. If a spill
+    /// fails, the members are kept in memory with a warning; If a spill file fails to be read
+    /// back, the job fails. Return a build error if the threshold is 0;
+    fn group_by_with_spill<F>(
+        &self, range: Range, key: F, conf: SpillConf,
+    ) -> Result<Stream<Pair<F::Target, Vec<D>>>, BuildJobError>
+    where
+        F: KeyFunction<D>;
+
+    /// Same as `group_by_with_accum`, but the groups are output in the order of their keys by
+    /// `order`. For `Range::Global`, all groups are merged on one worker before sorting, so the
+    /// output is totally ordered across workers; for `Range::Local`, groups are sorted within
+    /// each worker.
+    ///
+    /// The keys are only required to be `PartialOrd`, so whether they are comparable can't be
+    /// decided until the groups are built. If any two keys are not comparable, the job fails with
+    /// `UnorderedKeyError` instead of outputting groups in an arbitrary order;
+    fn group_by_ordered<F, A, O>(
+        &self, range: Range, key: F, accum_gen: A, order: OrderDirect,
+    ) -> Result<Stream<Pair<F::Target, O>>, BuildJobError>
+    where
+        O: Data,
+        F: KeyFunction<D>,
+        F::Target: PartialOrd,
+        A: AccumFactory<D, O> + 'static,
+        A::Target: 'static;
 }
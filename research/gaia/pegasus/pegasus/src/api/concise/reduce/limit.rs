@@ -20,4 +20,11 @@ use crate::Data;
 
 pub trait Limit<D: Data> {
     fn limit(&self, range: Range, size: u32) -> Result<Stream<D>, BuildJobError>;
+
+    /// Skip the first `offset` data and output at most `size` data after them, which is used for
+    /// pagination, e.g. `range(offset, offset + size)` in gremlin. With `Range::Global`, the data
+    /// of all workers are ordered by the index of the worker they come from, keeping the order
+    /// within each worker, before skipping, so the result is deterministic regardless of the order
+    /// they arrive. An empty stream is output if there are no more than `offset` data;
+    fn range(&self, range: Range, offset: u32, size: u32) -> Result<Stream<D>, BuildJobError>;
 }
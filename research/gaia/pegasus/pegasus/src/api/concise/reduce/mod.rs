@@ -18,9 +18,12 @@ use pegasus_common::downcast::*;
 pub mod accum;
 pub mod barrier;
 pub mod count;
+pub mod extreme;
 pub mod group;
 pub mod limit;
 pub mod order;
+pub mod sample;
+pub mod spill;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Range {
@@ -31,8 +34,33 @@ pub enum Range {
 impl_as_any!(Range);
 pub const RANGES: [Range; 2] = [Range::Local, Range::Global];
 
-pub use barrier::Barrier;
+/// How the partial results of all workers are combined into one for `Range::Global`;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GlobalCombine {
+    /// All the partial results are sent to one worker and combined there, which is the default,
+    /// as it takes the least steps, while the worker may become a bottleneck with many workers;
+    Linear,
+    /// The partial results are combined level by level in a tree of `fanout` children per node:
+    /// on each level, the partial results of each `fanout` workers holding them are sent to the
+    /// first of them and combined there, until they are combined into one on the worker 0. So no
+    /// worker receives more than `fanout` partial results on each level, at the cost of
+    /// `ceil(log_fanout(peers))` levels. It only differs from `Linear` if there are more than
+    /// `fanout` workers, otherwise there is only one level, which is the same as `Linear`. The
+    /// `fanout` should be at least 2;
+    Tree { fanout: u32 },
+}
+
+impl Default for GlobalCombine {
+    fn default() -> Self {
+        GlobalCombine::Linear
+    }
+}
+
+pub use barrier::{Barrier, BarrierMetrics, BarrierResult};
 pub use count::Count;
+pub use extreme::{Extreme, Tie};
 pub use group::Group;
 pub use limit::Limit;
 pub use order::{Order, OrderBy, OrderDirect};
+pub use sample::Sample;
+pub use spill::SpillConf;
@@ -13,8 +13,8 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::function::CompareFunction;
-use crate::api::Range;
+use crate::api::function::{CompareFunction, KeyExtractor, MultiKeyCompare};
+use crate::api::{GlobalCombine, Range, SpillConf};
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 
@@ -29,14 +29,58 @@ pub trait Order<D: Data + Ord> {
 
     fn top(&self, limit: u32, range: Range, order: OrderDirect)
         -> Result<Stream<D>, BuildJobError>;
+
+    /// Output the first `limit` data globally as `top(limit, Range::Global, order)` does, but the
+    /// local tops of the workers are combined by `combine`, see [`GlobalCombine`]. Return a build
+    /// error if `limit` is 0, or the fanout of `GlobalCombine::Tree` is less than 2;
+    fn top_global_with(
+        &self, limit: u32, order: OrderDirect, combine: GlobalCombine,
+    ) -> Result<Stream<D>, BuildJobError>;
 }
 
 pub trait OrderBy<D: Data> {
+    /// Sort the data by the comparator `cmp`, the sorting is stable, which means the data equal
+    /// under `cmp` keep the order they arrive. As the arriving order may vary across runs, use
+    /// `ThenBy::then_by` to break the tie if a deterministic order is required;
     fn sort_by<F>(&self, range: Range, cmp: F) -> Result<Stream<D>, BuildJobError>
     where
         F: CompareFunction<D> + 'static;
 
+    /// Output the first `limit` data sorted by `cmp`, which is the fused `sort_by` and `limit`.
+    /// Only a bounded heap of size `limit` is maintained instead of sorting all the data, and
+    /// it is equivalent to `sort_by` if `limit` is no less than the number of data;
     fn top_by<F>(&self, limit: u32, range: Range, cmp: F) -> Result<Stream<D>, BuildJobError>
     where
         F: CompareFunction<D> + 'static;
+
+    /// Same as `sort_by`, but sorts externally if there are too many data to hold in memory. Once
+    /// `conf.threshold` data are buffered in a scope, they are sorted and spilled to disk as a
+    /// sorted run, and all runs are merged at the end of the scope. The sorting is still stable.
+    ///
+    /// If a spill fails, e.g. the disk is full, the data are kept in memory with a warning; If a
+    /// spill file fails to be read back, the job fails. Return a build error if the threshold
+    /// is 0;
+    fn sort_by_with_spill<F>(
+        &self, range: Range, cmp: F, conf: SpillConf,
+    ) -> Result<Stream<D>, BuildJobError>
+    where
+        F: CompareFunction<D> + 'static;
+
+    /// Sort the data by multiple keys, each in its own direction, e.g. the
+    /// `order().by(a, asc).by(b, desc)` in gremlin; The data equal under all the keys keep the
+    /// order they arrive, see `MultiKeyCompare`;
+    fn sort_by_keys<K: Ord + 'static>(
+        &self, range: Range, keys: Vec<(KeyExtractor<D, K>, OrderDirect)>,
+    ) -> Result<Stream<D>, BuildJobError> {
+        self.sort_by(range, MultiKeyCompare::new(keys))
+    }
+
+    /// Output the first `limit` data sorted by multiple keys, each in its own direction; It is
+    /// the fused `sort_by_keys` and `limit`, while which of the data equal under all the keys
+    /// are kept at the boundary is undefined;
+    fn top_by_keys<K: Ord + 'static>(
+        &self, limit: u32, range: Range, keys: Vec<(KeyExtractor<D, K>, OrderDirect)>,
+    ) -> Result<Stream<D>, BuildJobError> {
+        self.top_by(limit, range, MultiKeyCompare::new(keys))
+    }
 }
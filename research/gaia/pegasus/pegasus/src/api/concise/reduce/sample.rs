@@ -0,0 +1,34 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::concise::reduce::Range;
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::Data;
+
+pub trait Sample<D: Data> {
+    /// Sample at most `n` data uniformly at random from each scope by reservoir sampling, where:
+    /// * `Range::Local`: each worker samples the data it received independently;
+    /// * `Range::Global`: the samples of all workers are merged into one worker, weighted by the
+    ///    number of data each worker received, so each data of the scope is sampled with the same
+    ///    probability;
+    ///
+    /// All data are output if there are no more than `n` data. With a `seed`, the sample is
+    /// deterministic if each worker receives the same data in the same order, otherwise the seed
+    /// is taken from the system time.
+    ///
+    /// Return a build error if `n` is 0;
+    fn sample(&self, n: u32, range: Range, seed: Option<u64>) -> Result<Stream<D>, BuildJobError>;
+}
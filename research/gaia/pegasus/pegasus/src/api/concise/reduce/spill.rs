@@ -0,0 +1,41 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::path::PathBuf;
+
+/// Configure the blocking operators to spill the data buffered to disk, e.g.
+/// `OrderBy::sort_by_with_spill`, so the data of a scope are not limited by memory.
+///
+/// Each spill is written into a new file named with prefix `pegasus_spill_` in `dir`, which is
+/// removed once its data are read back or the operator is dropped, e.g. the job is canceled. Only
+/// the files of a crashed process may be left in `dir`;
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpillConf {
+    /// The max number of data buffered in memory of a scope, the data are spilled once reaching;
+    pub threshold: usize,
+    /// The directory of spill files, which is `std::env::temp_dir()` by default;
+    pub dir: PathBuf,
+}
+
+impl SpillConf {
+    pub fn new(threshold: usize) -> Self {
+        SpillConf { threshold, dir: std::env::temp_dir() }
+    }
+
+    pub fn with_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.dir = dir.into();
+        self
+    }
+}
@@ -13,6 +13,7 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use crate::api::OrderDirect;
 use crate::Data;
 use std::borrow::Cow;
 use std::cmp::Ordering;
@@ -73,10 +74,54 @@ pub trait FilterFunction<D>: Send + 'static {
 
 pub trait LeftJoinFunction<D>: Send + 'static {
     fn exec(&self, left: &D, right: D) -> Option<D>;
+
+    /// Return `true` if the join folds all the right data of a left into the left by [`fold`],
+    /// and produces at most one result of the left by [`end`], other than producing a result by
+    /// [`exec`] on each right data; Default is `false`;
+    ///
+    /// [`fold`]: LeftJoinFunction::fold
+    /// [`end`]: LeftJoinFunction::end
+    /// [`exec`]: LeftJoinFunction::exec
+    fn is_fold(&self) -> bool {
+        false
+    }
+
+    /// Fold the `right` into the `left`, where `folded` is the number of right data already folded
+    /// into the `left`;
+    fn fold(&self, _left: &mut D, _folded: usize, _right: D) {}
+
+    /// Produce the result of the `left` once all its right data are folded, where `folded` is the
+    /// number of the right data, which may be zero;
+    fn end(&self, left: D, _folded: usize) -> Option<D> {
+        Some(left)
+    }
+
+    /// Return `true` if the join keeps all the right data of a left, and produces the results of
+    /// the left from them as a whole by [`collect`] once they are all received, other than by
+    /// [`exec`] on each right data or by [`fold`]; It takes precedence over [`is_fold`]; Default is
+    /// `false`;
+    ///
+    /// [`collect`]: LeftJoinFunction::collect
+    /// [`exec`]: LeftJoinFunction::exec
+    /// [`fold`]: LeftJoinFunction::fold
+    /// [`is_fold`]: LeftJoinFunction::is_fold
+    fn is_collect(&self) -> bool {
+        false
+    }
+
+    /// Produce the results of the `left` from all its `right` data, which are in the order they
+    /// are received, and may be empty; Default joins each right data by [`exec`];
+    ///
+    /// [`exec`]: LeftJoinFunction::exec
+    fn collect(&self, left: D, right: Vec<D>) -> Vec<D> {
+        right.into_iter().filter_map(|r| self.exec(&left, r)).collect()
+    }
 }
 
 pub trait EncodeFunction<D>: Send + 'static {
-    fn encode(&self, data: Vec<D>) -> Vec<u8>;
+    /// Encode a batch of results into bytes, return `Err` if the results can't be encoded, which
+    /// is reported to the client as an error, rather than as an empty batch;
+    fn encode(&self, data: Vec<D>) -> FnResult<Vec<u8>>;
 }
 
 ///
@@ -139,10 +184,30 @@ impl<D, L: LeftJoinFunction<D> + ?Sized> LeftJoinFunction<D> for Box<L> {
     fn exec(&self, left: &D, right: D) -> Option<D> {
         (**self).exec(left, right)
     }
+
+    fn is_fold(&self) -> bool {
+        (**self).is_fold()
+    }
+
+    fn fold(&self, left: &mut D, folded: usize, right: D) {
+        (**self).fold(left, folded, right)
+    }
+
+    fn end(&self, left: D, folded: usize) -> Option<D> {
+        (**self).end(left, folded)
+    }
+
+    fn is_collect(&self) -> bool {
+        (**self).is_collect()
+    }
+
+    fn collect(&self, left: D, right: Vec<D>) -> Vec<D> {
+        (**self).collect(left, right)
+    }
 }
 
 impl<D, E: EncodeFunction<D> + ?Sized> EncodeFunction<D> for Box<E> {
-    fn encode(&self, data: Vec<D>) -> Vec<u8> {
+    fn encode(&self, data: Vec<D>) -> FnResult<Vec<u8>> {
         (**self).encode(data)
     }
 }
@@ -155,6 +220,26 @@ impl<D: Data, T: LeftJoinFunction<D> + Sync + ?Sized> LeftJoinFunction<D> for Ar
     fn exec(&self, parent: &D, sub: D) -> Option<D> {
         (**self).exec(parent, sub)
     }
+
+    fn is_fold(&self) -> bool {
+        (**self).is_fold()
+    }
+
+    fn fold(&self, parent: &mut D, folded: usize, sub: D) {
+        (**self).fold(parent, folded, sub)
+    }
+
+    fn end(&self, parent: D, folded: usize) -> Option<D> {
+        (**self).end(parent, folded)
+    }
+
+    fn is_collect(&self) -> bool {
+        (**self).is_collect()
+    }
+
+    fn collect(&self, parent: D, subs: Vec<D>) -> Vec<D> {
+        (**self).collect(parent, subs)
+    }
 }
 
 /// impl functions for closure;
@@ -349,6 +434,96 @@ where
     }
 }
 
+pub struct SumClosure<D, F: Fn(&mut D, D) + Send> {
+    func: F,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Send + 'static, F> SumFunction<D> for SumClosure<D, F>
+where
+    F: Fn(&mut D, D) + Send + 'static,
+{
+    fn add(&self, seed: &mut D, next: D) {
+        (self.func)(seed, next)
+    }
+}
+
+impl<D, F> SumClosure<D, F>
+where
+    F: Fn(&mut D, D) + Send,
+{
+    pub fn new(func: F) -> Self {
+        SumClosure { func, _ph: std::marker::PhantomData }
+    }
+}
+
+#[macro_export]
+macro_rules! sum {
+    ($func: expr) => {
+        SumClosure::new($func)
+    };
+}
+
+/// Compare by the `first` comparator, and break the tie by the `then` comparator;
+pub struct ThenCompare<D, A: CompareFunction<D>, B: CompareFunction<D>> {
+    first: A,
+    then: B,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Send + 'static, A: CompareFunction<D>, B: CompareFunction<D>> CompareFunction<D>
+    for ThenCompare<D, A, B>
+{
+    fn compare(&self, left: &D, right: &D) -> Ordering {
+        match self.first.compare(left, right) {
+            Ordering::Equal => self.then.compare(left, right),
+            ord => ord,
+        }
+    }
+}
+
+pub trait ThenBy<D: Send + 'static>: CompareFunction<D> + Sized {
+    /// Chain a secondary comparator to break the tie of this comparator, e.g. the
+    /// `order().by(a).by(b)` in gremlin;
+    fn then_by<B: CompareFunction<D>>(self, then: B) -> ThenCompare<D, Self, B> {
+        ThenCompare { first: self, then, _ph: std::marker::PhantomData }
+    }
+}
+
+impl<D: Send + 'static, C: CompareFunction<D>> ThenBy<D> for C {}
+
+/// Extract the key to order the data by, e.g. the `a` in `order().by(a, asc)` in gremlin;
+pub type KeyExtractor<D, K> = Box<dyn Fn(&D) -> K + Send>;
+
+/// Compare by the keys extracted in turn, each in its own direction, where a later key only
+/// breaks the tie of the former keys. The data equal under all the keys are equal, and their
+/// relative order is left to the sorting, e.g. kept as they arrive by `OrderBy::sort_by`;
+pub struct MultiKeyCompare<D, K: Ord> {
+    keys: Vec<(KeyExtractor<D, K>, OrderDirect)>,
+}
+
+impl<D, K: Ord> MultiKeyCompare<D, K> {
+    pub fn new(keys: Vec<(KeyExtractor<D, K>, OrderDirect)>) -> Self {
+        MultiKeyCompare { keys }
+    }
+}
+
+impl<D: 'static, K: Ord + 'static> CompareFunction<D> for MultiKeyCompare<D, K> {
+    fn compare(&self, left: &D, right: &D) -> Ordering {
+        for (key, order) in self.keys.iter() {
+            let ord = key(left).cmp(&key(right));
+            let ord = match order {
+                OrderDirect::Asc => ord,
+                OrderDirect::Desc => ord.reverse(),
+            };
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
 #[macro_export]
 macro_rules! compare {
     ($func: expr) => {
@@ -375,8 +550,8 @@ where
     D: Send + 'static,
     F: Fn(Vec<D>) -> Vec<u8> + Send + 'static,
 {
-    fn encode(&self, data: Vec<D>) -> Vec<u8> {
-        (self.func)(data)
+    fn encode(&self, data: Vec<D>) -> FnResult<Vec<u8>> {
+        Ok((self.func)(data))
     }
 }
 
@@ -28,11 +28,11 @@ pub use concise::dedup::Dedup;
 pub use concise::exchange::Exchange;
 pub use concise::filter::Filter;
 pub use concise::fold::Fold;
-pub use concise::map::Map;
+pub use concise::map::{Map, OnError};
 pub use concise::reduce::*;
 pub use iteration::{Iteration, LoopCondition};
-pub use multiplex::subtask::{SubTask, SubtaskResult};
-pub use multiplex::Multiplexing;
+pub use multiplex::subtask::{SubTask, SubtaskCancel, SubtaskResult};
+pub use multiplex::{Multiplexing, Remainder};
 pub use primitive::binary::{Binary, BinaryInput, BinaryNotification, BinaryNotify, BinaryState};
 pub use primitive::branch::{Branch, Condition, IntoBranch};
 pub use primitive::sink::{ResultSet, Sink};
@@ -17,12 +17,53 @@ use crate::errors::BuildJobError;
 use crate::stream::Stream;
 use crate::Data;
 
+/// Decide how to deal with the trailing data fewer than `length` in `scope_by_size_with`;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Remainder {
+    /// Output the trailing data as a shorter scope;
+    Keep,
+    /// Discard the trailing data;
+    Drop,
+    /// Fail the job if there are trailing data;
+    Error,
+}
+
 pub trait Multiplexing<D: Data> {
     fn scope_by<F>(&self, key: F) -> Result<Stream<D>, BuildJobError>
     where
         F: FnMut(&D) -> Option<u32> + Send + Clone + 'static;
 
+    /// Same as `scope_by`, but each datum is paired with the id of the scope it enters. The
+    /// data whose `key` returns `None` are dropped as in `scope_by`;
+    fn scope_by_tagged<F>(&self, key: F) -> Result<Stream<(u32, D)>, BuildJobError>
+    where
+        F: FnMut(&D) -> Option<u32> + Send + Clone + 'static;
+
+    /// Divide the data of each worker into scopes of `length` data each, the trailing data fewer
+    /// than `length` enter a shorter scope at the end of the parent scope; Return
+    /// `BuildJobError::InvalidScope` if `length` is 0;
     fn scope_by_size(&self, length: usize) -> Result<Stream<D>, BuildJobError>;
+
+    /// Same as `scope_by_size`, but the trailing data fewer than `length` are dealt with by
+    /// `remainder`. For `Remainder::Drop` and `Remainder::Error`, the data are buffered until
+    /// `length` data are received, as whether they are trailing is unknown before the end of the
+    /// parent scope. As the number of data is only known at runtime, `Remainder::Error` fails the
+    /// job instead of returning a build error. Return `BuildJobError::InvalidScope` if `length` is
+    /// 0;
+    fn scope_by_size_with(
+        &self, length: usize, remainder: Remainder,
+    ) -> Result<Stream<D>, BuildJobError>;
+
+    /// Divide the data into scopes of contiguous windows, e.g. time windows, where the data with
+    /// `key` in `[i * window, (i + 1) * window)` enter the scope with id `i`. The keys are expected
+    /// to be increasing in each worker, so the window of a scope is closed once a datum of a
+    /// later window arrives, and the last window is closed at the end of the parent scope.
+    ///
+    /// The data arriving out of order whose windows have been closed are dropped, as well as the
+    /// data whose window id exceeds `u32::MAX`. Return `BuildJobError::InvalidScope` if `window` is 0;
+    fn scope_by_range<F>(&self, key: F, window: u64) -> Result<Stream<D>, BuildJobError>
+    where
+        F: FnMut(&D) -> u64 + Send + Clone + 'static;
 }
 
 pub mod subtask;
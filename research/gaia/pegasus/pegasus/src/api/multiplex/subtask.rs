@@ -13,13 +13,16 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use crate::api::function::LeftJoinFunction;
 use crate::api::ResultSet;
 use crate::data::DataSet;
 use crate::errors::BuildJobError;
 use crate::stream::Stream;
-use crate::{Data, JobConf};
+use crate::{Data, JobConf, Tag};
 use pegasus_common::codec::*;
+use std::collections::HashSet;
 use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
 
 pub struct SubtaskResult<T> {
     pub seq: u32,
@@ -46,6 +49,87 @@ pub trait SubTask<D: Data> {
         T: Data,
         R: Data,
         F: Fn(&D, T) -> Option<R> + Send + 'static;
+
+    /// Join the subtasks as [`join_subtask`] does by `func`, if `func` is a fold join (see
+    /// [`LeftJoinFunction::is_fold`]), each parent is joined with all the results of its subtask
+    /// folded, and the joined parents are produced once all the subtasks in the scope are ended;
+    /// Likewise, if `func` is a collect join (see [`LeftJoinFunction::is_collect`]), the results
+    /// of each parent are produced from all the results of its subtask collected, once all the
+    /// subtasks in the scope are ended;
+    ///
+    /// [`join_subtask`]: SubTask::join_subtask
+    fn join_subtask_with<F>(
+        &self, subtask: Stream<SubtaskResult<D>>, func: F,
+    ) -> Result<Stream<D>, BuildJobError>
+    where
+        F: LeftJoinFunction<D>;
+
+    /// Fork subtasks as [`fork_subtask`] does, but the subtasks can be canceled through `cancel`
+    /// once their results are no longer needed. The same `cancel` token should be given to the
+    /// [`join_subtask_limit`] that joins the subtasks.
+    ///
+    /// [`fork_subtask`]: SubTask::fork_subtask
+    /// [`join_subtask_limit`]: SubTask::join_subtask_limit
+    fn fork_cancelable_subtask<F, T>(
+        &self, cancel: &SubtaskCancel, func: F,
+    ) -> Result<Stream<SubtaskResult<T>>, BuildJobError>
+    where
+        T: Data,
+        F: FnOnce(Stream<D>) -> Result<Stream<T>, BuildJobError> + Send;
+
+    /// Join the subtasks forked by [`fork_cancelable_subtask`] as [`join_subtask`] does, but a parent
+    /// is satisfied after `limit` results of its subtask have been joined. Once satisfied, the
+    /// subtask of the parent is canceled through `cancel`, and the results it still produces are
+    /// discarded.
+    ///
+    /// The cancellation is observed by the sink of the subtask on each worker, which then cancels
+    /// the subtask scope on its input, and the cancel signal is propagated upstream to the operators
+    /// of the subtask on all workers by the dataflow, the same way as [`limit`] does. As the token is
+    /// shared by memory, the workers in other processes won't observe it, their results of the
+    /// subtask are discarded by the join instead.
+    ///
+    /// [`fork_cancelable_subtask`]: SubTask::fork_cancelable_subtask
+    /// [`join_subtask`]: SubTask::join_subtask
+    /// [`limit`]: crate::api::Limit::limit
+    fn join_subtask_limit<T, R, F>(
+        &self, subtask: Stream<SubtaskResult<T>>, limit: usize, cancel: &SubtaskCancel, func: F,
+    ) -> Result<Stream<R>, BuildJobError>
+    where
+        T: Data,
+        R: Data,
+        F: Fn(&D, T) -> Option<R> + Send + 'static;
+}
+
+/// A token shared by the subtasks and their join, through which the join cancels the subtasks whose
+/// parents are already satisfied;
+#[derive(Clone, Default)]
+pub struct SubtaskCancel {
+    canceled: Arc<RwLock<HashSet<Tag>>>,
+}
+
+impl SubtaskCancel {
+    pub fn new() -> Self {
+        SubtaskCancel::default()
+    }
+
+    /// Cancel the subtask `seq` forked in the scope `parent`;
+    pub fn cancel(&self, parent: &Tag, seq: u32) {
+        let tag = Tag::inherit(parent, seq);
+        self.canceled.write().expect("subtask cancel poisoned").insert(tag);
+    }
+
+    /// Check if the subtask whose scope is `tag` is canceled;
+    pub fn is_canceled(&self, tag: &Tag) -> bool {
+        self.canceled.read().expect("subtask cancel poisoned").contains(tag)
+    }
+
+    /// Clear the canceled subtasks forked in the scope `parent`, as it has been ended;
+    pub fn clear(&self, parent: &Tag) {
+        let mut canceled = self.canceled.write().expect("subtask cancel poisoned");
+        if !canceled.is_empty() {
+            canceled.retain(|t| !parent.is_parent_of(t));
+        }
+    }
 }
 
 impl<T: Data> Encode for SubtaskResult<T> {
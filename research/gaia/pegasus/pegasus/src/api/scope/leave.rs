@@ -18,7 +18,15 @@ use crate::stream::Stream;
 use crate::Data;
 
 pub trait LeaveScope<D: Data> {
-    /// Leave the current scope and return back to it's parent scope;
+    /// Leave the current scope and return back to it's parent scope, which is the inverse of
+    /// `enter`, `dyn_enter` and the `scope_by*` of `Multiplexing`. The data of all sub-scopes are
+    /// merged into the parent scope, the order of data in each sub-scope is preserved in each
+    /// worker, while the data of different sub-scopes may interleave.
+    ///
+    /// The reductions of `Range::Local` before leaving, e.g. `count`, output one result for each
+    /// sub-scope in each worker, and all of these results are merged into the parent scope after
+    /// leaving, rather than being reduced again. Apply another reduction after leaving if the
+    /// results of sub-scopes need to be reduced in the parent scope;
     ///
     fn leave(&self) -> Result<Stream<D>, BuildJobError>;
 
@@ -131,6 +131,14 @@ impl<V> StateMap<V> {
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&Tag, &V)> {
+        self.map.iter().filter_map(|(k, v)| v.as_ref().map(|v| (k, v)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Tag, &mut V)> {
+        self.map.iter_mut().filter_map(|(k, v)| v.as_mut().map(|v| (k, v)))
+    }
+
     #[inline]
     pub fn extract_notified(&mut self) -> &mut Vec<(Tag, V)> {
         &mut self.notified
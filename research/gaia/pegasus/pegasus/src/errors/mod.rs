@@ -171,6 +171,8 @@ impl TaskExecError for JobExecError {}
 // TODO: Make build error enumerate.;
 pub enum BuildJobError {
     Unsupported(String),
+    /// The scope is configured invalidly, e.g. `scope_by_size(0)`, which carries the reason;
+    InvalidScope(String),
     ServerError(Box<dyn std::error::Error + Send>),
     UserError(Box<dyn std::error::Error + Send>),
 }
@@ -179,6 +181,7 @@ impl Debug for BuildJobError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             BuildJobError::Unsupported(msg) => write!(f, "unsupported combination: {}", msg),
+            BuildJobError::InvalidScope(msg) => write!(f, "invalid scope: {}", msg),
             BuildJobError::UserError(e) => write!(f, "user defined error: {}", e),
             BuildJobError::ServerError(e) => write!(f, "server error: {}", e),
         }
@@ -198,6 +201,10 @@ impl BuildJobError {
         Err(BuildJobError::Unsupported(msg.into()))
     }
 
+    pub(crate) fn invalid_scope<T, S: Into<String>>(msg: S) -> Result<T, Self> {
+        Err(BuildJobError::InvalidScope(msg.into()))
+    }
+
     pub(crate) fn server_err<T, E: Into<Box<dyn std::error::Error + Send + Sync>>>(
         err: E,
     ) -> Result<T, Self> {
@@ -224,6 +231,24 @@ impl From<&str> for BuildJobError {
     }
 }
 
+/// The error raised by sorting groups if two of the group keys are not comparable, e.g. the
+/// keys are only partially ordered;
+pub struct UnorderedKeyError(pub String);
+
+impl Debug for UnorderedKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "group keys are not totally ordered: {}", self.0)
+    }
+}
+
+impl Display for UnorderedKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for UnorderedKeyError {}
+
 pub struct SpawnJobError(pub String);
 
 impl Debug for SpawnJobError {
@@ -13,13 +13,19 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::{Barrier, Dedup, Map, Range};
+use crate::api::meta::OperatorKind;
+use crate::api::state::OperatorState;
+use crate::api::{Barrier, Dedup, Exchange, Map, Range, Unary, UnaryState};
 use crate::codec::{shade_codec, ShadeCodec};
-use crate::communication::Pipeline;
+use crate::communication::{Input, Output, Pipeline};
+use crate::errors::JobExecError;
 use crate::operator::concise::{never_clone, NeverClone};
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 use pegasus_common::collections::{CollectionFactory, Drain, DrainSet, DrainSetFactory};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
 
 impl<D: Data + Eq> Dedup<D> for Stream<D> {
     fn dedup<S>(&self, range: Range) -> Result<Stream<D>, BuildJobError>
@@ -47,6 +53,66 @@ impl<D: Data + Eq> Dedup<D> for Stream<D> {
             input.drain().map(|item| Ok(item))
         })
     }
+
+    fn dedup_by_key<K, F>(&self, range: Range, key: F) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Hash + Eq + Send + 'static,
+        F: Fn(&D) -> K + Clone + Send + 'static,
+    {
+        let local_key = key.clone();
+        let local = self.unary_with_state("dedup_by_key", Pipeline, |meta| {
+            meta.set_kind(OperatorKind::Clip);
+            DedupByKeyHandle { key: local_key }
+        })?;
+        match range {
+            Range::Local => Ok(local),
+            Range::Global => {
+                let route_key = key.clone();
+                local
+                    .exchange_with_fn(move |item| {
+                        let mut hasher = DefaultHasher::new();
+                        route_key(item).hash(&mut hasher);
+                        hasher.finish()
+                    })?
+                    .unary_with_state("dedup_by_key_global", Pipeline, |meta| {
+                        meta.set_kind(OperatorKind::Clip);
+                        DedupByKeyHandle { key }
+                    })
+            }
+        }
+    }
+}
+
+/// Keep the first data of each key in a scope, the seen keys are the state of the scope;
+struct DedupByKeyHandle<F> {
+    key: F,
+}
+
+impl<D, K, F> UnaryState<D, D, HashSet<K>> for DedupByKeyHandle<F>
+where
+    D: Data,
+    K: Hash + Eq + Send + 'static,
+    F: Fn(&D) -> K + Send + 'static,
+{
+    type NotifyResult = Vec<D>;
+
+    fn on_receive(
+        &self, input: &mut Input<D>, output: &mut Output<D>, state: &mut OperatorState<HashSet<K>>,
+    ) -> Result<(), JobExecError> {
+        input.for_each_batch(|dataset| {
+            for datum in dataset.drain(..) {
+                if state.insert((self.key)(&datum)) {
+                    output.give(datum)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    #[inline(always)]
+    fn on_notify(&self, _: HashSet<K>) -> Self::NotifyResult {
+        vec![]
+    }
 }
 
 struct DefaultSetFactory<T: Send + Eq, C: DrainSet<T> + Default> {
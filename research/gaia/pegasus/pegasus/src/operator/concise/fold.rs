@@ -15,10 +15,11 @@
 
 use crate::api::meta::OperatorKind;
 use crate::api::notify::Notification;
-use crate::api::{Fold, Unary, UnaryNotify};
-use crate::communication::{Channel, Input, Output};
+use crate::api::{Fold, Map, Range, Unary, UnaryNotify};
+use crate::communication::{Aggregate, Channel, Input, Output, Pipeline};
 use crate::errors::{BuildJobError, JobExecError};
 use crate::stream::Stream;
+use crate::worker_id::get_current_worker_uncheck;
 use crate::{Data, Tag};
 use std::collections::HashMap;
 
@@ -79,4 +80,28 @@ impl<I: Data, O: Data> Fold<I, O> for Stream<I> {
             FoldHandle::new(seed, func)
         })
     }
+
+    fn fold_in_range<F, M>(
+        &self, seed: O, range: Range, func: F, combine: M,
+    ) -> Result<Stream<O>, BuildJobError>
+    where
+        F: Fn(&mut O, I) + Send + 'static,
+        M: Fn(&mut O, O) + Send + 'static,
+    {
+        let local = self.fold(seed.clone(), Pipeline, func)?;
+        match range {
+            Range::Local => Ok(local),
+            Range::Global => local
+                .map_with_fn(Pipeline, |partial| Ok((get_current_worker_uncheck().index, partial)))?
+                .fold(Vec::new(), Aggregate(0), |partials: &mut Vec<(u32, O)>, p| partials.push(p))?
+                .map_with_fn(Pipeline, move |mut partials| {
+                    partials.sort_by_key(|(index, _)| *index);
+                    let mut result = seed.clone();
+                    for (_, partial) in partials {
+                        combine(&mut result, partial);
+                    }
+                    Ok(result)
+                }),
+        }
+    }
 }
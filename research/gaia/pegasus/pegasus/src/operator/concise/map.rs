@@ -15,9 +15,9 @@
 
 use crate::api::function::*;
 use crate::api::meta::OperatorKind;
-use crate::api::{LazyUnary, Map, Unary};
-use crate::communication::Channel;
-use crate::errors::BuildJobError;
+use crate::api::{LazyUnary, Map, OnError, Unary};
+use crate::communication::{Channel, Pipeline};
+use crate::errors::{BuildJobError, JobExecError};
 use crate::stream::Stream;
 use crate::Data;
 use std::error::Error;
@@ -89,4 +89,81 @@ impl<I: Data> Map<I> for Stream<I> {
     {
         self.flat_map(channel, flat_map!(func))
     }
+
+    fn flat_map_result<O, C, R, E, F>(
+        &self, channel: C, on_error: OnError, func: F,
+    ) -> Result<Stream<O>, BuildJobError>
+    where
+        O: Data,
+        C: Into<Channel<I>>,
+        R: IntoIterator<Item = O>,
+        E: Error + Send + 'static,
+        F: Fn(I) -> Result<R, E> + Send + 'static,
+    {
+        self.unary("flat_map_result", channel, |meta| {
+            meta.set_kind(OperatorKind::Expand);
+            move |input, output| {
+                input.for_each_batch(|dataset| {
+                    for datum in dataset.drain(..) {
+                        match func(datum) {
+                            Ok(result) => {
+                                for item in result {
+                                    output.give(item)?;
+                                }
+                            }
+                            Err(err) => match on_error {
+                                OnError::Abort => {
+                                    let err: Box<dyn Error + Send> = Box::new(err);
+                                    return Err(JobExecError::from(err));
+                                }
+                                OnError::Skip => {
+                                    warn_worker!("skip data failed in flat map: {}", err);
+                                }
+                            },
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        })
+    }
+
+    fn filter_map<O, C, F>(&self, channel: C, func: F) -> Result<Stream<O>, BuildJobError>
+    where
+        O: Data,
+        C: Into<Channel<I>>,
+        F: Fn(I) -> Option<O> + Send + 'static,
+    {
+        self.unary("filter_map", channel, |meta| {
+            meta.set_kind(OperatorKind::Clip);
+            move |input, output| {
+                input.for_each_batch(|dataset| {
+                    for datum in dataset.drain(..) {
+                        if let Some(resp) = func(datum) {
+                            output.give(resp)?;
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        })
+    }
+
+    fn inspect<F>(&self, func: F) -> Result<Stream<I>, BuildJobError>
+    where
+        F: Fn(&I) + Send + 'static,
+    {
+        self.unary("inspect", Pipeline, |meta| {
+            meta.set_kind(OperatorKind::Map);
+            move |input, output| {
+                input.for_each_batch(|dataset| {
+                    for datum in dataset.iter() {
+                        func(datum);
+                    }
+                    output.forward(dataset)?;
+                    Ok(())
+                })
+            }
+        })
+    }
 }
@@ -13,25 +13,70 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::concise::reduce::barrier::Barrier;
+use crate::api::concise::reduce::barrier::{Barrier, BarrierMetrics, BarrierResult};
 use crate::api::notify::Notification;
 use crate::api::state::StateMap;
 use crate::api::{Range, Unary, UnaryNotify};
-use crate::communication::{Aggregate, Input, Output, Pipeline};
+use crate::communication::input::{new_input_session, InputProxy};
+use crate::communication::output::{new_output_session, OutputProxy};
+use crate::communication::{Aggregate, Channel, Input, Output, Pipeline};
 use crate::errors::{IOError, JobExecError};
+use crate::operator::{FiredState, OperatorCore};
 use crate::stream::Stream;
-use crate::{BuildJobError, Data};
+use crate::{BuildJobError, Data, Tag};
+use pegasus_common::codec::Encode;
 use pegasus_common::collections::{Collection, CollectionFactory, DefaultCollectionFactory};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Sample the size of data buffered in each scope, and report the metrics to the hook;
+struct BarrierProbe {
+    interval: usize,
+    hook: Box<dyn Fn(&BarrierMetrics) + Send>,
+    /// the total encoded size and number of the data sampled of each scope;
+    samples: HashMap<Tag, (usize, usize)>,
+}
+
+impl BarrierProbe {
+    /// Whether the datum is sampled if it is the `count`-th datum of the scope;
+    #[inline]
+    fn is_sampled(&self, count: usize) -> bool {
+        count == 1 || count % self.interval == 0
+    }
+
+    fn sample<D: Encode>(&mut self, tag: &Tag, datum: &D) {
+        let mut bytes = vec![];
+        if datum.write_to(&mut bytes).is_ok() {
+            let sample = self.samples.entry(tag.clone()).or_insert((0, 0));
+            sample.0 += bytes.len();
+            sample.1 += 1;
+        }
+    }
+
+    fn report(&self, tag: &Tag, count: usize) {
+        let bytes = match self.samples.get(tag) {
+            Some((size, sampled)) if *sampled > 0 => size * count / sampled,
+            _ => 0,
+        };
+        (self.hook)(&BarrierMetrics { tag: tag.clone(), count, bytes });
+    }
+}
 
 struct BarrierHandle<D: Data, C: CollectionFactory<D>> {
     factory: C,
     container: StateMap<C::Target>,
+    probe: Option<BarrierProbe>,
     _ph: std::marker::PhantomData<D>,
 }
 
 impl<D: Data, C: CollectionFactory<D>> BarrierHandle<D, C> {
     pub fn new(factory: C, container: StateMap<C::Target>) -> Self {
-        BarrierHandle { factory, container, _ph: std::marker::PhantomData }
+        BarrierHandle { factory, container, probe: None, _ph: std::marker::PhantomData }
+    }
+
+    fn with_probe(mut self, probe: BarrierProbe) -> Self {
+        self.probe = Some(probe);
+        self
     }
 }
 
@@ -47,11 +92,24 @@ where
         input.subscribe_notify();
         let factory = &self.factory;
         let container = self.container.entry(&input.tag).or_insert_with(|| factory.create());
+        let probe = &mut self.probe;
+        let tag = input.tag.clone();
         let mut full = false;
 
         input.for_each_batch(|data| {
             for datum in data.drain(..) {
+                let count = container.len() + 1;
+                if let Some(probe) = probe.as_mut() {
+                    if probe.is_sampled(count) {
+                        probe.sample(&tag, &datum);
+                    }
+                }
                 full = container.add(datum).is_some();
+                if let Some(probe) = probe.as_ref() {
+                    if count % probe.interval == 0 {
+                        probe.report(&tag, container.len());
+                    }
+                }
                 if full {
                     info_worker!("barrier is full, size={}", container.len());
                     let kind = std::io::ErrorKind::Interrupted;
@@ -71,11 +129,148 @@ where
         self.container.notify(n);
         let notified = self.container.extract_notified();
         assert_eq!(notified.len(), 1);
-        let result = notified.remove(0).1;
+        let (tag, result) = notified.remove(0);
+        if let Some(probe) = self.probe.as_mut() {
+            probe.report(&tag, result.len());
+            probe.samples.remove(&tag);
+        }
         vec![result]
     }
 }
 
+struct TimedCollection<C> {
+    deadline: Instant,
+    /// `None` if the collection has been flushed as timeout;
+    collection: Option<C>,
+}
+
+impl<C: Default> TimedCollection<C> {
+    fn new(timeout: Duration) -> Self {
+        TimedCollection { deadline: Instant::now() + timeout, collection: Some(C::default()) }
+    }
+}
+
+struct TimeoutBarrier<D, C> {
+    timeout: Duration,
+    scope_depth: usize,
+    /// whether the data of the scopes are collected on this worker, which is always true for
+    /// `Range::Local`, and only true on worker 0 for `Range::Global`;
+    is_collector: bool,
+    scopes: StateMap<TimedCollection<C>>,
+    /// number of scopes not flushed yet;
+    pending: usize,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D, C: Default> TimeoutBarrier<D, C> {
+    pub fn new(
+        timeout: Duration, scope_depth: usize, is_collector: bool,
+        mut scopes: StateMap<TimedCollection<C>>,
+    ) -> Self {
+        let mut pending = 0;
+        if is_collector && scope_depth == 0 {
+            // the root scope opens as the operator starts, so it is timed from now on, even if
+            // no data of it ever arrives;
+            scopes.insert(Tag::root(), TimedCollection::new(timeout));
+            pending += 1;
+        }
+        TimeoutBarrier {
+            timeout,
+            scope_depth,
+            is_collector,
+            scopes,
+            pending,
+            _ph: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<D: Data, C: Collection<D> + Data + Default> OperatorCore for TimeoutBarrier<D, C> {
+    fn on_receive(
+        &mut self, tag: &Tag, inputs: &[Box<dyn InputProxy>], _: &[Box<dyn OutputProxy>],
+    ) -> Result<FiredState, JobExecError> {
+        let mut input = new_input_session::<D>(&inputs[0], tag);
+        let pending = &mut self.pending;
+        let timeout = self.timeout;
+        let scope = self.scopes.entry(tag).or_insert_with(|| {
+            *pending += 1;
+            TimedCollection::new(timeout)
+        });
+        if let Some(ref mut collection) = scope.collection {
+            let mut full = false;
+            input.for_each_batch(|data| {
+                for datum in data.drain(..) {
+                    full = collection.add(datum).is_some();
+                    if full {
+                        info_worker!("barrier is full, size={}", collection.len());
+                        let kind = std::io::ErrorKind::Interrupted;
+                        Err(throw_io_error!(kind))?;
+                    }
+                }
+                Ok(())
+            })?;
+            if full {
+                input.cancel_scope();
+            }
+        } else {
+            // the scope has been flushed as timeout;
+            input.for_each_batch(|data| {
+                data.clear();
+                Ok(())
+            })?;
+            input.cancel_scope();
+        }
+        Ok(FiredState::Idle)
+    }
+
+    fn on_notify(
+        &mut self, n: Notification, outputs: &[Box<dyn OutputProxy>],
+    ) -> Result<(), JobExecError> {
+        self.scopes.notify(&n);
+        let notified = self.scopes.extract_notified();
+        if self.is_collector && n.is_belong_to(self.scope_depth) && notified.is_empty() {
+            // the scope ends before any data of it arrives;
+            let mut session = new_output_session::<BarrierResult<C>>(&outputs[0], &n.tag);
+            session.give(BarrierResult::Complete(C::default()))?;
+        }
+        for (tag, scope) in notified.drain(..) {
+            if let Some(collection) = scope.collection {
+                self.pending -= 1;
+                let mut session = new_output_session::<BarrierResult<C>>(&outputs[0], &tag);
+                session.give(BarrierResult::Complete(collection))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_timer(&self) -> Option<Instant> {
+        if self.pending > 0 {
+            self.scopes
+                .iter()
+                .filter(|(_, s)| s.collection.is_some())
+                .map(|(_, s)| s.deadline)
+                .min()
+        } else {
+            None
+        }
+    }
+
+    fn on_timer(&mut self, outputs: &[Box<dyn OutputProxy>]) -> Result<(), JobExecError> {
+        let now = Instant::now();
+        for (tag, scope) in self.scopes.iter_mut() {
+            if scope.deadline <= now {
+                if let Some(collection) = scope.collection.take() {
+                    debug_worker!("barrier of scope {:?} timeout, flush partial result;", tag);
+                    self.pending -= 1;
+                    let mut session = new_output_session::<BarrierResult<C>>(&outputs[0], tag);
+                    session.give(BarrierResult::Partial(collection))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<D: Data> Barrier<D> for Stream<D> {
     fn barrier<C: Collection<D> + Data + Default>(
         &self, range: Range,
@@ -116,4 +311,49 @@ impl<D: Data> Barrier<D> for Stream<D> {
             }
         }
     }
+
+    fn barrier_with_metrics<C, M>(
+        &self, range: Range, interval: usize, hook: M,
+    ) -> Result<Stream<C>, BuildJobError>
+    where
+        C: Collection<D> + Data + Default + 'static,
+        M: Fn(&BarrierMetrics) + Send + 'static,
+    {
+        if interval == 0 {
+            return BuildJobError::unsupported("barrier metrics interval should be positive");
+        }
+        let channel: Channel<D> = match range {
+            Range::Local => Pipeline.into(),
+            // TODO: change aggregate to worker 0 into aggregate by tag;
+            Range::Global => Aggregate(0).into(),
+        };
+        self.unary_with_notify("barrier_with_metrics", channel, |meta| {
+            let state = StateMap::new(meta);
+            let factory = DefaultCollectionFactory::new();
+            let probe = BarrierProbe { interval, hook: Box::new(hook), samples: HashMap::new() };
+            BarrierHandle::<D, DefaultCollectionFactory<D, C>>::new(factory, state)
+                .with_probe(probe)
+        })
+    }
+
+    fn barrier_with_timeout<C>(
+        &self, range: Range, timeout: Duration,
+    ) -> Result<Stream<BarrierResult<C>>, BuildJobError>
+    where
+        C: Collection<D> + Data + Default + 'static,
+    {
+        let channel: Channel<D> = match range {
+            Range::Local => Pipeline.into(),
+            // TODO: change aggregate to worker 0 into aggregate by tag;
+            Range::Global => Aggregate(0).into(),
+        };
+        let is_global = matches!(range, Range::Global);
+        self.concat("barrier_with_timeout", channel, |meta| {
+            meta.enable_notify();
+            let scope_depth = meta.scope_depth;
+            let is_collector = !is_global || meta.worker_id.index == 0;
+            let scopes = StateMap::new(meta);
+            Box::new(TimeoutBarrier::<D, C>::new(timeout, scope_depth, is_collector, scopes))
+        })
+    }
 }
@@ -13,12 +13,64 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::concise::reduce::Range;
-use crate::api::{Count, Fold};
+use crate::api::accum::{HyperLogLog, MAX_HLL_PRECISION, MIN_HLL_PRECISION};
+use crate::api::concise::reduce::{GlobalCombine, Range};
+use crate::api::notify::Notification;
+use crate::api::state::StateMap;
+use crate::api::{Count, Fold, Map};
+use crate::communication::input::{new_input_session, InputProxy};
+use crate::communication::output::{new_output_session, OutputProxy};
 use crate::communication::{Aggregate, Pipeline};
-use crate::errors::BuildJobError;
+use crate::errors::{BuildJobError, JobExecError};
+use crate::operator::concise::reduce::{tree_combine, tree_fanout};
+use crate::operator::{FiredState, OperatorCore};
 use crate::stream::Stream;
-use crate::Data;
+use crate::{Data, Tag};
+use std::hash::Hash;
+
+/// Count the data satisfying the predicate in each scope. Unlike the counting built on `fold`,
+/// it is notified of every scope, and outputs 0 for a scope without any data;
+struct CountIf<D, F> {
+    func: F,
+    scope_depth: usize,
+    counts: StateMap<u64>,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Data, F: Fn(&D) -> bool + Send + 'static> OperatorCore for CountIf<D, F> {
+    fn on_receive(
+        &mut self, tag: &Tag, inputs: &[Box<dyn InputProxy>], _: &[Box<dyn OutputProxy>],
+    ) -> Result<FiredState, JobExecError> {
+        let mut input = new_input_session::<D>(&inputs[0], tag);
+        let count = self.counts.entry(tag).or_insert_with(|| 0);
+        let func = &self.func;
+        input.for_each_batch(|data| {
+            for datum in data.drain(..) {
+                if func(&datum) {
+                    *count += 1;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(FiredState::Idle)
+    }
+
+    fn on_notify(
+        &mut self, n: Notification, outputs: &[Box<dyn OutputProxy>],
+    ) -> Result<(), JobExecError> {
+        self.counts.notify(&n);
+        let notified = self.counts.extract_notified();
+        if n.is_belong_to(self.scope_depth) && notified.is_empty() {
+            // the scope ends without any data;
+            notified.push((n.tag, 0));
+        }
+        for (tag, count) in notified.drain(..) {
+            let mut session = new_output_session::<u64>(&outputs[0], &tag);
+            session.give(count)?;
+        }
+        Ok(())
+    }
+}
 
 impl<D: Data> Count<D> for Stream<D> {
     fn count(&self, range: Range) -> Result<Stream<u64>, BuildJobError> {
@@ -29,4 +81,64 @@ impl<D: Data> Count<D> for Stream<D> {
             }
         }
     }
+
+    fn count_global_with(&self, combine: GlobalCombine) -> Result<Stream<u64>, BuildJobError> {
+        match tree_fanout(combine)? {
+            None => self.count(Range::Global),
+            Some(fanout) => {
+                let local = self.fold(0u64, Pipeline, |s, _| *s += 1)?;
+                tree_combine(&local, fanout, |partial| partial.fold(0u64, Pipeline, |s, u| *s += u))
+            }
+        }
+    }
+
+    fn count_if<F>(&self, range: Range, func: F) -> Result<Stream<u64>, BuildJobError>
+    where
+        F: Fn(&D) -> bool + Send + 'static,
+    {
+        let local = self.concat("count_if", Pipeline, |meta| {
+            meta.enable_notify();
+            let scope_depth = meta.scope_depth;
+            let counts = StateMap::new(meta);
+            Box::new(CountIf { func, scope_depth, counts, _ph: std::marker::PhantomData })
+        })?;
+        match range {
+            Range::Local => Ok(local),
+            // each worker outputs a count for each scope, even 0, so the aggregation always
+            // receives data and outputs the total count;
+            Range::Global => local.fold(0u64, Aggregate(0), |s, u| *s += u),
+        }
+    }
+
+    fn count_weighted<F>(&self, range: Range, func: F) -> Result<Stream<u64>, BuildJobError>
+    where
+        F: Fn(&D) -> u64 + Send + 'static,
+    {
+        let local = self.fold(0u64, Pipeline, move |s, d| *s += func(&d))?;
+        match range {
+            Range::Local => Ok(local),
+            Range::Global => local.fold(0u64, Aggregate(0), |s, u| *s += u),
+        }
+    }
+
+    fn count_distinct_approx(
+        &self, range: Range, precision: u8,
+    ) -> Result<Stream<u64>, BuildJobError>
+    where
+        D: Hash,
+    {
+        if precision < MIN_HLL_PRECISION || precision > MAX_HLL_PRECISION {
+            return BuildJobError::unsupported(format!(
+                "hyperloglog precision {} is out of [{}, {}]",
+                precision, MIN_HLL_PRECISION, MAX_HLL_PRECISION
+            ));
+        }
+        let seed = HyperLogLog::new(precision);
+        let local = self.fold(seed.clone(), Pipeline, |hll, d| hll.insert(&d))?;
+        let sketch = match range {
+            Range::Local => local,
+            Range::Global => local.fold(seed, Aggregate(0), |hll, other| hll.merge(&other))?,
+        };
+        sketch.map_with_fn(Pipeline, |hll| Ok(hll.estimate()))
+    }
 }
@@ -0,0 +1,80 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::concise::reduce::Range;
+use crate::api::{Extreme, Fold, Map, Tie};
+use crate::communication::Pipeline;
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::Data;
+use std::cmp::Ordering;
+
+/// Keep the data of the extreme key in `extreme`, `replace` tells if the current extreme should be
+/// replaced by the next data given the ordering of the next key to the current key;
+fn extreme_by_key<D, K, F>(
+    stream: &Stream<D>, range: Range, key: F, replace: fn(Ordering) -> bool,
+) -> Result<Stream<D>, BuildJobError>
+where
+    D: Data,
+    K: Ord,
+    F: Fn(&D) -> K + Clone + Send + 'static,
+{
+    let combine_key = key.clone();
+    let update = move |extreme: &mut Option<D>, next: D, key: &F| {
+        let is_replaced = match extreme {
+            Some(current) => replace(key(&next).cmp(&key(current))),
+            None => true,
+        };
+        if is_replaced {
+            *extreme = Some(next);
+        }
+    };
+    stream
+        .fold_in_range(
+            None,
+            range,
+            move |extreme, next| update(extreme, next, &key),
+            move |extreme, partial| {
+                if let Some(next) = partial {
+                    update(extreme, next, &combine_key);
+                }
+            },
+        )?
+        .flat_map_with_fn(Pipeline, |extreme| extreme.into_iter().map(Ok))
+}
+
+impl<D: Data> Extreme<D> for Stream<D> {
+    fn min_by_key<K, F>(&self, range: Range, key: F, tie: Tie) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Ord,
+        F: Fn(&D) -> K + Clone + Send + 'static,
+    {
+        match tie {
+            Tie::First => extreme_by_key(self, range, key, |ord| ord == Ordering::Less),
+            Tie::Last => extreme_by_key(self, range, key, |ord| ord != Ordering::Greater),
+        }
+    }
+
+    fn max_by_key<K, F>(&self, range: Range, key: F, tie: Tie) -> Result<Stream<D>, BuildJobError>
+    where
+        K: Ord,
+        F: Fn(&D) -> K + Clone + Send + 'static,
+    {
+        match tie {
+            Tie::First => extreme_by_key(self, range, key, |ord| ord == Ordering::Greater),
+            Tie::Last => extreme_by_key(self, range, key, |ord| ord != Ordering::Less),
+        }
+    }
+}
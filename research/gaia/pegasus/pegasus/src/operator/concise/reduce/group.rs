@@ -18,16 +18,25 @@ use crate::api::function::*;
 use crate::api::meta::OperatorMeta;
 use crate::api::notify::Notification;
 use crate::api::state::StateMap;
-use crate::api::{Group, Map, Range, Unary, UnaryNotify};
+use crate::api::{
+    Barrier, GlobalCombine, Group, Map, OrderDirect, Range, SpillConf, Unary, UnaryNotify,
+};
 use crate::codec::{shade_codec, ShadeCodec};
 use crate::communication::{Channel, Input, Output, Pipeline};
-use crate::errors::JobExecError;
+use crate::errors::{JobExecError, UnorderedKeyError};
+use crate::operator::concise::reduce::spill::SpillFile;
+use crate::operator::concise::reduce::{tree_combine, tree_fanout};
 use crate::operator::concise::{never_clone, NeverClone};
 use crate::preclude::Aggregate;
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
+use pegasus_common::codec::Codec;
 use pegasus_common::rc::RcPointer;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Debug;
+use std::path::Path;
 
 impl<D: Data> Group<D> for Stream<D> {
     fn group_by_with_accum<F, A, O>(
@@ -59,6 +68,35 @@ impl<D: Data> Group<D> for Stream<D> {
         }
     }
 
+    fn group_by_global_with<F, A, O>(
+        &self, key: F, accum_gen: A, combine: GlobalCombine,
+    ) -> Result<Stream<(<F as KeyFunction<D>>::Target, O)>, BuildJobError>
+    where
+        O: Data,
+        F: KeyFunction<D>,
+        A: AccumFactory<D, O> + 'static,
+        A::Target: 'static,
+    {
+        match tree_fanout(combine)? {
+            Some(fanout) if accum_gen.is_associative() => {
+                let accum = RcPointer::new(accum_gen);
+                let local = group(self, Pipeline, key, accum.clone())?;
+                tree_combine(&local, fanout, |partial| {
+                    let accum = accum.clone();
+                    partial
+                        .unary_with_notify("tree group", Pipeline, |meta| {
+                            GroupMerge::new(meta, accum)
+                        })?
+                        .flat_map_with_fn(Pipeline, |groups| {
+                            let x = groups.take();
+                            x.into_iter().map(|(k, mut v)| Ok((k, v.finalize())))
+                        })
+                })
+            }
+            _ => self.group_by_with_accum(Range::Global, key, accum_gen),
+        }
+    }
+
     fn group_by<F>(
         &self, range: Range, key: F,
     ) -> Result<Stream<(<F as KeyFunction<D>>::Target, Vec<D>)>, BuildJobError>
@@ -80,6 +118,62 @@ impl<D: Data> Group<D> for Stream<D> {
             }
         }
     }
+
+    fn group_by_with_spill<F>(
+        &self, range: Range, key: F, conf: SpillConf,
+    ) -> Result<Stream<(<F as KeyFunction<D>>::Target, Vec<D>)>, BuildJobError>
+    where
+        F: KeyFunction<D>,
+    {
+        if conf.threshold == 0 {
+            return BuildJobError::unsupported("spill threshold can't equal to 0");
+        }
+
+        // the members are not merged from partial groups, so that they are spilled only once;
+        match range {
+            Range::Local => spill_group(self, Pipeline, key, conf),
+            Range::Global => spill_group(self, Aggregate(0), key, conf),
+        }
+    }
+
+    fn group_by_ordered<F, A, O>(
+        &self, range: Range, key: F, accum_gen: A, order: OrderDirect,
+    ) -> Result<Stream<(<F as KeyFunction<D>>::Target, O)>, BuildJobError>
+    where
+        O: Data,
+        F: KeyFunction<D>,
+        F::Target: PartialOrd,
+        A: AccumFactory<D, O> + 'static,
+        A::Target: 'static,
+    {
+        // groups of global range are already merged on one worker, so a local barrier is enough;
+        self.group_by_with_accum(range, key, accum_gen)?
+            .barrier::<Vec<(F::Target, O)>>(Range::Local)?
+            .flat_map_with_fn(Pipeline, move |groups| sort_groups(groups, order).into_iter())
+    }
+}
+
+fn sort_groups<K: Key + PartialOrd, V>(
+    mut groups: Vec<(K, V)>, order: OrderDirect,
+) -> Vec<Result<(K, V), Box<dyn Error + Send>>> {
+    let mut unordered = None;
+    groups.sort_by(|a, b| {
+        let (left, right) = match order {
+            OrderDirect::Asc => (&a.0, &b.0),
+            OrderDirect::Desc => (&b.0, &a.0),
+        };
+        left.partial_cmp(right).unwrap_or_else(|| {
+            if unordered.is_none() {
+                unordered = Some(format!("{:?} and {:?}", a.0, b.0));
+            }
+            Ordering::Equal
+        })
+    });
+    if let Some(keys) = unordered {
+        vec![Err(Box::new(UnorderedKeyError(keys)))]
+    } else {
+        groups.into_iter().map(|g| Ok(g)).collect()
+    }
 }
 
 struct GroupByHandler<I, O, K: KeyFunction<I>, A: AccumFactory<I, O>> {
@@ -212,3 +306,180 @@ where
             groups.take().into_iter().map(|(k, mut v)| Ok((k, v.finalize())))
         })
 }
+
+/// The members of a group, where the earlier members are spilled to disk;
+struct SpillGroup<D> {
+    spilled: Vec<SpillFile>,
+    buffer: Vec<D>,
+}
+
+impl<D: Codec> SpillGroup<D> {
+    fn new() -> Self {
+        SpillGroup { spilled: vec![], buffer: vec![] }
+    }
+
+    fn spill(&mut self, dir: &Path) -> std::io::Result<()> {
+        let file = SpillFile::write(dir, &self.buffer)?;
+        self.spilled.push(file);
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Read all the members back in the order they arrive;
+    fn into_members(self) -> std::io::Result<Vec<D>> {
+        let len = self.spilled.iter().map(|file| file.len()).sum::<usize>() + self.buffer.len();
+        let mut members = Vec::with_capacity(len);
+        for file in self.spilled {
+            for member in file.into_reader::<D>()? {
+                members.push(member?);
+            }
+        }
+        members.extend(self.buffer);
+        Ok(members)
+    }
+}
+
+impl<D> Debug for SpillGroup<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "spill group: {} in memory, {} files", self.buffer.len(), self.spilled.len())
+    }
+}
+
+struct SpillGroupHandler<I, K: KeyFunction<I>> {
+    multi_states: StateMap<HashMap<K::Target, NeverClone<SpillGroup<I>>>>,
+    key_func: K,
+    conf: SpillConf,
+    /// stop spilling after a failure, and keep the rest in memory;
+    spill_failed: bool,
+}
+
+impl<I, K: KeyFunction<I>> SpillGroupHandler<I, K> {
+    pub fn new(meta: &OperatorMeta, key_func: K, conf: SpillConf) -> Self {
+        SpillGroupHandler { multi_states: StateMap::new(meta), key_func, conf, spill_failed: false }
+    }
+}
+
+impl<I: Data, K: KeyFunction<I>>
+    UnaryNotify<I, ShadeCodec<HashMap<K::Target, NeverClone<SpillGroup<I>>>>>
+    for SpillGroupHandler<I, K>
+{
+    type NotifyResult = Vec<ShadeCodec<HashMap<K::Target, NeverClone<SpillGroup<I>>>>>;
+
+    fn on_receive(
+        &mut self, input: &mut Input<I>,
+        _: &mut Output<ShadeCodec<HashMap<K::Target, NeverClone<SpillGroup<I>>>>>,
+    ) -> Result<(), JobExecError> {
+        input.subscribe_notify();
+        let mut multi_states = std::mem::replace(&mut self.multi_states, StateMap::default());
+        let state = multi_states.entry(&input.tag).or_insert_with(HashMap::new);
+        let result = input.for_each_batch(|data_set| {
+            for data in data_set.drain(..) {
+                let key = self.key_func.get_key(&data);
+                if !state.contains_key(&*key) {
+                    state.insert(key.clone().into_owned(), never_clone(SpillGroup::new()));
+                }
+                let group = state.get_mut(&*key).expect("group not found");
+                group.buffer.push(data);
+                if !self.spill_failed && group.buffer.len() >= self.conf.threshold {
+                    if let Err(err) = group.spill(&self.conf.dir) {
+                        warn_worker!(
+                            "spill group failure, keep members in memory, caused by {}",
+                            err
+                        );
+                        self.spill_failed = true;
+                    }
+                }
+            }
+            Ok(())
+        });
+        self.multi_states = multi_states;
+        result
+    }
+
+    fn on_notify(&mut self, n: &Notification) -> Self::NotifyResult {
+        self.multi_states.notify(n);
+        let notified = self.multi_states.extract_notified();
+        assert_eq!(notified.len(), 1);
+        let result = notified.remove(0).1;
+        vec![shade_codec(result)]
+    }
+}
+
+fn spill_group<I: Data, C, F>(
+    stream: &Stream<I>, channel: C, key: F, conf: SpillConf,
+) -> Result<Stream<(<F as KeyFunction<I>>::Target, Vec<I>)>, BuildJobError>
+where
+    C: Into<Channel<I>>,
+    F: KeyFunction<I>,
+{
+    stream
+        .unary_with_notify("group_by_with_spill", channel, |meta| {
+            SpillGroupHandler::new(meta, key, conf)
+        })?
+        .flat_map_with_fn(Pipeline, |groups| {
+            // the groups are loaded back one by one when output;
+            groups.take().into_iter().map(|(k, group)| {
+                group
+                    .take()
+                    .into_members()
+                    .map(|members| (k, members))
+                    .map_err(|err| Box::new(err) as Box<dyn Error + Send>)
+            })
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct Opaque(u32);
+
+    impl PartialOrd for Opaque {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            if self.0 == other.0 {
+                Some(Ordering::Equal)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn spill_group_test() {
+        let mut group = SpillGroup::new();
+        group.buffer.extend(vec![3u32, 1]);
+        group.spill(&std::env::temp_dir()).unwrap();
+        group.buffer.push(2);
+        assert_eq!(group.spilled.len(), 1);
+        assert_eq!(group.buffer.len(), 1);
+        let path = group.spilled[0].path().to_path_buf();
+        // the members keep the order they arrive;
+        assert_eq!(group.into_members().unwrap(), vec![3, 1, 2]);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn sort_groups_test() {
+        let groups = vec![(2u32, 'b'), (3, 'c'), (1, 'a')];
+        let asc = sort_groups(groups.clone(), OrderDirect::Asc)
+            .into_iter()
+            .map(|g| g.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(asc, vec![1, 2, 3]);
+        let desc = sort_groups(groups, OrderDirect::Desc)
+            .into_iter()
+            .map(|g| g.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(desc, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn sort_unordered_groups_test() {
+        let groups = vec![(Opaque(1), 1), (Opaque(2), 2)];
+        let result = sort_groups(groups, OrderDirect::Asc);
+        assert_eq!(result.len(), 1);
+        let err = result.into_iter().next().unwrap().err().expect("expect unordered error");
+        assert!(err.to_string().starts_with("group keys are not totally ordered"));
+    }
+}
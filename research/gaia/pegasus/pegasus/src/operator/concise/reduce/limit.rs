@@ -16,20 +16,26 @@
 use crate::api::concise::reduce::Range;
 use crate::api::meta::OperatorKind;
 use crate::api::state::OperatorState;
-use crate::api::{Limit, Unary, UnaryState};
+use crate::api::{Fold, Limit, Map, Unary, UnaryState};
 use crate::communication::{Aggregate, Input, Output, Pipeline};
 use crate::errors::JobExecError;
 use crate::stream::Stream;
+use crate::worker_id::get_current_worker_uncheck;
 use crate::{BuildJobError, Data};
 
 #[derive(Copy, Clone, Debug)]
 struct LimitHandle {
+    offset: u64,
     count: u64,
 }
 
 impl LimitHandle {
     pub fn new(count: u64) -> Self {
-        LimitHandle { count }
+        LimitHandle { offset: 0, count }
+    }
+
+    pub fn with_offset(offset: u64, count: u64) -> Self {
+        LimitHandle { offset, count }
     }
 }
 
@@ -39,12 +45,15 @@ impl<D: Data> UnaryState<D, D, u64> for LimitHandle {
     fn on_receive(
         &self, input: &mut Input<D>, output: &mut Output<D>, state: &mut OperatorState<u64>,
     ) -> Result<(), JobExecError> {
+        let end = self.offset + self.count;
         input.for_each_batch(|dataset| {
-            if **state < self.count {
+            if **state < end {
                 for datum in dataset.drain(..) {
                     **state += 1;
-                    output.give(datum)?;
-                    if **state >= self.count {
+                    if **state > self.offset {
+                        output.give(datum)?;
+                    }
+                    if **state >= end {
                         state.set_final();
                         break;
                     }
@@ -79,4 +88,29 @@ impl<D: Data> Limit<D> for Stream<D> {
             }),
         }
     }
+
+    fn range(&self, range: Range, offset: u32, size: u32) -> Result<Stream<D>, BuildJobError> {
+        match range {
+            Range::Global => self
+                .unary_with_state("range_local", Pipeline, |meta| {
+                    meta.set_kind(OperatorKind::Clip);
+                    LimitHandle::new(offset as u64 + size as u64)
+                })?
+                .map_with_fn(Pipeline, |datum| Ok((get_current_worker_uncheck().index, datum)))?
+                .fold(Vec::new(), Aggregate(0), |partials: &mut Vec<(u32, D)>, p| partials.push(p))?
+                .flat_map_with_fn(Pipeline, move |mut partials| {
+                    // the sort is stable, which keeps the order of the data from the same worker;
+                    partials.sort_by_key(|(index, _)| *index);
+                    partials
+                        .into_iter()
+                        .skip(offset as usize)
+                        .take(size as usize)
+                        .map(|(_, datum)| Ok(datum))
+                }),
+            Range::Local => self.unary_with_state("range", Pipeline, |meta| {
+                meta.set_kind(OperatorKind::Clip);
+                LimitHandle::with_offset(offset as u64, size as u64)
+            }),
+        }
+    }
 }
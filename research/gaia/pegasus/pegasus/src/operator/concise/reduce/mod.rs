@@ -15,6 +15,56 @@
 
 mod barrier;
 mod count;
+mod extreme;
 mod group;
 mod limit;
 mod order;
+mod sample;
+mod spill;
+
+use crate::api::{Exchange, GlobalCombine};
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::Data;
+
+/// Get the fanout of a tree combine, return `None` for a linear combine, or a build error if the
+/// fanout is less than 2;
+fn tree_fanout(combine: GlobalCombine) -> Result<Option<u32>, BuildJobError> {
+    match combine {
+        GlobalCombine::Linear => Ok(None),
+        GlobalCombine::Tree { fanout } if fanout < 2 => {
+            BuildJobError::unsupported(format!("fanout of tree combine {} is less than 2", fanout))
+        }
+        GlobalCombine::Tree { fanout } => Ok(Some(fanout)),
+    }
+}
+
+/// Combine the partial results of all the workers into one on the worker 0 in a tree, where
+/// `combine` combines the partial results received by a worker on each level, see
+/// [`GlobalCombine::Tree`]. On the level `k`, the worker `i` sends its partial results to the
+/// worker `i / fanout^k * fanout^k`, which only depends on the worker, so the route is fixed
+/// when the dataflow is built;
+fn tree_combine<D, F>(
+    stream: &Stream<D>, fanout: u32, combine: F,
+) -> Result<Stream<D>, BuildJobError>
+where
+    D: Data,
+    F: Fn(&Stream<D>) -> Result<Stream<D>, BuildJobError>,
+{
+    let peers = stream.peers() as u64;
+    let index = stream.index() as u64;
+    let mut combined: Option<Stream<D>> = None;
+    let mut width = 1u64;
+    while width < peers {
+        width *= fanout as u64;
+        let target = index / width * width;
+        let input = combined.as_ref().unwrap_or(stream);
+        let exchanged = input.exchange_with_fn(move |_| target)?;
+        combined = Some(combine(&exchanged)?);
+    }
+    match combined {
+        Some(combined) => Ok(combined),
+        // the partial results of the only worker are already combined;
+        None => combine(stream),
+    }
+}
@@ -16,9 +16,11 @@
 use crate::api::concise::reduce::barrier::Barrier;
 use crate::api::concise::reduce::order::{Order, OrderDirect};
 use crate::api::function::*;
-use crate::api::{Map, OrderBy, Range};
+use crate::api::{GlobalCombine, Map, OrderBy, Range, SpillConf};
 use crate::codec::{shade_codec, ShadeCodec};
 use crate::communication::Pipeline;
+use crate::operator::concise::reduce::spill::SpillFile;
+use crate::operator::concise::reduce::{tree_combine, tree_fanout};
 use crate::operator::concise::{never_clone, NeverClone};
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
@@ -26,7 +28,9 @@ use pegasus_common::codec::{Codec, Decode, Encode, ReadExt, WriteExt};
 use pegasus_common::collections::{Collection, CollectionFactory};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::error::Error;
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::ptr::NonNull;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::SeqCst;
@@ -59,6 +63,22 @@ impl<D: Data + Ord> Order<D> for Stream<D> {
             Ok(stream)
         }
     }
+
+    fn top_global_with(
+        &self, limit: u32, order: OrderDirect, combine: GlobalCombine,
+    ) -> Result<Stream<D>, BuildJobError> {
+        if limit == 0 {
+            return BuildJobError::unsupported("top n can't equal to 0");
+        }
+        match tree_fanout(combine)? {
+            None => self.top(limit, Range::Global, order),
+            Some(fanout) => {
+                let limit = limit as usize;
+                let local = get_top(&self, Range::Local, order, limit)?;
+                tree_combine(&local, fanout, |partial| get_top(partial, Range::Local, order, limit))
+            }
+        }
+    }
 }
 
 impl<D: Data> OrderBy<D> for Stream<D> {
@@ -100,6 +120,24 @@ impl<D: Data> OrderBy<D> for Stream<D> {
             }
         }
     }
+
+    fn sort_by_with_spill<F>(
+        &self, range: Range, cmp: F, conf: SpillConf,
+    ) -> Result<Stream<D>, BuildJobError>
+    where
+        F: CompareFunction<D> + 'static,
+    {
+        if conf.threshold == 0 {
+            return BuildJobError::unsupported("spill threshold can't equal to 0");
+        }
+
+        let param = OrdParam::<D, F>::new(conf.threshold, Box::new(cmp));
+        let factory = SpillSorterFactory { param, dir: conf.dir };
+        self.barrier_with(range, factory)?.flat_map_with_fn(Pipeline, move |input| {
+            let input = input.take().take();
+            input.into_sorted().map(|item| item.map_err(|e| Box::new(e) as Box<dyn Error + Send>))
+        })
+    }
 }
 
 #[inline]
@@ -456,3 +494,188 @@ impl<D: Send, C: CompareFunction<D>> CollectionFactory<D> for CustomOrdQueueFact
         never_clone(shade_codec(queue))
     }
 }
+
+/// Sort the data by the comparator with bounded memory, where the data buffered are sorted and
+/// spilled to disk as a sorted run once `param.limit` data are buffered;
+struct SpillSorter<D, C: CompareFunction<D>> {
+    buffer: Vec<D>,
+    runs: Vec<SpillFile>,
+    dir: PathBuf,
+    /// stop spilling after a failure, and keep the rest in memory;
+    spill_failed: bool,
+    param: OrdParam<D, C>,
+}
+
+impl<D: Codec + Send + 'static, C: CompareFunction<D>> SpillSorter<D, C> {
+    fn spill(&mut self) {
+        let cmp = unsafe { self.param.cmp.as_ref() };
+        self.buffer.sort_by(|a, b| cmp.compare(a, b));
+        match SpillFile::write(&self.dir, &self.buffer) {
+            Ok(run) => {
+                self.runs.push(run);
+                self.buffer.clear();
+            }
+            Err(err) => {
+                warn_worker!("spill sorted run failure, keep data in memory, caused by {}", err);
+                self.spill_failed = true;
+            }
+        }
+    }
+
+    /// Merge all the sorted runs and the data in memory, the earlier spilled run goes first for
+    /// the data equal under the comparator, so the sorting is stable;
+    fn into_sorted(mut self) -> SortedMerge<D, C> {
+        let cmp = unsafe { self.param.cmp.as_ref() };
+        self.buffer.sort_by(|a, b| cmp.compare(a, b));
+        let mut merge = SortedMerge {
+            sources: Vec::with_capacity(self.runs.len() + 1),
+            heads: BinaryHeap::new(),
+            failure: None,
+            param: self.param.clone(),
+        };
+        for run in self.runs.drain(..) {
+            match run.into_reader::<D>() {
+                Ok(reader) => merge.sources.push(Box::new(reader)),
+                Err(err) => {
+                    merge.failure = Some(err);
+                    return merge;
+                }
+            }
+        }
+        let buffer = std::mem::replace(&mut self.buffer, vec![]);
+        merge.sources.push(Box::new(buffer.into_iter().map(Ok)));
+        for index in 0..merge.sources.len() {
+            merge.pull(index);
+        }
+        merge
+    }
+}
+
+impl<D: Codec + Send + 'static, C: CompareFunction<D>> Collection<D> for SpillSorter<D, C> {
+    fn add(&mut self, item: D) -> Option<D> {
+        self.buffer.push(item);
+        if !self.spill_failed && self.buffer.len() >= self.param.limit {
+            self.spill();
+        }
+        None
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.runs.clear();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.runs.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len() + self.runs.iter().map(|run| run.len()).sum::<usize>()
+    }
+}
+
+impl<D, C: CompareFunction<D>> Debug for SpillSorter<D, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "spill sorter: {} in memory, {} runs", self.buffer.len(), self.runs.len())
+    }
+}
+
+struct SortedMerge<D, C: CompareFunction<D>> {
+    sources: Vec<Box<dyn Iterator<Item = std::io::Result<D>> + Send>>,
+    /// the head of each source with its index, the min head is on the top;
+    heads: BinaryHeap<std::cmp::Reverse<(Item<D, C>, usize)>>,
+    failure: Option<std::io::Error>,
+    param: OrdParam<D, C>,
+}
+
+impl<D, C: CompareFunction<D>> SortedMerge<D, C> {
+    fn pull(&mut self, index: usize) {
+        match self.sources[index].next() {
+            Some(Ok(inner)) => {
+                let item = Item { inner, cmp: self.param.cmp };
+                self.heads.push(std::cmp::Reverse((item, index)));
+            }
+            Some(Err(err)) => self.failure = Some(err),
+            None => (),
+        }
+    }
+}
+
+impl<D, C: CompareFunction<D>> Iterator for SortedMerge<D, C> {
+    type Item = std::io::Result<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.failure.take() {
+            self.heads.clear();
+            return Some(Err(err));
+        }
+        let std::cmp::Reverse((item, index)) = self.heads.pop()?;
+        self.pull(index);
+        Some(Ok(item.inner))
+    }
+}
+
+struct SpillSorterFactory<D, C: CompareFunction<D>> {
+    param: OrdParam<D, C>,
+    dir: PathBuf,
+}
+
+impl<D: Codec + Send + 'static, C: CompareFunction<D>> CollectionFactory<D>
+    for SpillSorterFactory<D, C>
+{
+    type Target = NeverClone<ShadeCodec<SpillSorter<D, C>>>;
+
+    fn create(&self) -> Self::Target {
+        let sorter = SpillSorter {
+            buffer: vec![],
+            runs: vec![],
+            dir: self.dir.clone(),
+            spill_failed: false,
+            param: self.param.clone(),
+        };
+        never_clone(shade_codec(sorter))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spill_sorter(threshold: usize) -> SpillSorter<(u32, u32), impl CompareFunction<(u32, u32)>> {
+        let cmp = CompareClosure::new(|a: &(u32, u32), b: &(u32, u32)| a.0.cmp(&b.0));
+        let param = OrdParam::new(threshold, Box::new(cmp));
+        let factory = SpillSorterFactory { param, dir: std::env::temp_dir() };
+        factory.create().take().take()
+    }
+
+    #[test]
+    fn spill_sort_test() {
+        let mut sorter = spill_sorter(3);
+        for i in 0..20u32 {
+            sorter.add((i % 3, i));
+        }
+        // 6 runs of 3 spilled, and 2 left in memory;
+        assert_eq!(sorter.runs.len(), 6);
+        assert_eq!(sorter.len(), 20);
+        let paths = sorter.runs.iter().map(|run| run.path().to_path_buf()).collect::<Vec<_>>();
+        let sorted = sorter.into_sorted().map(|item| item.unwrap()).collect::<Vec<_>>();
+        // stable for the data of the same key;
+        let mut expected = (0..20u32).map(|i| (i % 3, i)).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(sorted, expected);
+        for path in paths {
+            assert!(!path.exists());
+        }
+    }
+
+    #[test]
+    fn spill_sort_in_memory_test() {
+        let mut sorter = spill_sorter(100);
+        for i in (0..10u32).rev() {
+            sorter.add((i, i));
+        }
+        assert!(sorter.runs.is_empty());
+        let sorted = sorter.into_sorted().map(|item| item.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(sorted, (0..10u32).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,48 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::accum::Reservoir;
+use crate::api::concise::reduce::Range;
+use crate::api::{Fold, Map, Sample};
+use crate::communication::Pipeline;
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::worker_id::get_current_worker_uncheck;
+use crate::Data;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl<D: Data> Sample<D> for Stream<D> {
+    fn sample(&self, n: u32, range: Range, seed: Option<u64>) -> Result<Stream<D>, BuildJobError> {
+        if n == 0 {
+            return BuildJobError::unsupported("sample size should be positive");
+        }
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+        });
+        let reservoir = Reservoir::new(n as usize, seed);
+        self.fold_in_range(
+            reservoir,
+            range,
+            |r, d| {
+                if r.is_empty() {
+                    r.salt(get_current_worker_uncheck().index as u64 + 1);
+                }
+                r.insert(d)
+            },
+            |r, other| r.merge(other),
+        )?
+        .flat_map_with_fn(Pipeline, |r| r.into_items().into_iter().map(Ok))
+    }
+}
@@ -0,0 +1,113 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use pegasus_common::codec::{Decode, Encode};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SPILL_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A file of data spilled to disk, which is removed once dropped;
+pub(crate) struct SpillFile {
+    path: PathBuf,
+    count: usize,
+}
+
+impl SpillFile {
+    /// Write `data` into a new file in `dir`;
+    pub fn write<D: Encode>(dir: &Path, data: &[D]) -> std::io::Result<Self> {
+        let id = SPILL_FILE_ID.fetch_add(1, Ordering::SeqCst);
+        let path = dir.join(format!("pegasus_spill_{}_{}", std::process::id(), id));
+        // created before the file, so the file is removed if any write fails;
+        let mut spill = SpillFile { path, count: 0 };
+        let mut writer = BufWriter::new(File::create(&spill.path)?);
+        for datum in data {
+            datum.write_to(&mut writer)?;
+        }
+        writer.flush()?;
+        spill.count = data.len();
+        Ok(spill)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the data back in the order they are written;
+    pub fn into_reader<D: Decode>(self) -> std::io::Result<SpillReader<D>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        Ok(SpillReader { file: self, reader, read: 0, _ph: std::marker::PhantomData })
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn_worker!("remove spill file {:?} failure, caused by {}", self.path, err);
+            }
+        }
+    }
+}
+
+/// Read the data of a spill file, the file is removed once the reader is dropped;
+pub(crate) struct SpillReader<D> {
+    file: SpillFile,
+    reader: BufReader<File>,
+    read: usize,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Decode> Iterator for SpillReader<D> {
+    type Item = std::io::Result<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.read >= self.file.count {
+            return None;
+        }
+        self.read += 1;
+        let next = D::read_from(&mut self.reader);
+        if next.is_err() {
+            // stop reading the rest after failure;
+            self.read = self.file.count;
+        }
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spill_file_test() {
+        let spill = SpillFile::write(&std::env::temp_dir(), &[1u32, 3, 2]).unwrap();
+        let path = spill.path().to_path_buf();
+        assert!(path.exists());
+        assert_eq!(spill.len(), 3);
+        let reader = spill.into_reader::<u32>().unwrap();
+        assert_eq!(reader.map(|d| d.unwrap()).collect::<Vec<_>>(), vec![1, 3, 2]);
+        // removed after the reader is dropped;
+        assert!(!path.exists());
+    }
+}
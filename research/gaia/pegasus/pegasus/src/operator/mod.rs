@@ -22,6 +22,7 @@ use crate::event::EventBus;
 use crate::graph::Port;
 use crate::{Data, Tag};
 use std::collections::HashMap;
+use std::time::Instant;
 
 /// Describe the operator's state after it been fired;
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -50,6 +51,18 @@ pub trait OperatorCore: Send {
     ) -> Result<(), JobExecError> {
         Ok(())
     }
+
+    /// The earliest instant at which the timers of the operator are due, or `None` if there are
+    /// no timers. The operator is woken up at that instant even if no data or notification
+    /// arrives, and is not fired for the timers before it;
+    fn next_timer(&self) -> Option<Instant> {
+        None
+    }
+
+    /// Fire the timers, which is called once the instant given by `next_timer` is reached;
+    fn on_timer(&mut self, _o: &[Box<dyn OutputProxy>]) -> Result<(), JobExecError> {
+        Ok(())
+    }
 }
 
 mod cancel;
@@ -99,6 +112,16 @@ impl Operator {
         !self.actives.is_empty()
     }
 
+    #[inline]
+    pub fn next_timer(&self) -> Option<Instant> {
+        self.core.next_timer()
+    }
+
+    #[inline]
+    pub fn has_due_timers(&self) -> bool {
+        self.core.next_timer().map(|t| t <= Instant::now()).unwrap_or(false)
+    }
+
     #[inline]
     pub fn has_outstanding(&self) -> bool {
         let len = self.inputs.len();
@@ -163,6 +186,15 @@ impl Operator {
         Ok(())
     }
 
+    pub fn fire_timers(&mut self) -> Result<(), JobExecError> {
+        if self.has_due_timers() {
+            trace_worker!("fire operator {:?} on timers;", self.meta);
+            self.core.on_timer(&self.outputs)
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn fire_on_receive(&mut self, tag: &Tag) -> Result<(), JobExecError> {
         if self.actives.contains_key(tag) {
             Ok(())
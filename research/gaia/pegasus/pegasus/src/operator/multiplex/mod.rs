@@ -14,10 +14,18 @@
 //! limitations under the License.
 
 use crate::api::meta::OperatorMeta;
-use crate::api::{EnterScope, Multiplexing, ScopeInput, ScopeInputEmitter};
-use crate::errors::BuildJobError;
+use crate::api::notify::Notification;
+use crate::api::state::StateMap;
+use crate::api::{
+    complete, EnterScope, Map, Multiplexing, Remainder, ScopeInput, ScopeInputEmitter, Unary,
+};
+use crate::communication::input::{new_input_session, InputProxy};
+use crate::communication::output::{new_output_session, OutputProxy};
+use crate::communication::Pipeline;
+use crate::errors::{BuildJobError, JobExecError};
+use crate::operator::{FiredState, OperatorCore};
 use crate::stream::Stream;
-use crate::Data;
+use crate::{Data, Tag};
 
 impl<D: Data> Multiplexing<D> for Stream<D> {
     fn scope_by<F>(&self, key: F) -> Result<Stream<D>, BuildJobError>
@@ -27,9 +35,58 @@ impl<D: Data> Multiplexing<D> for Stream<D> {
         self.dyn_enter(|_| AnonymityEmitter::new(key))
     }
 
+    fn scope_by_tagged<F>(&self, key: F) -> Result<Stream<(u32, D)>, BuildJobError>
+    where
+        F: FnMut(&D) -> Option<u32> + Send + Clone + 'static,
+    {
+        // tag the data by the scope they actually entered, instead of calling `key` again;
+        self.scope_by(key)?.unary("tag_scope", Pipeline, |_meta| {
+            |input, output| {
+                input.for_each_batch(|dataset| {
+                    let id = dataset.tag.current_uncheck();
+                    for datum in dataset.drain(..) {
+                        output.give((id, datum))?;
+                    }
+                    Ok(())
+                })
+            }
+        })
+    }
+
     fn scope_by_size(&self, length: usize) -> Result<Stream<D>, BuildJobError> {
+        if length == 0 {
+            return BuildJobError::invalid_scope("length of scope_by_size can't be 0");
+        }
         self.dyn_enter(|meta| FixSizeEmitter::new(length, meta))
     }
+
+    fn scope_by_size_with(
+        &self, length: usize, remainder: Remainder,
+    ) -> Result<Stream<D>, BuildJobError> {
+        if length == 0 {
+            return BuildJobError::invalid_scope("length of scope_by_size can't be 0");
+        }
+        if remainder == Remainder::Keep {
+            return self.scope_by_size(length);
+        }
+        // each chunk of exactly `length` data enters a scope as a whole, then flattened in it;
+        self.concat("chunk", Pipeline, |meta| {
+            meta.enable_notify();
+            Box::new(ChunkOperator::<D>::new(length, remainder, meta))
+        })?
+        .scope_by_size(1)?
+        .flat_map_with_fn(Pipeline, |chunk: Vec<D>| chunk.into_iter().map(|item| Ok(item)))
+    }
+
+    fn scope_by_range<F>(&self, key: F, window: u64) -> Result<Stream<D>, BuildJobError>
+    where
+        F: FnMut(&D) -> u64 + Send + Clone + 'static,
+    {
+        if window == 0 {
+            return BuildJobError::invalid_scope("window of scope_by_range can't be 0");
+        }
+        self.dyn_enter(|_| WindowEmitter::new(key, window))
+    }
 }
 
 struct AnonymityEmitter<D: Data, F: FnMut(&D) -> Option<u32> + Send + Clone + 'static> {
@@ -103,4 +160,107 @@ impl<D: Data> ScopeInputEmitter<D> for FixSizeEmitter<D> {
     }
 }
 
+struct WindowEmitter<D: Data, F: FnMut(&D) -> u64 + Send + Clone + 'static> {
+    func: F,
+    window: u64,
+    current: Option<u32>,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Data, F: FnMut(&D) -> u64 + Send + Clone + 'static> WindowEmitter<D, F> {
+    pub fn new(func: F, window: u64) -> Self {
+        WindowEmitter { func, window, current: None, _ph: std::marker::PhantomData }
+    }
+}
+
+impl<D: Data, F: FnMut(&D) -> u64 + Send + Clone + 'static> Clone for WindowEmitter<D, F> {
+    fn clone(&self) -> Self {
+        WindowEmitter {
+            func: self.func.clone(),
+            window: self.window,
+            current: self.current,
+            _ph: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<D: Data, F: FnMut(&D) -> u64 + Send + Clone + 'static> ScopeInputEmitter<D>
+    for WindowEmitter<D, F>
+{
+    fn get_scope(&mut self, data: D) -> Option<ScopeInput<D>> {
+        let key = (self.func)(&data);
+        let id = key / self.window;
+        if id > u32::MAX as u64 {
+            warn_worker!("drop data of key {} as window id {} overflow;", key, id);
+            return None;
+        }
+        let id = id as u32;
+        match self.current {
+            Some(current) if id < current => {
+                warn_worker!("drop data of key {} as window {} has been closed;", key, id);
+                return None;
+            }
+            Some(current) if id > current => {
+                complete(current);
+                self.current = Some(id);
+            }
+            Some(_) => (),
+            None => self.current = Some(id),
+        }
+        Some(ScopeInput::new(id, false, data))
+    }
+}
+
+struct ChunkOperator<D> {
+    length: usize,
+    remainder: Remainder,
+    chunks: StateMap<Vec<D>>,
+}
+
+impl<D> ChunkOperator<D> {
+    pub fn new(length: usize, remainder: Remainder, meta: &OperatorMeta) -> Self {
+        ChunkOperator { length, remainder, chunks: StateMap::new(meta) }
+    }
+}
+
+impl<D: Data> OperatorCore for ChunkOperator<D> {
+    fn on_receive(
+        &mut self, tag: &Tag, inputs: &[Box<dyn InputProxy>], outputs: &[Box<dyn OutputProxy>],
+    ) -> Result<FiredState, JobExecError> {
+        let mut input = new_input_session::<D>(&inputs[0], tag);
+        let mut output = new_output_session::<Vec<D>>(&outputs[0], tag);
+        let length = self.length;
+        let chunk = self.chunks.entry(tag).or_insert_with(|| Vec::with_capacity(length));
+        input.for_each_batch(|dataset| {
+            for datum in dataset.drain(..) {
+                chunk.push(datum);
+                if chunk.len() == length {
+                    let full = std::mem::replace(chunk, Vec::with_capacity(length));
+                    output.give(full)?;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(FiredState::Idle)
+    }
+
+    fn on_notify(
+        &mut self, n: Notification, _: &[Box<dyn OutputProxy>],
+    ) -> Result<(), JobExecError> {
+        self.chunks.notify(&n.tag);
+        for (tag, chunk) in self.chunks.extract_notified().drain(..) {
+            if !chunk.is_empty() && self.remainder == Remainder::Error {
+                let msg = format!(
+                    "{} trailing data of scope {:?} are fewer than length {}",
+                    chunk.len(),
+                    tag,
+                    self.length
+                );
+                return Err(JobExecError::from(msg));
+            }
+        }
+        Ok(())
+    }
+}
+
 mod subtask;
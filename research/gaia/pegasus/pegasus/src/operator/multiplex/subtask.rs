@@ -19,7 +19,7 @@ use crate::api::notify::Notification;
 use crate::api::state::StateMap;
 use crate::api::{
     Binary, BinaryInput, BinaryNotification, BinaryNotify, Exchange, LeaveScope, Multiplexing,
-    ResultSet, SubTask, SubtaskResult,
+    ResultSet, SubTask, SubtaskCancel, SubtaskResult,
 };
 use crate::communication::input::{new_input_session, InputProxy};
 use crate::communication::output::{new_output_session, OutputProxy};
@@ -38,11 +38,43 @@ impl<D: Data> SubTask<D> for Stream<D> {
     {
         let m = self.scope_by_size(1)?;
         let sub = func(m)?;
-        sub.concat("subtask_sink", Pipeline, |meta| Box::new(SubtaskSink::<T>::new(meta)))?
+        sub.concat("subtask_sink", Pipeline, |meta| Box::new(SubtaskSink::<T>::new(meta, None)))?
             .owned_leave()?
             .exchange(route!(|item: &SubtaskResult<T>| item.seq as u64))
     }
 
+    fn join_subtask_with<F>(
+        &self, subtask: Stream<SubtaskResult<D>>, func: F,
+    ) -> Result<Stream<D>, BuildJobError>
+    where
+        F: LeftJoinFunction<D>,
+    {
+        if func.is_collect() || func.is_fold() {
+            self.binary_notify("join_subtask_fold", &subtask, Pipeline, Pipeline, |meta| {
+                SubtaskFoldJoin::new(meta, func)
+            })
+        } else {
+            self.join_subtask(subtask, move |p, s| func.exec(p, s))
+        }
+    }
+
+    fn fork_cancelable_subtask<F, T>(
+        &self, cancel: &SubtaskCancel, func: F,
+    ) -> Result<Stream<SubtaskResult<T>>, BuildJobError>
+    where
+        T: Data,
+        F: FnOnce(Stream<D>) -> Result<Stream<T>, BuildJobError> + Send,
+    {
+        let m = self.scope_by_size(1)?;
+        let sub = func(m)?;
+        let cancel = cancel.clone();
+        sub.concat("subtask_sink", Pipeline, |meta| {
+            Box::new(SubtaskSink::<T>::new(meta, Some(cancel)))
+        })?
+        .owned_leave()?
+        .exchange(route!(|item: &SubtaskResult<T>| item.seq as u64))
+    }
+
     fn fork_detached_subtask<F, T>(
         &self, _conf: JobConf, _func: F,
     ) -> Result<Stream<SubtaskResult<T>>, BuildJobError>
@@ -65,19 +97,38 @@ impl<D: Data> SubTask<D> for Stream<D> {
             SubtaskJoin::new(meta, func)
         })
     }
+
+    fn join_subtask_limit<T, R, F>(
+        &self, subtask: Stream<SubtaskResult<T>>, limit: usize, cancel: &SubtaskCancel, func: F,
+    ) -> Result<Stream<R>, BuildJobError>
+    where
+        T: Data,
+        R: Data,
+        F: Fn(&D, T) -> Option<R> + Send + 'static,
+    {
+        if limit == 0 {
+            return BuildJobError::unsupported("join_subtask_limit with limit = 0");
+        }
+        let cancel = cancel.clone();
+        self.binary_notify("join_subtask_limit", &subtask, Pipeline, Pipeline, |meta| {
+            SubtaskJoin::with_limit(meta, limit, cancel, func)
+        })
+    }
 }
 
 struct SubtaskSink<D: Data> {
     scope_depth: usize,
     state: StateMap<()>,
+    cancel: Option<SubtaskCancel>,
     _ph: std::marker::PhantomData<D>,
 }
 
 impl<D: Data> SubtaskSink<D> {
-    fn new(meta: &OperatorMeta) -> Self {
+    fn new(meta: &OperatorMeta, cancel: Option<SubtaskCancel>) -> Self {
         SubtaskSink {
             scope_depth: meta.scope_depth,
             state: StateMap::new(meta),
+            cancel,
             _ph: std::marker::PhantomData,
         }
     }
@@ -90,6 +141,16 @@ impl<D: Data> OperatorCore for SubtaskSink<D> {
         let mut input = new_input_session::<D>(&inputs[0], tag);
         let mut output = new_output_session::<SubtaskResult<D>>(&outputs[0], tag);
         self.state.entry(tag).or_insert(());
+        if self.cancel.as_ref().map(|c| c.is_canceled(tag)).unwrap_or(false) {
+            // the parent is satisfied, discard the rest data of the subtask, and stop the upstream
+            // operators of the subtask from producing more;
+            input.cancel_scope();
+            input.for_each_batch(|dataset| {
+                dataset.clear();
+                Ok(())
+            })?;
+            return Ok(FiredState::Idle);
+        }
         let seq = tag.current_uncheck();
         input.for_each_batch(|dataset| {
             if !dataset.is_empty() {
@@ -119,7 +180,9 @@ impl<D: Data> OperatorCore for SubtaskSink<D> {
 
 struct SubtaskJoin<L, R, O, F> {
     peers: u32,
-    parent_data: HashMap<Tag, Vec<Option<L>>>,
+    /// the parents of each scope, with the number of results joined with each parent;
+    parent_data: HashMap<Tag, Vec<Option<(L, usize)>>>,
+    limit: Option<(usize, SubtaskCancel)>,
     func: F,
     _ph: std::marker::PhantomData<(R, O)>,
 }
@@ -129,10 +192,17 @@ impl<L, R, O, F> SubtaskJoin<L, R, O, F> {
         SubtaskJoin {
             peers: meta.worker_id.peers,
             parent_data: HashMap::new(),
+            limit: None,
             func,
             _ph: std::marker::PhantomData,
         }
     }
+
+    pub fn with_limit(meta: &OperatorMeta, limit: usize, cancel: SubtaskCancel, func: F) -> Self {
+        let mut join = SubtaskJoin::new(meta, func);
+        join.limit = Some((limit, cancel));
+        join
+    }
 }
 
 impl<L, R, O, F> BinaryNotify<L, SubtaskResult<R>, O> for SubtaskJoin<L, R, O, F>
@@ -155,24 +225,37 @@ where
 
         input.left_for_each(|dataset| {
             for item in dataset.drain(..) {
-                parent_data.push(Some(item));
+                parent_data.push(Some((item, 0)));
             }
             Ok(())
         })?;
 
+        let tag = input.tag().clone();
         input.right_for_each(|dataset| {
             for data in dataset.drain(..) {
                 let offset = (data.seq / self.peers) as usize;
+                let seq = data.seq;
                 if let Some(parent) = parent_data.get_mut(offset) {
-                    if let Some(p) = parent.take() {
+                    if let Some((p, mut joined)) = parent.take() {
                         match data.take() {
                             ResultSet::Data(s_data) => {
                                 for r in s_data {
+                                    if let Some((limit, _)) = self.limit.as_ref() {
+                                        if joined >= *limit {
+                                            break;
+                                        }
+                                    }
                                     if let Some(join) = (self.func)(&p, r) {
                                         output.give(join)?;
+                                        joined += 1;
                                     }
                                 }
-                                parent.replace(p);
+                                if let Some((limit, cancel)) = self.limit.as_ref() {
+                                    if joined >= *limit {
+                                        cancel.cancel(&tag, seq);
+                                    }
+                                }
+                                parent.replace((p, joined));
                             }
                             ResultSet::End => (),
                         }
@@ -196,8 +279,113 @@ where
             }
             BinaryNotification::Right(t) => {
                 self.parent_data.remove(&t);
+                if let Some((_, cancel)) = self.limit.as_ref() {
+                    cancel.clear(&t);
+                }
             }
         }
         vec![]
     }
 }
+
+/// The parent folded by a fold join, or collecting the results of its subtask by a collect join;
+struct FoldParent<D> {
+    parent: D,
+    /// the number of results folded into the parent;
+    folded: usize,
+    /// the results collected, which is always empty unless the join is a collect join;
+    collected: Vec<D>,
+}
+
+struct SubtaskFoldJoin<D, F> {
+    peers: u32,
+    collect: bool,
+    /// the parents of each scope;
+    parent_data: HashMap<Tag, Vec<Option<FoldParent<D>>>>,
+    func: F,
+}
+
+impl<D, F: LeftJoinFunction<D>> SubtaskFoldJoin<D, F> {
+    pub fn new(meta: &OperatorMeta, func: F) -> Self {
+        let collect = func.is_collect();
+        SubtaskFoldJoin { peers: meta.worker_id.peers, collect, parent_data: HashMap::new(), func }
+    }
+}
+
+impl<D, F> BinaryNotify<D, SubtaskResult<D>, D> for SubtaskFoldJoin<D, F>
+where
+    D: Data,
+    F: LeftJoinFunction<D>,
+{
+    type NotifyResult = Vec<D>;
+
+    fn on_receive(
+        &mut self, input: &mut BinaryInput<D, SubtaskResult<D>>, _output: &mut Output<D>,
+    ) -> Result<(), JobExecError> {
+        input.subscribe_left_notify();
+        input.subscribe_right_notify();
+
+        let mut p = std::mem::replace(&mut self.parent_data, HashMap::new());
+        let parent_data = p.entry(input.tag().clone()).or_insert_with(|| vec![]);
+
+        input.left_for_each(|dataset| {
+            for item in dataset.drain(..) {
+                parent_data.push(Some(FoldParent { parent: item, folded: 0, collected: vec![] }));
+            }
+            Ok(())
+        })?;
+
+        input.right_for_each(|dataset| {
+            for data in dataset.drain(..) {
+                let offset = (data.seq / self.peers) as usize;
+                let seq = data.seq;
+                match parent_data.get_mut(offset) {
+                    Some(Some(p)) => {
+                        if let ResultSet::Data(s_data) = data.take() {
+                            if self.collect {
+                                p.collected.extend(s_data);
+                            } else {
+                                for r in s_data {
+                                    self.func.fold(&mut p.parent, p.folded, r);
+                                    p.folded += 1;
+                                }
+                            }
+                        }
+                    }
+                    Some(None) => {
+                        Err(format!("join subtask={} error: internal;", seq))?;
+                    }
+                    None => {
+                        Err(format!("join subtask={} error: parent lost;", seq))?;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        self.parent_data = p;
+        Ok(())
+    }
+
+    fn on_notify(&mut self, n: BinaryNotification) -> Self::NotifyResult {
+        match n {
+            BinaryNotification::Left(t) => {
+                self.parent_data.get_mut(&t).map(|p| p.shrink_to_fit());
+                vec![]
+            }
+            BinaryNotification::Right(t) => {
+                // all subtasks in the scope are ended, produce the results of the parents;
+                let mut result = vec![];
+                if let Some(parents) = self.parent_data.remove(&t) {
+                    for p in parents.into_iter().filter_map(|p| p) {
+                        if self.collect {
+                            result.extend(self.func.collect(p.parent, p.collected));
+                        } else if let Some(r) = self.func.end(p.parent, p.folded) {
+                            result.push(r);
+                        }
+                    }
+                }
+                result
+            }
+        }
+    }
+}
@@ -26,18 +26,29 @@ pub struct Schedule {
     memory_limit: u32,
     event_manager: EventManager,
     is_ready: bool,
+    /// the earliest instant at which the timers of the operators are due;
+    wake_up: Option<Instant>,
     is_closed: bool,
 }
 
 impl Schedule {
     pub fn new(memory_limit: u32, event_manager: EventManager) -> Self {
-        Schedule { step_count: 0, memory_limit, event_manager, is_ready: true, is_closed: false }
+        Schedule {
+            step_count: 0,
+            memory_limit,
+            event_manager,
+            is_ready: true,
+            wake_up: None,
+            is_closed: false,
+        }
     }
 
     #[inline]
     pub(crate) fn check_ready(&mut self) -> IOResult<bool> {
         if self.is_ready {
             Ok(true)
+        } else if self.wake_up.map(|t| t <= Instant::now()).unwrap_or(false) {
+            Ok(true)
         } else {
             self.event_manager.collect()
         }
@@ -77,6 +88,7 @@ impl Schedule {
         }
         self.event_manager.send_events()?;
 
+        self.wake_up = ops.iter().filter_map(|op| op.as_ref().and_then(|op| op.next_timer())).min();
         for op in ops {
             if let Some(op) = op {
                 if op.check_ready() {
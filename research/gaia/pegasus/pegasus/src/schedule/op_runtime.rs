@@ -43,12 +43,16 @@ impl OpRuntime {
     }
 
     pub fn check_ready(&mut self) -> bool {
-        self.op.has_actives() || self.op.has_outstanding() || self.op.has_notifications()
+        self.op.has_actives()
+            || self.op.has_outstanding()
+            || self.op.has_notifications()
+            || self.op.has_due_timers()
     }
 
     pub fn fire(&mut self) -> Result<bool, JobExecError> {
         let start = Instant::now();
         self.op.fire_actives()?;
+        self.op.fire_timers()?;
         self.elapse[0] += start.elapsed().as_micros();
 
         let len = self.inputs().len();
@@ -0,0 +1,254 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use pegasus::api::accum::{AvgAccum, CountAccum, DataSumAccum, Mean};
+use pegasus::api::function::*;
+use pegasus::api::{
+    Exchange, GlobalCombine, Group, OrderDirect, Range, ResultSet, Sink, SpillConf,
+};
+use pegasus::stream::Stream;
+use pegasus::sum;
+use pegasus::{BuildJobError, Configuration, Data, JobConf, Tag};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+struct ModKey(u32);
+
+impl KeyFunction<u32> for ModKey {
+    type Target = u32;
+
+    fn get_key(&self, item: &u32) -> Cow<u32> {
+        Cow::Owned(*item % self.0)
+    }
+}
+
+/// Group 0..10 read by each of the 2 workers by `item % 3`, and collect the groups output;
+fn group_with<O, F>(range: Range, func: F) -> HashMap<u32, Vec<O>>
+where
+    O: Data,
+    F: Fn(&Stream<u32>) -> Result<Stream<(u32, O)>, BuildJobError> + Clone + 'static,
+{
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "group_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let func = func.clone();
+        worker.dataflow(move |dfb| {
+            let stream =
+                dfb.input_from_iter(0..10u32)?.exchange_with_fn(|item: &u32| *item as u64)?;
+            func(&stream)?.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<(u32, O)>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut groups = HashMap::new();
+    while let Ok(data) = rx.recv() {
+        for (k, v) in data {
+            groups.entry(k).or_insert_with(Vec::new).push(v);
+        }
+    }
+    pegasus::shutdown_all();
+    groups
+}
+
+#[test]
+fn group_count_test() {
+    let groups = group_with(Range::Global, |stream| {
+        stream.group_by_with_accum(Range::Global, ModKey(3), CountAccum::new())
+    });
+    assert_eq!(groups.len(), 3);
+    // 0..10 is read by 2 workers;
+    assert_eq!(groups[&0], vec![8]);
+    assert_eq!(groups[&1], vec![6]);
+    assert_eq!(groups[&2], vec![6]);
+
+    let groups = group_with(Range::Local, |stream| {
+        stream.group_by_with_accum(Range::Local, ModKey(3), CountAccum::new())
+    });
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[&0].iter().sum::<u64>(), 8);
+    assert_eq!(groups[&1].iter().sum::<u64>(), 6);
+    assert_eq!(groups[&2].iter().sum::<u64>(), 6);
+}
+
+#[test]
+fn group_tree_combine_test() {
+    let linear = group_with(Range::Global, |stream| {
+        stream.group_by_global_with(ModKey(3), CountAccum::new(), GlobalCombine::Linear)
+    });
+    let tree = group_with(Range::Global, |stream| {
+        let combine = GlobalCombine::Tree { fanout: 2 };
+        stream.group_by_global_with(ModKey(3), CountAccum::new(), combine)
+    });
+    assert_eq!(linear.len(), 3);
+    assert_eq!(linear, tree);
+    assert_eq!(tree[&0], vec![8]);
+}
+
+#[test]
+fn group_sum_test() {
+    let groups = group_with(Range::Global, |stream| {
+        let sum = DataSumAccum::new(sum!(|seed: &mut u32, next: u32| *seed += next));
+        stream.group_by_with_accum(Range::Global, ModKey(3), sum)
+    });
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[&0], vec![Some(36)]);
+    assert_eq!(groups[&1], vec![Some(24)]);
+    assert_eq!(groups[&2], vec![Some(30)]);
+}
+
+#[test]
+fn group_avg_test() {
+    let groups = group_with(Range::Global, |stream| {
+        stream.group_by_with_accum(Range::Global, ModKey(3), AvgAccum::new())
+    });
+    let mean = |means: &Vec<Mean>| means[0].value();
+    assert_eq!(groups.len(), 3);
+    assert_eq!(mean(&groups[&0]), Some(4.5));
+    assert_eq!(mean(&groups[&1]), Some(4.0));
+    assert_eq!(mean(&groups[&2]), Some(5.0));
+    // groups of a single element(read by each of 2 workers);
+    let groups = group_with(Range::Global, |stream| {
+        stream.group_by_with_accum(Range::Global, ModKey(9), AvgAccum::new())
+    });
+    assert_eq!(groups.len(), 9);
+    assert_eq!(mean(&groups[&0]), Some(4.5));
+    for k in 1..9 {
+        assert_eq!(groups[&k][0].count(), 2);
+        assert_eq!(mean(&groups[&k]), Some(k as f64));
+    }
+}
+
+/// Collect the keys of groups in the order they are output;
+fn ordered_keys(range: Range, order: OrderDirect) -> Vec<u32> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "group_ordered_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(0..10u32)?
+                .exchange_with_fn(|item: &u32| *item as u64)?
+                .group_by_ordered(range, ModKey(5), CountAccum::new(), order)?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<(u32, u64)>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data.into_iter().map(|(k, _)| k).collect::<Vec<_>>())
+                                .expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut keys = vec![];
+    while let Ok(mut data) = rx.recv() {
+        keys.append(&mut data);
+    }
+    pegasus::shutdown_all();
+    keys
+}
+
+#[test]
+fn group_ordered_asc_test() {
+    assert_eq!(ordered_keys(Range::Global, OrderDirect::Asc), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn group_ordered_desc_test() {
+    assert_eq!(ordered_keys(Range::Global, OrderDirect::Desc), vec![4, 3, 2, 1, 0]);
+}
+
+/// All of 0..900 fall into the group 0, and the rest are evenly grouped into 1, 2, 3;
+struct SkewKey;
+
+impl KeyFunction<u32> for SkewKey {
+    type Target = u32;
+
+    fn get_key(&self, item: &u32) -> Cow<u32> {
+        if *item < 900 {
+            Cow::Owned(0)
+        } else {
+            Cow::Owned(*item % 3 + 1)
+        }
+    }
+}
+
+#[test]
+fn group_with_spill_test() {
+    let dir = std::env::temp_dir().join("pegasus_group_spill_test");
+    std::fs::create_dir_all(&dir).expect("create spill dir failure");
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "group_with_spill_test", 2);
+    let spill_dir = dir.clone();
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let spill = SpillConf::new(100).with_dir(spill_dir.clone());
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(0..1000u32)?
+                .group_by_with_spill(Range::Global, SkewKey, spill)?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<(u32, Vec<u32>)>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut groups = HashMap::new();
+    while let Ok(data) = rx.recv() {
+        for (k, v) in data {
+            assert!(groups.insert(k, v).is_none());
+        }
+    }
+    pegasus::shutdown_all();
+    assert_eq!(groups.len(), 4);
+    // the dominant group of 1800 members is spilled, and the small groups are kept in memory;
+    let mut dominant = groups.remove(&0).expect("group 0 not found");
+    dominant.sort();
+    assert_eq!(dominant, (0..900u32).flat_map(|i| vec![i, i]).collect::<Vec<_>>());
+    for (k, mut members) in groups {
+        members.sort();
+        let expected = (900..1000u32).filter(|i| i % 3 + 1 == k).flat_map(|i| vec![i, i]);
+        assert_eq!(members, expected.collect::<Vec<_>>());
+    }
+    // all spill files are removed after output;
+    assert_eq!(std::fs::read_dir(&dir).expect("read spill dir failure").count(), 0);
+    std::fs::remove_dir(&dir).ok();
+}
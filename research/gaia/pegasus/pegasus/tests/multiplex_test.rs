@@ -0,0 +1,209 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//! 
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//! 
+//! http://www.apache.org/licenses/LICENSE-2.0
+//! 
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use pegasus::api::{Count, LeaveScope, Multiplexing, Range, Remainder, ResultSet, Sink};
+use pegasus::stream::Stream;
+use pegasus::{BuildJobError, Configuration, Data, JobConf, JobSubmitError, Tag};
+use std::collections::HashMap;
+
+/// Worker 0 reads `source` into scopes created by `func`, while worker 1 reads nothing, and
+/// collect the data of each scope by scope id, return an error if the job fails;
+fn try_collect_scopes<O, F>(
+    name: &str, source: Vec<u32>, func: F,
+) -> Result<HashMap<u32, Vec<O>>, String>
+where
+    O: Data,
+    F: Fn(&Stream<u32>) -> Result<Stream<O>, BuildJobError> + Clone + 'static,
+{
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, name, 2);
+    let guard = pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let func = func.clone();
+        let source = if worker.id.index == 0 { source.clone() } else { vec![] };
+        worker.dataflow(move |dfb| {
+            let stream = dfb.input_from_iter(source.into_iter())?;
+            func(&stream)?.sink_by(move |_meta| {
+                move |t: &Tag, result: ResultSet<O>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send((t.current_uncheck(), data)).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+    std::mem::drop(tx);
+    let result = guard.unwrap().join().map_err(|err| err.to_string());
+
+    let mut scopes = HashMap::new();
+    while let Ok((id, data)) = rx.recv() {
+        scopes.entry(id).or_insert_with(Vec::new).extend(data);
+    }
+    pegasus::shutdown_all();
+    result.map(|_| scopes)
+}
+
+fn collect_scopes<O, F>(name: &str, source: Vec<u32>, func: F) -> HashMap<u32, Vec<O>>
+where
+    O: Data,
+    F: Fn(&Stream<u32>) -> Result<Stream<O>, BuildJobError> + Clone + 'static,
+{
+    try_collect_scopes(name, source, func).expect("run job failure;")
+}
+
+#[test]
+fn scope_by_range_test() {
+    // 3 is out of order as window 0 has been closed once 5 arrives;
+    let mut source = (0..10u32).collect::<Vec<_>>();
+    source.push(3);
+    source.extend(10..18u32);
+    let scopes = collect_scopes("scope_by_range_test", source, |stream| {
+        stream.scope_by_range(|item| *item as u64, 5)
+    });
+    assert_eq!(scopes.len(), 4);
+    for (id, mut data) in scopes {
+        data.sort();
+        let expected = (id * 5..std::cmp::min(id * 5 + 5, 18)).collect::<Vec<_>>();
+        assert_eq!(data, expected, "unexpected data of window {}", id);
+    }
+}
+
+#[test]
+fn scope_by_tagged_test() {
+    let scopes = collect_scopes("scope_by_tagged_test", (0..20u32).collect(), |stream| {
+        // data of 4 are dropped;
+        stream.scope_by_tagged(|item| if *item % 5 == 4 { None } else { Some(*item % 5) })
+    });
+    assert_eq!(scopes.len(), 4);
+    for (id, data) in scopes {
+        assert_eq!(data.len(), 4);
+        for (tag, item) in data {
+            assert_eq!(tag, id);
+            assert_eq!(tag, item % 5);
+        }
+    }
+}
+
+fn scope_by_size_in(size: u32, remainder: Remainder) -> Result<Vec<usize>, String> {
+    let scopes =
+        try_collect_scopes("scope_by_size_with_test", (0..size).collect(), move |stream| {
+            stream.scope_by_size_with(4, remainder)
+        })?;
+    let mut lengths = scopes.values().map(|data| data.len()).collect::<Vec<_>>();
+    lengths.sort();
+    Ok(lengths)
+}
+
+#[test]
+fn scope_by_size_divisible_test() {
+    for remainder in vec![Remainder::Keep, Remainder::Drop, Remainder::Error] {
+        assert_eq!(scope_by_size_in(12, remainder), Ok(vec![4, 4, 4]));
+    }
+}
+
+#[test]
+fn scope_by_size_remainder_test() {
+    assert_eq!(scope_by_size_in(10, Remainder::Keep), Ok(vec![2, 4, 4]));
+    assert_eq!(scope_by_size_in(10, Remainder::Drop), Ok(vec![4, 4]));
+    assert!(scope_by_size_in(10, Remainder::Error).is_err());
+}
+
+/// Build a dataflow on the input by `func`, return the error of building it, if any;
+fn try_build<O, F>(name: &str, func: F) -> Result<(), BuildJobError>
+where
+    O: Data,
+    F: Fn(&Stream<u32>) -> Result<Stream<O>, BuildJobError> + Clone + 'static,
+{
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let conf = JobConf::new(1, name, 1);
+    let result = pegasus::run(conf, |worker| {
+        let func = func.clone();
+        worker.dataflow(move |dfb| {
+            let stream = dfb.input_from_iter(0..10u32)?;
+            func(&stream)?.sink_by(|_meta| |_: &Tag, _: ResultSet<O>| ())?;
+            Ok(())
+        })
+    });
+    let result = match result {
+        Ok(guard) => {
+            if let Some(mut guard) = guard {
+                guard.join().expect("run job failure;");
+            }
+            Ok(())
+        }
+        Err(JobSubmitError::Build(err)) => Err(err),
+        Err(err) => panic!("unexpected submit error: {}", err),
+    };
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn invalid_scope_test() {
+    let is_invalid_scope =
+        |result: Result<(), BuildJobError>| matches!(result, Err(BuildJobError::InvalidScope(_)));
+    assert!(is_invalid_scope(try_build("scope_by_size_0", |s| s.scope_by_size(0))));
+    assert!(is_invalid_scope(try_build("scope_by_size_with_0", |s| {
+        s.scope_by_size_with(0, Remainder::Drop)
+    })));
+    assert!(is_invalid_scope(try_build("scope_by_range_0", |s| {
+        s.scope_by_range(|item| *item as u64, 0)
+    })));
+    assert!(try_build("scope_by_size_1", |s| s.scope_by_size(1)).is_ok());
+}
+
+#[test]
+fn leave_scopes_test() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "leave_scopes_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(0..10u32)?
+                .scope_by_size(4)?
+                .count(Range::Local)?
+                .leave()?
+                .sink_by(move |_meta| {
+                    move |t: &Tag, result: ResultSet<u64>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send((t.is_root(), data)).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("run job failure;");
+    std::mem::drop(tx);
+
+    let mut counts = vec![];
+    while let Ok((is_root, data)) = rx.recv() {
+        assert!(is_root);
+        counts.extend(data);
+    }
+    pegasus::shutdown_all();
+    // the count of each scope in each of the 2 workers is merged into the root scope;
+    counts.sort();
+    assert_eq!(counts, vec![2, 2, 4, 4, 4, 4]);
+}
@@ -15,14 +15,17 @@
 
 use pegasus::api::function::*;
 use pegasus::api::{
-    Barrier, Dedup, Exchange, Map, Order, OrderBy, OrderDirect, Range, ResultSet, Sink,
+    Barrier, BarrierResult, Count, Dedup, Exchange, Extreme, Fold, GlobalCombine, Limit, Map,
+    Order, OrderBy, OrderDirect, Range, ResultSet, Sample, Sink, SpillConf, Tie,
 };
 use pegasus::communication::Pipeline;
 use pegasus::compare;
-use pegasus::{Configuration, JobConf, Tag};
+use pegasus::stream::Stream;
+use pegasus::{BuildJobError, Configuration, Data, JobConf, JobSubmitError, Tag};
 use pegasus_common::codec::{Decode, Encode, ReadExt, WriteExt};
 use pegasus_common::collections::{Collection, Drain, DrainSet, Set};
 use std::collections::HashSet;
+use std::time::Duration;
 
 #[test]
 fn barrier_test() {
@@ -108,6 +111,123 @@ fn barrier_test() {
     pegasus::shutdown_all();
 }
 
+/// Each of the 2 workers reads 0..10 into a global barrier with timeout of 100ms, the input of
+/// worker `i` is delayed for `delays[i]` before its first datum;
+fn barrier_with_delay(delays: [Duration; 2]) -> Vec<BarrierResult<Vec<u32>>> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "barrier_timeout_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let delay = delays[worker.id.index as usize];
+        worker.dataflow(move |dfb| {
+            let source = (0..10u32).map(move |i| {
+                if i == 0 {
+                    std::thread::sleep(delay);
+                }
+                i
+            });
+            dfb.input_from_iter(source)?
+                .barrier_with_timeout::<Vec<u32>>(Range::Global, Duration::from_millis(100))?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<BarrierResult<Vec<u32>>>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn barrier_complete_test() {
+    let result = barrier_with_delay([Duration::from_millis(0); 2]);
+    assert_eq!(result.len(), 1);
+    assert!(!result[0].is_partial());
+    assert_eq!(result[0].clone().into_inner().len(), 20);
+}
+
+#[test]
+fn barrier_timeout_test() {
+    let result = barrier_with_delay([Duration::from_millis(0), Duration::from_secs(1)]);
+    // only the partial result flushed on timeout is output, the delayed data are discarded;
+    assert_eq!(result.len(), 1);
+    assert!(result[0].is_partial());
+    let mut data = result[0].clone().into_inner();
+    data.sort();
+    assert_eq!(data, (0..10).collect::<Vec<u32>>());
+}
+
+#[test]
+fn barrier_timeout_without_data_test() {
+    // no data arrives before the timeout, which is timed from the start of the barrier;
+    let result = barrier_with_delay([Duration::from_secs(1); 2]);
+    assert_eq!(result.len(), 1);
+    assert!(result[0].is_partial());
+    assert!(result[0].clone().into_inner().is_empty());
+}
+
+#[test]
+fn barrier_metrics_test() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let (metrics_tx, metrics_rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "barrier_metrics_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let metrics_tx = metrics_tx.clone();
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(0..100u32)?
+                .barrier_with_metrics::<Vec<u32>, _>(Range::Global, 30, move |m| {
+                    metrics_tx.send(m.clone()).expect("send error");
+                })?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<Vec<u32>>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+    std::mem::drop(metrics_tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].len(), 200);
+
+    let metrics = metrics_rx.iter().collect::<Vec<_>>();
+    // reported every 30 data buffered, and once before flush;
+    let counts = metrics.iter().map(|m| m.count).collect::<Vec<_>>();
+    assert_eq!(counts, vec![30, 60, 90, 120, 150, 180, 200]);
+    for m in metrics {
+        // each u32 is encoded in 4 bytes;
+        assert_eq!(m.bytes, m.count * 4);
+    }
+}
+
 #[test]
 fn dedup_test() {
     #[derive(Clone, Debug, Default)]
@@ -288,3 +408,719 @@ fn custom_top_test() {
     assert_eq!(vec![8, 8, 7, 7, 6], result);
     pegasus::shutdown_all();
 }
+
+fn count_in_range(range: Range, len: u32, predicate: Option<fn(&u32) -> bool>) -> Vec<u64> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "count_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            let stream =
+                dfb.input_from_iter(0..len)?.exchange_with_fn(|item: &u32| *item as u64)?;
+            let count = if let Some(predicate) = predicate {
+                stream.count_if(range, predicate)?
+            } else {
+                stream.count(range)?
+            };
+            count.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<u64>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn count_local_test() {
+    let result = count_in_range(Range::Local, 10, None);
+    // each worker outputs its own count;
+    assert_eq!(2, result.len());
+    assert_eq!(20, result.iter().sum::<u64>());
+}
+
+#[test]
+fn count_global_test() {
+    let result = count_in_range(Range::Global, 10, None);
+    assert_eq!(vec![20], result);
+}
+
+#[test]
+fn count_if_local_test() {
+    let result = count_in_range(Range::Local, 10, Some(|item: &u32| *item % 2 == 0));
+    assert_eq!(2, result.len());
+    assert_eq!(10, result.iter().sum::<u64>());
+}
+
+#[test]
+fn count_if_global_test() {
+    let result = count_in_range(Range::Global, 10, Some(|item: &u32| *item % 2 == 0));
+    assert_eq!(vec![10], result);
+    let result = count_in_range(Range::Global, 10, Some(|item: &u32| *item > 100));
+    assert_eq!(vec![0], result);
+}
+
+#[test]
+fn count_if_empty_input_test() {
+    // each worker outputs 0 for the scope without any data;
+    let result = count_in_range(Range::Local, 0, Some(|item: &u32| *item % 2 == 0));
+    assert_eq!(vec![0, 0], result);
+    let result = count_in_range(Range::Global, 0, Some(|item: &u32| *item % 2 == 0));
+    assert_eq!(vec![0], result);
+}
+
+fn count_weighted_in(range: Range) -> Vec<u64> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "count_weighted_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            // each item represents `item` identical data;
+            dfb.input_from_iter(0..10u32)?
+                .exchange_with_fn(|item: &u32| *item as u64)?
+                .count_weighted(range, |item| *item as u64)?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u64>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn count_weighted_local_test() {
+    let result = count_weighted_in(Range::Local);
+    assert_eq!(2, result.len());
+    assert_eq!(90, result.iter().sum::<u64>());
+}
+
+#[test]
+fn count_weighted_global_test() {
+    let result = count_weighted_in(Range::Global);
+    assert_eq!(vec![90], result);
+}
+
+fn count_distinct_approx_in(range: Range) -> Vec<u64> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "count_distinct_approx_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            // each worker reads 0..10000, and keeps the data of its own parity after exchange;
+            dfb.input_from_iter(0..10000u32)?
+                .exchange_with_fn(|item: &u32| *item as u64)?
+                .count_distinct_approx(range, 12)?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u64>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+fn within(estimate: u64, expected: u64) -> bool {
+    // about 3 times of the 1.6% standard error of precision 12;
+    (estimate as f64 - expected as f64).abs() / (expected as f64) < 0.05
+}
+
+#[test]
+fn count_distinct_approx_local_test() {
+    let result = count_distinct_approx_in(Range::Local);
+    assert_eq!(2, result.len());
+    for estimate in result {
+        assert!(within(estimate, 5000), "estimate {} out of tolerance", estimate);
+    }
+}
+
+#[test]
+fn count_distinct_approx_global_test() {
+    let result = count_distinct_approx_in(Range::Global);
+    assert_eq!(1, result.len());
+    // the duplicates read by the 2 workers are counted once after merging;
+    assert!(within(result[0], 10000), "estimate {} out of tolerance", result[0]);
+}
+
+/// Worker `i` of the 2 workers reads `i*5..i*5+5`, and concatenates the data into a string;
+fn concat_in_range(range: Range) -> Vec<String> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "fold_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let start = worker.id.index * 5;
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(start..start + 5)?
+                .fold_in_range(
+                    String::new(),
+                    range,
+                    |s, d| s.push_str(&d.to_string()),
+                    |s, partial| s.push_str(&partial),
+                )?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<String>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn fold_local_test() {
+    let mut result = concat_in_range(Range::Local);
+    result.sort();
+    assert_eq!(result, vec!["01234".to_owned(), "56789".to_owned()]);
+}
+
+#[test]
+fn fold_global_test() {
+    // the non-commutative concatenation is combined in the order of worker index;
+    let result = concat_in_range(Range::Global);
+    assert_eq!(result, vec!["0123456789".to_owned()]);
+}
+
+fn range_in(range: Range, offset: u32, size: u32) -> Vec<u32> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "range_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let start = worker.id.index * 10;
+        worker.dataflow(|dfb| {
+            let source = start..start + 10;
+            dfb.input_from_iter(source)?.range(range, offset, size)?.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<u32>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result.sort();
+    result
+}
+
+#[test]
+fn range_local_test() {
+    // worker 0 reads 0..10, and worker 1 reads 10..20;
+    assert_eq!(vec![2, 3, 4, 12, 13, 14], range_in(Range::Local, 2, 3));
+    assert_eq!(vec![8, 9, 18, 19], range_in(Range::Local, 8, 5));
+    assert!(range_in(Range::Local, 10, 5).is_empty());
+}
+
+#[test]
+fn range_global_test() {
+    // the data of worker 0 are always skipped before the data of worker 1;
+    assert_eq!(vec![2, 3, 4], range_in(Range::Global, 2, 3));
+    assert_eq!(vec![8, 9, 10, 11, 12], range_in(Range::Global, 8, 5));
+    assert_eq!(vec![15, 16, 17, 18, 19], range_in(Range::Global, 15, 10));
+    assert!(range_in(Range::Global, 20, 5).is_empty());
+}
+
+#[test]
+fn sort_then_by_test() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "sort_then_by_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let index = worker.id.index;
+        worker.dataflow(|dfb| {
+            let src = (0..10u32).map(move |i| (i % 2, i * 2 + index));
+            dfb.input_from_iter(src)?
+                .exchange_with_fn(|item: &(u32, u32)| item.1 as u64)?
+                .sort_by(
+                    Range::Global,
+                    compare!(|a: &(u32, u32), b: &(u32, u32)| a.0.cmp(&b.0))
+                        .then_by(compare!(|a: &(u32, u32), b: &(u32, u32)| b.1.cmp(&a.1))),
+                )?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<(u32, u32)>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    // sorted by the first field ascending, and the second field descending for ties;
+    let mut expected = vec![];
+    for k in 0..2u32 {
+        for v in (0..20u32).rev() {
+            if (v / 2) % 2 == k {
+                expected.push((k, v));
+            }
+        }
+    }
+    assert_eq!(expected, result);
+    pegasus::shutdown_all();
+}
+
+fn order_by_keys(limit: Option<u32>) -> Vec<(u32, u32)> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "order_by_keys_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let index = worker.id.index;
+        worker.dataflow(|dfb| {
+            let src = (0..10u32).map(move |i| (i % 3, i * 2 + index));
+            let stream =
+                dfb.input_from_iter(src)?.exchange_with_fn(|item: &(u32, u32)| item.1 as u64)?;
+            let keys: Vec<(KeyExtractor<(u32, u32), u32>, OrderDirect)> = vec![
+                (Box::new(|item: &(u32, u32)| item.0), OrderDirect::Asc),
+                (Box::new(|item: &(u32, u32)| item.1), OrderDirect::Desc),
+            ];
+            let stream = if let Some(limit) = limit {
+                stream.top_by_keys(limit, Range::Global, keys)?
+            } else {
+                stream.sort_by_keys(Range::Global, keys)?
+            };
+            stream.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<(u32, u32)>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn sort_by_keys_test() {
+    // sorted by the first field ascending, and the second field descending for ties;
+    let mut expected = vec![];
+    for k in 0..3u32 {
+        for v in (0..20u32).rev() {
+            if (v / 2) % 3 == k {
+                expected.push((k, v));
+            }
+        }
+    }
+    assert_eq!(expected, order_by_keys(None));
+    expected.truncate(5);
+    assert_eq!(expected, order_by_keys(Some(5)));
+}
+
+fn top_or_sort(limit: u32, fused: bool) -> Vec<u32> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "top_or_sort_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            let src = (0..1000u32).map(|i| (i * 7919) % 1000);
+            let stream = dfb.input_from_iter(src)?.exchange_with_fn(|item: &u32| *item as u64)?;
+            let cmp = compare!(|a: &u32, b: &u32| b.cmp(a));
+            let stream = if fused {
+                stream.top_by(limit, Range::Global, cmp)?
+            } else {
+                stream.sort_by(Range::Global, cmp)?.limit(Range::Global, limit)?
+            };
+            stream.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<u32>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn top_by_equals_sort_limit_test() {
+    for limit in vec![1, 10, 2000, 3000] {
+        let expected = top_or_sort(limit, false);
+        assert_eq!(expected.len(), std::cmp::min(limit as usize, 2000));
+        assert_eq!(expected, top_or_sort(limit, true));
+    }
+}
+
+/// Worker `i` of the 2 workers reads `i*100..i*100+100`, and samples `n` of the data;
+fn sample_in(range: Range, n: u32, seed: Option<u64>) -> Vec<u32> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "sample_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let start = worker.id.index * 100;
+        worker.dataflow(move |dfb| {
+            dfb.input_from_iter(start..start + 100)?.sample(n, range, seed)?.sink_by(
+                move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u32>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                },
+            )?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result.sort();
+    result
+}
+
+#[test]
+fn sample_local_test() {
+    let result = sample_in(Range::Local, 10, None);
+    assert_eq!(20, result.len());
+    assert_eq!(10, result.iter().filter(|d| **d < 100).count());
+    assert_eq!(sample_in(Range::Local, 100, None), (0..200).collect::<Vec<u32>>());
+}
+
+#[test]
+fn sample_global_test() {
+    let result = sample_in(Range::Global, 10, Some(42));
+    assert_eq!(10, result.len());
+    result.windows(2).for_each(|w| assert!(w[0] < w[1]));
+    assert_eq!(result, sample_in(Range::Global, 10, Some(42)));
+    assert_eq!(sample_in(Range::Global, 500, Some(42)), (0..200).collect::<Vec<u32>>());
+}
+
+fn dedup_by_key_in(range: Range) -> Vec<(u32, u32)> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "dedup_by_key_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            // each worker reads the same data, where the first data of key `k` is `(k, k)`;
+            let src = (0..20u32).map(|i| (i % 5, i));
+            dfb.input_from_iter(src)?.dedup_by_key(range, |item: &(u32, u32)| item.0)?.sink_by(
+                move |_meta| {
+                    move |_t: &Tag, result: ResultSet<(u32, u32)>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                },
+            )?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result.sort();
+    result
+}
+
+#[test]
+fn dedup_by_key_local_test() {
+    let expected =
+        vec![(0, 0), (0, 0), (1, 1), (1, 1), (2, 2), (2, 2), (3, 3), (3, 3), (4, 4), (4, 4)];
+    assert_eq!(expected, dedup_by_key_in(Range::Local));
+}
+
+#[test]
+fn dedup_by_key_global_test() {
+    let expected = vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)];
+    assert_eq!(expected, dedup_by_key_in(Range::Global));
+}
+
+/// Worker `i` of the 2 workers reads `(v % 5, i*100+v)` for `v` in `0..10`, and outputs the
+/// extreme data by the first field;
+fn extreme_in(range: Range, is_min: bool, tie: Tie) -> Vec<(u32, u32)> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "extreme_test", 2);
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let index = worker.id.index;
+        worker.dataflow(move |dfb| {
+            let src = (0..10u32).map(move |v| (v % 5, index * 100 + v));
+            let stream = dfb.input_from_iter(src)?;
+            let key = |item: &(u32, u32)| item.0;
+            let stream = if is_min {
+                stream.min_by_key(range, key, tie)?
+            } else {
+                stream.max_by_key(range, key, tie)?
+            };
+            stream.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<(u32, u32)>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    result.sort();
+    result
+}
+
+#[test]
+fn min_by_key_test() {
+    assert_eq!(vec![(0, 0), (0, 100)], extreme_in(Range::Local, true, Tie::First));
+    assert_eq!(vec![(0, 5), (0, 105)], extreme_in(Range::Local, true, Tie::Last));
+    assert_eq!(vec![(0, 0)], extreme_in(Range::Global, true, Tie::First));
+    assert_eq!(vec![(0, 105)], extreme_in(Range::Global, true, Tie::Last));
+}
+
+#[test]
+fn max_by_key_test() {
+    assert_eq!(vec![(4, 4), (4, 104)], extreme_in(Range::Local, false, Tie::First));
+    assert_eq!(vec![(4, 9), (4, 109)], extreme_in(Range::Local, false, Tie::Last));
+    assert_eq!(vec![(4, 4)], extreme_in(Range::Global, false, Tie::First));
+    assert_eq!(vec![(4, 109)], extreme_in(Range::Global, false, Tie::Last));
+}
+
+#[test]
+fn sort_by_with_spill_test() {
+    let dir = std::env::temp_dir().join("pegasus_sort_spill_test");
+    std::fs::create_dir_all(&dir).expect("create spill dir failure");
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "sort_by_with_spill_test", 2);
+    let spill_dir = dir.clone();
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let spill = SpillConf::new(7).with_dir(spill_dir.clone());
+        worker.dataflow(move |dfb| {
+            let src = (0..100u32).map(|i| (i * 37) % 100);
+            dfb.input_from_iter(src)?
+                .sort_by_with_spill(Range::Global, compare!(|a: &u32, b: &u32| b.cmp(a)), spill)?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u32>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("");
+    std::mem::drop(tx);
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    // the 200 data are spilled as runs of 7, and merged in descending order;
+    let expected = (0..100u32).rev().flat_map(|i| vec![i, i]).collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    // all spill files are removed after merging;
+    assert_eq!(std::fs::read_dir(&dir).expect("read spill dir failure").count(), 0);
+    std::fs::remove_dir(&dir).ok();
+}
+
+/// Each of the 5 workers reads `0..100` in a different order, and collects the output of `func`;
+fn global_with<O, F>(func: F) -> Result<Vec<O>, BuildJobError>
+where
+    O: Data,
+    F: Fn(&Stream<u32>) -> Result<Stream<O>, BuildJobError> + Clone + 'static,
+{
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "global_combine_test", 5);
+    let result = pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let func = func.clone();
+        let offset = worker.id.index;
+        worker.dataflow(move |dfb| {
+            let stream = dfb.input_from_iter((0..100u32).map(move |i| (i * 37 + offset) % 100))?;
+            func(&stream)?.sink_by(move |_meta| {
+                move |_t: &Tag, result: ResultSet<O>| match result {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("send error");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    });
+    std::mem::drop(tx);
+    let result = match result {
+        Ok(_) => {
+            let mut result = Vec::new();
+            while let Ok(data) = rx.recv() {
+                result.extend(data);
+            }
+            Ok(result)
+        }
+        Err(JobSubmitError::Build(err)) => Err(err),
+        Err(err) => panic!("unexpected submit error: {}", err),
+    };
+    pegasus::shutdown_all();
+    result
+}
+
+#[test]
+fn count_tree_combine_test() {
+    let linear = global_with(|stream| stream.count_global_with(GlobalCombine::Linear)).unwrap();
+    assert_eq!(linear, vec![500]);
+    // 5 workers are combined in 3 levels by fanout 2, and in 1 level by fanout 8;
+    for fanout in vec![2, 3, 8] {
+        let tree =
+            global_with(move |stream| stream.count_global_with(GlobalCombine::Tree { fanout }))
+                .unwrap();
+        assert_eq!(linear, tree);
+    }
+}
+
+#[test]
+fn top_tree_combine_test() {
+    for order in vec![OrderDirect::Asc, OrderDirect::Desc] {
+        let linear =
+            global_with(move |stream| stream.top_global_with(10, order, GlobalCombine::Linear))
+                .unwrap();
+        assert_eq!(linear.len(), 10);
+        for fanout in vec![2, 3] {
+            let tree = global_with(move |stream| {
+                stream.top_global_with(10, order, GlobalCombine::Tree { fanout })
+            })
+            .unwrap();
+            assert_eq!(linear, tree);
+        }
+    }
+}
+
+#[test]
+fn tree_combine_fanout_test() {
+    let result = global_with(|stream| stream.count_global_with(GlobalCombine::Tree { fanout: 1 }));
+    assert!(result.is_err());
+}
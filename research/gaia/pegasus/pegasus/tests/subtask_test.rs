@@ -13,10 +13,15 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use pegasus::api::{Count, Exchange, Iteration, Map, Range, ResultSet, Sink, SubTask};
+use pegasus::api::function::LeftJoinFunction;
+use pegasus::api::{
+    Count, Exchange, Iteration, Map, Range, ResultSet, Sink, SubTask, SubtaskCancel,
+};
 use pegasus::communication::Pipeline;
 use pegasus::{Configuration, JobConf};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 #[test]
 fn test_subtask_fork() {
@@ -172,6 +177,217 @@ fn test_subtask_fork_count_join() {
     pegasus::shutdown_all();
 }
 
+#[test]
+fn test_subtask_cancel_after_first_match() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let conf = JobConf::new(53, "test_subtask_cancel_after_first_match", 2);
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let produced = Arc::new(AtomicUsize::new(0));
+    let produced_c = produced.clone();
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let produced = produced_c.clone();
+        worker.dataflow(move |dfb| {
+            let src = if dfb.worker_id.index == 0 {
+                let vec = (0..100).collect::<Vec<u32>>();
+                dfb.input_from_iter(vec.into_iter())
+            } else {
+                dfb.input_from_iter(Vec::<u32>::new().into_iter())
+            }?;
+            let p = src.exchange_with_fn(|item: &u32| *item as u64)?;
+            let cancel = SubtaskCancel::new();
+            let produced = produced.clone();
+            let subtask = p.fork_cancelable_subtask(&cancel, |stream| {
+                stream
+                    .flat_map_with_fn(Pipeline, |item| (0..100_000u32).map(move |x| Ok(item + x)))?
+                    .map_with_fn(Pipeline, move |item| {
+                        produced.fetch_add(1, Ordering::SeqCst);
+                        Ok(item)
+                    })
+            })?;
+            let join = p.join_subtask_limit(subtask, 1, &cancel, |p, s| Some((*p, s)))?;
+            join.sink_by(|_| {
+                move |_, r| match r {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("sink data failure;");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+
+    std::mem::drop(tx);
+    let mut joined = HashMap::new();
+    while let Ok(r) = rx.recv() {
+        for (p, s) in r {
+            assert_eq!(s, p);
+            *joined.entry(p).or_insert(0) += 1;
+        }
+    }
+    assert_eq!(joined.len(), 100);
+    for (_, cnt) in joined {
+        assert_eq!(cnt, 1);
+    }
+    assert!(produced.load(Ordering::SeqCst) < 100 * 100_000);
+    pegasus::shutdown_all();
+}
+
+struct SumJoin;
+
+impl LeftJoinFunction<u32> for SumJoin {
+    fn exec(&self, left: &u32, right: u32) -> Option<u32> {
+        Some(*left + right)
+    }
+
+    fn is_fold(&self) -> bool {
+        true
+    }
+
+    fn fold(&self, left: &mut u32, _folded: usize, right: u32) {
+        *left += right;
+    }
+
+    fn end(&self, left: u32, folded: usize) -> Option<u32> {
+        if folded > 0 {
+            Some(left)
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_subtask_fold_join() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let conf = JobConf::new(54, "test_subtask_fold_join", 2);
+    let (tx, rx) = crossbeam_channel::unbounded();
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            let src = if dfb.worker_id.index == 0 {
+                let vec = (0..100).collect::<Vec<u32>>();
+                dfb.input_from_iter(vec.into_iter())
+            } else {
+                dfb.input_from_iter(Vec::<u32>::new().into_iter())
+            }?;
+            let p = src.exchange_with_fn(|item: &u32| *item as u64)?;
+            // parents of odd numbers have no sub results, and are dropped by the join;
+            let subtask = p.fork_subtask(|stream| {
+                stream.flat_map_with_fn(Pipeline, |item| {
+                    let len = if item % 2 == 0 { 4 } else { 0 };
+                    vec![1u32; len].into_iter().map(|x| Ok(x))
+                })
+            })?;
+            let join = p.join_subtask_with(subtask, SumJoin)?;
+            join.sink_by(|_| {
+                move |_, r| match r {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("sink data failure;");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+
+    std::mem::drop(tx);
+    let mut result = vec![];
+    while let Ok(r) = rx.recv() {
+        result.extend(r);
+    }
+    result.sort();
+    let expected = (0..100u32).filter(|i| i % 2 == 0).map(|i| i + 4).collect::<Vec<_>>();
+    assert_eq!(result, expected);
+    pegasus::shutdown_all();
+}
+
+/// Keep the sub results of the first branch that has any, where a sub result `r` is of the branch
+/// `r / 100`;
+struct FirstBranchJoin;
+
+impl LeftJoinFunction<u32> for FirstBranchJoin {
+    fn exec(&self, _left: &u32, right: u32) -> Option<u32> {
+        Some(right)
+    }
+
+    fn is_collect(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, _left: u32, right: Vec<u32>) -> Vec<u32> {
+        match right.iter().map(|r| r / 100).min() {
+            Some(first) => right.into_iter().filter(|r| r / 100 == first).collect(),
+            None => vec![],
+        }
+    }
+}
+
+#[test]
+fn test_subtask_collect_join() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let conf = JobConf::new(55, "test_subtask_collect_join", 2);
+    let (tx, rx) = crossbeam_channel::unbounded();
+    pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(|dfb| {
+            let src = if dfb.worker_id.index == 0 {
+                let vec = (0..100).collect::<Vec<u32>>();
+                dfb.input_from_iter(vec.into_iter())
+            } else {
+                dfb.input_from_iter(Vec::<u32>::new().into_iter())
+            }?;
+            let p = src.exchange_with_fn(|item: &u32| *item as u64)?;
+            // the first branch of odd numbers is empty, so their second branch is kept;
+            let subtask = p.fork_subtask(|stream| {
+                stream.flat_map_with_fn(Pipeline, |item| {
+                    let mut sub = vec![item + 200, item + 200];
+                    if item % 2 == 0 {
+                        sub.push(item + 100);
+                    }
+                    sub.into_iter().map(|x| Ok(x))
+                })
+            })?;
+            let join = p.join_subtask_with(subtask, FirstBranchJoin)?;
+            join.sink_by(|_| {
+                move |_, r| match r {
+                    ResultSet::Data(data) => {
+                        tx.send(data).expect("sink data failure;");
+                    }
+                    _ => (),
+                }
+            })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+
+    std::mem::drop(tx);
+    let mut result = vec![];
+    while let Ok(r) = rx.recv() {
+        result.extend(r);
+    }
+    result.sort();
+    let mut expected = vec![];
+    for i in 0..100u32 {
+        if i % 2 == 0 {
+            expected.push(i + 100);
+        } else {
+            expected.extend(vec![i + 200, i + 200]);
+        }
+    }
+    expected.sort();
+    assert_eq!(result, expected);
+    pegasus::shutdown_all();
+}
+
 #[test]
 #[ignore] // TODO: FIX
 fn test_subtask_in_iteration() {
@@ -18,7 +18,8 @@ use pegasus::api::notify::Notification;
 use pegasus::api::state::OperatorState;
 use pegasus::api::Range::Global;
 use pegasus::api::{
-    Exchange, Filter, Limit, Map, Multiplexing, NonBlockReceiver, Unary, UnaryNotify, UnaryState,
+    Exchange, Filter, Limit, Map, Multiplexing, NonBlockReceiver, OnError, Unary, UnaryNotify,
+    UnaryState,
 };
 use pegasus::api::{ResultSet, Sink};
 use pegasus::box_route;
@@ -26,6 +27,8 @@ use pegasus::communication::{Aggregate, Input, Output, Pipeline};
 use pegasus::errors::JobExecError;
 use pegasus::{Configuration, Data, JobConf, Tag};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Test unary that just forward input to output;
 /// Sink results to one collector, check if count is correct;
@@ -523,3 +526,142 @@ fn unary_test_state_01() {
     }
     pegasus::shutdown_all();
 }
+
+/// Parse each record into a number which is output twice, return an error if the job fails;
+fn parse_records(records: Vec<&'static str>, on_error: OnError) -> Result<Vec<u32>, String> {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "flat_map_result_test", 1);
+    let guard = pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let records = records.clone();
+        worker.dataflow(move |builder| {
+            builder
+                .input_from_iter(records.into_iter().map(|r| r.to_owned()))?
+                .flat_map_result(Pipeline, on_error, |record: String| {
+                    record.parse::<u32>().map(|n| vec![n, n])
+                })?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u32>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+    std::mem::drop(tx);
+    let result = guard.unwrap().join().map_err(|err| err.to_string());
+
+    let mut numbers = Vec::new();
+    while let Ok(data) = rx.recv() {
+        numbers.extend(data);
+    }
+    pegasus::shutdown_all();
+    result.map(|_| numbers)
+}
+
+#[test]
+fn flat_map_result_test() {
+    let records = vec!["1", "2", "3"];
+    assert_eq!(parse_records(records.clone(), OnError::Abort), Ok(vec![1, 1, 2, 2, 3, 3]));
+    assert_eq!(parse_records(records, OnError::Skip), Ok(vec![1, 1, 2, 2, 3, 3]));
+}
+
+#[test]
+fn flat_map_result_skip_test() {
+    let records = vec!["1", "x", "3", ""];
+    assert_eq!(parse_records(records, OnError::Skip), Ok(vec![1, 1, 3, 3]));
+}
+
+#[test]
+fn flat_map_result_abort_test() {
+    let records = vec!["1", "x", "3"];
+    assert!(parse_records(records, OnError::Abort).is_err());
+}
+
+#[test]
+fn inspect_test() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let inspected = Arc::new(AtomicUsize::new(0));
+    let conf = JobConf::new(1, "inspect_test", 2);
+    let guard = pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        let inspected = inspected.clone();
+        worker.dataflow(move |builder| {
+            builder
+                .input_from_iter(0..1000u32)?
+                .inspect(move |_| {
+                    inspected.fetch_add(1, Ordering::SeqCst);
+                })?
+                .sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<u32>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+    std::mem::drop(tx);
+    guard.unwrap().join().expect("run job failure;");
+
+    let mut result = Vec::new();
+    while let Ok(data) = rx.recv() {
+        result.extend(data);
+    }
+    pegasus::shutdown_all();
+    // each of the 2 workers reads 0..1000, which are passed through unchanged;
+    assert_eq!(inspected.load(Ordering::SeqCst), 2000);
+    result.sort();
+    assert_eq!(result, (0..1000u32).flat_map(|i| vec![i, i]).collect::<Vec<_>>());
+}
+
+#[test]
+fn filter_map_test() {
+    pegasus_common::logs::init_log();
+    pegasus::startup(Configuration::singleton()).ok();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let conf = JobConf::new(1, "filter_map_test", 1);
+    let guard = pegasus::run(conf, |worker| {
+        let tx = tx.clone();
+        worker.dataflow(move |builder| {
+            builder
+                .input_from_iter(0..10u32)?
+                .scope_by_size(5)?
+                // the even numbers are kept and transformed, while the odd are dropped;
+                .filter_map(Pipeline, |item| if item % 2 == 0 { Some(item * 10) } else { None })?
+                .sink_by(move |_meta| {
+                    move |t: &Tag, result: ResultSet<u32>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send((t.current_uncheck(), data)).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+            Ok(())
+        })
+    })
+    .expect("submit job failure;");
+    std::mem::drop(tx);
+    guard.unwrap().join().expect("run job failure;");
+
+    let mut scopes = HashMap::new();
+    while let Ok((id, data)) = rx.recv() {
+        scopes.entry(id).or_insert_with(Vec::new).extend(data);
+    }
+    pegasus::shutdown_all();
+    // the output stays in the scope of its input;
+    assert_eq!(scopes.len(), 2);
+    assert_eq!(scopes[&0], vec![0, 20, 40]);
+    assert_eq!(scopes[&1], vec![60, 80]);
+}
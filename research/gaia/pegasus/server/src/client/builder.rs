@@ -120,7 +120,20 @@ impl Plan {
         self
     }
 
+    pub fn sort_by(&mut self, n: Range, cmp: BinaryResource) -> &mut Self {
+        let sort_by = pb::SortBy { global: n == Range::Global, limit: -1, cmp };
+        let mut buf = Vec::with_capacity(sort_by.encoded_len());
+        sort_by.encode(&mut buf).expect("pb::SortBy encode failure;");
+        let mut op = new_operator(OpKind::Sort, pipeline());
+        op.resource = buf;
+        self.plan.push(op);
+        self
+    }
+
     pub fn limit(&mut self, n: Range, size: u32) -> &mut Self {
+        if self.try_fuse_sort(n, size) {
+            return self;
+        }
         let limit = match n {
             Range::Local => pb::Limit { global: false, size },
             Range::Global => pb::Limit { global: true, size },
@@ -135,6 +148,17 @@ impl Plan {
         self
     }
 
+    /// Collect all data of each scope into one list, e.g. gremlin `fold()`, which is output as one
+    /// data by `AnyData::with`. For `Range::Local` the list of each worker keeps the order the
+    /// data arrive, and for `Range::Global` the lists of all workers are concatenated in the order
+    /// of worker index into one list;
+    pub fn fold(&mut self, n: Range) -> &mut Self {
+        let mut op = new_operator(OpKind::Fold, pipeline());
+        op.resource = vec![(n == Range::Global) as u8];
+        self.plan.push(op);
+        self
+    }
+
     pub fn repeat<F>(&mut self, times: u32, mut func: F) -> &mut Self
     where
         F: FnMut(&mut Plan),
@@ -196,6 +220,27 @@ impl Plan {
         self.plan
     }
 
+    /// Fuse the limit into the previous sort of the same range if any, which keeps only the top
+    /// `size` data in a bounded heap instead of sorting all the data;
+    fn try_fuse_sort(&mut self, n: Range, size: u32) -> bool {
+        if let Some(pre) = self.plan.last_mut() {
+            if pre.kind() == OpKind::Sort && pre.nested_task.is_empty() {
+                let mut sort_by =
+                    pb::SortBy::decode(&pre.resource[0..]).expect("pb::SortBy decode failure;");
+                if sort_by.global == (n == Range::Global) {
+                    if sort_by.limit <= 0 || sort_by.limit > size as i64 {
+                        sort_by.limit = size as i64;
+                    }
+                    let mut buf = Vec::with_capacity(sort_by.encoded_len());
+                    sort_by.encode(&mut buf).expect("pb::SortBy encode failure;");
+                    pre.resource = buf;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
     #[inline]
     fn try_chain(&mut self, op: &mut pb::OperatorDef) {
         if let Some(pre) = self.plan.pop() {
@@ -266,11 +311,21 @@ impl JobBuilder {
         self
     }
 
+    pub fn sort_by(&mut self, n: Range, cmp: BinaryResource) -> &mut Self {
+        self.plan.sort_by(n, cmp);
+        self
+    }
+
     pub fn limit(&mut self, n: Range, size: u32) -> &mut Self {
         self.plan.limit(n, size);
         self
     }
 
+    pub fn fold(&mut self, n: Range) -> &mut Self {
+        self.plan.fold(n);
+        self
+    }
+
     pub fn repeat<F>(&mut self, times: u32, func: F) -> &mut Self
     where
         F: FnMut(&mut Plan),
@@ -366,4 +421,42 @@ mod test {
         // exchange.map is merge as one map;
         assert_eq!(job_req.plan.len(), 5);
     }
+
+    #[test]
+    fn test_fuse_sort_limit() {
+        let mut builder = JobBuilder::new(JobConf::new(1, "test_fuse", 2));
+        builder
+            .add_source(vec![0u8; 32])
+            .sort_by(Range::Global, vec![1u8; 32])
+            .limit(Range::Global, 10)
+            .limit(Range::Global, 20)
+            .sort_by(Range::Local, vec![2u8; 32])
+            .limit(Range::Global, 5)
+            .sink(vec![3u8; 32]);
+        let job_req = builder.build().unwrap();
+        // sort + limit + limit is fused into one sort;
+        assert_eq!(job_req.plan.len(), 4);
+        let sort_by = pb::SortBy::decode(&job_req.plan[0].resource[0..]).unwrap();
+        assert_eq!(sort_by, pb::SortBy { global: true, limit: 10, cmp: vec![1u8; 32] });
+        // local sort can't be fused with global limit;
+        let sort_by = pb::SortBy::decode(&job_req.plan[1].resource[0..]).unwrap();
+        assert_eq!(sort_by.limit, -1);
+        assert_eq!(job_req.plan[2].kind(), OpKind::Limit);
+    }
+
+    #[test]
+    fn test_fold_plan() {
+        let mut builder = JobBuilder::new(JobConf::new(1, "test_fold", 2));
+        builder
+            .add_source(vec![0u8; 32])
+            .fold(Range::Local)
+            .fold(Range::Global)
+            .sink(vec![1u8; 32]);
+        let job_req = builder.build().unwrap();
+        assert_eq!(job_req.plan.len(), 3);
+        assert_eq!(job_req.plan[0].kind(), OpKind::Fold);
+        assert_eq!(job_req.plan[0].resource, vec![0u8]);
+        assert_eq!(job_req.plan[1].kind(), OpKind::Fold);
+        assert_eq!(job_req.plan[1].resource, vec![1u8]);
+    }
 }
@@ -41,6 +41,7 @@ pub enum OpKind {
     Count = 13,
     Union = 14,
     Dedup = 15,
+    Fold = 16,
 }
 
 #[derive(Clone)]
@@ -286,6 +287,13 @@ impl OperatorDesc {
                     Err(e) => Err(format!("decode count failure: {}", e))?,
                 }
             }
+            OpKind::Fold => {
+                let mut reader = &op.resource[0..];
+                match <u8>::read_from(&mut reader) {
+                    Ok(global) => Ok(OperatorDesc::new(op_kind, ch_kind, global)),
+                    Err(e) => Err(format!("decode fold failure: {}", e))?,
+                }
+            }
             OpKind::Dedup => {
                 let mut reader = &op.resource[0..];
                 match <u8>::read_from(&mut reader) {
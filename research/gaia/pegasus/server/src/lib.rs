@@ -34,6 +34,15 @@ mod generated {
 
 pub trait AnyData: Data + Eq {
     fn with<T: Data + Eq>(raw: T) -> Self;
+
+    /// The multiplicity of the data, which is counted as `bulk` data by `count`; Default is 1;
+    fn bulk(&self) -> u64 {
+        1
+    }
+
+    /// Reset the multiplicity of the data to 1, e.g. a data kept by `dedup` stands for itself
+    /// only, rather than all the identical data it represents; Default does nothing;
+    fn reset_bulk(&mut self) {}
 }
 
 pub mod client;
@@ -22,8 +22,8 @@ use crate::AnyData;
 use pegasus::api::accum::{CountAccum, MaxAccum, MinAccum};
 use pegasus::api::function::*;
 use pegasus::api::{
-    Binary, Count, Dedup, Exchange, Filter, Group, Iteration, Limit, LoopCondition, Map, OrderBy,
-    ResultSet, SubTask, SubtaskResult, RANGES,
+    Binary, Count, Dedup, Exchange, Filter, Fold, Group, Iteration, Limit, LoopCondition, Map,
+    OrderBy, Range, ResultSet, SubTask, SubtaskResult, RANGES,
 };
 use pegasus::communication::{Aggregate, Broadcast, Channel, Pipeline};
 use pegasus::stream::Stream;
@@ -80,7 +80,9 @@ fn install<D: AnyData>(
         &OpKind::Count => {
             let global = op.get_resource::<u8>().expect("parse count resource failure;");
             let range = RANGES[*global as usize];
-            Ok(stream.count(range)?.map(Pipeline, map!(|p| Ok(D::with(p))))?)
+            Ok(stream
+                .count_weighted(range, |d| d.bulk())?
+                .map(Pipeline, map!(|p| Ok(D::with(p))))?)
         }
         &OpKind::Sort => {
             let res = op.get_resource::<SortByDesc>().expect("parse sort resource failure;");
@@ -155,7 +157,7 @@ fn install<D: AnyData>(
             let forked = stream.fork_subtask(|start| exec(&start, &s.subtask, factory))?;
             if let Some(ref joiner) = s.joiner {
                 let func = factory.left_join(joiner.get())?;
-                Ok(stream.join_subtask(forked, move |p, s| func.exec(p, s))?)
+                Ok(stream.join_subtask_with(forked, func)?)
             } else {
                 Ok(forked.flat_map(
                     Pipeline,
@@ -204,15 +206,40 @@ fn install<D: AnyData>(
                 .expect("should be unreachable, downcast DedupDesc failure;");
             if let Some(ref res) = dedup_desc.set {
                 let set_factory = factory.set(res.get())?;
-                Ok(stream.dedup_with(dedup_desc.range, set_factory)?)
+                // the traversers after dedup are distinct, which should be counted once each
+                Ok(stream.dedup_with(dedup_desc.range, set_factory)?.map(
+                    Pipeline,
+                    map!(|mut d: D| {
+                        d.reset_bulk();
+                        Ok(d)
+                    }),
+                )?)
             } else {
                 Err("custom set lost")?
             }
         }
+        &OpKind::Fold => {
+            let global = op.get_resource::<u8>().expect("parse fold resource failure;");
+            fold_to_list(stream, RANGES[*global as usize])
+        }
         _ => unimplemented!(),
     }
 }
 
+/// Collect all data of each scope into one `Vec<D>` which is output by `AnyData::with`, where the
+/// data are in the order they arrive on each worker, and the lists of different workers are
+/// concatenated in the order of worker index for `Range::Global`;
+fn fold_to_list<D: AnyData>(stream: &Stream<D>, range: Range) -> Result<Stream<D>, BuildJobError> {
+    stream
+        .fold_in_range(
+            Vec::new(),
+            range,
+            |list, d| list.push(d),
+            |list, partial| list.extend(partial),
+        )?
+        .map(Pipeline, map!(|list: Vec<D>| Ok(D::with(list))))
+}
+
 #[inline]
 fn gen_channel<D: AnyData>(
     ch: &ChannelDesc, factory: &Arc<dyn JobCompiler<D>>,
@@ -225,3 +252,100 @@ fn gen_channel<D: AnyData>(
         ChannelDesc::Aggregate(target) => Ok(Aggregate(*target as u64).into()),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pegasus::api::Sink;
+    use pegasus::codec::{Decode, Encode, ReadExt, WriteExt};
+    use pegasus::{Configuration, Data, JobConf, Tag};
+    use std::any::Any;
+
+    /// Either a number read from source, or a list of folded items;
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    enum Item {
+        Num(u32),
+        List(Vec<Item>),
+    }
+
+    impl Encode for Item {
+        fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+            match self {
+                Item::Num(n) => {
+                    writer.write_u8(0)?;
+                    writer.write_u32(*n)
+                }
+                Item::List(list) => {
+                    writer.write_u8(1)?;
+                    list.write_to(writer)
+                }
+            }
+        }
+    }
+
+    impl Decode for Item {
+        fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+            match reader.read_u8()? {
+                0 => Ok(Item::Num(reader.read_u32()?)),
+                _ => Ok(Item::List(Vec::<Item>::read_from(reader)?)),
+            }
+        }
+    }
+
+    impl AnyData for Item {
+        fn with<T: Data + Eq>(raw: T) -> Self {
+            let raw: Box<dyn Any> = Box::new(raw);
+            Item::List(*raw.downcast::<Vec<Item>>().expect("only list is folded"))
+        }
+    }
+
+    /// Worker `i` of the 2 workers reads `i*5..i*5+5`, and folds them into a list;
+    fn fold_in(range: Range) -> Vec<Item> {
+        pegasus_common::logs::init_log();
+        pegasus::startup(Configuration::singleton()).ok();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let conf = JobConf::new(1, "fold_to_list_test", 2);
+        pegasus::run(conf, |worker| {
+            let tx = tx.clone();
+            let start = worker.id.index * 5;
+            worker.dataflow(move |dfb| {
+                let source = dfb.input_from_iter((start..start + 5).map(Item::Num))?;
+                fold_to_list(&source, range)?.sink_by(move |_meta| {
+                    move |_t: &Tag, result: ResultSet<Item>| match result {
+                        ResultSet::Data(data) => {
+                            tx.send(data).expect("send error");
+                        }
+                        _ => (),
+                    }
+                })?;
+                Ok(())
+            })
+        })
+        .expect("");
+        std::mem::drop(tx);
+
+        let mut result = Vec::new();
+        while let Ok(data) = rx.recv() {
+            result.extend(data);
+        }
+        pegasus::shutdown_all();
+        result
+    }
+
+    fn list_of(range: std::ops::Range<u32>) -> Item {
+        Item::List(range.map(Item::Num).collect())
+    }
+
+    #[test]
+    fn fold_local_test() {
+        let mut result = fold_in(Range::Local);
+        result.sort_by_key(|item| format!("{:?}", item));
+        assert_eq!(result, vec![list_of(0..5), list_of(5..10)]);
+    }
+
+    #[test]
+    fn fold_global_test() {
+        // one list of all data, concatenated in the order of worker index;
+        assert_eq!(fold_in(Range::Global), vec![list_of(0..10)]);
+    }
+}
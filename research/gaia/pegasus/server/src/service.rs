@@ -150,10 +150,10 @@ impl<D: AnyData> Service<D> {
                 }?;
                 stream.sink_by(|_meta| {
                     move |_tag, result| match result {
-                        ResultSet::Data(data) => {
-                            let bytes = ec.encode(data);
-                            output.on_next(bytes);
-                        }
+                        ResultSet::Data(data) => match ec.encode(data) {
+                            Ok(bytes) => output.on_next(bytes),
+                            Err(e) => output.on_error(&*e),
+                        },
                         ResultSet::End => {
                             output.close();
                         }